@@ -2,16 +2,18 @@ use crate::application::state::AppState;
 use crate::core::events::*;
 use crate::core::traits::*;
 use anyhow::Result;
-use crossbeam_channel::bounded;
+use crossbeam_channel::{bounded, Select};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
-use crate::application::handlers::HandlerContext;
+use std::time::{Duration, Instant};
+use crate::application::handlers::{DirtyState, HandlerContext};
 use crate::application::handlers::library_handler::LibraryHandler;
 use crate::application::handlers::playback_handler::PlaybackHandler;
 use crate::application::handlers::ui_handler::UiHandler;
 use crate::modules::playback::shuffle_manager::ShuffleManager;
 use crate::modules::input::KeyConfig;
-use crate::utils::{EVENT_CHANNEL_CAPACITY, TICK_RATE_MS};
+#[cfg(all(target_os = "linux", feature = "mpris"))]
+use crate::modules::mpris::MprisServer;
+use crate::utils::{EVENT_CHANNEL_CAPACITY, GAPLESS_PRELOAD_SECS, IDLE_TICK_RATE_MS, SAVE_DEBOUNCE_SECS};
 
 /// Main application orchestrator
 pub struct Application {
@@ -24,7 +26,10 @@ pub struct Application {
     // Module references
     playback_backend: Option<Box<dyn PlaybackBackend>>,
     storage_backend: Option<Box<dyn StorageBackend>>,
+    scrobble_backend: Option<Box<dyn ScrobbleBackend>>,
     ui_renderer: Option<Box<dyn UiRenderer>>,
+    #[cfg(all(target_os = "linux", feature = "mpris"))]
+    mpris: Option<MprisServer>,
 
     config_dir: Option<std::path::PathBuf>,
     key_config: KeyConfig,
@@ -32,6 +37,10 @@ pub struct Application {
     // Keep track of running state
     running: bool,
 
+    // Unsaved state changes, and when the state was last written
+    dirty: DirtyState,
+    last_save: Instant,
+
     // Handlers
     playback_handler: PlaybackHandler,
     library_handler: LibraryHandler,
@@ -49,10 +58,15 @@ impl Application {
             shuffle_manager: ShuffleManager::new(),
             playback_backend: None,
             storage_backend: None,
+            scrobble_backend: None,
             ui_renderer: None,
+            #[cfg(all(target_os = "linux", feature = "mpris"))]
+            mpris: None,
             config_dir: None,
             key_config: KeyConfig::default(),
             running: false,
+            dirty: DirtyState::default(),
+            last_save: Instant::now(),
             playback_handler: PlaybackHandler,
             library_handler: LibraryHandler::new(),
             ui_handler: UiHandler,
@@ -71,6 +85,12 @@ impl Application {
         self
     }
 
+    /// Set the scrobble backend
+    #[cfg(feature = "scrobble")]
+    pub fn set_scrobble_backend(&mut self, backend: Box<dyn ScrobbleBackend>) {
+        self.scrobble_backend = Some(backend);
+    }
+
     /// Set the UI renderer
     pub fn with_ui_renderer(mut self, renderer: Box<dyn UiRenderer>) -> Self {
         self.ui_renderer = Some(renderer);
@@ -82,6 +102,11 @@ impl Application {
         self.event_tx.clone()
     }
 
+    /// Shared handle to the state, for readers outside the event loop.
+    pub fn state(&self) -> Arc<Mutex<AppState>> {
+        Arc::clone(&self.state)
+    }
+
     /// Initialize the application
     pub fn init(&mut self) -> Result<()> {
         // Resolve config directory early so modules can load configuration.
@@ -99,7 +124,10 @@ impl Application {
         if let Some(storage) = &self.storage_backend {
             match storage.load() {
                 Ok(loaded_state) => {
-                    let volume = loaded_state.config.volume;
+                    let volume = loaded_state.config.effective_volume();
+                    let normalize = loaded_state.config.normalize_volume;
+                    let crossfade_secs = loaded_state.config.crossfade_secs;
+                    let eq = loaded_state.config.eq;
                     let shuffle_enabled = loaded_state.config.shuffle;
                     let playlist_size = loaded_state.library.songs.len();
                     let active_sort = loaded_state.library.active_sort;
@@ -108,6 +136,9 @@ impl Application {
                     // Set volume on playback backend
                     if let Some(playback) = &mut self.playback_backend {
                         playback.set_volume(volume);
+                        playback.set_normalization(normalize);
+                        playback.set_crossfade(Duration::from_secs(u64::from(crossfade_secs)));
+                        playback.set_equalizer(eq);
                     }
 
                     // Initialize shuffle manager
@@ -144,14 +175,22 @@ impl Application {
     pub fn run(&mut self) -> Result<()> {
         self.running = true;
 
+        // Media keys and desktop widgets; playback works without them. Claimed here
+        // rather than in `init`, which one-shot commands run too.
+        #[cfg(all(target_os = "linux", feature = "mpris"))]
+        {
+            self.mpris = MprisServer::start(self.event_tx.clone(), self.state())
+                .inspect_err(|e| log::warn!("MPRIS unavailable: {:#}", e))
+                .ok();
+        }
+
         while self.running {
             self.process_events()?;
             self.poll_ui_input()?;
             self.tick_playback()?;
             self.render()?;
-
-            // Small sleep to prevent CPU spinning
-            std::thread::sleep(Duration::from_millis(TICK_RATE_MS));
+            self.save_if_due()?;
+            self.wait_for_next_tick()?;
         }
 
         Ok(())
@@ -164,15 +203,77 @@ impl Application {
 
     /// Cleanup resources and persist final state
     pub fn cleanup(&mut self) -> Result<()> {
+        if let Some(scroll) = self.ui_renderer.as_ref().and_then(|ui| ui.list_scroll()) {
+            let mut state = self.state.lock().unwrap();
+            state.config.browse_selected = state.ui.selected_index;
+            state.config.browse_scroll = scroll;
+        }
+        self.save_state()?;
+
+        if let Some(ui) = &mut self.ui_renderer {
+            ui.cleanup()?;
+        }
+
+        // Releases the MPRIS bus name.
+        #[cfg(all(target_os = "linux", feature = "mpris"))]
+        {
+            self.mpris = None;
+        }
+
+        Ok(())
+    }
+
+    /// Write pending changes once `SAVE_DEBOUNCE_SECS` have passed since the last save.
+    /// Only the changed parts are written, so settings changes skip the song list.
+    fn save_if_due(&mut self) -> Result<()> {
+        if !self.dirty.any() || self.last_save.elapsed() < Duration::from_secs(SAVE_DEBOUNCE_SECS) {
+            return Ok(());
+        }
+
+        if let Some(storage) = &self.storage_backend {
+            let state = self.state.lock().unwrap();
+            if self.dirty.config {
+                storage.save_config(&state)?;
+            }
+            if self.dirty.library {
+                storage.save_library(&state)?;
+            }
+        }
+        self.dirty = DirtyState::default();
+        self.last_save = Instant::now();
+        Ok(())
+    }
+
+    /// Write the state to storage now, if a backend is present.
+    fn save_state(&mut self) -> Result<()> {
         if let Some(storage) = &self.storage_backend {
             let state = self.state.lock().unwrap();
             storage.save(&state)?;
         }
+        self.dirty = DirtyState::default();
+        self.last_save = Instant::now();
+        Ok(())
+    }
 
-        if let Some(ui) = &mut self.ui_renderer {
-            ui.cleanup()?;
+    /// Block until the next tick, or earlier when a key is pressed (with a UI)
+    /// or an event arrives (without one). Idle ticks are long so a paused
+    /// player does not keep the CPU awake.
+    fn wait_for_next_tick(&mut self) -> Result<()> {
+        if !self.event_rx.is_empty() {
+            return Ok(());
         }
 
+        let timeout = tick_interval(&self.state.lock().unwrap());
+        match &mut self.ui_renderer {
+            Some(ui) => {
+                ui.wait_for_input(timeout)?;
+            }
+            None => {
+                let mut select = Select::new();
+                select.recv(&self.event_rx);
+                let _ = select.ready_timeout(timeout);
+            }
+        }
         Ok(())
     }
 
@@ -193,15 +294,47 @@ impl Application {
     }
 
     fn tick_playback(&mut self) -> Result<()> {
-        if let Some(playback) = &self.playback_backend {
+        if let Some(playback) = &mut self.playback_backend {
+            let sleep_due = self.state.lock().unwrap().playback.sleep_timer
+                .filter(|timer| timer.is_due(Instant::now()));
+            // With `after_track`, the track playing at the deadline is allowed to end.
+            let stop_now = sleep_due.is_some_and(|timer| !timer.after_track || !playback.is_playing());
+            let finishing_last = sleep_due.is_some();
+
+            if stop_now {
+                self.event_tx.send(AppEvent::Playback(PlaybackEvent::Stopped))?;
+                return Ok(());
+            }
+
+            if playback.take_queued_started() {
+                let event = if finishing_last { PlaybackEvent::Stopped } else { PlaybackEvent::QueuedTrackStarted };
+                self.event_tx.send(AppEvent::Playback(event))?;
+            }
+
             if playback.is_playing() && !playback.is_paused() {
                 let position = playback.position();
-                self.state.lock().unwrap().playback.current_elapsed = position;
+                let mut state = self.state.lock().unwrap();
+                state.playback.current_elapsed = position;
+
+                if !state.playback.play_counted && passes_play_threshold(&state, position) {
+                    self.event_tx
+                        .send(AppEvent::Playback(PlaybackEvent::PlayThresholdReached))?;
+                }
+
+                if let Some((a, b)) = state.playback.ab_loop()
+                    && position >= b
+                {
+                    self.event_tx
+                        .send(AppEvent::Playback(PlaybackEvent::SeekRequested { position: a }))?;
+                } else if !state.playback.preload_requested && !finishing_last && is_near_end(&state, position) {
+                    self.event_tx
+                        .send(AppEvent::Playback(PlaybackEvent::NearingEnd))?;
+                }
             }
 
             if playback.has_finished() {
-                self.event_tx
-                    .send(AppEvent::Playback(PlaybackEvent::TrackFinished))?;
+                let event = if finishing_last { PlaybackEvent::Stopped } else { PlaybackEvent::TrackFinished };
+                self.event_tx.send(AppEvent::Playback(event))?;
             }
         }
         Ok(())
@@ -218,13 +351,21 @@ impl Application {
 
     /// Apply state update then delegate side effects to the appropriate handler
     fn dispatch(&mut self, event: AppEvent) -> Result<()> {
-        self.state.lock().unwrap().apply_event(&event);
+        {
+            let mut state = self.state.lock().unwrap();
+            state.apply_event(&event);
+            #[cfg(all(target_os = "linux", feature = "mpris"))]
+            if let (Some(mpris), AppEvent::Playback(event)) = (&self.mpris, &event) {
+                mpris.announce(event, &state);
+            }
+        }
 
         let mut ctx = HandlerContext {
             state: &self.state,
             event_tx: &self.event_tx,
             playback: &mut self.playback_backend,
-            storage: &self.storage_backend,
+            scrobbler: &mut self.scrobble_backend,
+            dirty: &mut self.dirty,
             shuffle_manager: &mut self.shuffle_manager,
         };
 
@@ -239,8 +380,292 @@ impl Application {
     }
 }
 
+/// How long the event loop may sleep: one frame at the configured `fps` while something on screen moves
+/// (the progress bar, the title marquee, a scan), `IDLE_TICK_RATE_MS` otherwise.
+fn tick_interval(state: &AppState) -> Duration {
+    let playing = state.playback.is_playing && !state.playback.is_paused;
+    if playing || state.library.is_scanning {
+        state.config.frame_interval()
+    } else {
+        Duration::from_millis(IDLE_TICK_RATE_MS)
+    }
+}
+
+/// Whether the current track is close enough to its end to queue the next one.
+///
+/// The window grows with the crossfade so the next track is ready before the fade starts.
+fn is_near_end(state: &AppState, position: Duration) -> bool {
+    let window = Duration::from_secs(GAPLESS_PRELOAD_SECS + u64::from(state.config.crossfade_secs));
+
+    state.playback.current_song
+        .as_ref()
+        .and_then(|song| song.duration)
+        .is_some_and(|duration| {
+            duration.saturating_sub(position) <= window
+        })
+}
+
+/// Whether the current track has played long enough to count in the play history,
+/// per the configured `play_threshold`.
+fn passes_play_threshold(state: &AppState, position: Duration) -> bool {
+    state.playback.current_song
+        .as_ref()
+        .and_then(|song| song.duration)
+        .is_some_and(|duration| position >= state.config.play_threshold(duration))
+}
+
 impl Default for Application {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use crate::core::models::{RepeatMode, Song};
+    use crate::modules::playback::null_backend::NullPlaybackBackend;
+    use crate::modules::storage::json_backend::JsonStorageBackend;
+    use crate::utils::volume_percent_to_amplitude;
+
+    /// Storage that only counts how often each part is written.
+    #[derive(Default)]
+    struct SaveCounts {
+        full: AtomicUsize,
+        config: AtomicUsize,
+        library: AtomicUsize,
+    }
+
+    struct CountingStorage {
+        saves: Arc<SaveCounts>,
+    }
+
+    impl StorageBackend for CountingStorage {
+        fn load(&self) -> Result<AppState> {
+            Ok(AppState::default())
+        }
+
+        fn save(&self, _state: &AppState) -> Result<()> {
+            self.saves.full.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn save_config(&self, _state: &AppState) -> Result<()> {
+            self.saves.config.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn save_library(&self, _state: &AppState) -> Result<()> {
+            self.saves.library.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    /// Scrobbler that only records what it was told, as "playing <title>" / "scrobble <title>".
+    struct RecordingScrobbler {
+        calls: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl ScrobbleBackend for RecordingScrobbler {
+        fn now_playing(&mut self, song: &Song) {
+            self.calls.lock().unwrap().push(format!("playing {}", song.title));
+        }
+
+        fn scrobble(&mut self, song: &Song) {
+            self.calls.lock().unwrap().push(format!("scrobble {}", song.title));
+        }
+    }
+
+    #[test]
+    fn rapid_changes_are_saved_once() {
+        let saves = Arc::new(SaveCounts::default());
+        let mut app = Application::new()
+            .with_storage_backend(Box::new(CountingStorage { saves: Arc::clone(&saves) }));
+
+        for volume in 1..=20u8 {
+            app.event_sender()
+                .send(AppEvent::Playback(PlaybackEvent::VolumeChanged { volume: volume_percent_to_amplitude(volume) }))
+                .unwrap();
+            app.run_once().unwrap();
+            app.save_if_due().unwrap();
+        }
+        assert_eq!(saves.config.load(Ordering::SeqCst), 0, "changes inside the debounce window wait");
+
+        app.last_save -= Duration::from_secs(SAVE_DEBOUNCE_SECS);
+        app.save_if_due().unwrap();
+        app.save_if_due().unwrap();
+        assert_eq!(saves.config.load(Ordering::SeqCst), 1);
+        assert_eq!(saves.library.load(Ordering::SeqCst), 0, "a volume change never rewrites the library");
+        assert_eq!(app.state.lock().unwrap().config.volume_percent, 20);
+
+        app.cleanup().unwrap();
+        assert_eq!(saves.full.load(Ordering::SeqCst), 1, "cleanup always writes the final state");
+    }
+
+    #[test]
+    fn idle_loop_sleeps_long_but_wakes_for_events() {
+        let mut app = Application::new();
+        assert_eq!(tick_interval(&app.state.lock().unwrap()), Duration::from_millis(IDLE_TICK_RATE_MS));
+
+        app.state.lock().unwrap().playback.is_playing = true;
+        assert_eq!(tick_interval(&app.state.lock().unwrap()), Duration::from_millis(16));
+        app.state.lock().unwrap().playback.is_paused = true;
+        assert_eq!(tick_interval(&app.state.lock().unwrap()), Duration::from_millis(IDLE_TICK_RATE_MS));
+
+        let sender = app.event_sender();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            sender.send(AppEvent::Shutdown).unwrap();
+        });
+        let started = Instant::now();
+        app.wait_for_next_tick().unwrap();
+        assert!(started.elapsed() < Duration::from_millis(IDLE_TICK_RATE_MS), "an event ends the wait early");
+    }
+
+    /// An app on the silent backend with `n` songs of `length` each.
+    fn silent_app(n: usize, length: Duration) -> Application {
+        let app = Application::new().with_playback_backend(Box::new(NullPlaybackBackend::new()));
+        let songs = (0..n).map(|i| Song {
+            duration: Some(length),
+            ..Song::from_path(std::path::Path::new(&format!("/m/{i}.mp3")))
+        });
+        app.state.lock().unwrap().library.songs = Arc::new(songs.collect());
+        app
+    }
+
+    fn send(app: &Application, event: UiEvent) {
+        app.event_sender().send(AppEvent::Ui(event)).unwrap();
+    }
+
+    /// Run loop iterations until `done` holds, or fail after a second.
+    fn run_until(app: &mut Application, done: impl Fn(&AppState) -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(1);
+        while !done(&app.state.lock().unwrap()) {
+            assert!(Instant::now() < deadline, "timed out");
+            app.process_events().unwrap();
+            app.tick_playback().unwrap();
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn finished_tracks_advance_and_stop_at_the_end_of_the_library() {
+        let mut app = silent_app(2, Duration::from_millis(30));
+        send(&app, UiEvent::SelectionChanged { index: 0 });
+        send(&app, UiEvent::PlaySelectedRequested);
+
+        run_until(&mut app, |state| state.playback.current_index == Some(1));
+        run_until(&mut app, |state| !state.playback.is_playing);
+        assert_eq!(app.state.lock().unwrap().playback.current_index, Some(1), "repeat is off");
+    }
+
+    #[test]
+    fn repeat_one_replays_the_finished_track() {
+        let mut app = silent_app(3, Duration::from_millis(30));
+        app.state.lock().unwrap().config.repeat = RepeatMode::One;
+        send(&app, UiEvent::SelectionChanged { index: 1 });
+        send(&app, UiEvent::PlaySelectedRequested);
+        run_until(&mut app, |state| state.playback.is_playing);
+
+        let deadline = Instant::now() + Duration::from_millis(100);
+        run_until(&mut app, |_| Instant::now() >= deadline);
+        let state = app.state.lock().unwrap();
+        assert!(state.playback.is_playing);
+        assert_eq!(state.playback.current_index, Some(1));
+    }
+
+    #[test]
+    fn next_and_previous_move_through_the_library() {
+        let mut app = silent_app(3, Duration::from_secs(60));
+        send(&app, UiEvent::SelectionChanged { index: 0 });
+        send(&app, UiEvent::PlaySelectedRequested);
+        run_until(&mut app, |state| state.playback.current_index == Some(0));
+
+        send(&app, UiEvent::NextTrackRequested);
+        send(&app, UiEvent::NextTrackRequested);
+        run_until(&mut app, |state| state.playback.current_index == Some(2));
+
+        send(&app, UiEvent::PreviousTrackRequested);
+        run_until(&mut app, |state| state.playback.current_index == Some(1));
+    }
+
+    #[test]
+    fn toggled_favorite_survives_a_reload() {
+        let dir = std::env::temp_dir().join(format!("hextune_app_favorite_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("db.json");
+        let open = || {
+            let storage = JsonStorageBackend::with_path(path.clone()).unwrap();
+            let mut app = silent_app(0, Duration::ZERO).with_storage_backend(Box::new(storage));
+            app.config_dir = Some(dir.clone());
+            app
+        };
+
+        let mut state = AppState::default();
+        state.library.songs = silent_app(3, Duration::from_secs(60)).state.lock().unwrap().library.songs.clone();
+        JsonStorageBackend::with_path(path.clone()).unwrap().save(&state).unwrap();
+
+        let mut app = open();
+        app.init().unwrap();
+        send(&app, UiEvent::SelectionChanged { index: 1 });
+        send(&app, UiEvent::FavoriteToggleRequested);
+        run_until(&mut app, |state| state.library.songs[1].favorite);
+        app.cleanup().unwrap();
+
+        let mut reloaded = open();
+        reloaded.init().unwrap();
+        let favorites: Vec<bool> = reloaded.state.lock().unwrap().library.songs.iter().map(|s| s.favorite).collect();
+        assert_eq!(favorites, [false, true, false]);
+
+        send(&reloaded, UiEvent::SelectionChanged { index: 1 });
+        send(&reloaded, UiEvent::FavoriteToggleRequested);
+        run_until(&mut reloaded, |state| !state.library.songs[1].favorite);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn tracks_are_scrobbled_once_they_count_as_played() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let mut app = silent_app(1, Duration::from_secs(60));
+        app.scrobble_backend = Some(Box::new(RecordingScrobbler { calls: Arc::clone(&calls) }));
+        app.state.lock().unwrap().config.play_count_threshold_pct = 25;
+        send(&app, UiEvent::SelectionChanged { index: 0 });
+        send(&app, UiEvent::PlaySelectedRequested);
+        run_until(&mut app, |state| state.playback.is_playing);
+        assert_eq!(*calls.lock().unwrap(), ["playing 0"]);
+
+        send(&app, UiEvent::SeekRequested { position: Duration::from_secs(14) });
+        run_until(&mut app, |state| state.playback.current_elapsed >= Duration::from_secs(14));
+        assert_eq!(calls.lock().unwrap().len(), 1, "not yet a quarter of the way");
+
+        send(&app, UiEvent::SeekRequested { position: Duration::from_secs(16) });
+        run_until(&mut app, |state| state.playback.play_counted);
+        for _ in 0..3 {
+            app.tick_playback().unwrap();
+            app.process_events().unwrap();
+        }
+        assert_eq!(*calls.lock().unwrap(), ["playing 0", "scrobble 0"]);
+    }
+
+    #[test]
+    fn position_follows_the_backend_through_pause_and_seek() {
+        let mut app = silent_app(1, Duration::from_secs(60));
+        send(&app, UiEvent::SelectionChanged { index: 0 });
+        send(&app, UiEvent::PlaySelectedRequested);
+        run_until(&mut app, |state| state.playback.current_elapsed > Duration::ZERO);
+
+        send(&app, UiEvent::PauseRequested);
+        run_until(&mut app, |state| state.playback.is_paused);
+        send(&app, UiEvent::SeekRequested { position: Duration::from_secs(30) });
+        run_until(&mut app, |state| state.playback.current_elapsed == Duration::from_secs(30));
+        std::thread::sleep(Duration::from_millis(5));
+        app.tick_playback().unwrap();
+        assert_eq!(app.state.lock().unwrap().playback.current_elapsed, Duration::from_secs(30), "paused");
+
+        send(&app, UiEvent::ResumeRequested);
+        run_until(&mut app, |state| state.playback.current_elapsed > Duration::from_secs(30));
+        let elapsed = app.state.lock().unwrap().playback.current_elapsed;
+        assert!(elapsed < Duration::from_secs(31), "resumed from the seek target, got {:?}", elapsed);
+    }
+}
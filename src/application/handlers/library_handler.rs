@@ -3,14 +3,19 @@ use crate::application::handlers::HandlerContext;
 use crate::core::events::{AppEvent, LibraryEvent, UiEvent};
 use crate::modules::library::search_engine::SearchEngine;
 use anyhow::Result;
-use crate::modules::library::scanner;
+use crate::modules::library::scanner::{self, ScanFilter};
 use std::thread;
 use crate::modules::library::sorter::sort_songs;
+use crate::utils::format_roots;
+
+/// Shown in the status line when a scan is requested before any root is set.
+const NO_MUSIC_PATH: &str = "No music path set. Add one in Settings (s), then press F5 to scan.";
 
 /// Handles all [`LibraryEvent`] variants.
 ///
 /// Responsible for:
 /// - Keeping the shuffle manager in sync when the library changes
+/// - Running library scans in the background, one at a time
 /// - Executing search queries and emitting results
 /// - Persisting library changes to storage
 pub struct LibraryHandler {
@@ -40,7 +45,7 @@ impl LibraryHandler {
                     ctx.shuffle_manager.initialize(len, None);
                 }
 
-                ctx.persist_state()?;
+                ctx.mark_library_dirty();
             }
 
             LibraryEvent::LibraryLoaded { songs } => {
@@ -51,6 +56,43 @@ impl LibraryHandler {
                 }
             }
 
+            LibraryEvent::SongRemoved { .. } => {
+                // AppState already dropped the song and clears `current_song` if it was playing.
+                let (len, current_index, playing_removed) = {
+                    let state = ctx.state.lock().unwrap();
+                    (
+                        state.library.songs.len(),
+                        state.playback.current_index,
+                        state.playback.current_song.is_none(),
+                    )
+                };
+
+                if playing_removed && let Some(playback) = ctx.playback.as_mut() {
+                    playback.stop();
+                }
+
+                // Shuffle order and history hold indices from before the removal.
+                ctx.shuffle_manager.update_playlist_size(len);
+                if ctx.shuffle_manager.is_enabled() {
+                    ctx.shuffle_manager.initialize(len, current_index);
+                }
+
+                ctx.mark_library_dirty();
+            }
+
+            LibraryEvent::LibraryCleared => {
+                if let Some(playback) = ctx.playback.as_mut() {
+                    playback.stop();
+                }
+
+                ctx.shuffle_manager.update_playlist_size(0);
+                if ctx.shuffle_manager.is_enabled() {
+                    ctx.shuffle_manager.initialize(0, None);
+                }
+
+                ctx.mark_library_dirty();
+            }
+
             LibraryEvent::SearchRequested { query } => {
                 let results = {
                     let state = ctx.state.lock().unwrap();
@@ -66,15 +108,54 @@ impl LibraryHandler {
                     .send(AppEvent::Library(LibraryEvent::SearchResults { results }))?;
             }
 
-            LibraryEvent::ScanRequested { path } => {
+            LibraryEvent::ScanRequested { roots } => {
+                // Scanning nothing would empty the library.
+                if roots.is_empty() {
+                    ctx.event_tx.send(AppEvent::Ui(UiEvent::ShowError {
+                        message: NO_MUSIC_PATH.to_string(),
+                    }))?;
+                    return Ok(());
+                }
+
+                let filter = {
+                    let state = ctx.state.lock().unwrap();
+                    ScanFilter::new(&state.config.scan_include, &state.config.scan_exclude)
+                };
+                let filter = match filter {
+                    Ok(filter) => filter,
+                    Err(e) => {
+                        ctx.event_tx.send(AppEvent::Ui(UiEvent::ShowError {
+                            message: format!("Scan failed: {:#}", e),
+                        }))?;
+                        return Ok(());
+                    }
+                };
+
+                // Claim the scan before ScanStarted is applied, so a request queued behind
+                // this one is rejected too.
+                {
+                    let mut state = ctx.state.lock().unwrap();
+                    if state.library.is_scanning {
+                        drop(state);
+                        ctx.event_tx.send(AppEvent::Ui(UiEvent::ShowMessage {
+                            message: "A scan is already running".to_string(),
+                        }))?;
+                        return Ok(());
+                    }
+                    state.library.is_scanning = true;
+                }
+
+                log::info!("Scan of {} requested", format_roots(roots));
                 ctx.event_tx
-                    .send(AppEvent::Library(LibraryEvent::ScanStarted { path: path.clone() }))?;
+                    .send(AppEvent::Library(LibraryEvent::ScanStarted { roots: roots.clone() }))?;
 
                 let event_tx = ctx.event_tx.clone();
-                let scan_path = path.clone();
+                let scan_roots = roots.clone();
+                // O(1) Arc clone — the scanner reuses unchanged entries from it.
+                let previous = Arc::clone(&ctx.state.lock().unwrap().library.songs);
 
                 thread::spawn(move || {
-                    match scanner::scan_directory(&scan_path, |found| {
+                    match scanner::scan_roots(&scan_roots, &previous, &filter, |found| {
                         // drop the event if the channel is full or closed
                         let _ = event_tx
                             .send(AppEvent::Library(LibraryEvent::ScanProgress { found }));
@@ -85,22 +166,22 @@ impl LibraryHandler {
                                 songs,
                                 count,
                             })) {
-                                eprintln!("Failed to send ScanCompleted event: {}", err);
+                                log::error!("Failed to send ScanCompleted event: {}", err);
                             }
                         }
                         Err(e) => {
                             let message = e.to_string();
                             if let Err(err) = event_tx.send(AppEvent::Library(LibraryEvent::ScanFailed {
-                                path: scan_path.clone(),
+                                roots: scan_roots.clone(),
                                 message: message.clone(),
                             })) {
-                                eprintln!("Failed to send ScanFailed event: {}", err);
+                                log::error!("Failed to send ScanFailed event: {}", err);
                             }
 
                             if let Err(err) = event_tx.send(AppEvent::Ui(UiEvent::ShowError {
                                 message: format!("Scan failed: {}", message),
                             })) {
-                                eprintln!("Failed to send ShowError event: {}", err);
+                                log::error!("Failed to send ShowError event: {}", err);
                             }
                         }
                     }
@@ -117,6 +198,10 @@ impl LibraryHandler {
                     let current_path = state.playback.current_index
                         .and_then(|i| state.library.songs.get(i))
                         .map(|s| s.path.clone());
+                    let queued_paths: Vec<_> = state.playback.queue.iter()
+                        .filter_map(|&i| state.library.songs.get(i))
+                        .map(|s| s.path.clone())
+                        .collect();
 
                     state.library.songs = match field {
                         None => Arc::new({
@@ -137,9 +222,18 @@ impl LibraryHandler {
                     let new_current = current_path
                         .and_then(|p| state.library.songs.iter().position(|s| s.path == p));
 
+                    // The play queue stores indices too; point them at the moved songs.
+                    let requeued = queued_paths.iter()
+                        .filter_map(|p| state.library.songs.iter().position(|s| &s.path == p))
+                        .collect();
+                    state.playback.queue = requeued;
+
                     (new_selected, new_current)
                 };
 
+                // Shuffle history holds indices from before the sort.
+                ctx.shuffle_manager.clear_history();
+
                 ctx.event_tx.send(AppEvent::Library(LibraryEvent::SortChanged {
                     field: *field,
                     new_selected_index,
@@ -147,10 +241,20 @@ impl LibraryHandler {
                 }))?;
             }
 
+            LibraryEvent::RatingChanged { .. }
+            | LibraryEvent::BookmarkAdded { .. }
+            | LibraryEvent::BookmarkRemoved { .. } => {
+                // AppState already updated the library; ratings and bookmarks are stored with it.
+                ctx.mark_library_dirty();
+            }
+
             // All other variants are handled by AppState::apply_event.
+            LibraryEvent::ScanFailed { roots, message } => {
+                log::warn!("Scan of {} failed: {}", format_roots(roots), message);
+            }
+
             LibraryEvent::ScanStarted { .. }
             | LibraryEvent::ScanProgress { .. }
-            | LibraryEvent::ScanFailed { .. }
             | LibraryEvent::SearchResults { .. }
             | LibraryEvent::SortChanged { .. } => {}
         }
@@ -7,10 +7,12 @@ mod tests;
 
 use crate::application::state::AppState;
 use crate::core::events::{AppEvent, EventSender, PlaybackEvent};
-use crate::core::models::Song;
-use crate::core::traits::{PlaybackBackend, StorageBackend};
+use crate::core::models::{RadioMode, RepeatMode, Song};
+use crate::core::traits::{PlaybackBackend, ScrobbleBackend};
+use crate::modules::library::albums::adjacent_album_start;
 use crate::modules::playback::shuffle_manager::ShuffleManager;
 use anyhow::Result;
+use rand::seq::IndexedRandom;
 use std::sync::{Arc, Mutex};
 
 
@@ -24,6 +26,19 @@ pub enum NavTarget {
     Nothing,
 }
 
+/// Parts of the state changed since they were last saved.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DirtyState {
+    pub config: bool,
+    pub library: bool,
+}
+
+impl DirtyState {
+    pub fn any(&self) -> bool {
+        self.config || self.library
+    }
+}
+
 /// All dependencies that event handlers need to do their work.
 ///
 /// Passed by `&mut` into each handler method, giving handlers access to
@@ -32,23 +47,29 @@ pub struct HandlerContext<'a> {
     pub state: &'a Arc<Mutex<AppState>>,
     pub event_tx: &'a EventSender,
     pub playback: &'a mut Option<Box<dyn PlaybackBackend>>,
-    pub storage: &'a Option<Box<dyn StorageBackend>>,
+    pub scrobbler: &'a mut Option<Box<dyn ScrobbleBackend>>,
+    /// What changed in a way worth saving; `Application` flushes it.
+    pub dirty: &'a mut DirtyState,
     pub shuffle_manager: &'a mut ShuffleManager,
 }
 
 impl<'a> HandlerContext<'a> {
-    /// Save the current state to storage, if a backend is present.
-    pub fn persist_state(&self) -> Result<()> {
-        if let Some(storage) = self.storage {
-            let state = self.state.lock().unwrap();
-            storage.save(&state)?;
-        }
+    /// Schedule a save of the settings. Writes are debounced by `Application`,
+    /// so rapid changes like volume nudges cost one save.
+    pub fn mark_config_dirty(&mut self) {
+        self.dirty.config = true;
+    }
 
-        Ok(())
+    /// Schedule a save of the library, play queue and history.
+    pub fn mark_library_dirty(&mut self) {
+        self.dirty.library = true;
     }
 
     /// Advance to the next track, respecting shuffle mode and the `loop_playlist` flag.
     ///
+    /// - Play queue non-empty: pops the front and plays it, before any other rule.
+    /// - Radio on: a random song like the current one that radio has not played yet;
+    ///   once none are left, the rules below apply.
     /// - Shuffle on: delegates to `ShuffleManager::next_index`. When the queue is exhausted
     ///   and `loop_playlist` is false, falls back to `NavTarget::Restart` (replay current).
     /// - Shuffle off, sequential: `idx+1` if in range; wraps to 0 when `loop_playlist` is
@@ -59,8 +80,21 @@ impl<'a> HandlerContext<'a> {
         library_len: usize,
         loop_playlist: bool,
     ) -> Result<()> {
-        let target = if self.shuffle_manager.is_enabled() {
-            match self.shuffle_manager.next_index(current_index, loop_playlist) {
+        // Songs the user queued always come first and leave shuffle order untouched.
+        let queued = {
+            let mut state = self.state.lock().unwrap();
+            pop_valid_queued(&mut state, library_len)
+        };
+
+        let target = if let Some(idx) = queued.or_else(|| self.radio_next(current_index)) {
+            self.shuffle_manager.record_jump(current_index);
+            NavTarget::Go(idx)
+        } else if self.shuffle_manager.is_enabled() {
+            let state = self.state.lock().unwrap();
+            let next = self.shuffle_manager
+                .next_index_avoiding(current_index, loop_playlist, |idx| state.skipped_too_often(idx));
+            drop(state);
+            match next {
                 Some(idx) => NavTarget::Go(idx),
                 None => NavTarget::Restart,
             }
@@ -83,9 +117,42 @@ impl<'a> HandlerContext<'a> {
         self.execute_nav(target, current_index)
     }
 
+    /// Pick a random song matching the current one under the radio mode, skipping
+    /// songs radio already played. `None` when radio is off or the pool is used up;
+    /// the pool then starts over from whatever plays next.
+    fn radio_next(&self, current_index: Option<usize>) -> Option<usize> {
+        let mut guard = self.state.lock().unwrap();
+        let state = &mut *guard;
+        let mode = state.config.radio;
+        let current = current_index.and_then(|idx| state.library.songs.get(idx))?;
+        if mode == RadioMode::Off {
+            return None;
+        }
+
+        let played = &mut state.playback.radio_played;
+        let pool: Vec<usize> = state.library.songs.iter().enumerate()
+            .filter(|&(idx, song)| {
+                Some(idx) != current_index && mode.matches(current, song) && !played.contains(&song.path)
+            })
+            .map(|(idx, _)| idx)
+            .collect();
+
+        match pool.choose(&mut rand::rng()) {
+            Some(&next) => {
+                played.insert(current.path.clone());
+                played.insert(state.library.songs[next].path.clone());
+                Some(next)
+            }
+            None => {
+                played.clear();
+                None
+            }
+        }
+    }
+
     /// Go back to the previous track, respecting shuffle mode and the `loop_playlist` flag.
     ///
-    /// - Shuffle on: walks back through the existing shuffle history via
+    /// - Shuffle on: walks back through the songs that actually played via
     ///   `ShuffleManager::previous_index`. Falls back to `NavTarget::Restart` at the start.
     /// - Shuffle off, sequential: at index 0 wraps to the last song when `loop_playlist`
     ///   is true, otherwise restarts the current song.
@@ -117,6 +184,40 @@ impl<'a> HandlerContext<'a> {
         self.execute_nav(target, current_index)
     }
 
+    /// Play the first track of the adjacent album, whether or not shuffle is on;
+    /// past either end, only `loop_playlist` goes round.
+    pub fn advance_to_album(&mut self, current_index: Option<usize>, forward: bool, loop_playlist: bool) -> Result<()> {
+        let target = {
+            let state = self.state.lock().unwrap();
+            adjacent_album_start(&state.library.songs, current_index, forward, loop_playlist)
+        };
+        if target.is_some() {
+            self.shuffle_manager.record_jump(current_index);
+        }
+        self.execute_nav(target.map_or(NavTarget::Nothing, NavTarget::Go), current_index)
+    }
+
+    /// Predict the index `TrackFinished` would advance to, without touching shuffle state.
+    ///
+    /// Used to queue the next song for gapless playback. Returns `None` whenever the
+    /// outcome is unknown or is not "play another song": end of playlist with repeat
+    /// off, or the end of a shuffle pass (the next pass is reshuffled at random).
+    pub fn peek_next(
+        &self,
+        current_index: Option<usize>,
+        library_len: usize,
+        repeat: RepeatMode,
+    ) -> Option<usize> {
+        let idx = current_index?;
+
+        match repeat {
+            RepeatMode::One => Some(idx),
+            _ if self.shuffle_manager.is_enabled() => self.shuffle_manager.peek_next_index(Some(idx)),
+            RepeatMode::All if idx + 1 >= library_len => Some(0),
+            _ => Some(idx + 1).filter(|&next| next < library_len),
+        }
+    }
+
     /// Resolves a `NavTarget` into a `PlayRequested` event (or nothing).
     ///
     /// - `Go(idx)` → update `selected_index` to `idx` and play that song.
@@ -146,3 +247,12 @@ impl<'a> HandlerContext<'a> {
     }
 }
 
+/// Pop the first queued index that still exists in the library, dropping stale ones.
+fn pop_valid_queued(state: &mut AppState, library_len: usize) -> Option<usize> {
+    while let Some(idx) = state.playback.queue.pop_front() {
+        if idx < library_len {
+            return Some(idx);
+        }
+    }
+    None
+}
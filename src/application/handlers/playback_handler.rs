@@ -1,14 +1,21 @@
 use crate::application::handlers::HandlerContext;
-use crate::core::events::{AppEvent, PlaybackEvent};
+use crate::core::events::{AppEvent, PlaybackEvent, UiEvent};
 use anyhow::Result;
-use crate::core::models::RepeatMode;
+use std::time::Duration;
+use crate::core::models::{RadioMode, RepeatMode};
+use crate::modules::ui::progress_formatter::format_duration;
+use crate::utils::MAX_CONSECUTIVE_PLAY_FAILURES;
 
 /// Handles all [`PlaybackEvent`] variants.
 ///
 /// Responsible for:
-/// - Driving the playback backend (play, pause, resume, volume)
+/// - Driving the playback backend (play, pause, resume, seek, volume)
 /// - Auto-advancing to the next track when one finishes
-/// - Persisting volume and shuffle changes to storage
+/// - Queuing the upcoming track ahead of time for gapless playback
+/// - Stopping playback when the sleep timer runs out
+/// - Resuming long tracks where they were left off
+/// - Reporting what plays to the scrobbler
+/// - Persisting volume, mute, normalization, shuffle, crossfade, equalizer and play history to storage
 pub struct PlaybackHandler;
 
 impl PlaybackHandler {
@@ -16,46 +23,151 @@ impl PlaybackHandler {
         match event {
             PlaybackEvent::PlayRequested { song } => {
                 if let Some(playback) = ctx.playback.as_mut() {
-                    playback.play(song)?;
-                    ctx.event_tx
-                        .send(AppEvent::Playback(PlaybackEvent::Started {
-                            song: song.clone(),
-                        }))?;
+                    let event = match playback.play(song) {
+                        Ok(()) => PlaybackEvent::Started { song: song.clone() },
+                        Err(e) => {
+                            log::warn!("Could not play '{}': {:#}", song.path.display(), e);
+                            PlaybackEvent::Error {
+                                message: format!("Skipped '{}': {:#}", song.title, e),
+                            }
+                        }
+                    };
+                    ctx.event_tx.send(AppEvent::Playback(event))?;
+                }
+            }
+
+            // The message itself is shown by AppState::apply_event.
+            PlaybackEvent::Error { .. } => {
+                let (failures, failed_index, library_len, loop_playlist) = {
+                    let state = ctx.state.lock().unwrap();
+                    (
+                        state.playback.consecutive_failures,
+                        state.ui.selected_index, // the song that failed; current_index is unchanged
+                        state.library.songs.len(),
+                        state.config.repeat == RepeatMode::All,
+                    )
+                };
+
+                if failures < MAX_CONSECUTIVE_PLAY_FAILURES {
+                    ctx.advance_to_next(failed_index, library_len, loop_playlist)?;
+                } else {
+                    // Every remaining track may be broken; don't spin through the library.
+                    log::error!("Stopped skipping after {} songs in a row could not be played", failures);
+                    ctx.state.lock().unwrap().playback.consecutive_failures = 0;
+                    ctx.event_tx.send(AppEvent::Ui(UiEvent::ShowError {
+                        message: format!("Stopped skipping: {} songs in a row could not be played", failures),
+                    }))?;
                 }
             }
 
             PlaybackEvent::TrackFinished => {
                 // Read everything we need from state while holding the lock, then drop it.
-                let (repeat, current_index, library_len) = {
-                    let state = ctx.state.lock().unwrap();
+                let (repeat, replay, current_index, library_len) = {
+                    let mut state = ctx.state.lock().unwrap();
+                    if let Some(song) = &state.playback.current_song {
+                        log::info!("Finished '{}'", song.path.display());
+                    }
+                    let replay = state.should_replay();
+                    state.playback.track_repeats = if replay { state.playback.track_repeats + 1 } else { 0 };
                     (
                         state.config.repeat,
+                        replay,
                         state.playback.current_index, // authoritative index of what was playing
                         state.library.songs.len(),
                     )
                 };
 
-                match repeat {
-                    // Repeat the same song — ignore shuffle and loop settings.
-                    RepeatMode::One => {
-                        if let Some(idx) = current_index {
-                            let song = ctx.state.lock().unwrap().library.songs.get(idx).cloned();
-                            if let Some(song) = song {
-                                ctx.event_tx
-                                    .send(AppEvent::Playback(PlaybackEvent::PlayRequested { song }))?;
-                            }
+                if replay {
+                    // Repeat the same song (repeat-one, or `repeat_count` not reached yet)
+                    // — ignore shuffle and loop settings.
+                    if let Some(idx) = current_index {
+                        let song = ctx.state.lock().unwrap().library.songs.get(idx).cloned();
+                        if let Some(song) = song {
+                            ctx.event_tx
+                                .send(AppEvent::Playback(PlaybackEvent::PlayRequested { song }))?;
                         }
                     }
+                } else {
+                    // Repeat-all loops the playlist when exhausted; off stops at the end.
+                    ctx.advance_to_next(current_index, library_len, repeat == RepeatMode::All)?;
+                }
+                // AppState forgot where a long track was left off.
+                ctx.mark_library_dirty();
+            }
 
-                    // Loop playlist when exhausted.
-                    RepeatMode::All => {
-                        ctx.advance_to_next(current_index, library_len, true)?;
-                    }
+            PlaybackEvent::NearingEnd => {
+                let next = {
+                    let state = ctx.state.lock().unwrap();
+                    let current_index = state.playback.current_index;
+                    let repeat = state.config.repeat;
+                    // Mirror `TrackFinished`: a replay comes first, then the play queue.
+                    // Radio picks at random, so its next song cannot be preloaded.
+                    let replay = state.should_replay();
+                    let up_next = state.playback.queue.front().copied().filter(|_| !replay);
+                    up_next
+                        .or_else(|| if replay { current_index } else if state.config.radio != RadioMode::Off {
+                            None
+                        } else {
+                            ctx.peek_next(current_index, state.library.songs.len(), repeat)
+                        })
+                        // A song shuffle passes over is left to `TrackFinished` to pick around.
+                        .filter(|&idx| !(ctx.shuffle_manager.is_enabled() && state.skipped_too_often(idx)))
+                        .and_then(|idx| state.library.songs.get(idx).cloned().map(|song| (idx, song)))
+                };
+
+                if let (Some((idx, song)), Some(playback)) = (next, ctx.playback.as_mut()) {
+                    playback.queue_next(&song)?;
+                    ctx.state.lock().unwrap().playback.queued_index = Some(idx);
+                }
+            }
 
-                    // Stop at the end of the playlist.
-                    RepeatMode::Off => {
-                        ctx.advance_to_next(current_index, library_len, false)?;
+            PlaybackEvent::QueuedTrackStarted => {
+                let (repeat, replay, current_index, queued, from_play_queue) = {
+                    let mut state = ctx.state.lock().unwrap();
+                    let queued = state.playback.queued_index;
+                    // `NearingEnd` queued the same track again if it is being replayed.
+                    let replay = state.should_replay();
+                    state.playback.track_repeats = if replay { state.playback.track_repeats + 1 } else { 0 };
+                    let from_play_queue = !replay
+                        && queued.is_some()
+                        && state.playback.queue.front().copied() == queued;
+                    if from_play_queue {
+                        state.playback.queue.pop_front();
                     }
+                    (state.config.repeat, replay, state.playback.current_index, queued, from_play_queue)
+                };
+
+                // Keep the shuffle queue in step with what is actually playing now.
+                if from_play_queue {
+                    ctx.shuffle_manager.record_jump(current_index);
+                } else if !replay && ctx.shuffle_manager.is_enabled() {
+                    ctx.shuffle_manager.next_index(current_index, repeat == RepeatMode::All);
+                }
+
+                let song = queued.and_then(|idx| {
+                    let mut state = ctx.state.lock().unwrap();
+                    state.ui.selected_index = Some(idx);
+                    state.library.songs.get(idx).cloned()
+                });
+
+                // The backend is already playing it, so report `Started` rather than
+                // sending `PlayRequested`, which would reopen the file.
+                if let Some(song) = song {
+                    ctx.event_tx
+                        .send(AppEvent::Playback(PlaybackEvent::Started { song }))?;
+                }
+            }
+
+            PlaybackEvent::SeekRequested { position } => {
+                if let Some(playback) = ctx.playback.as_mut()
+                    && let Err(e) = playback.seek(*position)
+                {
+                    log::warn!("Seek to {:?} failed: {:#}", position, e);
+                    // Drop the A–B loop so the app loop doesn't retry the seek every tick.
+                    ctx.event_tx.send(AppEvent::Ui(UiEvent::LoopCleared))?;
+                    ctx.event_tx.send(AppEvent::Ui(UiEvent::ShowError {
+                        message: format!("Seek failed: {}", e),
+                    }))?;
                 }
             }
 
@@ -63,24 +175,100 @@ impl PlaybackHandler {
                 if let Some(playback) = ctx.playback.as_mut() {
                     playback.set_volume(*volume);
                 }
-                ctx.persist_state()?;
+                ctx.mark_config_dirty();
+            }
+
+            PlaybackEvent::MuteChanged { .. } => {
+                // State already updated: `effective_volume` reflects the new mute state.
+                let volume = ctx.state.lock().unwrap().config.effective_volume();
+                if let Some(playback) = ctx.playback.as_mut() {
+                    playback.set_volume(volume);
+                }
+                ctx.mark_config_dirty();
             }
 
             PlaybackEvent::Shuffle { .. } => {
                 // State already updated by AppState::apply_event before this handler runs.
-                ctx.persist_state()?;
+                ctx.mark_config_dirty();
+            }
+
+            PlaybackEvent::RepeatChanged { .. }
+            | PlaybackEvent::RepeatCountChanged { .. }
+            | PlaybackEvent::RadioChanged { .. } => {
+                // State already updated by AppState::apply_event before this handler runs.
+                ctx.mark_config_dirty();
+            }
+
+            PlaybackEvent::Enqueued { .. }
+            | PlaybackEvent::QueueCleared
+            | PlaybackEvent::RecentPlaysCleared
+            | PlaybackEvent::TrackSkipped { .. } => {
+                // State already updated by AppState::apply_event before this handler runs.
+                ctx.mark_library_dirty();
+            }
+
+            PlaybackEvent::NormalizeChanged { enabled } => {
+                if let Some(playback) = ctx.playback.as_mut() {
+                    playback.set_normalization(*enabled);
+                }
+                ctx.mark_config_dirty();
+            }
+
+            PlaybackEvent::CrossfadeChanged { secs } => {
+                if let Some(playback) = ctx.playback.as_mut() {
+                    playback.set_crossfade(Duration::from_secs(u64::from(*secs)));
+                }
+                ctx.mark_config_dirty();
+            }
+
+            PlaybackEvent::EqChanged { gains } => {
+                if let Some(playback) = ctx.playback.as_mut() {
+                    playback.set_equalizer(*gains);
+                }
+                ctx.mark_config_dirty();
+            }
+
+            PlaybackEvent::Started { song } => {
+                log::info!("Started '{}'", song.path.display());
+                if let Some(scrobbler) = ctx.scrobbler.as_mut() {
+                    scrobbler.now_playing(song);
+                }
+                // AppState kept where the previous track was left off.
+                ctx.mark_library_dirty();
+                let resume_at = {
+                    let state = ctx.state.lock().unwrap();
+                    state.library.resume_positions.get(&song.path).copied().filter(|_| state.config.resume_long_tracks)
+                };
+                if let Some(position) = resume_at {
+                    ctx.event_tx.send(AppEvent::Playback(PlaybackEvent::SeekRequested { position }))?;
+                    ctx.event_tx.send(AppEvent::Ui(UiEvent::ShowMessage {
+                        message: format!("Resumed at {}", format_duration(position)),
+                    }))?;
+                }
             }
 
-            PlaybackEvent::RepeatChanged { .. } => {
+            PlaybackEvent::PlayThresholdReached => {
                 // State already updated by AppState::apply_event before this handler runs.
-                ctx.persist_state()?;
+                ctx.mark_library_dirty();
+                let song = ctx.state.lock().unwrap().playback.current_song.clone();
+                if let (Some(scrobbler), Some(song)) = (ctx.scrobbler.as_mut(), song) {
+                    scrobbler.scrobble(&song);
+                }
+            }
+
+            PlaybackEvent::Stopped => {
+                log::info!("Playback stopped by the sleep timer");
+                if let Some(playback) = ctx.playback.as_mut() {
+                    playback.stop();
+                }
+                ctx.mark_library_dirty();
             }
 
-            // All other variants (Started, Paused, Resumed, Stopped, Error) only
+            // All other variants (Paused, Resumed, SleepTimerSet) only
             // update state — already handled by AppState::apply_event.
-            PlaybackEvent::Started { .. }
-            | PlaybackEvent::Paused
-            | PlaybackEvent::Resumed => {}
+            PlaybackEvent::Paused
+            | PlaybackEvent::Resumed
+            | PlaybackEvent::SleepTimerSet { .. } => {}
         }
 
         Ok(())
@@ -1,36 +1,33 @@
-/// Parametrized tests for `HandlerContext::advance_to_next` and
-/// `HandlerContext::advance_to_prev`.
-///
-///
-/// The tests here use a lightweight harness (`Fixture`) that sets up a
-/// `HandlerContext` backed by a real `ShuffleManager`, a real in-memory
-/// `AppState`, and a `crossbeam_channel` pair so we can inspect the events
-/// that `execute_nav` emits without running the full application loop.
+//! Parametrized tests for `HandlerContext::advance_to_next` and
+//! `HandlerContext::advance_to_prev`, plus handler-level guards.
+//!
+//!
+//! The tests here use a lightweight harness (`Fixture`) that sets up a
+//! `HandlerContext` backed by a real `ShuffleManager`, a real in-memory
+//! `AppState`, and a `crossbeam_channel` pair so we can inspect the events
+//! that `execute_nav` emits without running the full application loop.
 
 use crossbeam_channel::bounded;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
 use crate::application::state::AppState;
-use crate::core::events::{AppEvent, PlaybackEvent};
-use crate::core::models::Song;
+use crate::core::events::{AppEvent, LibraryEvent, PlaybackEvent, UiEvent};
+use crate::core::models::{RadioMode, RepeatMode, Song};
+use crate::core::traits::PlaybackBackend;
+use crate::modules::playback::null_backend::NullPlaybackBackend;
 use crate::modules::playback::shuffle_manager::ShuffleManager;
+use crate::utils::MAX_CONSECUTIVE_PLAY_FAILURES;
 
+use super::library_handler::LibraryHandler;
+use super::playback_handler::PlaybackHandler;
+use super::ui_handler::UiHandler;
 use super::HandlerContext;
 
 // ── Helpers ───────────────────────────────────────────────────────────────────
 
 fn make_song(title: &str) -> Song {
-    Song {
-        path: PathBuf::from(format!("{}.mp3", title)),
-        title: title.to_owned(),
-        artists: Vec::new(),
-        album: None,
-        track_number: None,
-        duration: None,
-        search_key: title.to_lowercase(),
-        order: 0,
-    }
+    Song::test_fixture(title)
 }
 
 fn state_with_songs(n: usize) -> AppState {
@@ -39,12 +36,59 @@ fn state_with_songs(n: usize) -> AppState {
     s
 }
 
+/// Log records of every test in the binary, from a logger installed on first use.
+fn captured_logs() -> &'static Mutex<Vec<String>> {
+    static LOGS: std::sync::OnceLock<Mutex<Vec<String>>> = std::sync::OnceLock::new();
+
+    struct Capture;
+    impl log::Log for Capture {
+        fn enabled(&self, _metadata: &log::Metadata) -> bool {
+            true
+        }
+        fn log(&self, record: &log::Record) {
+            if let Some(logs) = LOGS.get() {
+                logs.lock().unwrap().push(format!("{} {}", record.level(), record.args()));
+            }
+        }
+        fn flush(&self) {}
+    }
+    static CAPTURE: Capture = Capture;
+
+    LOGS.get_or_init(|| {
+        let _ = log::set_logger(&CAPTURE);
+        log::set_max_level(log::LevelFilter::Info);
+        Mutex::new(Vec::new())
+    })
+}
+
+/// Backend whose `play` fails for the listed titles, like an unreadable file.
+struct BrokenFilesBackend {
+    broken: Vec<String>,
+}
+
+impl PlaybackBackend for BrokenFilesBackend {
+    fn play(&mut self, song: &Song) -> anyhow::Result<()> {
+        if self.broken.contains(&song.title) {
+            anyhow::bail!("Failed to decode audio file");
+        }
+        Ok(())
+    }
+    fn stop(&mut self) {}
+    fn pause(&mut self) {}
+    fn resume(&mut self) {}
+    fn is_playing(&self) -> bool { false }
+    fn is_paused(&self) -> bool { false }
+    fn has_finished(&self) -> bool { false }
+    fn set_volume(&mut self, _volume: f32) {}
+}
+
 struct Fixture {
     state: Arc<Mutex<AppState>>,
     tx: crossbeam_channel::Sender<AppEvent>,
     rx: crossbeam_channel::Receiver<AppEvent>,
     playback: Option<Box<dyn crate::core::traits::PlaybackBackend>>,
-    storage: Option<Box<dyn crate::core::traits::StorageBackend>>,
+    scrobbler: Option<Box<dyn crate::core::traits::ScrobbleBackend>>,
+    dirty: super::DirtyState,
     shuffle: ShuffleManager,
 }
 
@@ -56,7 +100,8 @@ impl Fixture {
             tx,
             rx,
             playback: None,
-            storage: None,
+            scrobbler: None,
+            dirty: Default::default(),
             shuffle: ShuffleManager::new(),
         }
     }
@@ -66,7 +111,8 @@ impl Fixture {
             state: &self.state,
             event_tx: &self.tx,
             playback: &mut self.playback,
-            storage: &self.storage,
+            scrobbler: &mut self.scrobbler,
+            dirty: &mut self.dirty,
             shuffle_manager: &mut self.shuffle,
         }
     }
@@ -376,6 +422,58 @@ fn advance_to_next_shuffle_with_loop_continues_after_queue_exhausted() {
     assert!(titles.contains(&plays[0]), "reshuffled song must be in library");
 }
 
+// advance_to_next — radio
+
+/// Songs 0-5 alternate between artists A and B; song 6 has no artist.
+fn radio_fixture(mode: RadioMode) -> Fixture {
+    let fix = Fixture::new(7);
+    {
+        let mut state = fix.state.lock().unwrap();
+        let songs = Arc::make_mut(&mut state.library.songs);
+        for (i, song) in songs.iter_mut().enumerate().take(6) {
+            song.artists = vec![if i % 2 == 0 { "A" } else { "B" }.to_string()];
+        }
+        state.config.radio = mode;
+    }
+    fix
+}
+
+#[test]
+fn radio_same_artist_plays_only_that_artist_then_falls_back() {
+    let mut fix = radio_fixture(RadioMode::SameArtist);
+
+    let mut current = 0;
+    let mut played = Vec::new();
+    for _ in 0..2 {
+        fix.ctx().advance_to_next(Some(current), 7, false).unwrap();
+        current = fix.selected_index().unwrap();
+        played.push(current);
+    }
+    played.sort();
+    assert_eq!(played, [2, 4], "every other song by artist A, none twice");
+
+    // Songs 0, 2 and 4 have all played: back to library order.
+    fix.ctx().advance_to_next(Some(current), 7, false).unwrap();
+    assert_eq!(fix.selected_index(), Some(current + 1));
+}
+
+#[test]
+fn radio_never_picks_unrelated_songs() {
+    for _ in 0..20 {
+        let mut fix = radio_fixture(RadioMode::SameArtist);
+        fix.ctx().advance_to_next(Some(1), 7, false).unwrap();
+        assert!([3, 5].contains(&fix.selected_index().unwrap()));
+
+        // A song without an artist seeds no pool.
+        fix.ctx().advance_to_next(Some(6), 7, true).unwrap();
+        assert_eq!(fix.selected_index(), Some(0));
+    }
+
+    let mut fix = radio_fixture(RadioMode::Off);
+    fix.ctx().advance_to_next(Some(0), 7, false).unwrap();
+    assert_eq!(fix.selected_index(), Some(1), "radio off keeps library order");
+}
+
 // advance_to_prev — shuffle ON
 
 #[test]
@@ -418,6 +516,23 @@ fn advance_to_prev_shuffle_walks_back_through_history() {
     assert!(titles.contains(&back_plays[0]), "prev song must be in library");
 }
 
+#[test]
+fn advance_to_prev_shuffle_returns_the_song_that_actually_played() {
+    // The playlist starts at Song 0 while the shuffle queue may begin elsewhere.
+    let library_size = 6;
+    let mut fix = Fixture::new(library_size);
+    fix.shuffle.set_enabled(true);
+    fix.shuffle.initialize(library_size, None);
+
+    fix.ctx().advance_to_next(Some(0), library_size, false).unwrap();
+    fix.drain_play_requests();
+    let selected_index = fix.selected_index();
+
+    fix.ctx().advance_to_prev(selected_index, library_size, false).unwrap();
+
+    assert_eq!(fix.drain_play_requests(), ["Song 0"]);
+}
+
 #[test]
 fn advance_to_prev_shuffle_at_start_of_queue_emits_restart() {
     // When shuffle is on and queue_position == 0, previous_index returns None → Restart.
@@ -434,6 +549,75 @@ fn advance_to_prev_shuffle_at_start_of_queue_emits_restart() {
     assert_eq!(plays[0], "Song 2", "Restart must replay the current song");
 }
 
+// play queue
+
+#[test]
+fn advance_to_next_play_queue_takes_priority_over_shuffle() {
+    let library_size = 6;
+    let mut fix = Fixture::new(library_size);
+    fix.shuffle.set_enabled(true);
+    fix.shuffle.initialize(library_size, Some(0));
+    fix.state.lock().unwrap().playback.queue.extend([4, 2]);
+
+    fix.ctx().advance_to_next(Some(0), library_size, false).unwrap();
+    fix.ctx().advance_to_next(Some(4), library_size, false).unwrap();
+
+    assert_eq!(fix.drain_play_requests(), ["Song 4", "Song 2"]);
+    assert_eq!(fix.selected_index(), Some(2));
+    assert!(fix.state.lock().unwrap().playback.queue.is_empty());
+    assert_eq!(fix.shuffle.remaining_in_pass(), library_size - 1, "shuffle order must not advance");
+}
+
+#[test]
+fn advance_to_next_falls_back_to_sequential_once_queue_is_empty() {
+    let mut fix = Fixture::new(5);
+    fix.state.lock().unwrap().playback.queue.push_back(3);
+
+    fix.ctx().advance_to_next(Some(0), 5, false).unwrap();
+    fix.ctx().advance_to_next(Some(3), 5, false).unwrap();
+
+    assert_eq!(fix.drain_play_requests(), ["Song 3", "Song 4"]);
+}
+
+#[test]
+fn advance_to_next_skips_queued_indices_outside_the_library() {
+    let mut fix = Fixture::new(3);
+    fix.state.lock().unwrap().playback.queue.extend([9, 1]);
+
+    fix.ctx().advance_to_next(Some(0), 3, false).unwrap();
+
+    assert_eq!(fix.drain_play_requests(), ["Song 1"]);
+}
+
+// peek_next (gapless preload)
+
+#[test]
+fn peek_next_sequential_follows_repeat_mode() {
+    let mut fix = Fixture::new(3);
+    let ctx = fix.ctx();
+
+    assert_eq!(ctx.peek_next(Some(1), 3, RepeatMode::Off), Some(2));
+    assert_eq!(ctx.peek_next(Some(2), 3, RepeatMode::Off), None, "end of playlist");
+    assert_eq!(ctx.peek_next(Some(2), 3, RepeatMode::All), Some(0), "wraps when looping");
+    assert_eq!(ctx.peek_next(Some(1), 3, RepeatMode::One), Some(1), "repeat-one requeues itself");
+    assert_eq!(ctx.peek_next(None, 3, RepeatMode::All), None);
+}
+
+#[test]
+fn peek_next_shuffle_matches_advance_and_does_not_consume() {
+    let library_size = 5;
+    let mut fix = Fixture::new(library_size);
+    fix.shuffle.set_enabled(true);
+    fix.shuffle.initialize(library_size, Some(0));
+
+    let peeked = fix.ctx().peek_next(Some(0), library_size, RepeatMode::Off);
+    assert_eq!(peeked, fix.ctx().peek_next(Some(0), library_size, RepeatMode::Off));
+
+    fix.ctx().advance_to_next(Some(0), library_size, false).unwrap();
+    assert_eq!(fix.selected_index(), peeked, "advance must land on the peeked song");
+    assert_eq!(fix.drain_play_requests().len(), 1);
+}
+
 // empty library
 
 #[test]
@@ -448,4 +632,328 @@ fn advance_to_prev_empty_library_emits_nothing() {
     let mut fix = Fixture::new(0);
     fix.ctx().advance_to_prev(None, 0, true).unwrap();
     assert!(fix.drain_play_requests().is_empty());
-}
\ No newline at end of file
+}
+
+// LibraryHandler - scans
+
+#[test]
+fn scan_request_is_ignored_while_a_scan_is_running() {
+    let mut fx = Fixture::new(0);
+    let handler = LibraryHandler::new();
+    let request = LibraryEvent::ScanRequested { roots: vec![PathBuf::from("/nonexistent/hextune-test")] };
+
+    handler.handle(&request, &mut fx.ctx()).unwrap();
+    handler.handle(&request, &mut fx.ctx()).unwrap();
+
+    let started = fx
+        .rx
+        .try_iter()
+        .filter(|e| matches!(e, AppEvent::Library(LibraryEvent::ScanStarted { .. })))
+        .count();
+    assert_eq!(started, 1, "the second request arrived before the first scan finished");
+    assert!(fx.state.lock().unwrap().library.is_scanning);
+}
+
+#[test]
+fn scan_request_without_roots_reports_an_error_instead_of_scanning() {
+    let mut fx = Fixture::new(3);
+    let handler = LibraryHandler::new();
+
+    handler.handle(&LibraryEvent::ScanRequested { roots: Vec::new() }, &mut fx.ctx()).unwrap();
+
+    let events: Vec<_> = fx.rx.try_iter().collect();
+    assert!(matches!(&events[..], [AppEvent::Ui(UiEvent::ShowError { message })] if message.contains("Settings")));
+    let state = fx.state.lock().unwrap();
+    assert!(!state.library.is_scanning);
+    assert_eq!(state.library.songs.len(), 3, "the library is left alone");
+}
+
+// PlaybackHandler - play failures
+
+/// Feed `event` through state and the playback handler like the app loop does,
+/// then keep going with every playback event it emits. Returns the UI events.
+fn run_playback(fx: &mut Fixture, event: PlaybackEvent) -> Vec<AppEvent> {
+    let handler = PlaybackHandler;
+    let mut pending = vec![event];
+    let mut ui = Vec::new();
+
+    while let Some(event) = pending.pop() {
+        fx.state.lock().unwrap().apply_event(&AppEvent::Playback(event.clone()));
+        handler.handle(&event, &mut fx.ctx()).unwrap();
+
+        for emitted in fx.rx.try_iter() {
+            match emitted {
+                AppEvent::Playback(next) => pending.push(next),
+                other => ui.push(other),
+            }
+        }
+    }
+    ui
+}
+
+#[test]
+fn broken_song_is_skipped_and_the_next_one_plays() {
+    let mut fx = Fixture::new(4);
+    fx.playback = Some(Box::new(BrokenFilesBackend { broken: vec!["Song 1".to_owned()] }));
+    fx.state.lock().unwrap().ui.selected_index = Some(1);
+
+    let song = fx.state.lock().unwrap().library.songs[1].clone();
+    run_playback(&mut fx, PlaybackEvent::PlayRequested { song });
+
+    let state = fx.state.lock().unwrap();
+    assert_eq!(state.playback.current_song.as_ref().map(|s| s.title.as_str()), Some("Song 2"));
+    assert_eq!(state.playback.consecutive_failures, 0);
+    let error = state.ui.error_message.as_deref().unwrap_or_default();
+    assert!(error.contains("Song 1"), "the skipped track is named: {error}");
+}
+
+#[test]
+fn a_song_that_fails_to_play_is_logged() {
+    let logs = captured_logs();
+    let mut fx = Fixture::new(3);
+    fx.playback = Some(Box::new(BrokenFilesBackend { broken: vec!["Song 2".to_owned()] }));
+    fx.state.lock().unwrap().ui.selected_index = Some(2);
+
+    let song = fx.state.lock().unwrap().library.songs[2].clone();
+    run_playback(&mut fx, PlaybackEvent::PlayRequested { song });
+
+    let logs = logs.lock().unwrap();
+    assert!(
+        logs.iter().any(|record| record.starts_with("WARN Could not play 'Song 2.mp3'")),
+        "no warning among {logs:?}"
+    );
+}
+
+#[test]
+fn skipping_stops_after_too_many_failures_in_a_row() {
+    let mut fx = Fixture::new(10);
+    fx.playback = Some(Box::new(BrokenFilesBackend {
+        broken: (0..10).map(|i| format!("Song {i}")).collect(),
+    }));
+    fx.state.lock().unwrap().config.repeat = RepeatMode::All;
+    fx.state.lock().unwrap().ui.selected_index = Some(0);
+
+    let song = fx.state.lock().unwrap().library.songs[0].clone();
+    let ui = run_playback(&mut fx, PlaybackEvent::PlayRequested { song });
+
+    let state = fx.state.lock().unwrap();
+    assert_eq!(
+        state.ui.selected_index,
+        Some(MAX_CONSECUTIVE_PLAY_FAILURES as usize - 1),
+        "one attempt per allowed failure"
+    );
+    assert!(state.playback.current_song.is_none());
+    assert!(ui.iter().any(|e| matches!(e, AppEvent::Ui(UiEvent::ShowError { .. }))));
+}
+
+#[test]
+fn repeat_count_replays_each_track_before_advancing() {
+    let mut fx = Fixture::new(3);
+    fx.playback = Some(Box::new(BrokenFilesBackend { broken: Vec::new() }));
+    fx.state.lock().unwrap().config.repeat_count = 2;
+    fx.state.lock().unwrap().ui.selected_index = Some(0);
+    let song = fx.state.lock().unwrap().library.songs[0].clone();
+    run_playback(&mut fx, PlaybackEvent::PlayRequested { song });
+
+    let current = |fx: &Fixture| fx.state.lock().unwrap().playback.current_index;
+
+    run_playback(&mut fx, PlaybackEvent::TrackFinished);
+    assert_eq!(current(&fx), Some(0), "second play of the first track");
+
+    run_playback(&mut fx, PlaybackEvent::TrackFinished);
+    assert_eq!(current(&fx), Some(1));
+    assert_eq!(fx.state.lock().unwrap().playback.track_repeats, 0);
+}
+
+#[test]
+fn restart_seeks_to_the_start_or_replays_when_the_backend_cannot_seek() {
+    let restart = |fx: &mut Fixture| UiHandler.handle(&UiEvent::RestartTrackRequested, &mut fx.ctx()).unwrap();
+
+    let mut fx = Fixture::new(4);
+    fx.playback = Some(Box::new(NullPlaybackBackend::new()));
+    restart(&mut fx);
+    assert!(fx.rx.try_recv().is_err(), "nothing is playing");
+
+    for seekable in [true, false] {
+        let mut fx = Fixture::new(4);
+        fx.playback = if seekable {
+            Some(Box::new(NullPlaybackBackend::new()))
+        } else {
+            Some(Box::new(BrokenFilesBackend { broken: Vec::new() }))
+        };
+        fx.state.lock().unwrap().ui.selected_index = Some(1);
+        let song = fx.state.lock().unwrap().library.songs[1].clone();
+        run_playback(&mut fx, PlaybackEvent::PlayRequested { song });
+        fx.state.lock().unwrap().playback.current_elapsed = std::time::Duration::from_secs(30);
+        fx.state.lock().unwrap().ui.selected_index = Some(3);
+
+        restart(&mut fx);
+
+        if seekable {
+            assert_eq!(fx.state.lock().unwrap().playback.current_elapsed, std::time::Duration::ZERO);
+            assert!(matches!(fx.rx.try_recv(), Ok(AppEvent::Ui(UiEvent::ShowMessage { .. }))));
+        } else {
+            assert_eq!(fx.drain_play_requests(), vec!["Song 1"]);
+            assert_eq!(fx.selected_index(), Some(1), "the playing song, not the selection");
+        }
+    }
+}
+
+#[test]
+fn pausing_right_after_the_first_play_starts_paused() {
+    let mut fx = Fixture::new(2);
+    fx.playback = Some(Box::new(NullPlaybackBackend::new()));
+    let song = fx.state.lock().unwrap().library.songs[0].clone();
+
+    // The order `playlist --paused` sends them in, run first in, first out like the app loop.
+    let mut pending: std::collections::VecDeque<AppEvent> = [
+        AppEvent::Ui(UiEvent::SelectionChanged { index: 0 }),
+        AppEvent::Playback(PlaybackEvent::PlayRequested { song }),
+        AppEvent::Ui(UiEvent::PauseRequested),
+    ]
+    .into();
+    while let Some(event) = pending.pop_front() {
+        fx.state.lock().unwrap().apply_event(&event);
+        match &event {
+            AppEvent::Playback(e) => PlaybackHandler.handle(e, &mut fx.ctx()).unwrap(),
+            AppEvent::Ui(e) => UiHandler.handle(e, &mut fx.ctx()).unwrap(),
+            _ => {}
+        }
+        pending.extend(fx.rx.try_iter());
+    }
+
+    assert!(fx.playback.as_ref().unwrap().is_paused());
+    let state = fx.state.lock().unwrap();
+    assert!(state.playback.is_playing && state.playback.is_paused);
+    assert_eq!(state.ui.status_message, "Paused");
+}
+
+#[test]
+fn a_selected_last_song_wraps_to_the_first_with_repeat_all() {
+    let mut fx = Fixture::new(3);
+    fx.playback = Some(Box::new(NullPlaybackBackend::new()));
+    fx.state.lock().unwrap().config.repeat = RepeatMode::All;
+    fx.state.lock().unwrap().ui.selected_index = Some(2);
+    let song = fx.state.lock().unwrap().library.songs[2].clone();
+    run_playback(&mut fx, PlaybackEvent::PlayRequested { song });
+
+    run_playback(&mut fx, PlaybackEvent::TrackFinished);
+
+    let state = fx.state.lock().unwrap();
+    assert_eq!(state.playback.current_index, Some(0));
+    assert_eq!(state.playback.current_song.as_ref().map(|s| s.title.as_str()), Some("Song 0"));
+}
+
+#[test]
+fn relative_seeks_clamp_at_the_start_and_end_past_the_track() {
+    use super::ui_handler::seek_target;
+    use std::time::Duration;
+    let secs = Duration::from_secs;
+
+    assert_eq!(seek_target(secs(30), Some(secs(200)), 10), Some(secs(40)));
+    assert_eq!(seek_target(secs(30), Some(secs(200)), -60), Some(secs(0)), "clamped at the start");
+    assert_eq!(seek_target(secs(190), Some(secs(200)), 10), None, "the end means the next track");
+    assert_eq!(seek_target(secs(150), Some(secs(200)), 60), None);
+    assert_eq!(seek_target(secs(500), None, 60), Some(secs(560)), "no end to clamp to");
+}
+
+#[test]
+fn the_next_bookmark_wraps_back_to_the_first() {
+    use super::ui_handler::next_bookmark;
+    use std::time::Duration;
+    let secs = Duration::from_secs;
+    let marks = [secs(60), secs(300), secs(900)];
+
+    assert_eq!(next_bookmark(&marks, secs(0)), Some(0));
+    assert_eq!(next_bookmark(&marks, Duration::from_millis(60_400)), Some(1), "steps past the one just reached");
+    assert_eq!(next_bookmark(&marks, secs(1000)), Some(0));
+    assert_eq!(next_bookmark(&[], secs(10)), None);
+}
+
+#[test]
+fn a_long_track_resumes_where_it_was_left_off() {
+    use std::time::Duration;
+    let mut fx = Fixture::new(2);
+    fx.playback = Some(Box::new(NullPlaybackBackend::new()));
+    let book = Song { duration: Some(Duration::from_secs(3600)), ..make_song("Song 0") };
+    let other = make_song("Song 1");
+
+    run_playback(&mut fx, PlaybackEvent::PlayRequested { song: book.clone() });
+    run_playback(&mut fx, PlaybackEvent::SeekRequested { position: Duration::from_secs(1234) });
+    run_playback(&mut fx, PlaybackEvent::PlayRequested { song: other });
+    assert_eq!(fx.state.lock().unwrap().library.resume_positions.get(&book.path), Some(&Duration::from_secs(1234)));
+
+    let ui = run_playback(&mut fx, PlaybackEvent::PlayRequested { song: book });
+    assert_eq!(fx.state.lock().unwrap().playback.current_elapsed, Duration::from_secs(1234));
+    assert!(ui.iter().any(|event| matches!(
+        event,
+        AppEvent::Ui(UiEvent::ShowMessage { message }) if message == "Resumed at 20:34"
+    )));
+}
+
+#[test]
+fn seeking_past_the_end_moves_to_the_next_track() {
+    let mut fx = Fixture::new(3);
+    fx.playback = Some(Box::new(NullPlaybackBackend::new()));
+    fx.state.lock().unwrap().ui.selected_index = Some(0);
+    let song = Song { duration: Some(std::time::Duration::from_secs(60)), ..make_song("Song 0") };
+    run_playback(&mut fx, PlaybackEvent::PlayRequested { song });
+
+    UiHandler.handle(&UiEvent::SeekByRequested { delta_secs: 10 }, &mut fx.ctx()).unwrap();
+    assert!(matches!(
+        fx.rx.try_recv(),
+        Ok(AppEvent::Playback(PlaybackEvent::SeekRequested { position })) if position.as_secs() == 10
+    ));
+    let _ = fx.rx.try_iter().count();
+
+    UiHandler.handle(&UiEvent::SeekByRequested { delta_secs: 60 }, &mut fx.ctx()).unwrap();
+    assert!(matches!(fx.rx.try_recv(), Ok(AppEvent::Ui(UiEvent::NextTrackRequested))));
+}
+
+#[test]
+fn moving_on_before_a_track_counts_as_a_play_records_a_skip() {
+    let skip = |fx: &mut Fixture| {
+        UiHandler.handle(&UiEvent::NextTrackRequested, &mut fx.ctx()).unwrap();
+        let skipped: Vec<PlaybackEvent> = fx.rx.try_iter()
+            .filter_map(|e| match e {
+                AppEvent::Playback(event @ PlaybackEvent::TrackSkipped { .. }) => Some(event),
+                _ => None,
+            })
+            .collect();
+        for event in &skipped {
+            fx.state.lock().unwrap().apply_event(&AppEvent::Playback(event.clone()));
+        }
+    };
+
+    let mut fx = Fixture::new(3);
+    fx.playback = Some(Box::new(NullPlaybackBackend::new()));
+    fx.state.lock().unwrap().ui.selected_index = Some(0);
+    run_playback(&mut fx, PlaybackEvent::PlayRequested { song: make_song("Song 0") });
+
+    skip(&mut fx);
+    assert_eq!(fx.state.lock().unwrap().history.skip_count(&PathBuf::from("Song 0.mp3")), 1);
+
+    fx.state.lock().unwrap().playback.play_counted = true;
+    skip(&mut fx);
+    assert_eq!(fx.state.lock().unwrap().history.skip_count(&PathBuf::from("Song 0.mp3")), 1, "it already counted as a play");
+}
+
+#[test]
+fn shuffle_passes_over_songs_skipped_too_often() {
+    let library_size = 5;
+    let mut fix = Fixture::new(library_size);
+    {
+        let mut state = fix.state.lock().unwrap();
+        state.config.shuffle_skip_limit = 1;
+        for i in [1, 2, 4] {
+            let path = state.library.songs[i].path.clone();
+            state.history.record_skip(&path);
+            state.history.record_skip(&path);
+        }
+    }
+    fix.shuffle.set_enabled(true);
+    fix.shuffle.initialize(library_size, Some(0));
+
+    fix.ctx().advance_to_next(Some(0), library_size, false).unwrap();
+    assert_eq!(fix.drain_play_requests(), ["Song 3"]);
+}
@@ -1,34 +1,45 @@
-use crate::application::handlers::HandlerContext;
+use crate::application::handlers::{HandlerContext, NavTarget};
+use crate::application::state::AppState;
 use crate::core::events::{AppEvent, LibraryEvent, PlaybackEvent, UiEvent};
 use crate::core::models::RepeatMode;
-use crate::utils::volume_percent_to_amplitude;
+use crate::modules::ui::progress_formatter::format_duration;
+use crate::utils::{volume_percent_to_amplitude, CROSSFADE_MAX_SECS, SLEEP_TIMER_PRESETS_MINS};
 use anyhow::Result;
 use crate::modules::library::sorter::SortField;
+use crate::modules::playback::equalizer::EqGains;
+use std::path::PathBuf;
+use std::time::Duration;
 
 /// Handles all [`UiEvent`] variants that require side effects.
 ///
 /// Responsible for:
-/// - Translating user intent into domain events (play, next, prev, volume, shuffle, repeat).
+/// - Translating user intent into domain events (play, next, prev, volume, mute, shuffle, repeat, crossfade, equalizer, sleep timer, theme).
 /// - Validating input before acting (e.g. path must be a valid directory).
 /// - Persisting config changes to storage.
 ///
 /// Pure state updates (ShowMessage, ShowError, SelectionChanged, SearchToggled,
-/// SearchQueryChanged) are already handled by `AppState::apply_event`.
+/// SearchQueryChanged, A–B loop marks) are already handled by `AppState::apply_event`.
 pub struct UiHandler;
 
 impl UiHandler {
     pub fn handle(&self, event: &UiEvent, ctx: &mut HandlerContext) -> Result<()> {
         match event {
             UiEvent::PlaySelectedRequested => {
-                let song = {
+                let (song, current_index) = {
                     let state = ctx.state.lock().unwrap();
-                    state.ui.selected_index
-                        .and_then(|i| state.library.songs.get(i).cloned())
+                    let song = state.ui.selected_index
+                        .and_then(|i| state.library.songs.get(i).cloned());
+                    (song, state.playback.current_index)
                 };
                 if let Some(song) = song {
+                    ctx.shuffle_manager.record_jump(current_index);
                     ctx.event_tx
                         .send(AppEvent::Playback(PlaybackEvent::PlayRequested { song }))?;
                 }
+                // Playing a search result saved the query in the search history.
+                if ctx.state.lock().unwrap().ui.search_active {
+                    ctx.mark_config_dirty();
+                }
             }
 
             UiEvent::TogglePauseRequested => {
@@ -45,17 +56,41 @@ impl UiHandler {
                 }
             }
 
+            UiEvent::PauseRequested => {
+                if let Some(playback) = ctx.playback.as_mut()
+                    && playback.is_playing()
+                    && !playback.is_paused()
+                {
+                    playback.pause();
+                    ctx.event_tx.send(AppEvent::Playback(PlaybackEvent::Paused))?;
+                }
+            }
+
+            UiEvent::ResumeRequested => {
+                if let Some(playback) = ctx.playback.as_mut()
+                    && playback.is_paused()
+                {
+                    playback.resume();
+                    ctx.event_tx.send(AppEvent::Playback(PlaybackEvent::Resumed))?;
+                }
+            }
+
             UiEvent::NextTrackRequested => {
                 // RepeatMode::One does not loop on manual nav — user explicitly wants to move.
-                let (current_index, library_len, loop_playlist) = {
+                let (current_index, library_len, loop_playlist, skipped) = {
                     let state = ctx.state.lock().unwrap();
                     (
                         state.ui.selected_index,
                         state.library.songs.len(),
                         state.config.repeat == RepeatMode::All,
+                        skipped_path(&state),
                     )
                 };
 
+                if let Some(path) = skipped {
+                    ctx.event_tx.send(AppEvent::Playback(PlaybackEvent::TrackSkipped { path }))?;
+                }
+
                 // Re-initialize shuffle queue if this pass ran dry.
                 if ctx.shuffle_manager.is_enabled() && ctx.shuffle_manager.remaining_in_pass() == 0 {
                     ctx.shuffle_manager.initialize(library_len, current_index);
@@ -78,6 +113,42 @@ impl UiHandler {
                 ctx.advance_to_prev(current_index, library_len, loop_playlist)?;
             }
 
+            UiEvent::AlbumSkipRequested { forward } => {
+                let (current_index, loop_playlist, skipped) = {
+                    let state = ctx.state.lock().unwrap();
+                    (
+                        state.ui.selected_index,
+                        state.config.repeat == RepeatMode::All,
+                        skipped_path(&state).filter(|_| *forward),
+                    )
+                };
+
+                if let Some(path) = skipped {
+                    ctx.event_tx.send(AppEvent::Playback(PlaybackEvent::TrackSkipped { path }))?;
+                }
+                ctx.advance_to_album(current_index, *forward, loop_playlist)?;
+            }
+
+            UiEvent::RestartTrackRequested => {
+                let (playing, current_index) = {
+                    let state = ctx.state.lock().unwrap();
+                    (state.playback.current_song.is_some(), state.playback.current_index)
+                };
+                if !playing {
+                    return Ok(());
+                }
+
+                // Seeking keeps the decoder open; a backend that cannot seek replays the file.
+                if ctx.playback.as_mut().is_some_and(|playback| playback.seek(Duration::ZERO).is_ok()) {
+                    ctx.state.lock().unwrap().playback.current_elapsed = Duration::ZERO;
+                    ctx.event_tx.send(AppEvent::Ui(UiEvent::ShowMessage {
+                        message: "Restarted from the beginning".to_string(),
+                    }))?;
+                } else {
+                    ctx.execute_nav(NavTarget::Restart, current_index)?;
+                }
+            }
+
             UiEvent::VolumeChangeRequested { volume } => {
                 let volume_f32 = volume_percent_to_amplitude(*volume);
                 ctx.event_tx
@@ -89,13 +160,24 @@ impl UiHandler {
                 }))?;
             }
 
-            UiEvent::PathChangeRequested { path } => {
+            UiEvent::MuteToggled => {
+                let muted = ctx.state.lock().unwrap().config.is_muted();
+                ctx.event_tx
+                    .send(AppEvent::Playback(PlaybackEvent::MuteChanged { muted: !muted }))?;
+            }
+
+            UiEvent::PathAddRequested { path } => {
                 match path.canonicalize() {
                     Ok(canonical) if canonical.is_dir() => {
-                        ctx.state.lock().unwrap().config.root_path = Some(canonical);
-                        ctx.persist_state()?;
+                        let added = ctx.state.lock().unwrap().config.add_root(canonical);
+                        let message = if added {
+                            ctx.mark_config_dirty();
+                            "Music path added. Run refresh to scan."
+                        } else {
+                            "That music path is already in the library."
+                        };
                         ctx.event_tx.send(AppEvent::Ui(UiEvent::ShowMessage {
-                            message: "Music path updated. Run refresh to scan.".to_string(),
+                            message: message.to_string(),
                         }))?;
                     }
                     Ok(_) => {
@@ -112,8 +194,19 @@ impl UiHandler {
 
             }
 
+            UiEvent::PathRemoveRequested { path } => {
+                if ctx.state.lock().unwrap().config.remove_root(path) {
+                    ctx.mark_config_dirty();
+                    ctx.event_tx.send(AppEvent::Ui(UiEvent::ShowMessage {
+                        message: "Music path removed. Run refresh to drop its songs.".to_string(),
+                    }))?;
+                }
+            }
+
             UiEvent::SearchToggled { active } => {
                 if !active {
+                    // Leaving search saved the query in the search history.
+                    ctx.mark_config_dirty();
                     ctx.event_tx.send(AppEvent::Ui(UiEvent::ShowMessage {
                         message: "Search cleared".to_string(),
                     }))?;
@@ -141,19 +234,107 @@ impl UiHandler {
                     .send(AppEvent::Playback(PlaybackEvent::RepeatChanged { mode: *mode }))?;
             }
 
-            UiEvent::RefreshRequested => {
-                let root_path = ctx.state.lock().unwrap().config.root_path.clone();
-                match root_path {
-                    Some(path) => {
-                        ctx.event_tx
-                            .send(AppEvent::Library(LibraryEvent::ScanRequested { path }))?;
-                    }
-                    None => {
-                        ctx.event_tx.send(AppEvent::Ui(UiEvent::ShowError {
-                            message: "No music path set. Configure it in Settings (s).".to_string(),
-                        }))?;
-                    }
+            UiEvent::EnqueueSelectedRequested => {
+                let selected = ctx.state.lock().unwrap().ui.selected_index;
+                if let Some(index) = selected {
+                    ctx.event_tx
+                        .send(AppEvent::Playback(PlaybackEvent::Enqueued { index }))?;
+                }
+            }
+
+            UiEvent::EnqueueAllRequested { indices } => {
+                if indices.is_empty() {
+                    ctx.event_tx.send(AppEvent::Ui(UiEvent::ShowMessage {
+                        message: "No matches to queue".to_string(),
+                    }))?;
+                    return Ok(());
+                }
+                for &index in indices {
+                    ctx.event_tx
+                        .send(AppEvent::Playback(PlaybackEvent::Enqueued { index }))?;
                 }
+                ctx.event_tx.send(AppEvent::Ui(UiEvent::ShowMessage {
+                    message: format!(
+                        "Queued {} matching song{}",
+                        indices.len(),
+                        if indices.len() == 1 { "" } else { "s" }
+                    ),
+                }))?;
+            }
+
+            UiEvent::RemoveSelectedRequested => {
+                let selected = ctx.state.lock().unwrap().ui.selected_index;
+                if let Some(index) = selected {
+                    ctx.event_tx
+                        .send(AppEvent::Library(LibraryEvent::SongRemoved { index }))?;
+                }
+            }
+
+            UiEvent::FavoriteToggleRequested => {
+                let rated = {
+                    let state = ctx.state.lock().unwrap();
+                    state.ui.selected_index.and_then(|index| {
+                        state.library.songs.get(index).map(|song| (index, song.rating, song.favorite))
+                    })
+                };
+                if let Some((index, rating, favorite)) = rated {
+                    ctx.event_tx.send(AppEvent::Library(LibraryEvent::RatingChanged {
+                        index,
+                        rating,
+                        favorite: !favorite,
+                    }))?;
+                }
+            }
+
+            UiEvent::NormalizeSet { enabled } => {
+                ctx.event_tx
+                    .send(AppEvent::Playback(PlaybackEvent::NormalizeChanged { enabled: *enabled }))?;
+            }
+
+            UiEvent::CrossfadeChangeRequested { secs } => {
+                let secs = (*secs).min(CROSSFADE_MAX_SECS);
+                ctx.event_tx
+                    .send(AppEvent::Playback(PlaybackEvent::CrossfadeChanged { secs }))?;
+            }
+
+            UiEvent::EqChangeRequested { gains } => {
+                let gains = EqGains::new(gains.bass, gains.mid, gains.treble);
+                ctx.event_tx
+                    .send(AppEvent::Playback(PlaybackEvent::EqChanged { gains }))?;
+            }
+
+            UiEvent::SleepTimerCycleRequested => {
+                let current = ctx.state.lock().unwrap().playback.sleep_timer.map(|t| t.minutes);
+                let minutes = match current {
+                    None => SLEEP_TIMER_PRESETS_MINS[0],
+                    Some(m) => SLEEP_TIMER_PRESETS_MINS.iter().copied().find(|&p| p > m).unwrap_or(0),
+                };
+                ctx.event_tx.send(AppEvent::Playback(PlaybackEvent::SleepTimerSet {
+                    minutes,
+                    after_track: false,
+                }))?;
+            }
+
+            UiEvent::ThemeChangeRequested { name } => {
+                ctx.state.lock().unwrap().config.theme = Some(name.clone());
+                ctx.mark_config_dirty();
+                ctx.event_tx.send(AppEvent::Ui(UiEvent::ShowMessage {
+                    message: format!("Theme: {}", name),
+                }))?;
+            }
+
+            UiEvent::ProgressLabelChangeRequested { label } => {
+                ctx.state.lock().unwrap().config.progress_label = *label;
+                ctx.mark_config_dirty();
+                ctx.event_tx.send(AppEvent::Ui(UiEvent::ShowMessage {
+                    message: format!("Progress label: {}", label.name()),
+                }))?;
+            }
+
+            UiEvent::RefreshRequested => {
+                let roots = ctx.state.lock().unwrap().config.root_paths.clone();
+                ctx.event_tx
+                    .send(AppEvent::Library(LibraryEvent::ScanRequested { roots }))?;
             }
 
             UiEvent::SortCycleRequested => {
@@ -162,13 +343,75 @@ impl UiHandler {
                     match state.library.active_sort {
                         None => Some(SortField::default()),     // natural → title
                         Some(SortField::Duration) => None,      // duration → natural
-                        Some(f) => Some(f.next()),     // title→artist→album→duration
+                        Some(f) => Some(f.next()),     // title→artist→album→track→duration
                     }
                 };
                 ctx.event_tx
                     .send(AppEvent::Library(LibraryEvent::SortRequested { field: next_field }))?;
             }
 
+            UiEvent::SeekRequested { position } => {
+                if ctx.state.lock().unwrap().playback.current_song.is_some() {
+                    ctx.event_tx
+                        .send(AppEvent::Playback(PlaybackEvent::SeekRequested { position: *position }))?;
+                }
+            }
+
+            UiEvent::SeekByRequested { delta_secs } => {
+                let (elapsed, duration) = {
+                    let state = ctx.state.lock().unwrap();
+                    match &state.playback.current_song {
+                        Some(song) => (state.playback.current_elapsed, song.duration),
+                        None => return Ok(()),
+                    }
+                };
+
+                match seek_target(elapsed, duration, *delta_secs) {
+                    Some(position) => {
+                        ctx.event_tx.send(AppEvent::Playback(PlaybackEvent::SeekRequested { position }))?;
+                        ctx.event_tx.send(AppEvent::Ui(UiEvent::ShowMessage {
+                            message: format!("Jumped to {}", format_duration(position)),
+                        }))?;
+                    }
+                    None => ctx.event_tx.send(AppEvent::Ui(UiEvent::NextTrackRequested))?,
+                }
+            }
+
+            UiEvent::BookmarkAddRequested => {
+                let bookmark = {
+                    let state = ctx.state.lock().unwrap();
+                    state.playback.current_song.as_ref().map(|song| (song.path.clone(), state.playback.current_elapsed))
+                };
+                match bookmark {
+                    Some((path, position)) => {
+                        ctx.event_tx.send(AppEvent::Library(LibraryEvent::BookmarkAdded { path, position }))?
+                    }
+                    None => ctx.event_tx.send(AppEvent::Ui(UiEvent::ShowMessage {
+                        message: "Nothing is playing to bookmark".to_string(),
+                    }))?,
+                }
+            }
+
+            UiEvent::NextBookmarkRequested => {
+                let (marks, elapsed) = {
+                    let state = ctx.state.lock().unwrap();
+                    match &state.playback.current_song {
+                        Some(song) => (state.library.bookmarks_for(&song.path).to_vec(), state.playback.current_elapsed),
+                        None => return Ok(()),
+                    }
+                };
+
+                let message = match next_bookmark(&marks, elapsed) {
+                    Some(at) => {
+                        let position = marks[at];
+                        ctx.event_tx.send(AppEvent::Playback(PlaybackEvent::SeekRequested { position }))?;
+                        format!("Bookmark {}/{}: {}", at + 1, marks.len(), format_duration(position))
+                    }
+                    None => "This track has no bookmarks".to_string(),
+                };
+                ctx.event_tx.send(AppEvent::Ui(UiEvent::ShowMessage { message }))?;
+            }
+
             UiEvent::QuitRequested => {
                 ctx.event_tx.send(AppEvent::Shutdown)?;
             }
@@ -176,7 +419,10 @@ impl UiHandler {
             // Pure state updates — already handled by AppState::apply_event.
             UiEvent::ShowMessage { .. }
             | UiEvent::ShowError { .. }
-            | UiEvent::SelectionChanged { .. } => {}
+            | UiEvent::SelectionChanged { .. }
+            | UiEvent::SetLoopPointA
+            | UiEvent::SetLoopPointB
+            | UiEvent::LoopCleared => {}
         }
 
         Ok(())
@@ -200,4 +446,34 @@ impl UiHandler {
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// Where jumping `delta_secs` from `elapsed` lands: never before the start, and
+/// `None` at or past the end of a track whose `duration` is known.
+pub(super) fn seek_target(elapsed: Duration, duration: Option<Duration>, delta_secs: i64) -> Option<Duration> {
+    let step = Duration::from_secs(delta_secs.unsigned_abs());
+    let target = if delta_secs < 0 { elapsed.saturating_sub(step) } else { elapsed + step };
+    match duration {
+        Some(duration) if target >= duration => None,
+        _ => Some(target),
+    }
+}
+
+/// The playing track's path, if moving on now skips it: it is still playing
+/// and has not yet counted as a play.
+fn skipped_path(state: &AppState) -> Option<PathBuf> {
+    let playback = &state.playback;
+    playback.current_song.as_ref()
+        .filter(|_| playback.is_playing && !playback.play_counted)
+        .map(|song| song.path.clone())
+}
+
+/// Index in `marks` (sorted) of the first bookmark after `elapsed`, wrapping to the first.
+pub(super) fn next_bookmark(marks: &[Duration], elapsed: Duration) -> Option<usize> {
+    if marks.is_empty() {
+        return None;
+    }
+    // A bookmark just jumped to sits at `elapsed` for a moment; step past it.
+    let from = elapsed.as_secs();
+    Some(marks.iter().position(|mark| mark.as_secs() > from).unwrap_or(0))
+}
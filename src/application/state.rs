@@ -1,10 +1,17 @@
-use crate::core::models::{RepeatMode, Song};
+use crate::core::models::{DefaultCommand, PlayRecord, RadioMode, RepeatMode, Song};
 use crate::core::events::*;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime};
 use serde::{Deserialize, Serialize};
 use crate::modules::library::sorter::SortField;
+use crate::modules::playback::equalizer::EqGains;
+use crate::modules::ui::progress_formatter::{format_duration, ProgressLabel};
+use crate::utils::{
+    amplitude_to_volume, format_roots, volume_percent_to_amplitude, DEFAULT_FPS, FPS_MAX, FPS_MIN, PLAY_THRESHOLD_PCT,
+    PLAY_THRESHOLD_SECS, RECENT_PLAYS_MAX, RESUME_MARGIN_SECS, RESUME_MIN_TRACK_SECS, SEARCH_HISTORY_MAX, VOLUME_MAX,
+};
 
 /// Complete application state (single source of truth)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,27 +25,202 @@ pub struct AppState {
     #[serde(default)]
     pub playback: PlaybackState,
 
+    #[serde(default)]
+    pub history: HistoryState,
+
     #[serde(skip)]
     pub ui: UiState,
 }
 
+/// Listening history, keyed by file path so it survives rescans and re-sorts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HistoryState {
+    /// Shared like `library.songs`, so the renderer can hold it without copying.
+    #[serde(default)]
+    pub plays: Arc<BTreeMap<PathBuf, PlayRecord>>,
+
+    /// The last `RECENT_PLAYS_MAX` plays, oldest first. Entries stay when their
+    /// song leaves the library.
+    #[serde(default)]
+    pub recent: Arc<VecDeque<(PathBuf, SystemTime)>>,
+
+    /// How often each song was skipped before it counted as a play, keyed by path.
+    #[serde(default)]
+    pub skips: Arc<BTreeMap<PathBuf, u32>>,
+}
+
+impl HistoryState {
+    pub fn play_count(&self, path: &Path) -> u32 {
+        self.plays.get(path).map_or(0, |record| record.play_count)
+    }
+
+    pub fn skip_count(&self, path: &Path) -> u32 {
+        self.skips.get(path).copied().unwrap_or(0)
+    }
+
+    pub fn record_skip(&mut self, path: &Path) {
+        *Arc::make_mut(&mut self.skips).entry(path.to_path_buf()).or_default() += 1;
+    }
+
+    pub fn record_play(&mut self, path: &Path, at: SystemTime) {
+        Arc::make_mut(&mut self.plays)
+            .entry(path.to_path_buf())
+            .and_modify(|record| {
+                record.play_count += 1;
+                record.last_played = at;
+            })
+            .or_insert(PlayRecord { play_count: 1, last_played: at });
+
+        let recent = Arc::make_mut(&mut self.recent);
+        recent.push_back((path.to_path_buf(), at));
+        while recent.len() > RECENT_PLAYS_MAX {
+            recent.pop_front();
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConfigState {
+    /// Directories the library is scanned from, canonicalized when added.
     #[serde(default)]
-    pub root_path: Option<PathBuf>,
+    pub root_paths: Vec<PathBuf>,
 
-    #[serde(default = "default_volume")]
-    pub volume: f32,
+    /// Single directory written by versions before `root_paths`; `migrate_legacy_fields` moves it over.
+    #[serde(default, rename = "root_path", skip_serializing)]
+    legacy_root_path: Option<PathBuf>,
+
+    /// User volume (0-100); the playback amplitude is derived from it, so the
+    /// shown value never drifts through float conversions.
+    #[serde(default = "default_volume_percent")]
+    pub volume_percent: u8,
+
+    /// Amplitude written by versions before `volume_percent`; `migrate_legacy_fields` converts it.
+    #[serde(default, rename = "volume", skip_serializing)]
+    legacy_volume: Option<f32>,
+
+    /// Amplitude saved when muting; `Some` while muted.
+    #[serde(default)]
+    pub muted_volume: Option<f32>,
 
     #[serde(default)]
     pub shuffle: bool,
 
     #[serde(default)]
     pub repeat: RepeatMode,
+
+    /// Continue with songs like the one that ended instead of library order.
+    #[serde(default)]
+    pub radio: RadioMode,
+
+    /// Apply per-track ReplayGain so songs play at a similar loudness.
+    #[serde(default)]
+    pub normalize_volume: bool,
+
+    /// Seconds of overlap between automatically-advanced tracks; 0 disables crossfade.
+    #[serde(default)]
+    pub crossfade_secs: u8,
+
+    /// TUI color theme name; `None` uses `theme.toml` if present, else the dark preset.
+    #[serde(default)]
+    pub theme: Option<String>,
+
+    /// Globs (relative to each root) a file must match one of to be scanned; empty means all.
+    #[serde(default)]
+    pub scan_include: Vec<String>,
+
+    /// Globs (relative to each root) of files and directories the scanner skips.
+    #[serde(default)]
+    pub scan_exclude: Vec<String>,
+
+    /// Play each track this many times before moving on (1 = once). `RepeatMode::One` overrides it.
+    #[serde(default = "default_repeat_count")]
+    pub repeat_count: u32,
+
+    /// Text inside the TUI progress bar.
+    #[serde(default)]
+    pub progress_label: ProgressLabel,
+
+    /// Audio output device name; `None` uses the system default.
+    #[serde(default)]
+    pub output_device: Option<String>,
+
+    /// Equalizer band gains; all zero bypasses the filters.
+    #[serde(default)]
+    pub eq: EqGains,
+
+    /// Scroll titles and artists that overflow the TUI now-playing box; off keeps them still.
+    #[serde(default = "default_scroll_titles")]
+    pub scroll_titles: bool,
+
+    /// Recent search queries, newest first and without repeats.
+    #[serde(default)]
+    pub search_history: Arc<Vec<String>>,
+
+    /// Redraws per second while something moves on screen; fewer save power,
+    /// more smooth the progress bar. Clamped to `FPS_MIN`-`FPS_MAX` when used.
+    #[serde(default = "default_fps")]
+    pub fps: u16,
+
+    /// Subcommand run when `hextune` is started without one.
+    #[serde(default)]
+    pub default_command: DefaultCommand,
+
+    /// Reopen tracks of `RESUME_MIN_TRACK_SECS` or more (audiobooks, podcasts)
+    /// where they were left off.
+    #[serde(default = "default_resume_long_tracks")]
+    pub resume_long_tracks: bool,
+
+    /// A play counts in the history once this percentage of the track has played...
+    #[serde(default = "default_play_count_threshold_pct")]
+    pub play_count_threshold_pct: u8,
+
+    /// ...or this many seconds, whichever comes first.
+    #[serde(default = "default_play_count_threshold_secs")]
+    pub play_count_threshold_secs: u32,
+
+    /// Shuffle leaves out songs skipped more than this many times; 0 keeps them all.
+    #[serde(default)]
+    pub shuffle_skip_limit: u32,
+
+    /// Where `browse` was left: the selected song (library index)...
+    #[serde(default)]
+    pub browse_selected: Option<usize>,
+
+    /// ...and the first song list row on screen.
+    #[serde(default)]
+    pub browse_scroll: usize,
+
+    /// Ask before quitting the TUI while a song is loaded or queued.
+    #[serde(default)]
+    pub confirm_quit: bool,
+}
+
+fn default_volume_percent() -> u8 {
+    VOLUME_MAX
+}
+
+fn default_repeat_count() -> u32 {
+    1
+}
+
+fn default_scroll_titles() -> bool {
+    true
+}
+
+fn default_fps() -> u16 {
+    DEFAULT_FPS
 }
 
-fn default_volume() -> f32 {
-    1.0
+fn default_resume_long_tracks() -> bool {
+    true
+}
+
+fn default_play_count_threshold_pct() -> u8 {
+    PLAY_THRESHOLD_PCT
+}
+
+fn default_play_count_threshold_secs() -> u32 {
+    PLAY_THRESHOLD_SECS
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -61,7 +243,70 @@ pub struct LibraryState {
     pub scan_progress: usize,
 
     #[serde(skip)]
-    pub last_scan_path: Option<PathBuf>,
+    pub last_scan_roots: Vec<PathBuf>,
+
+    /// Saved positions in long tracks, keyed by file path like the play history;
+    /// each list is in whole seconds, earliest first.
+    #[serde(default)]
+    pub bookmarks: BTreeMap<PathBuf, Vec<Duration>>,
+
+    /// Where long tracks were left off, in whole seconds; see `remember_position`.
+    #[serde(default)]
+    pub resume_positions: BTreeMap<PathBuf, Duration>,
+}
+
+impl LibraryState {
+    pub fn bookmarks_for(&self, path: &Path) -> &[Duration] {
+        self.bookmarks.get(path).map_or(&[], Vec::as_slice)
+    }
+
+    /// Bookmark `position`, cut to the second; false when it already is.
+    pub fn add_bookmark(&mut self, path: &Path, position: Duration) -> bool {
+        let position = Duration::from_secs(position.as_secs());
+        let marks = self.bookmarks.entry(path.to_path_buf()).or_default();
+        match marks.binary_search(&position) {
+            Ok(_) => false,
+            Err(at) => {
+                marks.insert(at, position);
+                true
+            }
+        }
+    }
+
+    /// False when there was no bookmark at `position`.
+    pub fn remove_bookmark(&mut self, path: &Path, position: Duration) -> bool {
+        let Some(marks) = self.bookmarks.get_mut(path) else {
+            return false;
+        };
+        let before = marks.len();
+        marks.retain(|mark| mark.as_secs() != position.as_secs());
+        let removed = marks.len() < before;
+        if marks.is_empty() {
+            self.bookmarks.remove(path);
+        }
+        removed
+    }
+
+    /// Drop the bookmarks and resume positions of files that no longer exist.
+    pub fn prune_missing_positions(&mut self) {
+        self.bookmarks.retain(|path, _| path.exists());
+        self.resume_positions.retain(|path, _| path.exists());
+    }
+
+    /// Keep where `song` was left off, if it is long enough to resume. A
+    /// position near either end clears it instead: the track was finished or
+    /// barely started.
+    pub fn remember_position(&mut self, song: &Song, elapsed: Duration) {
+        let Some(duration) = song.duration.filter(|d| d.as_secs() >= RESUME_MIN_TRACK_SECS) else {
+            return;
+        };
+        let margin = Duration::from_secs(RESUME_MARGIN_SECS);
+        if elapsed < margin || elapsed + margin >= duration {
+            self.resume_positions.remove(&song.path);
+        } else {
+            self.resume_positions.insert(song.path.clone(), Duration::from_secs(elapsed.as_secs()));
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -80,6 +325,74 @@ pub struct PlaybackState {
 
     #[serde(skip)]
     pub current_elapsed: Duration,
+
+    /// Library index handed to the backend via `queue_next`, if any.
+    #[serde(skip)]
+    pub queued_index: Option<usize>,
+
+    /// Set once `NearingEnd` fired for the current track so it is not repeated.
+    #[serde(skip)]
+    pub preload_requested: bool,
+
+    /// Library indices the user queued to play next, ahead of shuffle/sequential order.
+    #[serde(default)]
+    pub queue: VecDeque<usize>,
+
+    /// A–B loop marks within the current track; see [`PlaybackState::ab_loop`].
+    #[serde(skip)]
+    pub loop_a: Option<Duration>,
+
+    #[serde(skip)]
+    pub loop_b: Option<Duration>,
+
+    /// Set once the current track has been counted in the play history.
+    #[serde(skip)]
+    pub play_counted: bool,
+
+    /// Songs in a row that failed to play; reset when one starts.
+    #[serde(skip)]
+    pub consecutive_failures: u32,
+
+    /// Times the current track has been replayed for `repeat_count`.
+    #[serde(skip)]
+    pub track_repeats: u32,
+
+    /// Pending auto-stop, if the sleep timer is set.
+    #[serde(skip)]
+    pub sleep_timer: Option<SleepTimer>,
+
+    /// Songs radio mode already played; cleared when its pool runs out or the mode changes.
+    #[serde(skip)]
+    pub radio_played: HashSet<PathBuf>,
+}
+
+/// When playback should stop on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SleepTimer {
+    pub deadline: Instant,
+    /// Length the timer was set to, used to pick the next preset in the TUI.
+    pub minutes: u32,
+    /// Let the track playing at the deadline finish instead of cutting it off.
+    pub after_track: bool,
+}
+
+impl SleepTimer {
+    pub fn new(now: Instant, minutes: u32, after_track: bool) -> Self {
+        Self {
+            deadline: now + Duration::from_secs(u64::from(minutes) * 60),
+            minutes,
+            after_track,
+        }
+    }
+
+    pub fn is_due(&self, now: Instant) -> bool {
+        now >= self.deadline
+    }
+
+    /// Time left until the deadline; zero once it has passed.
+    pub fn remaining(&self, now: Instant) -> Duration {
+        self.deadline.saturating_duration_since(now)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -110,10 +423,112 @@ impl Default for UiState {
 impl Default for ConfigState {
     fn default() -> Self {
         Self {
-            root_path: None,
-            volume: default_volume(),
+            root_paths: Vec::new(),
+            legacy_root_path: None,
+            volume_percent: default_volume_percent(),
+            legacy_volume: None,
+            muted_volume: None,
             shuffle: false,
             repeat: Default::default(),
+            radio: RadioMode::Off,
+            normalize_volume: false,
+            crossfade_secs: 0,
+            theme: None,
+            scan_include: Vec::new(),
+            scan_exclude: Vec::new(),
+            repeat_count: default_repeat_count(),
+            progress_label: ProgressLabel::default(),
+            output_device: None,
+            eq: EqGains::default(),
+            scroll_titles: default_scroll_titles(),
+            search_history: Arc::new(Vec::new()),
+            fps: default_fps(),
+            default_command: DefaultCommand::Browse,
+            resume_long_tracks: default_resume_long_tracks(),
+            play_count_threshold_pct: default_play_count_threshold_pct(),
+            play_count_threshold_secs: default_play_count_threshold_secs(),
+            shuffle_skip_limit: 0,
+            browse_selected: None,
+            browse_scroll: 0,
+            confirm_quit: false,
+        }
+    }
+}
+
+impl ConfigState {
+    /// How far into a track of `duration` a play counts: the smaller of
+    /// `play_count_threshold_pct` (capped at 100) and `play_count_threshold_secs`.
+    pub fn play_threshold(&self, duration: Duration) -> Duration {
+        let pct = u32::from(self.play_count_threshold_pct.min(100));
+        (duration * pct / 100).min(Duration::from_secs(u64::from(self.play_count_threshold_secs)))
+    }
+
+    /// `play_threshold` in words, for telling users when plays are recorded.
+    pub fn play_threshold_note(&self) -> String {
+        format!(
+            "A song counts once it plays {}% of the way through (or {} seconds).",
+            self.play_count_threshold_pct.min(100),
+            self.play_count_threshold_secs
+        )
+    }
+
+    pub fn is_muted(&self) -> bool {
+        self.muted_volume.is_some()
+    }
+
+    /// Amplitude for the user volume, ignoring mute.
+    pub fn volume(&self) -> f32 {
+        volume_percent_to_amplitude(self.volume_percent)
+    }
+
+    /// Time between frames for the configured `fps`, kept within `FPS_MIN`-`FPS_MAX`
+    /// so a hand-edited value can neither spin the CPU nor freeze the display.
+    pub fn frame_interval(&self) -> Duration {
+        Duration::from_millis(1000 / u64::from(self.fps.clamp(FPS_MIN, FPS_MAX)))
+    }
+
+    /// Amplitude the playback backend should actually use (0.0 while muted).
+    pub fn effective_volume(&self) -> f32 {
+        if self.is_muted() { 0.0 } else { self.volume() }
+    }
+
+    /// Put `query` at the front of the search history, dropping an older copy
+    /// and the oldest entries past `SEARCH_HISTORY_MAX`. Blank queries are ignored.
+    pub fn record_search(&mut self, query: &str) {
+        let query = query.trim();
+        if query.is_empty() || self.search_history.first().is_some_and(|q| q == query) {
+            return;
+        }
+        let history = Arc::make_mut(&mut self.search_history);
+        history.retain(|q| q != query);
+        history.insert(0, query.to_string());
+        history.truncate(SEARCH_HISTORY_MAX);
+    }
+
+    /// Add a library root; `false` when it is already listed.
+    pub fn add_root(&mut self, path: PathBuf) -> bool {
+        if self.root_paths.contains(&path) {
+            return false;
+        }
+        self.root_paths.push(path);
+        true
+    }
+
+    /// Remove a library root; `false` when it was not listed.
+    pub fn remove_root(&mut self, path: &Path) -> bool {
+        let before = self.root_paths.len();
+        self.root_paths.retain(|root| root != path);
+        self.root_paths.len() != before
+    }
+
+    /// Convert settings stored by older versions (an amplitude volume, a single
+    /// `root_path`) to their current fields; called once after loading.
+    pub fn migrate_legacy_fields(&mut self) {
+        if let Some(amplitude) = self.legacy_volume.take() {
+            self.volume_percent = amplitude_to_volume(amplitude);
+        }
+        if let Some(root) = self.legacy_root_path.take() {
+            self.add_root(root);
         }
     }
 }
@@ -125,7 +540,9 @@ impl Default for LibraryState {
             active_sort: None,
             is_scanning: false,
             scan_progress: 0,
-            last_scan_path: None,
+            last_scan_roots: Vec::new(),
+            bookmarks: BTreeMap::new(),
+            resume_positions: BTreeMap::new(),
         }
     }
 }
@@ -138,6 +555,178 @@ impl Default for PlaybackState {
             is_paused: false,
             current_index: None,
             current_elapsed: Duration::from_secs(0),
+            queued_index: None,
+            preload_requested: false,
+            queue: VecDeque::new(),
+            loop_a: None,
+            loop_b: None,
+            play_counted: false,
+            consecutive_failures: 0,
+            track_repeats: 0,
+            sleep_timer: None,
+            radio_played: HashSet::new(),
+        }
+    }
+}
+
+impl AppState {
+    /// Whether the current track plays again when it ends: always under
+    /// `RepeatMode::One`, otherwise until it has played `repeat_count` times.
+    pub fn should_replay(&self) -> bool {
+        self.config.repeat == RepeatMode::One
+            || self.playback.track_repeats + 1 < self.config.repeat_count
+    }
+
+    /// Drop the song at `index` and shift every stored index past it down by one.
+    /// Removing the playing song stops playback; the selection moves to the next row.
+    fn rate_song(&mut self, index: usize, rating: Option<u8>, favorite: bool) {
+        let songs = Arc::make_mut(&mut self.library.songs);
+        let Some(song) = songs.get_mut(index) else {
+            return;
+        };
+        song.rating = rating;
+        song.favorite = favorite;
+
+        let marks = song.rating_marks();
+        self.ui.status_message = if marks.is_empty() {
+            format!("Unrated: {}", song.title)
+        } else {
+            format!("{} {}", marks, song.title)
+        };
+
+        if self.playback.current_index == Some(index)
+            && let Some(current) = self.playback.current_song.as_mut()
+        {
+            current.rating = rating;
+            current.favorite = favorite;
+        }
+    }
+
+    fn remove_song(&mut self, index: usize) {
+        if index >= self.library.songs.len() {
+            return;
+        }
+
+        let mut songs = (*self.library.songs).clone();
+        let removed = songs.remove(index);
+        let remaining = songs.len();
+        self.library.songs = Arc::new(songs);
+
+        // `None` for the removed song itself, the shifted index for anything after it.
+        let shift = |i: usize| match i.cmp(&index) {
+            std::cmp::Ordering::Less => Some(i),
+            std::cmp::Ordering::Equal => None,
+            std::cmp::Ordering::Greater => Some(i - 1),
+        };
+
+        self.ui.selected_index = self.ui.selected_index.and_then(|i| {
+            shift(i).or_else(|| (remaining > 0).then(|| index.min(remaining - 1)))
+        });
+        self.ui.search_results.retain(|&i| i != index);
+        for i in &mut self.ui.search_results {
+            *i = shift(*i).unwrap_or(*i);
+        }
+
+        self.playback.queue = self.playback.queue.iter().filter_map(|&i| shift(i)).collect();
+        self.playback.queued_index = self.playback.queued_index.and_then(shift);
+
+        match self.playback.current_index {
+            Some(current) if current == index => {
+                self.playback.current_song = None;
+                self.playback.is_playing = false;
+                self.playback.is_paused = false;
+                self.playback.current_index = None;
+                self.playback.current_elapsed = Duration::from_secs(0);
+                self.playback.clear_loop();
+            }
+            current => self.playback.current_index = current.and_then(shift),
+        }
+
+        self.ui.status_message = format!("Removed from library: {}", removed.title);
+    }
+
+    /// Whether shuffle should pass over the library song at `index` under `shuffle_skip_limit`.
+    pub fn skipped_too_often(&self, index: usize) -> bool {
+        let limit = self.config.shuffle_skip_limit;
+        limit > 0
+            && self.library.songs.get(index)
+                .is_some_and(|song| self.history.skip_count(&song.path) > limit)
+    }
+
+    /// Keep where the playing track is, so a long one can resume there later.
+    fn remember_position(&mut self) {
+        if self.config.resume_long_tracks
+            && let Some(song) = &self.playback.current_song
+        {
+            self.library.remember_position(song, self.playback.current_elapsed);
+        }
+    }
+
+    /// Empty the library, play queue and play history, stopping playback.
+    /// Music paths are kept so the next scan rebuilds the library.
+    fn clear_library(&mut self) {
+        self.library.songs = Arc::new(Vec::new());
+        self.library.active_sort = None;
+        self.library.bookmarks.clear();
+        self.library.resume_positions.clear();
+        self.history = HistoryState::default();
+
+        self.playback.queue.clear();
+        self.playback.queued_index = None;
+        self.playback.current_song = None;
+        self.playback.is_playing = false;
+        self.playback.is_paused = false;
+        self.playback.current_index = None;
+        self.playback.current_elapsed = Duration::from_secs(0);
+        self.playback.clear_loop();
+
+        self.ui.selected_index = None;
+        self.ui.search_results.clear();
+        self.ui.status_message = "Library cleared".to_string();
+    }
+
+    /// Swap in a rebuilt library. Queued and playing songs are matched by path,
+    /// so they survive if still present; the selection is clamped to the new length.
+    pub fn replace_songs(&mut self, songs: Vec<Song>) {
+        {
+            let old = &self.library.songs;
+            let positions: HashMap<&Path, usize> =
+                songs.iter().enumerate().map(|(i, song)| (song.path.as_path(), i)).collect();
+            let remap = |i: usize| old.get(i).and_then(|song| positions.get(song.path.as_path()).copied());
+
+            self.playback.queue = self.playback.queue.iter().filter_map(|&i| remap(i)).collect();
+            self.playback.queued_index = self.playback.queued_index.and_then(remap);
+            self.playback.current_index = self.playback.current_index.and_then(remap);
+        }
+
+        self.ui.selected_index = self.ui.selected_index
+            .and_then(|i| songs.len().checked_sub(1).map(|last| i.min(last)));
+        self.ui.search_results.clear();
+        self.library.songs = Arc::new(songs);
+    }
+}
+
+impl PlaybackState {
+    /// The active A–B loop, if both marks are set and B comes after A.
+    pub fn ab_loop(&self) -> Option<(Duration, Duration)> {
+        match (self.loop_a, self.loop_b) {
+            (Some(a), Some(b)) if b > a => Some((a, b)),
+            _ => None,
+        }
+    }
+
+    fn clear_loop(&mut self) {
+        self.loop_a = None;
+        self.loop_b = None;
+    }
+
+    fn loop_status(&self) -> &'static str {
+        match (self.loop_a, self.loop_b) {
+            (Some(_), Some(_)) if self.ab_loop().is_some() => "Looping A–B",
+            (Some(_), Some(_)) => "Loop ignored: B must come after A",
+            (Some(_), None) => "Loop start (A) set",
+            (None, Some(_)) => "Loop end (B) set",
+            (None, None) => "Loop cleared",
         }
     }
 }
@@ -148,6 +737,7 @@ impl Default for AppState {
             config: ConfigState::default(),
             library: LibraryState::default(),
             playback: PlaybackState::default(),
+            history: HistoryState::default(),
             ui: UiState {
                 selected_index: None,
                 status_message: "Welcome".to_string(),
@@ -166,12 +756,26 @@ impl AppState {
         match event {
             AppEvent::Playback(pe) => match pe {
                 PlaybackEvent::Started { song } => {
+                    self.remember_position();
                     self.playback.current_song = Some(song.clone());
                     self.playback.is_playing = true;
                     self.playback.is_paused = false;
                     self.playback.current_index = self.ui.selected_index;
+                    self.playback.queued_index = None;
+                    self.playback.preload_requested = false;
+                    // Loop marks belong to the previous track.
+                    self.playback.clear_loop();
+                    self.playback.play_counted = false;
                     self.ui.status_message = format!("Playing: {}", song.title);
-                    self.ui.error_message = None;
+                    // Keep the note about skipped songs visible while their successor plays.
+                    if self.playback.consecutive_failures == 0 {
+                        self.ui.error_message = None;
+                    }
+                    self.playback.consecutive_failures = 0;
+                }
+                PlaybackEvent::Error { message } => {
+                    self.playback.consecutive_failures += 1;
+                    self.ui.error_message = Some(message.clone());
                 }
                 PlaybackEvent::Paused => {
                     self.playback.is_paused = true;
@@ -185,10 +789,33 @@ impl AppState {
                 }
                 PlaybackEvent::TrackFinished => {
                     self.playback.is_playing = false;
+                    if let Some(song) = &self.playback.current_song {
+                        self.library.resume_positions.remove(&song.path);
+                    }
+                    // Tracks without a known duration never reach the threshold; count them here.
+                    self.count_current_play();
                     // Don't clear current_song - might still want to display it
                 }
+                PlaybackEvent::PlayThresholdReached => {
+                    self.count_current_play();
+                }
+                PlaybackEvent::TrackSkipped { path } => {
+                    self.history.record_skip(path);
+                }
+                PlaybackEvent::NearingEnd => {
+                    self.playback.preload_requested = true;
+                }
+                PlaybackEvent::SeekRequested { position } => {
+                    self.playback.current_elapsed = *position;
+                }
                 PlaybackEvent::VolumeChanged { volume } => {
-                    self.config.volume = *volume;
+                    self.config.volume_percent = amplitude_to_volume(*volume);
+                    // An explicit volume change always cancels mute.
+                    self.config.muted_volume = None;
+                }
+                PlaybackEvent::MuteChanged { muted } => {
+                    self.config.muted_volume = muted.then_some(self.config.volume());
+                    self.ui.status_message = if *muted { "Muted" } else { "Unmuted" }.to_string();
                 }
                 PlaybackEvent::Shuffle { enabled} => {
                     self.config.shuffle = *enabled;
@@ -196,15 +823,82 @@ impl AppState {
                 PlaybackEvent::RepeatChanged { mode } => {
                     self.config.repeat = *mode;
                 }
+                PlaybackEvent::RadioChanged { mode } => {
+                    self.config.radio = *mode;
+                    self.playback.radio_played.clear();
+                    self.ui.status_message = format!("Radio: {}", mode.label());
+                }
+                PlaybackEvent::RepeatCountChanged { count } => {
+                    self.config.repeat_count = *count;
+                    self.ui.status_message = match count {
+                        1 => "Each track plays once".to_string(),
+                        n => format!("Each track plays {} times", n),
+                    };
+                }
+                PlaybackEvent::Enqueued { index } => {
+                    self.playback.queue.push_back(*index);
+                    if let Some(song) = self.library.songs.get(*index) {
+                        self.ui.status_message = format!(
+                            "Queued: {} ({} in queue)",
+                            song.title,
+                            self.playback.queue.len()
+                        );
+                    }
+                }
+                PlaybackEvent::QueueCleared => {
+                    self.playback.queue.clear();
+                    self.ui.status_message = "Queue cleared".to_string();
+                }
+                PlaybackEvent::RecentPlaysCleared => {
+                    self.history.recent = Arc::default();
+                    self.ui.status_message = "Recently played cleared".to_string();
+                }
+                PlaybackEvent::NormalizeChanged { enabled } => {
+                    self.config.normalize_volume = *enabled;
+                    self.ui.status_message =
+                        if *enabled { "Normalization on" } else { "Normalization off" }.to_string();
+                }
+                PlaybackEvent::CrossfadeChanged { secs } => {
+                    self.config.crossfade_secs = *secs;
+                    self.ui.status_message = match secs {
+                        0 => "Crossfade off".to_string(),
+                        s => format!("Crossfade: {}s", s),
+                    };
+                }
+                PlaybackEvent::EqChanged { gains } => {
+                    self.config.eq = *gains;
+                    self.ui.status_message = if gains.is_flat() {
+                        "Equalizer off".to_string()
+                    } else {
+                        format!("Equalizer: {} (from the next track)", gains)
+                    };
+                }
+                PlaybackEvent::SleepTimerSet { minutes, after_track } => {
+                    self.playback.sleep_timer = (*minutes > 0)
+                        .then(|| SleepTimer::new(Instant::now(), *minutes, *after_track));
+                    self.ui.status_message = match (minutes, after_track) {
+                        (0, _) => "Sleep timer off".to_string(),
+                        (m, false) => format!("Sleep timer: stopping in {} min", m),
+                        (m, true) => format!("Sleep timer: stopping after the track playing in {} min", m),
+                    };
+                }
+                PlaybackEvent::Stopped => {
+                    self.remember_position();
+                    self.playback.is_playing = false;
+                    self.playback.is_paused = false;
+                    self.playback.queued_index = None;
+                    self.playback.sleep_timer = None;
+                    self.ui.status_message = "Sleep timer: playback stopped".to_string();
+                }
                 _ => {}
             },
 
             AppEvent::Library(le) => match le {
-                LibraryEvent::ScanStarted { path } => {
+                LibraryEvent::ScanStarted { roots } => {
                     self.library.is_scanning = true;
                     self.library.scan_progress = 0;
-                    self.library.last_scan_path = Some(path.clone());
-                    self.ui.status_message = format!("Scanning {:?}...", path);
+                    self.library.last_scan_roots = roots.clone();
+                    self.ui.status_message = format!("Scanning {}...", format_roots(roots));
                     self.ui.error_message = None;
                 }
                 LibraryEvent::ScanProgress { found } => {
@@ -227,20 +921,40 @@ impl AppState {
                     self.playback.is_paused = false;
                     self.playback.current_index = None;
                     self.playback.current_elapsed = Duration::from_secs(0);
+                    self.playback.clear_loop();
+                    // Queued indices referred to the old library.
+                    self.playback.queue.clear();
                 }
-                LibraryEvent::ScanFailed { path, message } => {
+                LibraryEvent::ScanFailed { roots, message } => {
                     self.library.is_scanning = false;
                     self.library.scan_progress = 0;
-                    self.library.last_scan_path = Some(path.clone());
+                    self.library.last_scan_roots = roots.clone();
                     self.ui.status_message = format!("Scan failed: {}", message);
                     self.ui.error_message = Some(message.clone());
                 }
                 LibraryEvent::LibraryLoaded { songs } => {
                     self.library.songs = Arc::new(songs.clone());
                     if self.ui.selected_index.is_none() && !songs.is_empty() {
-                        self.ui.selected_index = Some(0);
+                        // The library may have shrunk since the selection was saved.
+                        let last = songs.len() - 1;
+                        self.ui.selected_index = Some(self.config.browse_selected.map_or(0, |i| i.min(last)));
                     }
                 }
+                LibraryEvent::SongRemoved { index } => self.remove_song(*index),
+                LibraryEvent::RatingChanged { index, rating, favorite } => {
+                    self.rate_song(*index, *rating, *favorite)
+                }
+                LibraryEvent::LibraryCleared => self.clear_library(),
+                LibraryEvent::BookmarkAdded { path, position } => {
+                    self.ui.status_message = if self.library.add_bookmark(path, *position) {
+                        format!("Bookmarked {}", format_duration(*position))
+                    } else {
+                        format!("Already bookmarked at {}", format_duration(*position))
+                    };
+                }
+                LibraryEvent::BookmarkRemoved { path, position } if self.library.remove_bookmark(path, *position) => {
+                    self.ui.status_message = format!("Removed the bookmark at {}", format_duration(*position));
+                }
                 LibraryEvent::SearchResults { results } => {
                     self.ui.search_results = results.clone();
 
@@ -277,6 +991,8 @@ impl AppState {
                     self.ui.search_active = *active;
 
                     if !active {
+                        self.config.record_search(&self.ui.search_query);
+
                         // Clear search when toggled off
                         self.ui.search_query.clear();
                         self.ui.search_results.clear();
@@ -300,10 +1016,47 @@ impl AppState {
                     self.ui.search_query = query.clone();
                     // Note: Actual search is triggered by LibraryEvent::SearchRequested
                 }
+                UiEvent::SetLoopPointA if self.playback.current_song.is_some() => {
+                    self.playback.loop_a = Some(self.playback.current_elapsed);
+                    self.ui.status_message = self.playback.loop_status().to_string();
+                }
+                UiEvent::SetLoopPointB if self.playback.current_song.is_some() => {
+                    self.playback.loop_b = Some(self.playback.current_elapsed);
+                    self.ui.status_message = self.playback.loop_status().to_string();
+                }
+                UiEvent::LoopCleared => {
+                    self.playback.clear_loop();
+                    self.ui.status_message = self.playback.loop_status().to_string();
+                }
+                // A manual track change starts a fresh run of `repeat_count` plays.
+                UiEvent::NextTrackRequested
+                | UiEvent::PreviousTrackRequested
+                | UiEvent::AlbumSkipRequested { .. } => {
+                    self.playback.track_repeats = 0;
+                }
+                UiEvent::PlaySelectedRequested => {
+                    self.playback.track_repeats = 0;
+                    if self.ui.search_active {
+                        self.config.record_search(&self.ui.search_query);
+                    }
+                }
                 _ => {}
             },
 
-            AppEvent::Shutdown => {}
+            AppEvent::Shutdown => self.remember_position(),
+        }
+    }
+}
+
+impl AppState {
+    /// Record the current track in the play history, at most once per play.
+    fn count_current_play(&mut self) {
+        if self.playback.play_counted {
+            return;
+        }
+        if let Some(song) = &self.playback.current_song {
+            self.history.record_play(&song.path, SystemTime::now());
+            self.playback.play_counted = true;
         }
     }
 }
@@ -314,6 +1067,7 @@ fn sort_field_label(field: Option<SortField>) -> &'static str {
         Some(SortField::Title)    => "Sorted by title",
         Some(SortField::Artist)   => "Sorted by artist",
         Some(SortField::Album)    => "Sorted by album",
+        Some(SortField::Track)    => "Sorted by track number",
         Some(SortField::Duration) => "Sorted by duration",
     }
 }
@@ -328,14 +1082,9 @@ mod tests {
 
     fn make_song(title: &str) -> Song {
         Song {
-            path: PathBuf::from(format!("{}.mp3", title)),
-            title: title.to_owned(),
             artists: vec!["Test Artist".to_owned()],
             album: Some("Test Album".to_owned()),
-            track_number: None,
-            duration: None,
-            search_key: title.to_lowercase(),
-            order: 0,
+            ..Song::test_fixture(title)
         }
     }
 
@@ -374,6 +1123,19 @@ mod tests {
         assert_eq!(state.playback.current_index, Some(3));
     }
 
+    #[test]
+    fn started_resets_gapless_preload_for_the_new_track() {
+        let mut state = AppState::default();
+        apply(&mut state, AppEvent::Playback(PlaybackEvent::NearingEnd));
+        state.playback.queued_index = Some(1);
+        assert!(state.playback.preload_requested);
+
+        apply(&mut state, AppEvent::Playback(PlaybackEvent::Started { song: make_song("X") }));
+
+        assert!(!state.playback.preload_requested);
+        assert_eq!(state.playback.queued_index, None);
+    }
+
     #[test]
     fn started_updates_status_and_clears_error() {
         let mut state = AppState::default();
@@ -440,15 +1202,102 @@ mod tests {
         );
     }
 
+    // ── Sleep timer ───────────────────────────────────────────────────────────
+
+    #[test]
+    fn sleep_timer_is_due_from_its_deadline_on() {
+        let now = Instant::now();
+        let timer = SleepTimer::new(now, 30, false);
+
+        assert!(!timer.is_due(now + Duration::from_secs(30 * 60 - 1)));
+        assert!(timer.is_due(now + Duration::from_secs(30 * 60)));
+        assert!(timer.is_due(now + Duration::from_secs(31 * 60)));
+        assert_eq!(timer.remaining(now + Duration::from_secs(60)), Duration::from_secs(29 * 60));
+        assert_eq!(timer.remaining(now + Duration::from_secs(31 * 60)), Duration::ZERO);
+    }
+
+    #[test]
+    fn sleep_zero_cancels_the_timer() {
+        let mut state = AppState::default();
+
+        apply(&mut state, AppEvent::Playback(PlaybackEvent::SleepTimerSet { minutes: 15, after_track: true }));
+        let timer = state.playback.sleep_timer.expect("timer set");
+        assert_eq!((timer.minutes, timer.after_track), (15, true));
+
+        apply(&mut state, AppEvent::Playback(PlaybackEvent::SleepTimerSet { minutes: 0, after_track: false }));
+        assert!(state.playback.sleep_timer.is_none());
+    }
+
     // ── PlaybackEvent::VolumeChanged ──────────────────────────────────────────
 
     #[test]
     fn volume_changed_updates_config_volume() {
         let mut state = AppState::default();
 
-        apply(&mut state, AppEvent::Playback(PlaybackEvent::VolumeChanged { volume: 0.42 }));
+        apply(&mut state, AppEvent::Playback(PlaybackEvent::VolumeChanged { volume: volume_percent_to_amplitude(42) }));
+
+        assert_eq!(state.config.volume_percent, 42);
+    }
+
+    #[test]
+    fn volume_changed_while_muted_cancels_mute() {
+        let mut state = AppState::default();
+        apply(&mut state, AppEvent::Playback(PlaybackEvent::MuteChanged { muted: true }));
+
+        apply(&mut state, AppEvent::Playback(PlaybackEvent::VolumeChanged { volume: volume_percent_to_amplitude(30) }));
+
+        assert!(!state.config.is_muted());
+        assert_eq!(state.config.effective_volume(), volume_percent_to_amplitude(30));
+    }
+
+    // ── PlaybackEvent::MuteChanged ────────────────────────────────────────────
+
+    #[test]
+    fn mute_changed_preserves_volume_and_silences_effective_volume() {
+        let mut state = AppState::default();
+        state.config.volume_percent = 60;
+
+        apply(&mut state, AppEvent::Playback(PlaybackEvent::MuteChanged { muted: true }));
+
+        assert!(state.config.is_muted());
+        assert_eq!(state.config.muted_volume, Some(volume_percent_to_amplitude(60)));
+        assert_eq!(state.config.volume_percent, 60, "volume must not be overwritten");
+        assert_eq!(state.config.effective_volume(), 0.0);
+    }
+
+    #[test]
+    fn unmute_restores_effective_volume() {
+        let mut state = AppState::default();
+        state.config.volume_percent = 60;
+        apply(&mut state, AppEvent::Playback(PlaybackEvent::MuteChanged { muted: true }));
+
+        apply(&mut state, AppEvent::Playback(PlaybackEvent::MuteChanged { muted: false }));
+
+        assert!(!state.config.is_muted());
+        assert_eq!(state.config.effective_volume(), volume_percent_to_amplitude(60));
+    }
+
+    // ── PlaybackEvent::Enqueued / QueueCleared ───────────────────────────────
+
+    #[test]
+    fn enqueued_appends_to_queue_in_order() {
+        let mut state = state_with_songs(3);
+
+        apply(&mut state, AppEvent::Playback(PlaybackEvent::Enqueued { index: 2 }));
+        apply(&mut state, AppEvent::Playback(PlaybackEvent::Enqueued { index: 0 }));
+
+        assert_eq!(state.playback.queue, [2, 0]);
+        assert!(state.ui.status_message.contains("2 in queue"));
+    }
+
+    #[test]
+    fn queue_cleared_empties_queue() {
+        let mut state = state_with_songs(3);
+        state.playback.queue.extend([1, 2]);
+
+        apply(&mut state, AppEvent::Playback(PlaybackEvent::QueueCleared));
 
-        assert!((state.config.volume - 0.42).abs() < f32::EPSILON);
+        assert!(state.playback.queue.is_empty());
     }
 
     // ── PlaybackEvent::Shuffle ────────────────────────────────────────────────
@@ -465,6 +1314,33 @@ mod tests {
         assert!(!state.config.shuffle);
     }
 
+    // ── repeat_count ──────────────────────────────────────────────────────────
+
+    #[test]
+    fn should_replay_until_repeat_count_and_always_for_repeat_one() {
+        let mut state = state_with_songs(2);
+        assert!(!state.should_replay(), "default plays each track once");
+
+        state.config.repeat_count = 3;
+        state.playback.track_repeats = 1;
+        assert!(state.should_replay());
+        state.playback.track_repeats = 2;
+        assert!(!state.should_replay());
+
+        state.config.repeat = RepeatMode::One;
+        assert!(state.should_replay());
+    }
+
+    #[test]
+    fn manual_track_change_resets_repeat_counter() {
+        let mut state = state_with_songs(2);
+        state.playback.track_repeats = 2;
+
+        apply(&mut state, AppEvent::Ui(UiEvent::NextTrackRequested));
+
+        assert_eq!(state.playback.track_repeats, 0);
+    }
+
     // ── PlaybackEvent::RepeatChanged ──────────────────────────────────────────
 
     #[test]
@@ -484,12 +1360,12 @@ mod tests {
     #[test]
     fn scan_started_sets_scanning_flag_and_status() {
         let mut state = AppState::default();
-        let path = PathBuf::from("/music");
+        let roots = vec![PathBuf::from("/music")];
 
-        apply(&mut state, AppEvent::Library(LibraryEvent::ScanStarted { path: path.clone() }));
+        apply(&mut state, AppEvent::Library(LibraryEvent::ScanStarted { roots: roots.clone() }));
 
         assert!(state.library.is_scanning);
-        assert_eq!(state.library.last_scan_path, Some(path));
+        assert_eq!(state.library.last_scan_roots, roots);
         assert!(state.ui.status_message.contains("Scanning"));
         assert!(state.ui.error_message.is_none());
     }
@@ -500,7 +1376,7 @@ mod tests {
         state.library.scan_progress = 42;
 
         apply(&mut state, AppEvent::Library(LibraryEvent::ScanStarted {
-            path: PathBuf::from("/music"),
+            roots: vec![PathBuf::from("/music")],
         }));
 
         assert_eq!(state.library.scan_progress, 0);
@@ -529,8 +1405,38 @@ mod tests {
         assert!(state.ui.status_message.contains("25"));
     }
 
+    // ── replace_songs ─────────────────────────────────────────────────────────
+
+    #[test]
+    fn replace_songs_follows_queued_songs_by_path_and_clamps_selection() {
+        let mut state = state_with_songs(4);
+        state.playback.queue = VecDeque::from([3, 1]);
+        state.playback.current_index = Some(2);
+        state.ui.selected_index = Some(3);
+        let kept = vec![state.library.songs[3].clone(), state.library.songs[0].clone()];
+
+        state.replace_songs(kept);
+
+        assert_eq!(state.playback.queue, [0], "song 1 is gone, song 3 moved to the front");
+        assert_eq!(state.playback.current_index, None);
+        assert_eq!(state.ui.selected_index, Some(1));
+
+        state.replace_songs(Vec::new());
+        assert_eq!(state.ui.selected_index, None);
+    }
+
     // ── LibraryEvent::ScanCompleted ───────────────────────────────────────────
 
+    #[test]
+    fn scan_completed_clears_play_queue() {
+        let mut state = state_with_songs(3);
+        state.playback.queue.push_back(1);
+
+        apply(&mut state, AppEvent::Library(LibraryEvent::ScanCompleted { songs: Vec::new(), count: 0 }));
+
+        assert!(state.playback.queue.is_empty());
+    }
+
     #[test]
     fn scan_completed_replaces_songs_and_clears_scanning_flag() {
         let mut state = state_with_songs(3);
@@ -617,7 +1523,7 @@ mod tests {
         state.library.scan_progress = 10;
 
         apply(&mut state, AppEvent::Library(LibraryEvent::ScanFailed {
-            path: PathBuf::from("/music"),
+            roots: vec![PathBuf::from("/music")],
             message: "permission denied".to_owned(),
         }));
 
@@ -660,6 +1566,91 @@ mod tests {
         assert_eq!(state.ui.selected_index, Some(2));
     }
 
+    #[test]
+    fn library_loaded_restores_the_saved_selection_within_the_library() {
+        let load = |saved, songs: usize| {
+            let mut state = AppState::default();
+            state.config.browse_selected = saved;
+            apply(&mut state, AppEvent::Library(LibraryEvent::LibraryLoaded {
+                songs: (0..songs).map(|i| make_song(&format!("S{}", i))).collect(),
+            }));
+            state.ui.selected_index
+        };
+
+        assert_eq!(load(Some(3), 5), Some(3));
+        assert_eq!(load(Some(99), 5), Some(4), "clamped to the last song");
+        assert_eq!(load(Some(3), 0), None);
+    }
+
+    // ── LibraryEvent::SongRemoved ─────────────────────────────────────────────
+
+    #[test]
+    fn removing_the_playing_song_stops_playback_and_keeps_a_selection() {
+        let mut state = state_with_songs(4);
+        state.ui.selected_index = Some(3);
+        apply(&mut state, AppEvent::Playback(PlaybackEvent::Started { song: make_song("Song 3") }));
+
+        apply(&mut state, AppEvent::Library(LibraryEvent::SongRemoved { index: 3 }));
+
+        assert_eq!(state.library.songs.len(), 3);
+        assert_eq!(state.playback.current_index, None);
+        assert!(state.playback.current_song.is_none());
+        assert!(!state.playback.is_playing);
+        assert_eq!(state.ui.selected_index, Some(2), "the last row was removed; select the new last");
+    }
+
+    #[test]
+    fn removing_an_earlier_song_shifts_stored_indices() {
+        let mut state = state_with_songs(5);
+        state.playback.current_index = Some(3);
+        state.ui.selected_index = Some(4);
+        state.playback.queue = VecDeque::from([0, 1, 4]);
+        state.ui.search_results = vec![1, 2, 4];
+
+        apply(&mut state, AppEvent::Library(LibraryEvent::SongRemoved { index: 1 }));
+
+        assert_eq!(state.library.songs[1].title, "Song 2");
+        assert_eq!(state.playback.current_index, Some(2));
+        assert_eq!(state.ui.selected_index, Some(3));
+        assert_eq!(state.playback.queue, [0, 3], "the removed song leaves the queue");
+        assert_eq!(state.ui.search_results, [1, 3]);
+    }
+
+    #[test]
+    fn removing_an_out_of_range_index_changes_nothing() {
+        let mut state = state_with_songs(2);
+
+        apply(&mut state, AppEvent::Library(LibraryEvent::SongRemoved { index: 2 }));
+
+        assert_eq!(state.library.songs.len(), 2);
+    }
+
+    // ── LibraryEvent::LibraryCleared ──────────────────────────────────────────
+
+    #[test]
+    fn clearing_empties_library_queue_and_history_and_a_scan_rebuilds_it() {
+        let mut state = state_with_songs(3);
+        state.config.root_paths = vec![PathBuf::from("/music")];
+        state.ui.selected_index = Some(1);
+        state.playback.queue = VecDeque::from([2, 0]);
+        state.history.record_play(&state.library.songs[0].path.clone(), SystemTime::now());
+        apply(&mut state, AppEvent::Playback(PlaybackEvent::Started { song: make_song("Song 1") }));
+
+        apply(&mut state, AppEvent::Library(LibraryEvent::LibraryCleared));
+
+        assert!(state.library.songs.is_empty());
+        assert!(state.playback.queue.is_empty());
+        assert!(state.history.plays.is_empty() && state.history.recent.is_empty());
+        assert_eq!((state.ui.selected_index, state.playback.current_index), (None, None));
+        assert!(!state.playback.is_playing);
+        assert_eq!(state.config.root_paths, [PathBuf::from("/music")], "a refresh can repopulate");
+
+        let songs: Vec<Song> = (0..2).map(|i| make_song(&format!("New {i}"))).collect();
+        apply(&mut state, AppEvent::Library(LibraryEvent::ScanCompleted { songs, count: 2 }));
+        assert_eq!(state.library.songs.len(), 2);
+        assert_eq!(state.ui.selected_index, Some(0));
+    }
+
     // ── LibraryEvent::SearchResults ───────────────────────────────────────────
 
     #[test]
@@ -827,6 +1818,54 @@ mod tests {
         assert_eq!(state.ui.selected_index, Some(0));
     }
 
+    #[test]
+    fn leaving_search_remembers_the_query_newest_first_without_repeats() {
+        let mut state = AppState::default();
+        for query in ["queen", "bowie", " queen ", ""] {
+            state.ui.search_active = true;
+            state.ui.search_query = query.to_owned();
+            apply(&mut state, AppEvent::Ui(UiEvent::SearchToggled { active: false }));
+        }
+
+        assert_eq!(*state.config.search_history, vec!["queen".to_owned(), "bowie".to_owned()]);
+    }
+
+    #[test]
+    fn search_history_keeps_the_newest_entries() {
+        let mut config = ConfigState::default();
+        for i in 0..SEARCH_HISTORY_MAX + 5 {
+            config.record_search(&format!("query {i}"));
+        }
+
+        assert_eq!(config.search_history.len(), SEARCH_HISTORY_MAX);
+        assert_eq!(config.search_history[0], format!("query {}", SEARCH_HISTORY_MAX + 4));
+    }
+
+    #[test]
+    fn frame_interval_clamps_the_configured_fps() {
+        let mut config = ConfigState::default();
+        assert_eq!(config.frame_interval(), Duration::from_millis(16), "60fps by default");
+
+        config.fps = 0;
+        assert_eq!(config.frame_interval(), Duration::from_millis(1000 / u64::from(FPS_MIN)));
+        config.fps = 1000;
+        assert_eq!(config.frame_interval(), Duration::from_millis(1000 / u64::from(FPS_MAX)));
+        config.fps = 30;
+        assert_eq!(config.frame_interval(), Duration::from_millis(33));
+    }
+
+    #[test]
+    fn playing_a_search_result_remembers_the_query() {
+        let mut state = state_with_songs(3);
+        state.ui.search_active = true;
+        state.ui.search_query = "song".to_owned();
+
+        apply(&mut state, AppEvent::Ui(UiEvent::PlaySelectedRequested));
+
+        assert_eq!(*state.config.search_history, vec!["song".to_owned()]);
+        assert!(state.ui.search_active, "search stays open");
+    }
+
     // ── UiEvent::SearchQueryChanged ───────────────────────────────────────────
 
     #[test]
@@ -840,6 +1879,291 @@ mod tests {
         assert_eq!(state.ui.search_query, "bowie");
     }
 
+    // ── A–B loop ──────────────────────────────────────────────────────────────
+
+    fn playing_at(secs: u64) -> AppState {
+        let mut state = state_with_songs(1);
+        state.playback.current_song = Some(make_song("Song 0"));
+        state.playback.current_elapsed = Duration::from_secs(secs);
+        state
+    }
+
+    fn mark(state: &mut AppState, event: UiEvent, secs: u64) {
+        state.playback.current_elapsed = Duration::from_secs(secs);
+        apply(state, AppEvent::Ui(event));
+    }
+
+    #[test]
+    fn loop_points_record_current_position() {
+        let mut state = playing_at(0);
+
+        mark(&mut state, UiEvent::SetLoopPointA, 10);
+        assert_eq!(state.playback.ab_loop(), None, "one mark is not a loop");
+
+        mark(&mut state, UiEvent::SetLoopPointB, 25);
+        assert_eq!(
+            state.playback.ab_loop(),
+            Some((Duration::from_secs(10), Duration::from_secs(25)))
+        );
+        assert_eq!(state.ui.status_message, "Looping A–B");
+    }
+
+    #[test]
+    fn loop_with_b_not_after_a_is_ignored() {
+        let mut state = playing_at(0);
+
+        mark(&mut state, UiEvent::SetLoopPointA, 30);
+        mark(&mut state, UiEvent::SetLoopPointB, 30);
+        assert_eq!(state.playback.ab_loop(), None);
+
+        mark(&mut state, UiEvent::SetLoopPointB, 12);
+        assert_eq!(state.playback.ab_loop(), None);
+        assert!(state.ui.status_message.contains("ignored"));
+    }
+
+    #[test]
+    fn loop_points_need_a_current_song() {
+        let mut state = AppState::default();
+
+        apply(&mut state, AppEvent::Ui(UiEvent::SetLoopPointA));
+
+        assert_eq!(state.playback.loop_a, None);
+    }
+
+    #[test]
+    fn loop_is_cleared_explicitly_and_on_track_change() {
+        let mut state = playing_at(0);
+        mark(&mut state, UiEvent::SetLoopPointA, 5);
+        mark(&mut state, UiEvent::SetLoopPointB, 8);
+
+        apply(&mut state, AppEvent::Ui(UiEvent::LoopCleared));
+        assert_eq!((state.playback.loop_a, state.playback.loop_b), (None, None));
+
+        mark(&mut state, UiEvent::SetLoopPointA, 5);
+        mark(&mut state, UiEvent::SetLoopPointB, 8);
+        apply(&mut state, AppEvent::Playback(PlaybackEvent::Started { song: make_song("Next") }));
+        assert_eq!(state.playback.ab_loop(), None);
+    }
+
+    #[test]
+    fn seek_requested_moves_elapsed_position() {
+        let mut state = playing_at(40);
+
+        apply(&mut state, AppEvent::Playback(PlaybackEvent::SeekRequested {
+            position: Duration::from_secs(3),
+        }));
+
+        assert_eq!(state.playback.current_elapsed, Duration::from_secs(3));
+    }
+
+    // ── Play history ──────────────────────────────────────────────────────────
+
+    fn play_through(state: &mut AppState, song: &Song) {
+        apply(state, AppEvent::Playback(PlaybackEvent::Started { song: song.clone() }));
+        apply(state, AppEvent::Playback(PlaybackEvent::TrackFinished));
+    }
+
+    #[test]
+    fn each_finished_play_bumps_play_count() {
+        let mut state = AppState::default();
+        let song = make_song("Loop Me");
+
+        for _ in 0..3 {
+            play_through(&mut state, &song);
+        }
+
+        assert_eq!(state.history.play_count(&song.path), 3);
+        assert_eq!(state.history.play_count(&make_song("Other").path), 0);
+    }
+
+    #[test]
+    fn play_counted_at_threshold_is_not_counted_again_on_finish() {
+        let mut state = AppState::default();
+        let song = make_song("Once");
+        apply(&mut state, AppEvent::Playback(PlaybackEvent::Started { song: song.clone() }));
+
+        apply(&mut state, AppEvent::Playback(PlaybackEvent::PlayThresholdReached));
+        apply(&mut state, AppEvent::Playback(PlaybackEvent::PlayThresholdReached));
+        apply(&mut state, AppEvent::Playback(PlaybackEvent::TrackFinished));
+
+        assert_eq!(state.history.play_count(&song.path), 1);
+    }
+
+    #[test]
+    fn play_records_last_played_time() {
+        let mut state = AppState::default();
+        let song = make_song("Timed");
+        let before = SystemTime::now();
+
+        play_through(&mut state, &song);
+
+        let record = state.history.plays[&song.path];
+        assert!(record.last_played >= before);
+    }
+
+    #[test]
+    fn saved_state_without_history_loads_empty_history() {
+        let state: AppState = serde_json::from_str(r#"{"config": {}, "library": {"songs": []}}"#).unwrap();
+
+        assert!(state.history.plays.is_empty());
+    }
+
+    #[test]
+    fn history_round_trips_through_json() {
+        let mut state = AppState::default();
+        let song = make_song("Saved");
+        play_through(&mut state, &song);
+
+        let json = serde_json::to_string(&state).unwrap();
+        let loaded: AppState = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(loaded.history.play_count(&song.path), 1);
+    }
+
+    #[test]
+    fn recent_plays_keep_the_newest_and_can_be_cleared() {
+        let mut state = AppState::default();
+        let first = make_song("First");
+        let last = make_song("Last");
+
+        play_through(&mut state, &first);
+        for _ in 0..RECENT_PLAYS_MAX {
+            play_through(&mut state, &last);
+        }
+
+        assert_eq!(state.history.recent.len(), RECENT_PLAYS_MAX);
+        assert!(state.history.recent.iter().all(|(path, _)| *path == last.path), "the oldest play fell off");
+        assert_eq!(state.history.play_count(&first.path), 1, "play counts are kept");
+
+        apply(&mut state, AppEvent::Playback(PlaybackEvent::RecentPlaysCleared));
+        assert!(state.history.recent.is_empty());
+        assert_eq!(state.history.play_count(&last.path), RECENT_PLAYS_MAX as u32);
+    }
+
+    // ── Bookmarks ─────────────────────────────────────────────────────────────
+
+    #[test]
+    fn bookmarks_are_kept_sorted_by_the_second_without_duplicates() {
+        let mut state = AppState::default();
+        let song = make_song("Audiobook");
+        let at = |secs: f64| Duration::from_secs_f64(secs);
+
+        for position in [at(600.4), at(90.0), at(600.9)] {
+            apply(&mut state, AppEvent::Library(LibraryEvent::BookmarkAdded { path: song.path.clone(), position }));
+        }
+
+        assert_eq!(state.library.bookmarks_for(&song.path), [at(90.0), at(600.0)]);
+        assert_eq!(state.ui.status_message, "Already bookmarked at 10:00");
+        assert!(state.library.bookmarks_for(Path::new("other.mp3")).is_empty());
+    }
+
+    #[test]
+    fn removing_the_last_bookmark_forgets_the_track() {
+        let mut state = AppState::default();
+        let path = make_song("Audiobook").path;
+        state.library.add_bookmark(&path, Duration::from_secs(90));
+        state.library.add_bookmark(&path, Duration::from_secs(30));
+
+        assert!(!state.library.remove_bookmark(&path, Duration::from_secs(45)));
+        apply(&mut state, AppEvent::Library(LibraryEvent::BookmarkRemoved { path: path.clone(), position: Duration::from_secs(30) }));
+        assert_eq!(state.library.bookmarks_for(&path), [Duration::from_secs(90)]);
+
+        assert!(state.library.remove_bookmark(&path, Duration::from_millis(90_500)));
+        assert!(state.library.bookmarks.is_empty());
+    }
+
+    #[test]
+    fn bookmarks_of_missing_files_are_pruned() {
+        let mut state = AppState::default();
+        let kept = std::env::current_exe().unwrap();
+        state.library.add_bookmark(&kept, Duration::from_secs(5));
+        state.library.add_bookmark(Path::new("/no/such/file.mp3"), Duration::from_secs(5));
+
+        state.library.prune_missing_positions();
+
+        assert_eq!(state.library.bookmarks.keys().collect::<Vec<_>>(), [&kept]);
+    }
+
+    // ── Play threshold ────────────────────────────────────────────────────────
+
+    #[test]
+    fn short_tracks_count_at_the_percentage() {
+        let config = ConfigState { play_count_threshold_pct: 40, play_count_threshold_secs: 300, ..ConfigState::default() };
+
+        assert_eq!(config.play_threshold(Duration::from_secs(180)), Duration::from_secs(72));
+        assert_eq!(
+            ConfigState { play_count_threshold_pct: 250, ..config }.play_threshold(Duration::from_secs(180)),
+            Duration::from_secs(180),
+            "capped at the whole track"
+        );
+    }
+
+    #[test]
+    fn long_tracks_count_after_the_absolute_seconds() {
+        let config = ConfigState { play_count_threshold_pct: 40, play_count_threshold_secs: 300, ..ConfigState::default() };
+
+        assert_eq!(config.play_threshold(Duration::from_secs(3600)), Duration::from_secs(300));
+        assert_eq!(ConfigState::default().play_threshold(Duration::from_secs(600)), Duration::from_secs(240));
+    }
+
+    // ── Resume positions ──────────────────────────────────────────────────────
+
+    fn long_song(title: &str, minutes: u64) -> Song {
+        Song { duration: Some(Duration::from_secs(minutes * 60)), ..make_song(title) }
+    }
+
+    #[test]
+    fn only_tracks_past_the_threshold_keep_their_position() {
+        let mut library = LibraryState::default();
+        let short = long_song("Single", RESUME_MIN_TRACK_SECS / 60 - 1);
+        let book = long_song("Audiobook", RESUME_MIN_TRACK_SECS / 60);
+
+        library.remember_position(&short, Duration::from_secs(300));
+        library.remember_position(&book, Duration::from_millis(300_700));
+
+        assert_eq!(library.resume_positions.get(&short.path), None);
+        assert_eq!(library.resume_positions.get(&book.path), Some(&Duration::from_secs(300)));
+    }
+
+    #[test]
+    fn positions_near_either_end_are_not_kept() {
+        let mut library = LibraryState::default();
+        let book = long_song("Audiobook", 60);
+        library.remember_position(&book, Duration::from_secs(600));
+
+        library.remember_position(&book, Duration::from_secs(3600 - RESUME_MARGIN_SECS / 2));
+        assert!(library.resume_positions.is_empty(), "as good as finished");
+
+        library.remember_position(&book, Duration::from_secs(600));
+        library.remember_position(&book, Duration::from_secs(RESUME_MARGIN_SECS / 2));
+        assert!(library.resume_positions.is_empty(), "barely started");
+    }
+
+    #[test]
+    fn finishing_a_long_track_forgets_its_position() {
+        let mut state = AppState::default();
+        let book = long_song("Audiobook", 60);
+        apply(&mut state, AppEvent::Playback(PlaybackEvent::Started { song: book.clone() }));
+        state.playback.current_elapsed = Duration::from_secs(900);
+        apply(&mut state, AppEvent::Shutdown);
+        assert_eq!(state.library.resume_positions.get(&book.path), Some(&Duration::from_secs(900)));
+
+        apply(&mut state, AppEvent::Playback(PlaybackEvent::TrackFinished));
+        assert!(state.library.resume_positions.is_empty());
+    }
+
+    #[test]
+    fn positions_are_not_kept_with_resume_turned_off() {
+        let mut state = AppState::default();
+        state.config.resume_long_tracks = false;
+        let book = long_song("Audiobook", 60);
+        apply(&mut state, AppEvent::Playback(PlaybackEvent::Started { song: book }));
+        state.playback.current_elapsed = Duration::from_secs(900);
+
+        apply(&mut state, AppEvent::Playback(PlaybackEvent::Started { song: make_song("Next") }));
+        assert!(state.library.resume_positions.is_empty());
+    }
+
     // ── Shutdown / no-op ──────────────────────────────────────────────────────
 
     #[test]
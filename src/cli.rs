@@ -1,17 +1,44 @@
 use clap::{Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
+use std::time::Duration;
 use clap::builder::PossibleValue;
-use crate::core::models::RepeatMode;
+use crate::cli_handlers::OutputFormat;
+use crate::core::models::{RadioMode, RepeatMode};
 use crate::modules::library::sorter::SortField;
-use crate::utils::{APP_NAME, VOLUME_MAX};
+use crate::modules::storage::StorageFormat;
+use crate::utils::{parse_position, APP_NAME, CROSSFADE_MAX_SECS, EQ_MAX_DB, FPS_MAX, FPS_MIN, RATING_MAX, VOLUME_MAX};
 
 #[derive(Parser)]
 #[command(name = APP_NAME)]
 #[command(version)]
 #[command(about = "Lightweight terminal music player", long_about = None)]
 pub struct Cli {
+    /// Format of the state file: json (default) or toml. Also settable with HEXTUNE_CONFIG_FORMAT
+    #[arg(long, global = true, value_name = "FORMAT")]
+    pub config_format: Option<StorageFormat>,
+
+    /// Settings file to use instead of the one in the config directory, e.g. for a separate
+    /// profile; the library is kept beside it. Also settable with HEXTUNE_CONFIG
+    #[arg(long, global = true, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// Audio output device to use for this run instead of the saved one (see `devices`)
+    #[arg(long, global = true, value_name = "NAME")]
+    pub device: Option<String>,
+
+    /// Play nothing: tracks advance on a simulated clock, for machines without an audio
+    /// device (e.g. CI). Also settable with HEXTUNE_NULL_AUDIO=1
+    #[arg(long, global = true)]
+    pub no_audio: bool,
+
+    /// Log track changes, playback errors and scans to hextune.log beside the settings file.
+    /// RUST_LOG (e.g. RUST_LOG=debug) also turns it on and picks the levels
+    #[arg(short, long, global = true)]
+    pub verbose: bool,
+
+    /// Without one, runs `default_command` from the settings: browse, unless changed
     #[command(subcommand)]
-    pub command: Commands,
+    pub command: Option<Commands>,
 }
 
 #[derive(Subcommand)]
@@ -20,37 +47,193 @@ pub enum Commands {
     Play {
         /// Path to the audio file path
         file: PathBuf,
+
+        /// Load the song but start paused
+        #[arg(long, alias = "start-paused")]
+        paused: bool,
     },
 
-    /// Set the root music directory path
+    /// Set the root music directory path, replacing any others
     Path {
         /// Path to the music directory
         directory: PathBuf,
     },
 
-    /// Refresh the music library from the configured path
-    Refresh,
+    /// Add another directory to scan for music
+    PathAdd {
+        /// Path to the music directory
+        directory: PathBuf,
+    },
+
+    /// Stop scanning a music directory
+    PathRemove {
+        /// Music directory, as listed by `path-list`
+        directory: PathBuf,
+    },
+
+    /// List the music directories the library is scanned from
+    PathList,
+
+    /// Refresh the music library from the configured paths
+    Refresh {
+        /// Only scan files matching this glob, relative to the music path (repeatable).
+        /// Replaces the saved include list; pass "" to clear it
+        #[arg(long = "include", value_name = "GLOB")]
+        include: Vec<String>,
+
+        /// Skip files and directories matching this glob, relative to the music path
+        /// (repeatable). Replaces the saved exclude list; pass "" to clear it
+        #[arg(long = "exclude", value_name = "GLOB")]
+        exclude: Vec<String>,
+
+        /// Keep the current library and its order, only adding files not in it yet
+        #[arg(long, visible_alias = "merge")]
+        append: bool,
+
+        /// Keep songs whose files no longer exist instead of dropping them
+        #[arg(long)]
+        keep_missing: bool,
+
+        /// Only re-read the tags of the songs already in the library, keeping their order,
+        /// ratings and play counts. New and deleted files are left alone
+        #[arg(long, conflicts_with_all = ["include", "exclude", "append", "keep_missing"])]
+        metadata_only: bool,
+    },
 
     /// Play songs from the library from the configured path
-    Playlist,
+    Playlist {
+        /// Index of the song to start with (0-based, as printed by `list`)
+        #[arg(long, value_name = "INDEX", conflicts_with = "start_title")]
+        start: Option<usize>,
+
+        /// Start with the best search match for this title (searches title, artist, and album)
+        #[arg(long, value_name = "QUERY")]
+        start_title: Option<String>,
+
+        /// Play the songs marked as favorites through the play queue, then carry on in library order
+        #[arg(long, conflicts_with_all = ["start", "start_title"])]
+        favorites: bool,
+
+        /// Load the first song but start paused
+        #[arg(long, alias = "start-paused")]
+        paused: bool,
+
+        /// Show a two-line player below the prompt instead of the status lines
+        #[arg(long)]
+        mini: bool,
+    },
 
     /// List song form the library from the configured path
-    List,
+    List {
+        /// One unaligned, uncolored line per song, for scripts (the default when piped)
+        #[arg(long)]
+        plain: bool,
+
+        /// Print only this page of the library, counting from 1
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+        page: Option<u32>,
+
+        /// Songs per page; implies --page 1 when given alone
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+        per_page: Option<u32>,
+    },
 
     /// Select a song from your library by index
     Select {
+        /// Song index, or a title to search for (title, artist, and album); the best match plays
+        #[arg(value_name = "INDEX|TITLE")]
+        song: String,
+
+        /// Load the song but start paused
+        #[arg(long, alias = "start-paused")]
+        paused: bool,
+
+        /// Play just this song and exit, without playback controls
+        #[arg(long)]
+        single: bool,
+    },
+
+    /// Save the embedded cover art of a library song to a file
+    Art {
         /// Song index
         index: usize,
+
+        /// File to write the image to
+        out: PathBuf,
+    },
+
+    /// Show every known metadata field for a library song
+    Info {
+        /// Song index
+        index: usize,
+
+        /// Print the metadata as JSON for scripting
+        #[arg(long)]
+        json: bool,
     },
 
     /// Search for songs in your library
     Search {
         /// Search query (searches title, artist, and album)
-        query: String,
+        #[arg(required_unless_present = "history")]
+        query: Option<String>,
+
+        /// List recent search queries, newest first, instead of searching
+        #[arg(long, conflicts_with = "query")]
+        history: bool,
+
+        /// Only list songs whose title, artist or album contains the query as typed, no fuzzy matching
+        #[arg(long)]
+        exact: bool,
+
+        /// Tell upper and lower case apart
+        #[arg(long)]
+        case_sensitive: bool,
+
+        /// Play every match, best match first, instead of listing them
+        #[arg(long, conflicts_with = "history")]
+        play: bool,
+
+        /// How to print the matches: text (default), paths or json
+        #[arg(long, value_name = "FORMAT", conflicts_with_all = ["history", "play"])]
+        output: Option<OutputFormat>,
+
+        /// Print only the matches' file paths, one per line; short for `--output paths`
+        #[arg(long, conflicts_with_all = ["history", "play", "output"])]
+        paths: bool,
+    },
+
+    /// List songs matching exact conditions, e.g. 'artist=Queen and duration>180'
+    Filter {
+        /// Conditions on title, artist, album, genre (=, !=, ~ contains) or duration, year,
+        /// track (=, !=, <, <=, >, >=), joined with 'and' / 'or'
+        expr: String,
+
+        /// Play the matches in library order instead of listing them
+        #[arg(long)]
+        play: bool,
     },
 
     /// Browse and play songs with interactive TUI
-    Browse,
+    Browse {
+        /// Color theme: dark, light, solarized, or custom (from theme.toml). Remembered for next time
+        #[arg(long)]
+        theme: Option<String>,
+
+        /// Filter the list by typing, without pressing `/` first. Letter shortcuts
+        /// are disabled while this is on; Ctrl+c quits
+        #[arg(long)]
+        quick_filter: bool,
+
+        /// Redraws per second while playing (default 60); lower saves power. Remembered for next time
+        #[arg(long, value_parser = clap::value_parser!(u16).range(i64::from(FPS_MIN)..=i64::from(FPS_MAX)))]
+        fps: Option<u16>,
+
+        /// Show a two-line player below the prompt instead of the full-screen browser.
+        /// Playback keys work; the song list is not shown
+        #[arg(long, conflicts_with = "quick_filter")]
+        mini: bool,
+    },
 
     /// Set volume between 0 and 100 (or show current if no argument)
     Volume {
@@ -59,6 +242,112 @@ pub enum Commands {
         volume: Option<u8>,
     },
 
+    /// Add a library song to the play queue (0-based index, as printed by `search`)
+    Enqueue {
+        /// Index of the song to queue
+        index: usize,
+    },
+
+    /// Remove every song from the play queue
+    QueueClear,
+
+    /// Find duplicate tracks (same path, or same title, artists and duration) and
+    /// remove all but the first copy from the library
+    Dedupe {
+        /// Only list the duplicate groups; change nothing
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Empty the library, play queue and play history. Files and music paths are kept,
+    /// so `refresh` rebuilds it
+    Clear {
+        /// Do not ask for confirmation
+        #[arg(long, short)]
+        yes: bool,
+    },
+
+    /// Remove a song from the library (0-based index, as printed by `search`).
+    /// Unless --delete-file is given, the file stays on disk and the next `refresh` adds it back
+    #[command(visible_alias = "delete")]
+    Remove {
+        /// Index of the song to remove
+        index: usize,
+
+        /// Also move the file to the system trash
+        #[arg(long)]
+        delete_file: bool,
+    },
+
+    /// Rate a library song (0-based index, as printed by `search`) and mark it as a favorite
+    Rate {
+        /// Index of the song to rate
+        index: usize,
+
+        /// Stars from 1 to 5; 0 clears the rating
+        #[arg(value_parser = clap::value_parser!(u8).range(0..=i64::from(RATING_MAX)))]
+        stars: u8,
+
+        /// Also mark the song as a favorite
+        #[arg(long, conflicts_with = "unfavorite")]
+        favorite: bool,
+
+        /// Also remove the song from the favorites
+        #[arg(long)]
+        unfavorite: bool,
+    },
+
+    /// Bookmark a position in a library song (0-based index), e.g. a chapter of an audiobook
+    Bookmark {
+        /// Index of the song
+        index: usize,
+
+        /// Position as seconds, m:ss or h:mm:ss
+        #[arg(value_parser = parse_position)]
+        position: Duration,
+
+        /// Delete the bookmark at this position instead
+        #[arg(long)]
+        remove: bool,
+    },
+
+    /// List bookmarked positions, of every song or of one
+    Bookmarks {
+        /// Index of the song
+        index: Option<usize>,
+    },
+
+    /// Toggle mute without losing the current volume level
+    Mute,
+
+    /// Overlap automatic track changes by this many seconds (0 disables crossfade)
+    Crossfade {
+        /// Crossfade length in seconds (0 - 12). Manual skips always cut immediately
+        #[arg(value_parser = clap::value_parser!(u8).range(0..=i64::from(CROSSFADE_MAX_SECS)))]
+        secs: u8,
+    },
+
+    /// Set the three-band equalizer (all 0 turns it off). Applies from the next track
+    #[command(allow_negative_numbers = true)]
+    Eq {
+        /// Bass gain in dB (-12 to 12)
+        #[arg(value_parser = clap::value_parser!(i8).range(-i64::from(EQ_MAX_DB)..=i64::from(EQ_MAX_DB)))]
+        bass: i8,
+        /// Mid gain in dB (-12 to 12)
+        #[arg(value_parser = clap::value_parser!(i8).range(-i64::from(EQ_MAX_DB)..=i64::from(EQ_MAX_DB)))]
+        mid: i8,
+        /// Treble gain in dB (-12 to 12)
+        #[arg(value_parser = clap::value_parser!(i8).range(-i64::from(EQ_MAX_DB)..=i64::from(EQ_MAX_DB)))]
+        treble: i8,
+    },
+
+    /// Toggle ReplayGain loudness normalization
+    Normalize {
+        /// Explicitly enable or disable (true/false). If omitted, toggles current state
+        #[arg(value_parser = clap::value_parser!(bool))]
+        enabled: Option<bool>,
+    },
+
     /// Toggle shuffle mode for playlist playback
     Shuffle {
         /// Explicitly set shuffle state (true/false). If omitted, toggles current state
@@ -73,7 +362,33 @@ pub enum Commands {
         mode: Option<RepeatMode>,
     },
 
-    /// List library songs sorted by a chosen field (default: title)
+    /// Continue with songs like the one that ended (same artist or album) instead of library order
+    Radio {
+        /// Radio mode: off, artist, album. If omitted, cycles to the next mode
+        #[arg(value_enum)]
+        mode: Option<RadioMode>,
+    },
+
+    /// Start playing the library like `playlist`, stopping after this many minutes.
+    /// While playing, `z` steps through timer presets and off
+    Sleep {
+        /// Minutes until playback stops (at least 1)
+        #[arg(value_parser = clap::value_parser!(u32).range(1..))]
+        minutes: u32,
+
+        /// Let the track playing at the deadline finish before stopping
+        #[arg(long)]
+        after_track: bool,
+    },
+
+    /// Play each track N times before moving on (1 = normal playback). Repeat-one still loops forever
+    RepeatCount {
+        /// How many times each track plays
+        #[arg(value_parser = clap::value_parser!(u32).range(1..))]
+        n: u32,
+    },
+
+    /// Sort the library by a chosen field (default: title) and print it. The order is saved
     Sort {
         /// Field to sort by
         #[arg(value_enum, default_value = "title")]
@@ -82,6 +397,137 @@ pub enum Commands {
 
     /// Show the current status of the player
     Status,
+
+    /// Pause the running `browse` or `playlist` session
+    Pause,
+
+    /// Resume the running `browse` or `playlist` session
+    Resume,
+
+    /// Pause or resume the running `browse` or `playlist` session, e.g. from a global hotkey
+    #[command(visible_alias = "playpause")]
+    Toggle,
+
+    /// Run a headless player in the background of this terminal, controlled with `remote`
+    Daemon,
+
+    /// Control the running `daemon`, `browse` or `playlist` session
+    Remote {
+        #[command(subcommand)]
+        action: RemoteAction,
+    },
+
+    /// List audio output devices, or pick the one to play through
+    Devices {
+        /// Device to use from now on, as listed; "" goes back to the system default
+        name: Option<String>,
+    },
+
+    /// Check the setup and print what was found: state files, library, music path,
+    /// audio output and playable formats. Useful when there is no sound
+    Doctor {
+        /// Print the report as JSON, e.g. to attach to a bug report
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Print a shell completion script, e.g. `hextune completions bash > ~/.local/share/bash-completion/completions/hextune`
+    Completions {
+        /// Shell to generate completions for
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+
+    /// Link a Last.fm account for scrobbling: allow hextune in the browser, and the session is stored in scrobble.toml
+    #[cfg(feature = "scrobble")]
+    ScrobbleAuth,
+
+    /// Show play counts: top tracks and artists, listening time and recent plays
+    Stats {
+        /// How many entries to list in each ranking
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+    },
+
+    /// List the last played tracks, newest first
+    Recent {
+        /// How many plays to list
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+
+        /// Forget the recently played list (play counts are kept)
+        #[arg(long)]
+        clear: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RemoteAction {
+    /// Play a library song (0-based index, as printed by `list`)
+    Play {
+        /// Song index
+        index: usize,
+    },
+
+    /// Skip to the next track
+    Next,
+
+    /// Go back to the previous track
+    Prev,
+
+    /// Skip to the first track of the next album
+    NextAlbum,
+
+    /// Go back to the first track of the previous album
+    PrevAlbum,
+
+    /// Pause playback
+    Pause,
+
+    /// Resume playback
+    Resume,
+
+    /// Pause or resume playback
+    Toggle,
+
+    /// Set the volume (0 - 100)
+    Volume {
+        #[arg(value_parser = clap::value_parser!(u8).range(0..=i64::from(VOLUME_MAX)))]
+        percent: u8,
+    },
+
+    /// Print the playback state as JSON
+    Status,
+
+    /// Stop the session
+    Quit,
+}
+
+impl ValueEnum for OutputFormat {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Self::Text, Self::Paths, Self::Json]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        match self {
+            Self::Text => Some(PossibleValue::new("text").help("Numbered lines for reading")),
+            Self::Paths => Some(PossibleValue::new("paths").help("One absolute file path per line")),
+            Self::Json => Some(PossibleValue::new("json").help("An array of song objects")),
+        }
+    }
+}
+
+impl ValueEnum for StorageFormat {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Self::Json, Self::Toml]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        match self {
+            Self::Json => Some(PossibleValue::new("json").help("<config dir>/hextune/db.json")),
+            Self::Toml => Some(PossibleValue::new("toml").help("<config dir>/hextune/db.toml")),
+        }
+    }
 }
 
 impl ValueEnum for RepeatMode {
@@ -96,4 +542,52 @@ impl ValueEnum for RepeatMode {
             Self::One => Some(PossibleValue::new("one").help("Repeat the current song")),
         }
     }
-}
\ No newline at end of file
+}
+
+impl ValueEnum for RadioMode {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Self::Off, Self::SameArtist, Self::SameAlbum]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        match self {
+            Self::Off => Some(PossibleValue::new("off").help("Follow the library order")),
+            Self::SameArtist => Some(PossibleValue::new("artist").help("Songs by the same artist")),
+            Self::SameAlbum => Some(PossibleValue::new("album").help("Songs from the same album")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn cli_definition_is_consistent() {
+        // Catches conflicting flags, unknown `conflicts_with` ids and duplicate names.
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn subcommands_parse_into_their_variants() {
+        let parse = |args: &[&str]| Cli::try_parse_from([APP_NAME].iter().chain(args)).map(|cli| cli.command.unwrap());
+
+        assert!(matches!(parse(&["volume", "40"]), Ok(Commands::Volume { volume: Some(40) })));
+        assert!(matches!(parse(&["shuffle", "true"]), Ok(Commands::Shuffle { .. })));
+        assert!(matches!(parse(&["loop", "one"]), Ok(Commands::Loop { .. })));
+        assert!(matches!(parse(&["radio", "artist"]), Ok(Commands::Radio { mode: Some(RadioMode::SameArtist) })));
+        assert!(matches!(parse(&["playpause"]), Ok(Commands::Toggle)));
+        assert!(parse(&["search", "x", "--history"]).is_err());
+        assert!(parse(&["sleep", "0"]).is_err());
+    }
+
+    #[test]
+    fn bare_invocation_leaves_the_command_to_the_settings() {
+        let cli = Cli::try_parse_from([APP_NAME, "--no-audio"]).unwrap();
+        assert!(cli.command.is_none() && cli.no_audio);
+
+        let err = Cli::try_parse_from([APP_NAME, "brwose"]).err().unwrap();
+        assert_eq!(err.kind(), clap::error::ErrorKind::InvalidSubcommand);
+    }
+}
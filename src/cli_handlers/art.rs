@@ -0,0 +1,41 @@
+use crate::cli_handlers::context::CliContext;
+use crate::cli_handlers::CliCommand;
+use crate::utils::APP_NAME;
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+
+pub struct ArtCommand {
+    pub index: usize,
+    pub out: PathBuf,
+}
+
+impl CliCommand for ArtCommand {
+    fn execute(self: Box<Self>) -> Result<()> {
+        let ctx = CliContext::load()?;
+
+        if ctx.state.library.songs.is_empty() {
+            ctx.ui.print_error(&format!("Library is empty. Run '{} refresh' first.", APP_NAME));
+            return Ok(());
+        }
+
+        let song = ctx.song_at(self.index)?;
+
+        let Some(cover) = song.album_art() else {
+            ctx.ui.print_message(&format!("'{}' has no embedded cover art.", song.title));
+            return Ok(());
+        };
+
+        std::fs::write(&self.out, &cover.data)
+            .with_context(|| format!("Failed to write cover to {}", self.out.display()))?;
+
+        ctx.ui.print_message(&format!(
+            "Saved cover of '{}' ({}, {} KB) to {}",
+            song.title,
+            cover.mime_type,
+            cover.data.len().div_ceil(1024),
+            self.out.display()
+        ));
+
+        Ok(())
+    }
+}
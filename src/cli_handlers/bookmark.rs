@@ -0,0 +1,108 @@
+use crate::cli_handlers::context::CliContext;
+use crate::cli_handlers::CliCommand;
+use crate::core::events::{AppEvent, LibraryEvent};
+use crate::modules::ui::progress_formatter::format_duration;
+use crate::utils::APP_NAME;
+use anyhow::Result;
+use std::time::Duration;
+
+pub struct BookmarkCommand {
+    pub index: usize,
+    pub position: Duration,
+    pub remove: bool,
+}
+
+impl CliCommand for BookmarkCommand {
+    fn execute(self: Box<Self>) -> Result<()> {
+        let ctx = CliContext::load()?;
+
+        if ctx.state.library.songs.is_empty() {
+            ctx.ui.print_error(&format!("Library is empty. Run '{} refresh' first.", APP_NAME));
+            return Ok(());
+        }
+
+        let song = ctx.song_at(self.index)?;
+        let path = song.path.clone();
+        let position = Duration::from_secs(self.position.as_secs());
+        if let Some(duration) = song.duration
+            && position >= duration
+        {
+            anyhow::bail!(
+                "{} is past the end of [{}] {}, which is {} long",
+                format_duration(position),
+                self.index,
+                song.title,
+                format_duration(duration)
+            );
+        }
+
+        let marks = ctx.state.library.bookmarks_for(&path);
+        let event = if self.remove {
+            if !marks.contains(&position) {
+                ctx.ui.print_error(&format!("[{}] {} has no bookmark at {}", self.index, song, format_duration(position)));
+                return Ok(());
+            }
+            ctx.ui.print_message(&format!("Removed the bookmark at {} in [{}] {}", format_duration(position), self.index, song));
+            LibraryEvent::BookmarkRemoved { path, position }
+        } else {
+            if marks.contains(&position) {
+                ctx.ui.print_message(&format!("[{}] {} is already bookmarked at {}", self.index, song, format_duration(position)));
+                return Ok(());
+            }
+            ctx.ui.print_message(&format!("Bookmarked [{}] {} at {}", self.index, song, format_duration(position)));
+            LibraryEvent::BookmarkAdded { path, position }
+        };
+
+        let mut app = CliContext::new_app(ctx)?;
+
+        app.init()?;
+        app.event_sender().send(AppEvent::Library(event))?;
+        app.run_once()?;
+        app.cleanup()?;
+
+        Ok(())
+    }
+}
+
+pub struct BookmarksCommand {
+    /// `None` lists the bookmarks of every song.
+    pub index: Option<usize>,
+}
+
+impl CliCommand for BookmarksCommand {
+    fn execute(self: Box<Self>) -> Result<()> {
+        let ctx = CliContext::load()?;
+        let library = &ctx.state.library;
+
+        let songs: Vec<usize> = match self.index {
+            Some(index) => {
+                ctx.song_at(index)?;
+                vec![index]
+            }
+            None => (0..library.songs.len()).collect(),
+        };
+        let bookmarked: Vec<(usize, &[Duration])> = songs
+            .into_iter()
+            .map(|index| (index, library.bookmarks_for(&library.songs[index].path)))
+            .filter(|(_, marks)| !marks.is_empty())
+            .collect();
+
+        if bookmarked.is_empty() {
+            ctx.ui.print_message(&format!(
+                "No bookmarks yet. Add one with '{} bookmark <INDEX> <POSITION>', or ';' while a track plays.",
+                APP_NAME
+            ));
+            return Ok(());
+        }
+
+        ctx.ui.print_message("─── Bookmarks ──────────────────────────");
+        for (index, marks) in bookmarked {
+            let positions: Vec<String> = marks.iter().map(|mark| format_duration(*mark)).collect();
+            ctx.ui.print_message(&format!("  [{}] {}", index, library.songs[index]));
+            ctx.ui.print_message(&format!("        {}", positions.join("  ")));
+        }
+        ctx.ui.print_message("────────────────────────────────────────");
+
+        Ok(())
+    }
+}
@@ -1,38 +1,78 @@
 use crate::application::app::Application;
 use crate::cli_handlers::CliCommand;
-use crate::modules::playback::rodio_backend::RodioBackend;
-use crate::modules::storage::json_backend::JsonStorageBackend;
+use crate::cli_handlers::context::attach_session_services;
+use crate::core::events::{AppEvent, LibraryEvent};
+use crate::modules::playback;
+use crate::modules::storage;
 use crate::modules::ui::terminal::renderer::TerminalRenderer;
 use crate::modules::ui::tui::renderer::TuiRenderer;
-use crate::utils::APP_NAME;
+use crate::modules::ui::tui::theme::{available_themes, Theme};
 use anyhow::Result;
-use crate::core::traits::StorageBackend;
 
-pub struct BrowseCommand;
+pub struct BrowseCommand {
+    pub theme: Option<String>,
+    pub quick_filter: bool,
+    pub fps: Option<u16>,
+    pub mini: bool,
+}
 
 impl CliCommand for BrowseCommand {
     fn execute(self: Box<Self>) -> Result<()> {
-        let storage = JsonStorageBackend::new()?;
-        let state = storage.load()?;
+        let storage = storage::open()?;
+        let mut state = storage.load()?;
 
-        if state.library.songs.is_empty() {
-            let ui = TerminalRenderer::new();
-            ui.print_error(&format!("Library is empty. Run '{} refresh' first.", APP_NAME));
-            return Ok(());
+        let custom_theme = dirs::config_dir().and_then(|dir| Theme::load_custom(&dir));
+
+        if let Some(theme) = self.theme {
+            let names = available_themes(custom_theme.is_some());
+            if !names.contains(&theme.as_str()) {
+                let ui = TerminalRenderer::new();
+                ui.print_error(&format!("Unknown theme '{}'. Available: {}", theme, names.join(", ")));
+                return Ok(());
+            }
+            // Saved like a pick in the settings modal, so the next session keeps it.
+            state.config.theme = Some(theme);
+            storage.save(&state)?;
+        }
+
+        if let Some(fps) = self.fps {
+            state.config.fps = fps;
+            storage.save(&state)?;
         }
 
         let mut tui_renderer = TuiRenderer::new();
         tui_renderer.set_songs(state.library.songs.clone());
+        tui_renderer.set_list_scroll(state.config.browse_scroll);
+        tui_renderer.set_custom_theme(custom_theme);
+        tui_renderer.set_quick_filter(self.quick_filter);
+        tui_renderer.set_compact(self.mini);
 
+        let backend = playback::open_backend(state.config.output_device.as_deref())?;
+        if let Some(meter) = backend.level_meter() {
+            tui_renderer.set_level_meter(meter);
+        }
         let mut app = Application::new()
-            .with_playback_backend(Box::new(RodioBackend::new()?))
-            .with_storage_backend(Box::new(storage))
+            .with_playback_backend(backend)
+            .with_storage_backend(storage)
             .with_ui_renderer(Box::new(tui_renderer));
 
+        let _control = attach_session_services(&mut app).unwrap_or_else(|e| {
+            eprintln!("Warning: remote control unavailable: {:#}", e);
+            None
+        });
+
         app.init()?;
+
+        // An empty library is scanned right away; with no music path set, the
+        // status line says how to add one in Settings.
+        if state.library.songs.is_empty() {
+            let roots = state.config.root_paths.clone();
+            app.event_sender().send(AppEvent::Library(LibraryEvent::ScanRequested { roots }))?;
+        }
+
         app.run()?;
         app.cleanup()?;
 
         Ok(())
     }
-}
\ No newline at end of file
+}
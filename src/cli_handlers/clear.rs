@@ -0,0 +1,38 @@
+use crate::cli_handlers::context::CliContext;
+use crate::cli_handlers::CliCommand;
+use crate::core::events::{AppEvent, LibraryEvent};
+use crate::utils::APP_NAME;
+use anyhow::Result;
+
+pub struct ClearCommand {
+    pub yes: bool,
+}
+
+impl CliCommand for ClearCommand {
+    fn execute(self: Box<Self>) -> Result<()> {
+        let ctx = CliContext::load()?;
+
+        let count = ctx.state.library.songs.len();
+        let question = format!(
+            "Remove all {} song{}, the play queue and the play history from the library? Files are kept.",
+            count,
+            if count == 1 { "" } else { "s" }
+        );
+        if !self.yes && !ctx.ui.confirm(&question)? {
+            ctx.ui.print_message("Nothing was cleared.");
+            return Ok(());
+        }
+
+        ctx.ui.print_message(&format!("✓ Library cleared. Run '{} refresh' to rebuild it.", APP_NAME));
+
+        let mut app = CliContext::new_app(ctx)?;
+
+        app.init()?;
+        app.event_sender()
+            .send(AppEvent::Library(LibraryEvent::LibraryCleared))?;
+        app.run_once()?;
+        app.cleanup()?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use crate::cli::Cli;
+use crate::cli_handlers::CliCommand;
+use crate::utils::APP_NAME;
+use anyhow::Result;
+use clap::CommandFactory;
+use clap_complete::Shell;
+use std::io::Write;
+
+pub struct CompletionsCommand {
+    pub shell: Shell,
+}
+
+impl CliCommand for CompletionsCommand {
+    fn execute(self: Box<Self>) -> Result<()> {
+        write_completions(self.shell, &mut std::io::stdout())?;
+        Ok(())
+    }
+}
+
+/// Write the completion script for `shell`. Subcommands, flags and flag values
+/// complete everywhere; bash and zsh also complete positional values such as repeat modes.
+fn write_completions(shell: Shell, out: &mut impl Write) -> Result<()> {
+    clap_complete::generate(shell, &mut Cli::command(), APP_NAME, out);
+    out.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::ValueEnum;
+
+    #[test]
+    fn every_shell_gets_a_script_with_value_completions() {
+        let script = |shell| {
+            let mut script = Vec::new();
+            write_completions(shell, &mut script).unwrap();
+            String::from_utf8(script).unwrap()
+        };
+
+        for &shell in Shell::value_variants() {
+            let script = script(shell);
+            assert!(script.contains("browse"), "{shell}: subcommands");
+            assert!(script.contains("start-title"), "{shell}: flags");
+            assert!(script.contains("toml"), "{shell}: --config-format values");
+        }
+        assert!(script(Shell::Bash).contains("off all one"), "repeat modes");
+    }
+}
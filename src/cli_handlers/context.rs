@@ -1,30 +1,138 @@
 use crate::application::state::AppState;
-use crate::core::traits::StorageBackend;
-use crate::modules::storage::json_backend::JsonStorageBackend;
+use crate::core::traits::{PlaybackBackend, StorageBackend};
+use crate::modules::storage;
 use crate::modules::ui::terminal::renderer::TerminalRenderer;
+use crate::modules::ui::tui::renderer::TuiRenderer;
+use crate::modules::ui::tui::theme::Theme;
 use anyhow::Result;
 use crate::application::app::Application;
-use crate::modules::playback::rodio_backend::RodioBackend;
+use crate::core::events::{AppEvent, PlaybackEvent, UiEvent};
+use crate::core::models::Song;
+use crate::modules::control::ControlServer;
+#[cfg(feature = "scrobble")]
+use crate::modules::scrobble::Scrobbler;
+use crate::modules::library::search_engine::SearchEngine;
+use crate::modules::playback;
+
+/// How `CliContext::run_session` starts playing.
+#[derive(Debug, Default)]
+pub struct SessionStart {
+    /// Library index of the song to play first.
+    pub index: usize,
+    /// Library indices for the play queue.
+    pub queue: Vec<usize>,
+    /// Load the first song paused.
+    pub paused: bool,
+    /// Stop playback after this many minutes; 0 leaves the sleep timer off.
+    pub sleep_minutes: u32,
+    pub after_track: bool,
+    /// Show the two-line mini player instead of the status lines.
+    pub mini: bool,
+}
 
 pub struct CliContext {
-    pub storage: JsonStorageBackend,
+    pub storage: Box<dyn StorageBackend>,
     pub state: AppState,
     pub ui: TerminalRenderer,
-    pub backend: RodioBackend,
+    pub backend: Box<dyn PlaybackBackend>,
 }
 
 impl CliContext {
     pub fn load() -> Result<Self> {
-        let storage = JsonStorageBackend::new()?;
+        let storage = storage::open()?;
         let state = storage.load()?;
+        let backend = playback::open_backend(state.config.output_device.as_deref())?;
         Ok(Self {
             storage,
             state,
             ui: TerminalRenderer::new(),
-            backend: RodioBackend::new()?,
+            backend,
         })
     }
 
+    /// Look up a library song by its 0-based index.
+    ///
+    /// Fails with a message that spells out the valid range, so every
+    /// index-taking command reports out-of-range input the same way.
+    pub fn song_at(&self, index: usize) -> Result<&Song> {
+        let songs = &self.state.library.songs;
+        songs.get(index).ok_or_else(|| anyhow::anyhow!(
+            "Invalid index {}. Library has {} songs (0-{}).",
+            index,
+            songs.len(),
+            songs.len().saturating_sub(1)
+        ))
+    }
+
+    /// Index of the library song best matching `query` (title, artist, album).
+    ///
+    /// Fails when nothing matches, or when several songs tie for the best match;
+    /// the tied songs are listed so the query can be refined.
+    pub fn song_matching(&self, query: &str) -> Result<usize> {
+        best_match(&self.state.library.songs, query).map_err(anyhow::Error::msg)
+    }
+
+    /// Run a playback session through `indices` in order: the first song plays
+    /// now and the rest go to the play queue, which plays ahead of library order.
+    pub fn play_in_order(self, indices: &[usize]) -> Result<()> {
+        let Some((&first, rest)) = indices.split_first() else {
+            return Ok(());
+        };
+        self.run_session(SessionStart { index: first, queue: rest.to_vec(), ..SessionStart::default() })
+    }
+
+    /// Run the interactive playback session every playing command shares: the
+    /// event loop with the terminal UI and remote control, until the user quits.
+    pub fn run_session(self, start: SessionStart) -> Result<()> {
+        let mut app = if start.mini { self.new_mini_app() } else { CliContext::new_app(self)? };
+
+        let _control = attach_session_services(&mut app).unwrap_or_else(|e| {
+            eprintln!("Warning: remote control unavailable: {:#}", e);
+            None
+        });
+
+        app.init()?;
+
+        // Read after `init`, which loads the library the session plays from.
+        let first_song = app.state().lock().unwrap().library.songs.get(start.index).cloned();
+        let first_song = first_song.ok_or_else(|| anyhow::anyhow!("Invalid index {}.", start.index))?;
+
+        // `Started` takes the playing index from the selection.
+        let events = app.event_sender();
+        events.send(AppEvent::Ui(UiEvent::SelectionChanged { index: start.index }))?;
+        events.send(AppEvent::Playback(PlaybackEvent::PlayRequested { song: first_song }))?;
+        if start.paused {
+            // Handled after the play, so its `Paused` lands after `Started`.
+            events.send(AppEvent::Ui(UiEvent::PauseRequested))?;
+        }
+        for index in start.queue {
+            events.send(AppEvent::Playback(PlaybackEvent::Enqueued { index }))?;
+        }
+        if start.sleep_minutes > 0 {
+            events.send(AppEvent::Playback(PlaybackEvent::SleepTimerSet {
+                minutes: start.sleep_minutes,
+                after_track: start.after_track,
+            }))?;
+        }
+
+        app.run()?;
+        app.cleanup()?;
+
+        Ok(())
+    }
+
+    /// Like `new_app`, with the TUI's mini player as the interface.
+    fn new_mini_app(self) -> Application {
+        let mut mini = TuiRenderer::new();
+        mini.set_compact(true);
+        mini.set_custom_theme(dirs::config_dir().and_then(|dir| Theme::load_custom(&dir)));
+
+        Application::new()
+            .with_playback_backend(self.backend)
+            .with_storage_backend(self.storage)
+            .with_ui_renderer(Box::new(mini))
+    }
+
     pub fn new_app<T>(context: T) -> Result<Application> where T: Into<Option<CliContext>> {
 
         let ctx = match context.into() {
@@ -33,8 +141,85 @@ impl CliContext {
         };
 
         Ok(Application::new()
-            .with_playback_backend(Box::new(ctx.backend))
-            .with_storage_backend(Box::new(ctx.storage))
+            .with_playback_backend(ctx.backend)
+            .with_storage_backend(ctx.storage)
             .with_ui_renderer(Box::new(ctx.ui)))
     }
 }
+
+/// Attach the services a playback session offers besides its UI: the control
+/// socket that lets `pause`, `remote` and friends reach it, and scrobbling when
+/// built in, which is a no-op without credentials in scrobble.toml. Playback
+/// works without either.
+///
+/// Returns the control server, which removes its socket when dropped: `None`
+/// when another session already listens, an error when the socket could not
+/// be set up. Scrobbling is attached either way.
+pub fn attach_session_services(app: &mut Application) -> Result<Option<ControlServer>> {
+    let control = ControlServer::start(app.event_sender(), app.state());
+
+    #[cfg(feature = "scrobble")]
+    if let Some(scrobbler) = dirs::config_dir().and_then(|dir| Scrobbler::start(&dir)) {
+        app.set_scrobble_backend(Box::new(scrobbler));
+    }
+
+    control
+}
+
+/// How many tied songs an ambiguous title query lists.
+const AMBIGUOUS_SHOWN: usize = 5;
+
+fn best_match(songs: &[Song], query: &str) -> Result<usize, String> {
+    let results = SearchEngine::new().search(songs, query);
+    let Some(best) = results.first() else {
+        return Err(format!("No song matches '{}'.", query));
+    };
+
+    let tied: Vec<_> = results.iter().take_while(|result| result.score == best.score).collect();
+    if tied.len() == 1 {
+        return Ok(best.index);
+    }
+
+    let mut message = format!("'{}' matches {} songs equally well; be more specific or use an index:", query, tied.len());
+    for result in tied.iter().take(AMBIGUOUS_SHOWN) {
+        message.push_str(&format!("\n  [{}] {}", result.index, result.song));
+    }
+    if tied.len() > AMBIGUOUS_SHOWN {
+        message.push_str(&format!("\n  ...and {} more", tied.len() - AMBIGUOUS_SHOWN));
+    }
+    Err(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn song(title: &str, artist: &str) -> Song {
+        Song {
+            title: title.to_string(),
+            artists: vec![artist.to_string()],
+            search_key: format!("{} {}", title, artist).to_lowercase(),
+            ..Song::from_path(Path::new("/m/x.mp3"))
+        }
+    }
+
+    #[test]
+    fn a_clear_title_query_picks_its_song() {
+        let songs = [song("Bohemian Rhapsody", "Queen"), song("Under Pressure", "Queen"), song("Heroes", "David Bowie")];
+
+        assert_eq!(best_match(&songs, "under pressure"), Ok(1));
+        assert_eq!(best_match(&songs, "heroes"), Ok(2));
+        assert!(best_match(&songs, "zzzz").unwrap_err().contains("No song matches"));
+    }
+
+    #[test]
+    fn tied_matches_are_listed_instead_of_guessed() {
+        let songs = [song("Intro", "Queen"), song("Intro", "Muse"), song("Outro", "Muse")];
+
+        let err = best_match(&songs, "intro").unwrap_err();
+
+        assert!(err.contains("matches 2 songs"), "{err}");
+        assert!(err.contains("[0]") && err.contains("[1]") && !err.contains("[2]"), "{err}");
+    }
+}
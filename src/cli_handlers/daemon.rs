@@ -0,0 +1,43 @@
+use crate::application::app::Application;
+use crate::cli_handlers::CliCommand;
+use crate::cli_handlers::context::{attach_session_services, CliContext};
+use crate::utils::APP_NAME;
+use anyhow::Result;
+
+/// Runs the player without a UI; the control socket (and MPRIS, when built in) is its only input.
+pub struct DaemonCommand;
+
+impl CliCommand for DaemonCommand {
+    fn execute(self: Box<Self>) -> Result<()> {
+        let ctx = CliContext::load()?;
+
+        if ctx.state.library.songs.is_empty() {
+            ctx.ui.print_error(&format!("Library is empty. Run '{} refresh' first.", APP_NAME));
+            return Ok(());
+        }
+
+        let ui = ctx.ui;
+        let mut app = Application::new()
+            .with_playback_backend(ctx.backend)
+            .with_storage_backend(ctx.storage);
+
+        let Some(control) = attach_session_services(&mut app)? else {
+            ui.print_error(&format!("Another {} session is already running.", APP_NAME));
+            return Ok(());
+        };
+        ui.print_message(&format!(
+            "Listening on {}. Control it with '{} remote <ACTION>'; '{} remote quit' stops it.",
+            control.path().display(),
+            APP_NAME,
+            APP_NAME
+        ));
+
+        app.init()?;
+        app.run()?;
+        app.cleanup()?;
+
+        // Removes the socket.
+        drop(control);
+        Ok(())
+    }
+}
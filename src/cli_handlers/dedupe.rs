@@ -0,0 +1,70 @@
+use crate::cli_handlers::context::CliContext;
+use crate::cli_handlers::CliCommand;
+use crate::core::events::{AppEvent, LibraryEvent};
+use crate::modules::library::dedupe::duplicate_groups;
+use crate::utils::APP_NAME;
+use anyhow::Result;
+
+pub struct DedupeCommand {
+    pub dry_run: bool,
+}
+
+impl CliCommand for DedupeCommand {
+    fn execute(self: Box<Self>) -> Result<()> {
+        let ctx = CliContext::load()?;
+        let songs = &ctx.state.library.songs;
+
+        if songs.is_empty() {
+            ctx.ui.print_error(&format!("Library is empty. Run '{} refresh' first.", APP_NAME));
+            return Ok(());
+        }
+
+        let groups = duplicate_groups(songs);
+        if groups.is_empty() {
+            ctx.ui.print_message("No duplicates found.");
+            return Ok(());
+        }
+
+        for group in &groups {
+            ctx.ui.print_message(&format!("  keep   [{}] {}", group[0], songs[group[0]]));
+            for &index in &group[1..] {
+                let label = if self.dry_run { "dup " } else { "drop" };
+                ctx.ui.print_message(&format!("  {} [{}] {}", label, index, songs[index]));
+            }
+        }
+
+        // Highest index first, so the indices still to remove stay valid.
+        let mut extra: Vec<usize> = groups.iter().flat_map(|g| g[1..].iter().copied()).collect();
+        extra.sort_unstable_by(|a, b| b.cmp(a));
+        let count = extra.len();
+
+        if self.dry_run {
+            ctx.ui.print_message(&format!(
+                "{} duplicate{} in {} group{}. Run without --dry-run to remove them from the library.",
+                count,
+                if count == 1 { "" } else { "s" },
+                groups.len(),
+                if groups.len() == 1 { "" } else { "s" },
+            ));
+            return Ok(());
+        }
+
+        ctx.ui.print_message(&format!(
+            "✓ Removed {} duplicate{} from the library (files are kept).",
+            count,
+            if count == 1 { "" } else { "s" }
+        ));
+
+        let mut app = CliContext::new_app(ctx)?;
+
+        app.init()?;
+        for index in extra {
+            app.event_sender()
+                .send(AppEvent::Library(LibraryEvent::SongRemoved { index }))?;
+            app.run_once()?;
+        }
+        app.cleanup()?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use crate::cli_handlers::CliCommand;
+use crate::modules::playback::rodio_backend::{is_named, RodioBackend};
+use crate::modules::storage;
+use crate::modules::ui::terminal::renderer::TerminalRenderer;
+use anyhow::Result;
+
+pub struct DevicesCommand {
+    pub name: Option<String>,
+}
+
+impl CliCommand for DevicesCommand {
+    fn execute(self: Box<Self>) -> Result<()> {
+        // No `CliContext`: it opens an output device, which may be the broken one.
+        let storage = storage::open()?;
+        let mut state = storage.load()?;
+        let ui = TerminalRenderer::new();
+        let names = RodioBackend::output_device_names()?;
+
+        match self.name {
+            None => {
+                if names.is_empty() {
+                    ui.print_error("No audio output devices found.");
+                    return Ok(());
+                }
+
+                let default = RodioBackend::default_device_name();
+                let selected = state.config.output_device.as_deref();
+                for name in &names {
+                    let mut tags = Vec::new();
+                    if default.as_deref() == Some(name.as_str()) {
+                        tags.push("default");
+                    }
+                    if selected.is_some_and(|s| is_named(name, s)) {
+                        tags.push("selected");
+                    }
+                    let tags = if tags.is_empty() { String::new() } else { format!(" ({})", tags.join(", ")) };
+                    ui.print_message(&format!("  {}{}", name, tags));
+                }
+
+                if let Some(selected) = selected
+                    && !names.iter().any(|n| is_named(n, selected))
+                {
+                    ui.print_error(&format!(
+                        "Saved device '{}' is not connected; the default device is used instead.",
+                        selected
+                    ));
+                }
+            }
+            Some(name) if name.is_empty() => {
+                state.config.output_device = None;
+                storage.save_config(&state)?;
+                ui.print_message("✓ Using the system default output device");
+            }
+            Some(name) => match names.iter().find(|n| is_named(n, &name)) {
+                Some(found) => {
+                    state.config.output_device = Some(found.clone());
+                    storage.save_config(&state)?;
+                    ui.print_message(&format!("✓ Output device set to: {}", found));
+                }
+                None => ui.print_error(&format!(
+                    "Unknown output device '{}'. Available: {}",
+                    name,
+                    names.join(", ")
+                )),
+            },
+        }
+
+        Ok(())
+    }
+}
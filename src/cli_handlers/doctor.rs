@@ -0,0 +1,157 @@
+use crate::application::state::AppState;
+use crate::cli_handlers::CliCommand;
+use crate::modules::playback::rodio_backend::RodioBackend;
+use crate::modules::storage::{self, StateFile};
+use crate::modules::ui::terminal::renderer::TerminalRenderer;
+use crate::utils::{APP_NAME, SUPPORTED_EXTENSIONS, UNPLAYABLE_EXTENSIONS};
+use anyhow::Result;
+
+pub struct DoctorCommand {
+    pub json: bool,
+}
+
+/// One line of the report.
+struct Check {
+    name: &'static str,
+    ok: bool,
+    detail: String,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self { name, ok: true, detail: detail.into() }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>) -> Self {
+        Self { name, ok: false, detail: detail.into() }
+    }
+}
+
+impl CliCommand for DoctorCommand {
+    fn execute(self: Box<Self>) -> Result<()> {
+        // Every check runs on its own, so a broken audio device or state file
+        // does not hide the rest of the report.
+        let mut checks = Vec::new();
+
+        let state = match storage::open() {
+            Ok(storage) => {
+                // Load first: a file found corrupted now is backed up during the load.
+                let loaded = storage.load();
+                for file in storage.files() {
+                    checks.extend(file_checks(&file));
+                }
+                match loaded {
+                    Ok(state) => {
+                        checks.push(Check::pass("State", "loaded"));
+                        Some(state)
+                    }
+                    Err(e) => {
+                        checks.push(Check::fail("State", format!("could not be loaded: {:#}", e)));
+                        None
+                    }
+                }
+            }
+            Err(e) => {
+                checks.push(Check::fail("Settings file", format!("{:#}", e)));
+                None
+            }
+        };
+
+        if let Some(state) = &state {
+            checks.push(library_check(state));
+            checks.push(root_path_check(state));
+        }
+
+        checks.push(audio_check(state.as_ref().and_then(|s| s.config.output_device.as_deref())));
+        checks.push(formats_check());
+
+        let ui = TerminalRenderer::new();
+        if self.json {
+            let report = serde_json::json!({
+                "version": env!("CARGO_PKG_VERSION"),
+                "ok": checks.iter().all(|c| c.ok),
+                "checks": checks
+                    .iter()
+                    .map(|c| serde_json::json!({ "name": c.name, "ok": c.ok, "detail": c.detail }))
+                    .collect::<Vec<_>>(),
+            });
+            ui.print_message(&serde_json::to_string_pretty(&report)?);
+        } else {
+            ui.print_message(&format!("─── {} doctor ({}) ─────────────────", APP_NAME, env!("CARGO_PKG_VERSION")));
+            for check in &checks {
+                let mark = if check.ok { "✓" } else { "✗" };
+                ui.print_message(&format!("  {} {:<13} {}", mark, check.name, check.detail));
+            }
+            ui.print_message("────────────────────────────────────────");
+        }
+
+        Ok(())
+    }
+}
+
+/// The file itself, plus a warning when an earlier run had to back it up.
+fn file_checks(file: &StateFile) -> Vec<Check> {
+    let mut checks = vec![if file.path.exists() {
+        Check::pass(file.label, file.path.display().to_string())
+    } else {
+        Check::pass(file.label, format!("{} (not created yet)", file.path.display()))
+    }];
+
+    if file.backup.exists() {
+        checks.push(Check::fail(
+            "Backup",
+            format!(
+                "{} was corrupted and reset to defaults; the old contents are in {}",
+                file.path.display(),
+                file.backup.display()
+            ),
+        ));
+    }
+
+    checks
+}
+
+fn library_check(state: &AppState) -> Check {
+    match state.library.songs.len() {
+        0 => Check::fail("Library", format!("empty; run '{} refresh'", APP_NAME)),
+        n => Check::pass("Library", format!("{} songs", n)),
+    }
+}
+
+fn root_path_check(state: &AppState) -> Check {
+    let roots = &state.config.root_paths;
+    if roots.is_empty() {
+        return Check::fail("Music path", format!("not set; run '{} path <DIR>'", APP_NAME));
+    }
+    match roots.iter().find(|path| !path.is_dir()) {
+        Some(path) => Check::fail("Music path", format!("{} is not a directory", path.display())),
+        None => Check::pass(
+            "Music path",
+            roots.iter().map(|path| path.display().to_string()).collect::<Vec<_>>().join(", "),
+        ),
+    }
+}
+
+fn audio_check(configured: Option<&str>) -> Check {
+    let device = configured.map(str::to_string).or_else(RodioBackend::default_device_name);
+
+    match (RodioBackend::new(configured), device) {
+        (Ok(_), Some(name)) => Check::pass("Audio output", name),
+        (Ok(_), None) => Check::pass("Audio output", "opened a fallback device (no default set)"),
+        (Err(e), Some(name)) => Check::fail("Audio output", format!("{} could not be opened: {:#}", name, e)),
+        (Err(e), None) => Check::fail("Audio output", format!("no output device found: {:#}", e)),
+    }
+}
+
+fn formats_check() -> Check {
+    let (playable, scan_only): (Vec<&str>, Vec<&str>) = SUPPORTED_EXTENSIONS
+        .iter()
+        .copied()
+        .partition(|ext| !UNPLAYABLE_EXTENSIONS.contains(ext));
+
+    let mut detail = format!("plays {}", playable.join(", "));
+    if !scan_only.is_empty() {
+        detail.push_str(&format!("; listed but not playable: {}", scan_only.join(", ")));
+    }
+    Check::pass("Formats", detail)
+}
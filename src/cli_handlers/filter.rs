@@ -0,0 +1,53 @@
+use crate::cli_handlers::context::CliContext;
+use crate::cli_handlers::CliCommand;
+use crate::modules::library::filter::Filter;
+use crate::utils::APP_NAME;
+use anyhow::Result;
+
+pub struct FilterCommand {
+    pub expr: String,
+    pub play: bool,
+}
+
+impl CliCommand for FilterCommand {
+    fn execute(self: Box<Self>) -> Result<()> {
+        let ctx = CliContext::load()?;
+
+        if ctx.state.library.songs.is_empty() {
+            ctx.ui.print_error(&format!("Library is empty. Run '{} refresh' first.", APP_NAME));
+            return Ok(());
+        }
+
+        let filter = match Filter::parse(&self.expr) {
+            Ok(filter) => filter,
+            Err(reason) => {
+                ctx.ui.print_error(&format!("Invalid filter '{}': {}", self.expr, reason));
+                return Ok(());
+            }
+        };
+
+        let matches: Vec<usize> = ctx
+            .state
+            .library
+            .songs
+            .iter()
+            .enumerate()
+            .filter(|(_, song)| filter.matches(song))
+            .map(|(i, _)| i)
+            .collect();
+
+        if !self.play {
+            let results: Vec<_> = matches.iter().map(|&i| (i, ctx.state.library.songs[i].clone())).collect();
+            ctx.ui.print_search_results(&self.expr, &results);
+            return Ok(());
+        }
+
+        if matches.is_empty() {
+            ctx.ui.print_message(&format!("No songs found matching: '{}'", self.expr));
+            return Ok(());
+        }
+        ctx.ui.print_message(&format!("Playing {} matching song{}", matches.len(), if matches.len() == 1 { "" } else { "s" }));
+
+        ctx.play_in_order(&matches)
+    }
+}
@@ -0,0 +1,47 @@
+use crate::cli_handlers::context::CliContext;
+use crate::cli_handlers::CliCommand;
+use crate::utils::APP_NAME;
+use anyhow::Result;
+
+pub struct InfoCommand {
+    pub index: usize,
+    pub json: bool,
+}
+
+impl CliCommand for InfoCommand {
+    fn execute(self: Box<Self>) -> Result<()> {
+        let ctx = CliContext::load()?;
+
+        if ctx.state.library.songs.is_empty() {
+            ctx.ui.print_error(&format!("Library is empty. Run '{} refresh' first.", APP_NAME));
+            return Ok(());
+        }
+
+        let song = ctx.song_at(self.index)?;
+
+        if self.json {
+            let info = serde_json::json!({
+                "index": self.index,
+                "path": song.path,
+                "title": song.title,
+                "artists": song.artists,
+                "album": song.album,
+                "track_number": song.track_number,
+                "duration_secs": song.duration.map(|d| d.as_secs()),
+                "genre": song.genre,
+                "year": song.year,
+                "bitrate_kbps": song.bitrate,
+                "sample_rate_hz": song.sample_rate,
+                "channels": song.channels,
+                "track_gain_db": song.track_gain_db,
+                "rating": song.rating,
+                "favorite": song.favorite,
+            });
+            ctx.ui.print_message(&serde_json::to_string_pretty(&info)?);
+        } else {
+            ctx.ui.print_song_info(self.index, song);
+        }
+
+        Ok(())
+    }
+}
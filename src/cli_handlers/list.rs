@@ -1,21 +1,67 @@
 use crate::cli_handlers::CliCommand;
-use crate::utils::APP_NAME;
+use crate::utils::{APP_NAME, LIST_PAGE_SIZE};
 use anyhow::Result;
 use crate::cli_handlers::context::CliContext;
+use std::ops::Range;
 
-pub struct ListCommand;
+pub struct ListCommand {
+    pub plain: bool,
+    pub page: Option<u32>,
+    pub per_page: Option<u32>,
+}
 
 impl CliCommand for ListCommand {
     fn execute(self: Box<Self>) -> Result<()> {
         let ctx = CliContext::load()?;
+        let songs = &ctx.state.library.songs;
 
-        if ctx.state.library.songs.is_empty() {
+        if songs.is_empty() {
             ctx.ui.print_error(&format!("Library is empty. Run '{} refresh' first.", APP_NAME));
             return Ok(());
         }
 
-        ctx.ui.print_song_list(&ctx.state.library.songs);
+        if self.page.is_none() && self.per_page.is_none() {
+            ctx.ui.print_song_list(songs, 0..songs.len(), self.plain);
+            return Ok(());
+        }
+
+        let page = self.page.unwrap_or(1) as usize;
+        let per_page = self.per_page.map_or(LIST_PAGE_SIZE, |n| n as usize);
+        let (range, pages) = page_range(songs.len(), page, per_page).map_err(anyhow::Error::msg)?;
+        ctx.ui.print_song_list(songs, range, self.plain);
+        println!("Page {} of {}", page, pages);
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// The library indices on 1-based `page` of `per_page` songs, and how many
+/// pages there are; an error when `page` is past the last one.
+fn page_range(len: usize, page: usize, per_page: usize) -> Result<(Range<usize>, usize), String> {
+    let pages = len.div_ceil(per_page).max(1);
+    if page == 0 || page > pages {
+        return Err(format!("Page {} does not exist; with {} songs per page the last page is {}.", page, per_page, pages));
+    }
+
+    let start = (page - 1) * per_page;
+    Ok((start..len.min(start + per_page), pages))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pages_slice_the_library_and_the_last_one_may_be_short() {
+        assert_eq!(page_range(120, 1, 50), Ok((0..50, 3)));
+        assert_eq!(page_range(120, 3, 50), Ok((100..120, 3)));
+        assert_eq!(page_range(100, 2, 50), Ok((50..100, 2)));
+        assert_eq!(page_range(3, 1, 50), Ok((0..3, 1)));
+    }
+
+    #[test]
+    fn pages_past_the_end_are_rejected() {
+        assert!(page_range(100, 3, 50).is_err());
+        assert!(page_range(120, 0, 50).is_err());
+    }
+}
@@ -1,29 +1,73 @@
+mod art;
+mod bookmark;
 mod browse;
+mod clear;
+mod completions;
+mod dedupe;
+mod devices;
+mod doctor;
+mod filter;
+mod info;
 mod list;
 mod path;
 mod play;
 mod playlist;
+mod queue;
+mod rate;
+mod recent;
 mod refresh;
+mod remote;
+mod remove;
+#[cfg(feature = "scrobble")]
+mod scrobble_auth;
 mod search;
 mod select;
 mod settings;
 mod sort;
+mod stats;
 mod status;
 mod context;
+mod daemon;
 
+pub use art::ArtCommand;
+pub use bookmark::{BookmarkCommand, BookmarksCommand};
 pub use browse::BrowseCommand;
+pub use clear::ClearCommand;
+pub use completions::CompletionsCommand;
+pub use daemon::DaemonCommand;
+pub use dedupe::DedupeCommand;
+pub use devices::DevicesCommand;
+pub use doctor::DoctorCommand;
+pub use filter::FilterCommand;
+pub use info::InfoCommand;
 pub use list::ListCommand;
-pub use path::PathCommand;
+pub use path::{PathAddCommand, PathCommand, PathListCommand, PathRemoveCommand};
 pub use play::PlayCommand;
 pub use playlist::PlaylistCommand;
+pub use queue::{EnqueueCommand, QueueClearCommand};
+pub use rate::RateCommand;
+pub use recent::RecentCommand;
 pub use refresh::RefreshCommand;
-pub use search::SearchCommand;
+pub use remote::RemoteCommand;
+pub use remove::RemoveCommand;
+#[cfg(feature = "scrobble")]
+pub use scrobble_auth::ScrobbleAuthCommand;
+pub use search::{OutputFormat, SearchCommand};
 pub use select::SelectCommand;
-pub use settings::{LoopCommand, ShuffleCommand, VolumeCommand};
+pub use settings::{
+    CrossfadeCommand, EqCommand, LoopCommand, MuteCommand, NormalizeCommand, RadioCommand, RepeatCountCommand,
+    ShuffleCommand, VolumeCommand,
+};
 pub use sort::SortCommand;
+pub use stats::StatsCommand;
 pub use status::StatusCommand;
 
-use crate::cli::Commands;
+use crate::cli::{Commands, RemoteAction};
+use crate::core::models::DefaultCommand;
+use crate::modules::control::ControlRequest;
+use crate::modules::library::search_engine::SearchOptions;
+use crate::modules::playback::equalizer::EqGains;
+use crate::modules::storage;
 use anyhow::Result;
 
 /// Every CLI command implements this trait.
@@ -38,18 +82,113 @@ pub trait CliCommand {
 /// Keeping this in one place means `main.rs` never needs to know about concrete command types.
 pub fn from_cli(cmd: Commands) -> Box<dyn CliCommand> {
     match cmd {
-        Commands::Browse => Box::new(BrowseCommand),
-        Commands::Play { file } => Box::new(PlayCommand { file }),
+        Commands::Browse { theme, quick_filter, fps, mini } => {
+            Box::new(BrowseCommand { theme, quick_filter, fps, mini })
+        }
+        Commands::Play { file, paused } => Box::new(PlayCommand { file, paused }),
         Commands::Path { directory } => Box::new(PathCommand { directory }),
-        Commands::Refresh => Box::new(RefreshCommand),
-        Commands::Playlist => Box::new(PlaylistCommand),
-        Commands::List => Box::new(ListCommand),
-        Commands::Select { index } => Box::new(SelectCommand { index }),
-        Commands::Search { query } => Box::new(SearchCommand { query }),
+        Commands::PathAdd { directory } => Box::new(PathAddCommand { directory }),
+        Commands::PathRemove { directory } => Box::new(PathRemoveCommand { directory }),
+        Commands::PathList => Box::new(PathListCommand),
+        Commands::Refresh { include, exclude, append, keep_missing, metadata_only } => {
+            Box::new(RefreshCommand { include, exclude, append, keep_missing, metadata_only })
+        }
+        Commands::Playlist { start, start_title, favorites, paused, mini } => Box::new(PlaylistCommand {
+            start,
+            start_title,
+            favorites,
+            paused,
+            sleep_minutes: 0,
+            after_track: false,
+            mini,
+        }),
+        Commands::Sleep { minutes, after_track } => Box::new(PlaylistCommand {
+            start: None,
+            start_title: None,
+            favorites: false,
+            paused: false,
+            sleep_minutes: minutes,
+            after_track,
+            mini: false,
+        }),
+        Commands::List { plain, page, per_page } => Box::new(ListCommand { plain, page, per_page }),
+        Commands::Select { song, paused, single } => Box::new(SelectCommand { song, paused, single }),
+        Commands::Art { index, out } => Box::new(ArtCommand { index, out }),
+        Commands::Info { index, json } => Box::new(InfoCommand { index, json }),
+        Commands::Search { query, history, exact, case_sensitive, play, output, paths } => Box::new(SearchCommand {
+            query,
+            history,
+            options: SearchOptions { exact, case_sensitive },
+            play,
+            output: if paths { OutputFormat::Paths } else { output.unwrap_or_default() },
+        }),
+        Commands::Filter { expr, play } => Box::new(FilterCommand { expr, play }),
         Commands::Volume { volume } => Box::new(VolumeCommand { volume }),
+        Commands::Enqueue { index } => Box::new(EnqueueCommand { index }),
+        Commands::QueueClear => Box::new(QueueClearCommand),
+        Commands::Dedupe { dry_run } => Box::new(DedupeCommand { dry_run }),
+        Commands::Clear { yes } => Box::new(ClearCommand { yes }),
+        Commands::Remove { index, delete_file } => Box::new(RemoveCommand { index, delete_file }),
+        Commands::Rate { index, stars, favorite, unfavorite } => Box::new(RateCommand {
+            index,
+            stars,
+            favorite: if favorite { Some(true) } else if unfavorite { Some(false) } else { None },
+        }),
+        Commands::Bookmark { index, position, remove } => Box::new(BookmarkCommand { index, position, remove }),
+        Commands::Bookmarks { index } => Box::new(BookmarksCommand { index }),
+        Commands::Mute => Box::new(MuteCommand),
+        Commands::Crossfade { secs } => Box::new(CrossfadeCommand { secs }),
+        Commands::Eq { bass, mid, treble } => Box::new(EqCommand { gains: EqGains::new(bass, mid, treble) }),
+        Commands::Normalize { enabled } => Box::new(NormalizeCommand { enabled }),
         Commands::Shuffle { enabled } => Box::new(ShuffleCommand { enabled }),
         Commands::Loop { mode } => Box::new(LoopCommand { mode }),
+        Commands::Radio { mode } => Box::new(RadioCommand { mode }),
+        Commands::RepeatCount { n } => Box::new(RepeatCountCommand { count: n }),
         Commands::Sort { by } => Box::new(SortCommand { field: by }),
         Commands::Status => Box::new(StatusCommand),
+        Commands::Pause => Box::new(RemoteCommand { request: ControlRequest::Pause }),
+        Commands::Resume => Box::new(RemoteCommand { request: ControlRequest::Resume }),
+        Commands::Toggle => Box::new(RemoteCommand { request: ControlRequest::Toggle }),
+        Commands::Daemon => Box::new(DaemonCommand),
+        Commands::Remote { action } => Box::new(RemoteCommand { request: remote_request(action) }),
+        Commands::Stats { top } => Box::new(StatsCommand { top }),
+        Commands::Recent { limit, clear } => Box::new(RecentCommand { limit, clear }),
+        Commands::Devices { name } => Box::new(DevicesCommand { name }),
+        Commands::Doctor { json } => Box::new(DoctorCommand { json }),
+        Commands::Completions { shell } => Box::new(CompletionsCommand { shell }),
+        #[cfg(feature = "scrobble")]
+        Commands::ScrobbleAuth => Box::new(ScrobbleAuthCommand),
     }
-}
\ No newline at end of file
+}
+
+/// The command a bare `hextune` runs, as chosen by `default_command` in the settings.
+pub fn default_command() -> Result<Commands> {
+    let state = storage::open()?.load()?;
+    Ok(match state.config.default_command {
+        DefaultCommand::Browse => Commands::Browse { theme: None, quick_filter: false, fps: None, mini: false },
+        DefaultCommand::Playlist => Commands::Playlist {
+            start: None,
+            start_title: None,
+            favorites: false,
+            paused: false,
+            mini: false,
+        },
+        DefaultCommand::List => Commands::List { plain: false, page: None, per_page: None },
+    })
+}
+
+fn remote_request(action: RemoteAction) -> ControlRequest {
+    match action {
+        RemoteAction::Play { index } => ControlRequest::Play { index },
+        RemoteAction::Next => ControlRequest::Next,
+        RemoteAction::Prev => ControlRequest::Previous,
+        RemoteAction::NextAlbum => ControlRequest::NextAlbum,
+        RemoteAction::PrevAlbum => ControlRequest::PreviousAlbum,
+        RemoteAction::Pause => ControlRequest::Pause,
+        RemoteAction::Resume => ControlRequest::Resume,
+        RemoteAction::Toggle => ControlRequest::Toggle,
+        RemoteAction::Volume { percent } => ControlRequest::Volume { percent },
+        RemoteAction::Status => ControlRequest::Status,
+        RemoteAction::Quit => ControlRequest::Quit,
+    }
+}
@@ -1,8 +1,7 @@
 use crate::cli_handlers::CliCommand;
-use crate::core::traits::StorageBackend;
 use crate::utils::APP_NAME;
 use anyhow::Result;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use crate::cli_handlers::context::CliContext;
 
 pub struct PathCommand {
@@ -13,17 +12,86 @@ impl CliCommand for PathCommand {
     fn execute(self: Box<Self>) -> Result<()> {
         let mut ctx = CliContext::load()?;
 
-        let path = self.directory.canonicalize()?;
-        if !path.is_dir() {
-            anyhow::bail!("The path provided is not a valid directory.");
-        }
+        let path = music_directory(&self.directory)?;
 
-        ctx.state.config.root_path = Some(path.clone());
-        ctx.storage.save(&ctx.state)?;
+        ctx.state.config.root_paths = vec![path.clone()];
+        ctx.storage.save_config(&ctx.state)?;
 
         ctx.ui.print_message(&format!("Music path updated to: {:?}", path));
         ctx.ui.print_message(&format!("Run '{} refresh' to scan for music files.", APP_NAME));
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+pub struct PathAddCommand {
+    pub directory: PathBuf,
+}
+
+impl CliCommand for PathAddCommand {
+    fn execute(self: Box<Self>) -> Result<()> {
+        let mut ctx = CliContext::load()?;
+
+        let path = music_directory(&self.directory)?;
+
+        if !ctx.state.config.add_root(path.clone()) {
+            ctx.ui.print_message(&format!("{:?} is already a music path.", path));
+            return Ok(());
+        }
+        ctx.storage.save_config(&ctx.state)?;
+
+        ctx.ui.print_message(&format!("Added music path: {:?}", path));
+        ctx.ui.print_message(&format!("Run '{} refresh' to scan for music files.", APP_NAME));
+
+        Ok(())
+    }
+}
+
+pub struct PathRemoveCommand {
+    pub directory: PathBuf,
+}
+
+impl CliCommand for PathRemoveCommand {
+    fn execute(self: Box<Self>) -> Result<()> {
+        let mut ctx = CliContext::load()?;
+
+        // A root whose directory is gone can still be removed by its listed path.
+        let path = self.directory.canonicalize().unwrap_or(self.directory);
+
+        if !ctx.state.config.remove_root(&path) {
+            ctx.ui.print_error(&format!("{:?} is not a music path. Run '{} path-list' to see them.", path, APP_NAME));
+            return Ok(());
+        }
+        ctx.storage.save_config(&ctx.state)?;
+
+        ctx.ui.print_message(&format!("Removed music path: {:?}", path));
+        ctx.ui.print_message(&format!("Run '{} refresh' to drop its songs from the library.", APP_NAME));
+
+        Ok(())
+    }
+}
+
+pub struct PathListCommand;
+
+impl CliCommand for PathListCommand {
+    fn execute(self: Box<Self>) -> Result<()> {
+        let ctx = CliContext::load()?;
+
+        if ctx.state.config.root_paths.is_empty() {
+            ctx.ui.print_message(&format!("No music path set. Run '{} path <DIR>' first.", APP_NAME));
+        }
+        for root in &ctx.state.config.root_paths {
+            ctx.ui.print_message(&root.display().to_string());
+        }
+
+        Ok(())
+    }
+}
+
+fn music_directory(directory: &Path) -> Result<PathBuf> {
+    let path = directory.canonicalize()?;
+    if !path.is_dir() {
+        anyhow::bail!("The path provided is not a valid directory.");
+    }
+    Ok(path)
+}
@@ -1,13 +1,15 @@
-use crate::cli_handlers::context::CliContext;
+use crate::application::state::{AppState, LibraryState, PlaybackState};
+use crate::cli_handlers::context::{CliContext, SessionStart};
 use crate::cli_handlers::CliCommand;
 use crate::core::models::Song;
-use crate::core::traits::PlaybackBackend;
-use crate::utils::CLI_PLAYBACK_POLL_MS;
+use crate::core::traits::StorageBackend;
 use anyhow::Result;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 pub struct PlayCommand {
     pub file: PathBuf,
+    pub paused: bool,
 }
 
 impl CliCommand for PlayCommand {
@@ -15,18 +17,84 @@ impl CliCommand for PlayCommand {
         let mut ctx = CliContext::load()?;
         let song = Song::from_path(&self.file);
 
-        ctx.ui.print_message(&format!("Playing: {}", song.title));
+        ctx.storage = Box::new(SingleSongStorage { inner: ctx.storage, song });
+        ctx.run_session(SessionStart { index: 0, paused: self.paused, ..SessionStart::default() })
+    }
+}
+
+/// Storage for a one-file session: the saved settings with a library of just
+/// `song`. Settings, bookmarks and resume positions are saved; the real
+/// library, queue and history are left as they are.
+struct SingleSongStorage {
+    inner: Box<dyn StorageBackend>,
+    song: Song,
+}
 
-        ctx.backend.set_volume(ctx.state.config.volume);
-        ctx.backend.play(&song)?;
+impl StorageBackend for SingleSongStorage {
+    fn load(&self) -> Result<AppState> {
+        let mut state = self.inner.load()?;
+        state.library = LibraryState {
+            songs: Arc::new(vec![self.song.clone()]),
+            bookmarks: std::mem::take(&mut state.library.bookmarks),
+            resume_positions: std::mem::take(&mut state.library.resume_positions),
+            ..LibraryState::default()
+        };
+        state.playback = PlaybackState::default();
+        Ok(state)
+    }
 
-        ctx.ui.print_message("Press Ctrl+C to stop");
-        while ctx.backend.is_playing() {
-            std::thread::sleep(std::time::Duration::from_millis(CLI_PLAYBACK_POLL_MS));
-        }
+    fn save(&self, state: &AppState) -> Result<()> {
+        self.inner.save_config(state)?;
+        self.save_library(state)
+    }
 
-        ctx.ui.print_message("✓ Playback finished");
+    fn save_config(&self, state: &AppState) -> Result<()> {
+        self.inner.save_config(state)
+    }
 
-        Ok(())
+    fn save_library(&self, state: &AppState) -> Result<()> {
+        let mut saved = self.inner.load()?;
+        saved.library.bookmarks = state.library.bookmarks.clone();
+        saved.library.resume_positions = state.library.resume_positions.clone();
+        self.inner.save_library(&saved)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::storage::json_backend::JsonStorageBackend;
+    use std::path::Path;
+
+    #[test]
+    fn a_one_file_session_leaves_the_library_alone() {
+        let dir = std::env::temp_dir().join(format!("hextune_play_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let inner = JsonStorageBackend::with_path(dir.join("config.json")).unwrap();
+        let mut saved = AppState::default();
+        saved.library.songs = Arc::new(vec![Song::from_path(Path::new("/m/a.mp3")), Song::from_path(Path::new("/m/b.mp3"))]);
+        saved.playback.queue.push_back(1);
+        inner.save(&saved).unwrap();
+
+        let file = dir.join("c.mp3");
+        std::fs::write(&file, b"").unwrap();
+        let storage = SingleSongStorage { inner: Box::new(inner), song: Song::from_path(&file) };
+        let mut session = storage.load().unwrap();
+        assert_eq!(session.library.songs.len(), 1);
+        assert_eq!(session.library.songs[0].title, "c");
+        assert!(session.playback.queue.is_empty(), "the queue points into the real library");
+
+        session.config.volume_percent = 40;
+        session.library.add_bookmark(&file, std::time::Duration::from_secs(75));
+        storage.save(&session).unwrap();
+
+        let reloaded = JsonStorageBackend::with_path(dir.join("config.json")).unwrap().load().unwrap();
+        let reopened = storage.load().unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(reloaded.config.volume_percent, 40);
+        assert_eq!(reloaded.library.songs.len(), 2);
+        assert_eq!(reloaded.playback.queue, [1]);
+        assert_eq!(reloaded.library.bookmarks_for(&file), [std::time::Duration::from_secs(75)]);
+        assert_eq!(reopened.library.bookmarks_for(&file).len(), 1, "bookmarks carry over to the next session");
+    }
+}
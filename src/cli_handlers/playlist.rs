@@ -1,10 +1,20 @@
 use crate::cli_handlers::CliCommand;
-use crate::core::events::{AppEvent, PlaybackEvent};
 use crate::utils::APP_NAME;
 use anyhow::Result;
-use crate::cli_handlers::context::CliContext;
-
-pub struct PlaylistCommand;
+use crate::cli_handlers::context::{CliContext, SessionStart};
+
+pub struct PlaylistCommand {
+    pub start: Option<usize>,
+    pub start_title: Option<String>,
+    /// Play the favorites first, through the play queue.
+    pub favorites: bool,
+    /// Load the first song paused.
+    pub paused: bool,
+    /// Stop playback after this many minutes; 0 leaves the sleep timer off.
+    pub sleep_minutes: u32,
+    pub after_track: bool,
+    pub mini: bool,
+}
 
 impl CliCommand for PlaylistCommand {
     fn execute(self: Box<Self>) -> Result<()> {
@@ -15,18 +25,32 @@ impl CliCommand for PlaylistCommand {
             return Ok(());
         }
 
-        let first_song = ctx.state.library.songs[0].clone();
-
-        let mut app = CliContext::new_app(ctx)?;
-
-        app.init()?;
-
-        app.event_sender()
-            .send(AppEvent::Playback(PlaybackEvent::PlayRequested { song: first_song }))?;
-
-        app.run()?;
-        app.cleanup()?;
-
-        Ok(())
+        let favorites: Vec<usize> = if self.favorites {
+            let songs = &ctx.state.library.songs;
+            let favorites: Vec<usize> = (0..songs.len()).filter(|&i| songs[i].favorite).collect();
+            if favorites.is_empty() {
+                ctx.ui.print_error("No favorites yet. Mark songs with 'f' in browse or with 'rate --favorite'.");
+                return Ok(());
+            }
+            favorites
+        } else {
+            Vec::new()
+        };
+
+        let start = match (&self.start, &self.start_title) {
+            (Some(index), _) => *index,
+            (None, Some(query)) => ctx.song_matching(query)?,
+            (None, None) => favorites.first().copied().unwrap_or(0),
+        };
+        ctx.song_at(start)?;
+
+        ctx.run_session(SessionStart {
+            index: start,
+            queue: favorites.iter().skip(1).copied().collect(),
+            paused: self.paused,
+            sleep_minutes: self.sleep_minutes,
+            after_track: self.after_track,
+            mini: self.mini,
+        })
     }
 }
\ No newline at end of file
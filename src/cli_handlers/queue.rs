@@ -0,0 +1,61 @@
+use crate::cli_handlers::context::CliContext;
+use crate::cli_handlers::CliCommand;
+use crate::core::events::{AppEvent, PlaybackEvent};
+use crate::utils::APP_NAME;
+use anyhow::Result;
+
+// ── Enqueue ───────────────────────────────────────────────────────────────────
+pub struct EnqueueCommand {
+    pub index: usize,
+}
+
+impl CliCommand for EnqueueCommand {
+    fn execute(self: Box<Self>) -> Result<()> {
+        let ctx = CliContext::load()?;
+
+        if ctx.state.library.songs.is_empty() {
+            ctx.ui.print_error(&format!("Library is empty. Run '{} refresh' first.", APP_NAME));
+            return Ok(());
+        }
+
+        let title = ctx.song_at(self.index)?.title.clone();
+        let position = ctx.state.playback.queue.len() + 1;
+        ctx.ui.print_message(&format!("Queued: {} (position {})", title, position));
+
+        let mut app = CliContext::new_app(ctx)?;
+
+        app.init()?;
+        app.event_sender()
+            .send(AppEvent::Playback(PlaybackEvent::Enqueued { index: self.index }))?;
+        app.run_once()?;
+        app.cleanup()?;
+
+        Ok(())
+    }
+}
+
+// ── Queue clear ───────────────────────────────────────────────────────────────
+pub struct QueueClearCommand;
+
+impl CliCommand for QueueClearCommand {
+    fn execute(self: Box<Self>) -> Result<()> {
+        let ctx = CliContext::load()?;
+
+        let count = ctx.state.playback.queue.len();
+        ctx.ui.print_message(&format!(
+            "Cleared {} queued song{}",
+            count,
+            if count == 1 { "" } else { "s" }
+        ));
+
+        let mut app = CliContext::new_app(ctx)?;
+
+        app.init()?;
+        app.event_sender()
+            .send(AppEvent::Playback(PlaybackEvent::QueueCleared))?;
+        app.run_once()?;
+        app.cleanup()?;
+
+        Ok(())
+    }
+}
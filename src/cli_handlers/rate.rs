@@ -0,0 +1,42 @@
+use crate::cli_handlers::context::CliContext;
+use crate::cli_handlers::CliCommand;
+use crate::core::events::{AppEvent, LibraryEvent};
+use crate::utils::{APP_NAME, RATING_MAX};
+use anyhow::Result;
+
+pub struct RateCommand {
+    pub index: usize,
+    /// 0 clears the rating.
+    pub stars: u8,
+    /// `None` leaves the favorite mark as it is.
+    pub favorite: Option<bool>,
+}
+
+impl CliCommand for RateCommand {
+    fn execute(self: Box<Self>) -> Result<()> {
+        let ctx = CliContext::load()?;
+
+        if ctx.state.library.songs.is_empty() {
+            ctx.ui.print_error(&format!("Library is empty. Run '{} refresh' first.", APP_NAME));
+            return Ok(());
+        }
+
+        let song = ctx.song_at(self.index)?;
+        let rating = (self.stars > 0).then_some(self.stars);
+        let favorite = self.favorite.unwrap_or(song.favorite);
+        ctx.ui.print_message(&match rating {
+            Some(stars) => format!("Rated [{}] {}: {}/{}", self.index, song, stars, RATING_MAX),
+            None => format!("Cleared the rating of [{}] {}", self.index, song),
+        });
+
+        let mut app = CliContext::new_app(ctx)?;
+
+        app.init()?;
+        app.event_sender()
+            .send(AppEvent::Library(LibraryEvent::RatingChanged { index: self.index, rating, favorite }))?;
+        app.run_once()?;
+        app.cleanup()?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+use crate::cli_handlers::context::CliContext;
+use crate::cli_handlers::CliCommand;
+use crate::core::events::{AppEvent, PlaybackEvent};
+use crate::modules::library::stats::{format_ago, recent_plays};
+use anyhow::Result;
+use std::time::SystemTime;
+
+pub struct RecentCommand {
+    pub limit: usize,
+    pub clear: bool,
+}
+
+impl CliCommand for RecentCommand {
+    fn execute(self: Box<Self>) -> Result<()> {
+        let ctx = CliContext::load()?;
+
+        if self.clear {
+            let count = ctx.state.history.recent.len();
+            ctx.ui.print_message(&format!(
+                "Cleared {} recent play{} (play counts are kept)",
+                count,
+                if count == 1 { "" } else { "s" }
+            ));
+
+            let mut app = CliContext::new_app(ctx)?;
+
+            app.init()?;
+            app.event_sender()
+                .send(AppEvent::Playback(PlaybackEvent::RecentPlaysCleared))?;
+            app.run_once()?;
+            app.cleanup()?;
+
+            return Ok(());
+        }
+
+        let songs = &ctx.state.library.songs;
+        let plays = recent_plays(songs, ctx.state.history.recent.iter(), self.limit);
+
+        if plays.is_empty() {
+            ctx.ui.print_message(&format!("No plays recorded yet. {}", ctx.state.config.play_threshold_note()));
+            return Ok(());
+        }
+
+        let now = SystemTime::now();
+        ctx.ui.print_message("─── Recently played ────────────────────");
+        for play in &plays {
+            let ago = format_ago(play.played_at, now);
+            match play.index {
+                Some(index) => ctx.ui.print_message(&format!("  {:>12}  [{}] {}", ago, index, songs[index])),
+                None => ctx.ui.print_message(&format!("  {:>12}  {} (missing)", ago, play.path.display())),
+            }
+        }
+        ctx.ui.print_message("────────────────────────────────────────");
+
+        Ok(())
+    }
+}
@@ -1,34 +1,96 @@
 use crate::cli_handlers::context::CliContext;
 use crate::cli_handlers::CliCommand;
-use crate::core::traits::StorageBackend;
-use crate::modules::library::scanner;
-use crate::utils::APP_NAME;
+use crate::modules::library::scanner::{self, ScanFilter};
+use crate::utils::{format_roots, APP_NAME};
 use anyhow::Result;
 use std::sync::Arc;
 
-pub struct RefreshCommand;
+pub struct RefreshCommand {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub append: bool,
+    pub keep_missing: bool,
+    pub metadata_only: bool,
+}
 
 impl CliCommand for RefreshCommand {
     fn execute(self: Box<Self>) -> Result<()> {
         let mut ctx = CliContext::load()?;
 
-        let root_path = ctx.state.config.root_path
-            .as_ref()
-            .ok_or_else(|| anyhow::anyhow!(
-                "No music path set. Run '{} path <DIR>' first.", APP_NAME
-            ))?
-            .clone();
+        if self.metadata_only {
+            if ctx.state.library.songs.is_empty() {
+                ctx.ui.print_error(&format!("Library is empty. Run '{} refresh' first.", APP_NAME));
+                return Ok(());
+            }
 
-        ctx.ui.print_message(&format!("Scanning {:?}...", root_path));
+            ctx.ui.print_message(&format!("Re-reading tags of {} songs...", ctx.state.library.songs.len()));
+            let refresh = scanner::refresh_metadata(&ctx.state.library.songs);
+            ctx.state.library.songs = Arc::new(refresh.songs);
+            ctx.storage.save_library(&ctx.state)?;
 
-        let songs = scanner::scan_directory(&root_path, |_| {})?;
-        let count = songs.len();
+            ctx.ui.print_message(&format!("✓ Metadata refreshed. {} songs changed.", refresh.changed));
+            if refresh.missing > 0 {
+                ctx.ui.print_message(&format!(
+                    "  Skipped {} songs whose files no longer exist; a full refresh removes them.",
+                    refresh.missing
+                ));
+            }
+            return Ok(());
+        }
 
-        ctx.state.library.songs = Arc::new(songs);
+        let roots = ctx.state.config.root_paths.clone();
+        if roots.is_empty() {
+            anyhow::bail!("No music path set. Run '{} path <DIR>' first.", APP_NAME);
+        }
+
+        // Patterns given here are saved, so rescans from the TUI use them too.
+        if !self.include.is_empty() {
+            ctx.state.config.scan_include = without_empty(self.include);
+        }
+        if !self.exclude.is_empty() {
+            ctx.state.config.scan_exclude = without_empty(self.exclude);
+        }
+        let config = &ctx.state.config;
+        let filter = ScanFilter::new(&config.scan_include, &config.scan_exclude)?;
+
+        ctx.ui.print_message(&format!("Scanning {}...", format_roots(&roots)));
+        if !config.scan_include.is_empty() {
+            ctx.ui.print_message(&format!("  Including: {}", config.scan_include.join(", ")));
+        }
+        if !config.scan_exclude.is_empty() {
+            ctx.ui.print_message(&format!("  Excluding: {}", config.scan_exclude.join(", ")));
+        }
+
+        let previous = Arc::clone(&ctx.state.library.songs);
+        let scanned = scanner::scan_roots(&roots, &previous, &filter, |_| {})?;
+
+        // A full rescan already leaves out deleted files; a merge keeps them until pruned here.
+        let (summary, (mut songs, missing)) = if self.append {
+            let merge = scanner::merge_scan(&previous, scanned);
+            (
+                format!("{} added, {} unchanged", merge.added, merge.unchanged),
+                scanner::split_missing(merge.songs),
+            )
+        } else {
+            let (_, missing) = scanner::split_missing(previous.to_vec());
+            (format!("Found {} songs", scanned.len()), (scanned, missing))
+        };
+
+        if self.keep_missing {
+            songs.extend(missing.iter().cloned());
+        }
+        ctx.state.replace_songs(songs);
         ctx.storage.save(&ctx.state)?;
 
-        ctx.ui.print_message(&format!("✓ Refresh complete. Found {} songs.", count));
+        ctx.ui.print_message(&format!("✓ Refresh complete. {}.", summary));
+        if !missing.is_empty() {
+            let action = if self.keep_missing { "Kept" } else { "Removed" };
+            ctx.ui.print_message(&format!("  {} {} songs whose files no longer exist.", action, missing.len()));
+        }
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+fn without_empty(patterns: Vec<String>) -> Vec<String> {
+    patterns.into_iter().filter(|p| !p.is_empty()).collect()
+}
@@ -0,0 +1,29 @@
+use crate::cli_handlers::CliCommand;
+use crate::modules::control::{self, ControlRequest};
+use crate::modules::ui::terminal::renderer::TerminalRenderer;
+use crate::utils::APP_NAME;
+use anyhow::Result;
+
+/// Sends a request to the running session. Silent on success so it suits
+/// hotkeys; `status` prints the session's JSON reply.
+pub struct RemoteCommand {
+    pub request: ControlRequest,
+}
+
+impl CliCommand for RemoteCommand {
+    fn execute(self: Box<Self>) -> Result<()> {
+        let ui = TerminalRenderer::new();
+        match control::send(self.request)? {
+            None => ui.print_error(&format!(
+                "No running {} session. Start one with '{} daemon', '{} browse' or '{} playlist'.",
+                APP_NAME, APP_NAME, APP_NAME, APP_NAME
+            )),
+            Some(reply) if reply == "ok" => {}
+            Some(reply) => match serde_json::from_str::<serde_json::Value>(&reply) {
+                Ok(json) => ui.print_message(&serde_json::to_string_pretty(&json)?),
+                Err(_) => ui.print_message(&reply),
+            },
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use crate::cli_handlers::context::CliContext;
+use crate::cli_handlers::CliCommand;
+use crate::core::events::{AppEvent, LibraryEvent};
+use crate::utils::APP_NAME;
+use anyhow::{Context, Result};
+
+pub struct RemoveCommand {
+    pub index: usize,
+    pub delete_file: bool,
+}
+
+impl CliCommand for RemoveCommand {
+    fn execute(self: Box<Self>) -> Result<()> {
+        let ctx = CliContext::load()?;
+
+        if ctx.state.library.songs.is_empty() {
+            ctx.ui.print_error(&format!("Library is empty. Run '{} refresh' first.", APP_NAME));
+            return Ok(());
+        }
+
+        let song = ctx.song_at(self.index)?.clone();
+
+        if self.delete_file {
+            trash::delete(&song.path)
+                .with_context(|| format!("Could not move {} to the trash", song.path.display()))?;
+            ctx.ui.print_message(&format!("Moved to trash: {}", song.path.display()));
+        }
+        ctx.ui.print_message(&format!("Removed from library: [{}] {}", self.index, song));
+
+        let mut app = CliContext::new_app(ctx)?;
+
+        app.init()?;
+        app.event_sender()
+            .send(AppEvent::Library(LibraryEvent::SongRemoved { index: self.index }))?;
+        app.run_once()?;
+        app.cleanup()?;
+
+        Ok(())
+    }
+}
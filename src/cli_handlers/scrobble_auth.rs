@@ -0,0 +1,38 @@
+use crate::cli_handlers::CliCommand;
+use crate::modules::scrobble::{self, lastfm, ScrobbleConfig};
+use crate::modules::ui::terminal::renderer::TerminalRenderer;
+use anyhow::{Context, Result};
+
+/// Links a Last.fm account: the user authorizes a request token in the browser,
+/// then the session key it unlocks is stored in `scrobble.toml`.
+pub struct ScrobbleAuthCommand;
+
+impl CliCommand for ScrobbleAuthCommand {
+    fn execute(self: Box<Self>) -> Result<()> {
+        let ui = TerminalRenderer::new();
+        let config_dir = dirs::config_dir().context("No config directory on this system")?;
+        let config_path = scrobble::config_path(&config_dir);
+
+        let Some(config) = ScrobbleConfig::load(&config_dir).lastfm else {
+            ui.print_error(&format!(
+                "Add a [lastfm] section with api_key and api_secret to '{}' first. Get them at {}",
+                config_path.display(),
+                lastfm::API_ACCOUNT_URL
+            ));
+            return Ok(());
+        };
+
+        let client = lastfm::LastFmClient::new(&config.api_key, &config.api_secret);
+        let token = client.request_token()?;
+        ui.print_message(&format!("Open this page and allow access:\n  {}", client.auth_url(&token)));
+        if !ui.confirm("Done?")? {
+            ui.print_message("Last.fm was not linked.");
+            return Ok(());
+        }
+
+        let session = client.request_session(&token)?;
+        scrobble::save_session_key(&config_dir, &session.key)?;
+        ui.print_message(&format!("✓ Scrobbling to Last.fm as {}.", session.name));
+        Ok(())
+    }
+}
@@ -1,28 +1,140 @@
 use crate::cli_handlers::context::CliContext;
 use crate::cli_handlers::CliCommand;
-use crate::modules::library::search_engine::SearchEngine;
+use crate::modules::library::search_engine::{SearchEngine, SearchOptions};
+use crate::core::models::Song;
 use crate::utils::APP_NAME;
 use anyhow::Result;
+use std::path::PathBuf;
+
+/// How `search` prints its matches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// `[index] Artist - Title [m:ss]` lines under a match count
+    #[default]
+    Text,
+    /// One absolute file path per line and nothing else, for piping
+    Paths,
+    /// An array of song objects
+    Json,
+}
 
 pub struct SearchCommand {
-    pub query: String,
+    /// Always set unless `history` is.
+    pub query: Option<String>,
+    pub history: bool,
+    pub options: SearchOptions,
+    pub play: bool,
+    pub output: OutputFormat,
 }
 
 impl CliCommand for SearchCommand {
     fn execute(self: Box<Self>) -> Result<()> {
-        let ctx = CliContext::load()?;
+        let mut ctx = CliContext::load()?;
+
+        if self.history {
+            if ctx.state.config.search_history.is_empty() {
+                ctx.ui.print_message("No searches yet.");
+            }
+            for query in ctx.state.config.search_history.iter() {
+                ctx.ui.print_message(query);
+            }
+            return Ok(());
+        }
+        let query = self.query.unwrap_or_default();
 
         if ctx.state.library.songs.is_empty() {
             ctx.ui.print_error(&format!("Library is empty. Run '{} refresh' first.", APP_NAME));
             return Ok(());
         }
 
-        let search_engine = SearchEngine::new();
-        let results = search_engine.search(&ctx.state.library.songs, &self.query);
+        let search_engine = SearchEngine::new().with_options(self.options);
+        let results = search_engine.search(&ctx.state.library.songs, &query);
         let indexed = search_engine.search_result_to_song_index(results);
 
-        ctx.ui.print_search_results(&self.query, &indexed);
+        match self.output {
+            OutputFormat::Text => {}
+            // Scripted searches stay out of the search history.
+            OutputFormat::Paths => {
+                let (lines, skipped) = path_lines(&indexed);
+                for line in &lines {
+                    ctx.ui.print_message(line);
+                }
+                for path in skipped {
+                    ctx.ui.print_error(&format!("Skipped {:?}: the path contains a line break", path));
+                }
+                return Ok(());
+            }
+            OutputFormat::Json => {
+                let songs: Vec<serde_json::Value> = indexed.iter().map(|(index, song)| song_json(*index, song)).collect();
+                ctx.ui.print_message(&serde_json::to_string_pretty(&songs)?);
+                return Ok(());
+            }
+        }
+
+        ctx.state.config.record_search(&query);
+        ctx.storage.save_config(&ctx.state)?;
+
+        if !self.play {
+            ctx.ui.print_search_results(&query, &indexed);
+            return Ok(());
+        }
+
+        if indexed.is_empty() {
+            ctx.ui.print_message(&format!("No songs found matching: '{}'", query));
+            return Ok(());
+        }
+        ctx.ui.print_message(&format!("Playing {} matching song{}", indexed.len(), if indexed.len() == 1 { "" } else { "s" }));
 
-        Ok(())
+        // Results come best match first, which becomes the play order.
+        let order: Vec<usize> = indexed.iter().map(|(index, _)| *index).collect();
+        ctx.play_in_order(&order)
     }
-}
\ No newline at end of file
+}
+/// The absolute paths of `results`, one line each, in match order. Paths that
+/// would break the one-per-line format are returned apart instead.
+fn path_lines(results: &[(usize, Song)]) -> (Vec<String>, Vec<PathBuf>) {
+    let mut lines = Vec::with_capacity(results.len());
+    let mut skipped = Vec::new();
+    for (_, song) in results {
+        let path = std::path::absolute(&song.path).unwrap_or_else(|_| song.path.clone());
+        let line = path.display().to_string();
+        if line.contains(['\n', '\r']) {
+            skipped.push(path);
+        } else {
+            lines.push(line);
+        }
+    }
+    (lines, skipped)
+}
+
+fn song_json(index: usize, song: &Song) -> serde_json::Value {
+    serde_json::json!({
+        "index": index,
+        "title": song.title,
+        "artists": song.artists,
+        "album": song.album,
+        "path": song.path,
+        "duration_secs": song.duration.map(|d| d.as_secs()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn path_lines_are_the_matched_paths_in_order() {
+        let titled = |path: &str, title: &str| Song { title: title.to_string(), ..Song::from_path(Path::new(path)) };
+        let results = vec![
+            (4, titled("/music/b.mp3", "Two\nlines")),
+            (1, titled("/music/a.mp3", "A")),
+            (2, titled("/music/odd\nname.mp3", "Odd")),
+        ];
+
+        let (lines, skipped) = path_lines(&results);
+
+        assert_eq!(lines, ["/music/b.mp3", "/music/a.mp3"], "a newline in a title does not matter");
+        assert_eq!(skipped, [PathBuf::from("/music/odd\nname.mp3")]);
+    }
+}
@@ -1,12 +1,15 @@
-use crate::cli_handlers::context::CliContext;
+use crate::cli_handlers::context::{CliContext, SessionStart};
 use crate::cli_handlers::CliCommand;
-use crate::core::traits::PlaybackBackend;
 use crate::utils::APP_NAME;
 use crate::utils::CLI_PLAYBACK_POLL_MS;
 use anyhow::Result;
 
 pub struct SelectCommand {
-    pub index: usize,
+    /// A library index, or a title query when it is not a number.
+    pub song: String,
+    pub paused: bool,
+    /// Play just this song straight through the backend, then exit.
+    pub single: bool,
 }
 
 impl CliCommand for SelectCommand {
@@ -18,18 +21,30 @@ impl CliCommand for SelectCommand {
             return Ok(());
         }
 
-        let song = ctx.state.library.songs.get(self.index)
-            .ok_or_else(|| anyhow::anyhow!(
-                "Invalid index {}. Library has {} songs (0-{}).",
-                self.index,
-                ctx.state.library.songs.len(),
-                ctx.state.library.songs.len() - 1
-            ))?;
+        let index = match self.song.parse::<usize>() {
+            Ok(index) => index,
+            Err(_) => {
+                let index = ctx.song_matching(&self.song)?;
+                ctx.ui.print_message(&format!("Selected [{}] {}", index, ctx.state.library.songs[index]));
+                index
+            }
+        };
+        let song = ctx.song_at(index)?.clone();
+
+        if !self.single {
+            return ctx.run_session(SessionStart { index, paused: self.paused, ..SessionStart::default() });
+        }
 
         ctx.ui.print_message(&format!("Playing: {}", song.title));
 
-        ctx.backend.set_volume(ctx.state.config.volume);
-        ctx.backend.play(song)?;
+        ctx.backend.set_volume(ctx.state.config.effective_volume());
+        ctx.backend.set_normalization(ctx.state.config.normalize_volume);
+        ctx.backend.set_equalizer(ctx.state.config.eq);
+        ctx.backend.play(&song)?;
+        if self.paused {
+            ctx.backend.pause();
+            ctx.ui.print_message("Paused");
+        }
 
         ctx.ui.print_message("Press Ctrl+C to stop");
         while ctx.backend.is_playing() {
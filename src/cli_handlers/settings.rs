@@ -1,9 +1,10 @@
 use crate::cli_handlers::context::CliContext;
 use crate::cli_handlers::CliCommand;
 use crate::core::events::{AppEvent, PlaybackEvent, UiEvent};
-use crate::core::models::RepeatMode;
+use crate::core::models::{RadioMode, RepeatMode};
+use crate::modules::playback::equalizer::EqGains;
 use crate::modules::ui::terminal::renderer::TerminalRenderer;
-use crate::utils::{amplitude_to_volume, volume_percent_to_amplitude};
+use crate::utils::volume_percent_to_amplitude;
 use anyhow::Result;
 
 // ── Volume ────────────────────────────────────────────────────────────────────
@@ -31,7 +32,7 @@ impl CliCommand for VolumeCommand {
                 ui.print_message(&format!("Volume set to: {}%", vol));
             }
             None => {
-                let current_percent = amplitude_to_volume(ctx.state.config.volume);
+                let current_percent = ctx.state.config.volume_percent;
                 ctx.ui.print_message(&format!("Current volume: {}%", current_percent));
             }
         }
@@ -40,6 +41,113 @@ impl CliCommand for VolumeCommand {
     }
 }
 
+// ── Mute ──────────────────────────────────────────────────────────────────────
+pub struct MuteCommand;
+
+impl CliCommand for MuteCommand {
+    fn execute(self: Box<Self>) -> Result<()> {
+        let ctx = CliContext::load()?;
+
+        if ctx.state.config.is_muted() {
+            let percent = ctx.state.config.volume_percent;
+            ctx.ui.print_message(&format!("Unmuted (volume {}%)", percent));
+        } else {
+            ctx.ui.print_message("Muted");
+        }
+
+        let mut app = CliContext::new_app(ctx)?;
+
+        app.init()?;
+        app.event_sender()
+            .send(AppEvent::Ui(UiEvent::MuteToggled))?;
+        app.run_once()?;
+        app.cleanup()?;
+
+        Ok(())
+    }
+}
+
+// ── Crossfade ─────────────────────────────────────────────────────────────────
+pub struct CrossfadeCommand {
+    pub secs: u8,
+}
+
+impl CliCommand for CrossfadeCommand {
+    fn execute(self: Box<Self>) -> Result<()> {
+        let ctx = CliContext::load()?;
+
+        if self.secs == 0 {
+            ctx.ui.print_message("Crossfade disabled");
+        } else {
+            ctx.ui.print_message(&format!(
+                "Crossfade set to: {}s (automatic track changes only)",
+                self.secs
+            ));
+        }
+
+        let mut app = CliContext::new_app(ctx)?;
+
+        app.init()?;
+        app.event_sender()
+            .send(AppEvent::Ui(UiEvent::CrossfadeChangeRequested { secs: self.secs }))?;
+        app.run_once()?;
+        app.cleanup()?;
+
+        Ok(())
+    }
+}
+
+// ── Equalizer ─────────────────────────────────────────────────────────────────
+pub struct EqCommand {
+    pub gains: EqGains,
+}
+
+impl CliCommand for EqCommand {
+    fn execute(self: Box<Self>) -> Result<()> {
+        let ctx = CliContext::load()?;
+
+        if self.gains.is_flat() {
+            ctx.ui.print_message("Equalizer disabled");
+        } else {
+            ctx.ui.print_message(&format!("Equalizer set to: {}", self.gains));
+        }
+
+        let mut app = CliContext::new_app(ctx)?;
+
+        app.init()?;
+        app.event_sender()
+            .send(AppEvent::Ui(UiEvent::EqChangeRequested { gains: self.gains }))?;
+        app.run_once()?;
+        app.cleanup()?;
+
+        Ok(())
+    }
+}
+
+// ── Normalize ─────────────────────────────────────────────────────────────────
+pub struct NormalizeCommand {
+    pub enabled: Option<bool>,
+}
+
+impl CliCommand for NormalizeCommand {
+    fn execute(self: Box<Self>) -> Result<()> {
+        let ctx = CliContext::load()?;
+
+        let new_state = self.enabled.unwrap_or(!ctx.state.config.normalize_volume);
+        ctx.ui.print_message(&format!("Normalization set to: {}", new_state));
+
+        let mut app = CliContext::new_app(ctx)?;
+
+        app.init()?;
+        app.event_sender()
+            .send(AppEvent::Ui(UiEvent::NormalizeSet { enabled: new_state }))?;
+        app.run_once()?;
+        app.cleanup()?;
+
+        Ok(())
+    }
+}
+
 // ── Shuffle ───────────────────────────────────────────────────────────────────
 pub struct ShuffleCommand {
     pub enabled: Option<bool>,
@@ -91,6 +199,59 @@ impl CliCommand for LoopCommand {
     }
 }
 
+// ── Radio ─────────────────────────────────────────────────────────────────────
+pub struct RadioCommand {
+    pub mode: Option<RadioMode>,
+}
+
+impl CliCommand for RadioCommand {
+    fn execute(self: Box<Self>) -> Result<()> {
+        let ctx = CliContext::load()?;
+
+        let new_mode = self.mode.unwrap_or_else(|| ctx.state.config.radio.cycle());
+        ctx.ui.print_message(&format!("Radio mode set to: {}", new_mode.label()));
+
+        let mut app = CliContext::new_app(ctx)?;
+
+        app.init()?;
+        app.event_sender()
+            .send(AppEvent::Playback(PlaybackEvent::RadioChanged { mode: new_mode }))?;
+        app.run_once()?;
+        app.cleanup()?;
+
+        Ok(())
+    }
+}
+
+// ── Repeat count ──────────────────────────────────────────────────────────────
+pub struct RepeatCountCommand {
+    pub count: u32,
+}
+
+impl CliCommand for RepeatCountCommand {
+    fn execute(self: Box<Self>) -> Result<()> {
+        let ctx = CliContext::load()?;
+
+        match self.count {
+            1 => ctx.ui.print_message("Each track plays once"),
+            n => ctx.ui.print_message(&format!("Each track plays {} times before moving on", n)),
+        }
+        if ctx.state.config.repeat == RepeatMode::One {
+            ctx.ui.print_message("Note: repeat mode is 'one', which keeps looping the same track");
+        }
+
+        let mut app = CliContext::new_app(ctx)?;
+
+        app.init()?;
+        app.event_sender()
+            .send(AppEvent::Playback(PlaybackEvent::RepeatCountChanged { count: self.count }))?;
+        app.run_once()?;
+        app.cleanup()?;
+
+        Ok(())
+    }
+}
+
 /// Human-readable label used in terminal feedback messages
 fn repeat_mode_description(mode: RepeatMode) -> &'static str {
     match mode {
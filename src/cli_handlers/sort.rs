@@ -1,5 +1,6 @@
 use crate::cli_handlers::CliCommand;
-use crate::modules::library::sorter::{sort_songs, SortField};
+use crate::core::events::{AppEvent, LibraryEvent};
+use crate::modules::library::sorter::SortField;
 use crate::utils::APP_NAME;
 use anyhow::Result;
 use crate::cli_handlers::context::CliContext;
@@ -17,9 +18,18 @@ impl CliCommand for SortCommand {
             return Ok(());
         }
 
-        let sorted = sort_songs(&ctx.state.library.songs, self.field);
-        ctx.ui.print_song_list_refs(&sorted);
+        let mut app = CliContext::new_app(ctx)?;
+
+        app.init()?;
+        app.event_sender()
+            .send(AppEvent::Library(LibraryEvent::SortRequested { field: Some(self.field) }))?;
+        app.run_once()?;
+        app.cleanup()?;
+
+        // Reload so the listing reflects exactly what was saved.
+        let ctx = CliContext::load()?;
+        ctx.ui.print_song_list(&ctx.state.library.songs, 0..ctx.state.library.songs.len(), false);
 
         Ok(())
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,65 @@
+use crate::cli_handlers::context::CliContext;
+use crate::cli_handlers::CliCommand;
+use crate::modules::library::stats::{format_ago, listening_stats, most_skipped};
+use anyhow::Result;
+use std::time::SystemTime;
+
+pub struct StatsCommand {
+    pub top: usize,
+}
+
+impl CliCommand for StatsCommand {
+    fn execute(self: Box<Self>) -> Result<()> {
+        let ctx = CliContext::load()?;
+        let songs = &ctx.state.library.songs;
+        let stats = listening_stats(songs, &ctx.state.history.plays, self.top);
+
+        if stats.total_plays == 0 {
+            ctx.ui.print_message(&format!("No plays recorded yet. {}", ctx.state.config.play_threshold_note()));
+            return Ok(());
+        }
+
+        let total_secs = stats.total_time.as_secs();
+        ctx.ui.print_message("─── Listening stats ────────────────────");
+        ctx.ui.print_message(&format!("  Plays          {}", stats.total_plays));
+        ctx.ui.print_message(&format!(
+            "  Listening time {}h {:02}m",
+            total_secs / 3600,
+            (total_secs % 3600) / 60
+        ));
+
+        ctx.ui.print_message("─── Top tracks ─────────────────────────");
+        for (rank, (index, plays)) in stats.top_tracks.iter().enumerate() {
+            ctx.ui.print_message(&format!("  {:2}. [{}] {} ({} plays)", rank + 1, index, songs[*index], plays));
+        }
+
+        if !stats.top_artists.is_empty() {
+            ctx.ui.print_message("─── Top artists ────────────────────────");
+            for (rank, (artist, plays)) in stats.top_artists.iter().enumerate() {
+                ctx.ui.print_message(&format!("  {:2}. {} ({} plays)", rank + 1, artist, plays));
+            }
+        }
+
+        let skipped = most_skipped(songs, &ctx.state.history.skips, self.top);
+        if !skipped.is_empty() {
+            ctx.ui.print_message("─── Most skipped ───────────────────────");
+            for (index, skips) in &skipped {
+                ctx.ui.print_message(&format!("  [{}] {} ({} skips)", index, songs[*index], skips));
+            }
+        }
+
+        let now = SystemTime::now();
+        ctx.ui.print_message("─── Recently played ────────────────────");
+        for (index, last_played) in &stats.recent {
+            ctx.ui.print_message(&format!(
+                "  [{}] {} — {}",
+                index,
+                songs[*index],
+                format_ago(*last_played, now)
+            ));
+        }
+        ctx.ui.print_message("────────────────────────────────────────");
+
+        Ok(())
+    }
+}
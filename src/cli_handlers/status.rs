@@ -1,5 +1,5 @@
 use crate::cli_handlers::CliCommand;
-use crate::utils::{amplitude_to_volume, repeat_label, APP_NAME};
+use crate::utils::{repeat_label, APP_NAME};
 use anyhow::Result;
 use crate::cli_handlers::context::CliContext;
 
@@ -9,7 +9,8 @@ impl CliCommand for StatusCommand {
     fn execute(self: Box<Self>) -> Result<()> {
         let ctx = CliContext::load()?;
 
-        let volume = amplitude_to_volume(ctx.state.config.volume);
+        let volume = ctx.state.config.volume_percent;
+        let muted = if ctx.state.config.is_muted() { " (muted)" } else { "" };
         let shuffle = if ctx.state.config.shuffle { "On" } else { "Off" };
         let repeat = format!(
             "{} {}",
@@ -17,16 +18,18 @@ impl CliCommand for StatusCommand {
             repeat_label(ctx.state.config.repeat)
         );
         let song_count = ctx.state.library.songs.len();
-        let library_path = ctx.state
-            .config
-            .root_path
-            .map(|p| p.display().to_string())
-            .unwrap_or_else(|| "(not set)".to_string());
+        let roots = &ctx.state.config.root_paths;
+        let library_path = if roots.is_empty() {
+            "(not set)".to_string()
+        } else {
+            roots.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", ")
+        };
 
         ctx.ui.print_message(&format!("─── {} ──────────────────────────", APP_NAME));
-        ctx.ui.print_message(&format!("  Volume   {}%", volume));
+        ctx.ui.print_message(&format!("  Volume   {}%{}", volume, muted));
         ctx.ui.print_message(&format!("  Shuffle  {}", shuffle));
         ctx.ui.print_message(&format!("  Repeat   {}", repeat));
+        ctx.ui.print_message(&format!("  Radio    {}", ctx.state.config.radio.label()));
         ctx.ui.print_message("────────────────────────────────────────");
         ctx.ui.print_message(&format!("  Library  {} songs", song_count));
         ctx.ui.print_message(&format!("  Path     {}", library_path));
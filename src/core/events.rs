@@ -1,6 +1,9 @@
-use crate::core::models::{RepeatMode, Song};
+use crate::core::models::{RadioMode, RepeatMode, Song};
 use std::path::PathBuf;
+use std::time::Duration;
 use crate::modules::library::sorter::SortField;
+use crate::modules::playback::equalizer::EqGains;
+use crate::modules::ui::progress_formatter::ProgressLabel;
 
 /// All events that can occur in the application
 #[derive(Debug, Clone)]
@@ -35,23 +38,75 @@ pub enum PlaybackEvent {
     /// Current track finished
     TrackFinished,
 
+    /// A requested song could not be played (unreadable file, unsupported format)
+    Error { message: String },
+
+    /// Current track is about to end; queue the next one for gapless playback
+    NearingEnd,
+
+    /// Current track has played long enough to count as a play
+    PlayThresholdReached,
+
+    /// User moved on from the track at `path` before it counted as a play
+    TrackSkipped { path: PathBuf },
+
+    /// Backend moved on to the queued track without a gap
+    QueuedTrackStarted,
+
+    /// Request to jump to `position` within the current track
+    SeekRequested { position: Duration },
+
     /// Volume changed (0.0 - 1.0)
     VolumeChanged { volume: f32 },
 
+    /// Output muted or unmuted. The stored volume is left untouched.
+    MuteChanged { muted: bool },
+
     /// Shuffle enabled or disabled
     Shuffle { enabled: bool },
 
     /// Repeat mode changed.
     RepeatChanged { mode: RepeatMode },
+
+    /// Radio mode changed
+    RadioChanged { mode: RadioMode },
+
+    /// Number of times each track plays before advancing changed.
+    RepeatCountChanged { count: u32 },
+
+    /// Song added to the end of the play queue
+    Enqueued { index: usize },
+
+    /// Play queue emptied
+    QueueCleared,
+
+    /// Recently played list emptied (play counts are kept)
+    RecentPlaysCleared,
+
+    /// ReplayGain loudness normalization enabled or disabled
+    NormalizeChanged { enabled: bool },
+
+    /// Crossfade window changed (seconds, 0 = off)
+    CrossfadeChanged { secs: u8 },
+
+    /// Equalizer gains changed; used from the next track on
+    EqChanged { gains: EqGains },
+
+    /// Stop playback in `minutes` (0 cancels), optionally letting the track
+    /// playing at that point finish first
+    SleepTimerSet { minutes: u32, after_track: bool },
+
+    /// Playback stopped by the sleep timer
+    Stopped,
 }
 
 #[derive(Debug, Clone)]
 pub enum LibraryEvent {
-    /// Request to scan directory
-    ScanRequested { path: PathBuf },
+    /// Request to scan the library roots
+    ScanRequested { roots: Vec<PathBuf> },
 
     /// Scanning started
-    ScanStarted { path: PathBuf },
+    ScanStarted { roots: Vec<PathBuf> },
 
     /// Scan progress update
     ScanProgress { found: usize },
@@ -60,11 +115,26 @@ pub enum LibraryEvent {
     ScanCompleted { songs: Vec<Song>, count: usize },
 
     /// Scanning failed 
-    ScanFailed { path: PathBuf, message: String },
+    ScanFailed { roots: Vec<PathBuf>, message: String },
 
     /// Library loaded from storage
     LibraryLoaded { songs: Vec<Song> },
 
+    /// Song at `index` dropped from the library; the file stays on disk
+    SongRemoved { index: usize },
+
+    /// Song at `index` rated (`None` clears the stars) or (un)marked as a favorite
+    RatingChanged { index: usize, rating: Option<u8>, favorite: bool },
+
+    /// Every song, the play queue and the play history dropped; files and music paths stay
+    LibraryCleared,
+
+    /// Position bookmarked in the file at `path`
+    BookmarkAdded { path: PathBuf, position: Duration },
+
+    /// Bookmark at `position` in the file at `path` deleted
+    BookmarkRemoved { path: PathBuf, position: Duration },
+
     /// Search requested
     SearchRequested { query: String },
 
@@ -94,12 +164,24 @@ pub enum UiEvent {
     /// User requested pause/resume toggle
     TogglePauseRequested,
 
+    /// Pause if playing; nothing otherwise
+    PauseRequested,
+
+    /// Resume if paused; nothing otherwise
+    ResumeRequested,
+
     /// User requested next track
     NextTrackRequested,
 
     /// User requested previous track
     PreviousTrackRequested,
 
+    /// Play the first track of the next (or previous) album in library order
+    AlbumSkipRequested { forward: bool },
+
+    /// Play the current track again from the start; nothing if nothing is playing
+    RestartTrackRequested,
+
     /// User changed selection
     SelectionChanged { index: usize },
 
@@ -115,8 +197,47 @@ pub enum UiEvent {
     /// User requested volume change (0-100)
     VolumeChangeRequested { volume: u8 },
 
-    /// User requested path change
-    PathChangeRequested { path: PathBuf },
+    /// Mark the current position as the start (A) of an A–B loop
+    SetLoopPointA,
+
+    /// Mark the current position as the end (B) of an A–B loop
+    SetLoopPointB,
+
+    /// Remove both A–B loop marks
+    LoopCleared,
+
+    /// User clicked the progress bar; seek the current track to `position`
+    SeekRequested { position: Duration },
+
+    /// User asked to jump `delta_secs` forward (or back, when negative) in the current track
+    SeekByRequested { delta_secs: i64 },
+
+    /// User asked to bookmark the current position of the playing track
+    BookmarkAddRequested,
+
+    /// User asked to jump to the playing track's next bookmark, wrapping around
+    NextBookmarkRequested,
+
+    /// User requested the selected song be added to the play queue
+    EnqueueSelectedRequested,
+
+    /// User requested every search match be added to the play queue, in this order
+    EnqueueAllRequested { indices: Vec<usize> },
+
+    /// User requested the selected song be removed from the library
+    RemoveSelectedRequested,
+
+    /// User toggled the favorite mark on the selected song
+    FavoriteToggleRequested,
+
+    /// User requested mute/unmute toggle
+    MuteToggled,
+
+    /// User requested a directory be added to the library roots
+    PathAddRequested { path: PathBuf },
+
+    /// User requested a library root be removed
+    PathRemoveRequested { path: PathBuf },
 
     /// Search mode toggled
     SearchToggled { active: bool },
@@ -133,6 +254,24 @@ pub enum UiEvent {
     /// Set repeat mode explicitly
     RepeatChangeRequested { mode: RepeatMode },
 
+    /// Set loudness normalization explicitly
+    NormalizeSet { enabled: bool },
+
+    /// User requested a new crossfade window (seconds, 0 = off)
+    CrossfadeChangeRequested { secs: u8 },
+
+    /// User set the equalizer band gains
+    EqChangeRequested { gains: EqGains },
+
+    /// User asked for the next sleep timer preset (cycles back to off)
+    SleepTimerCycleRequested,
+
+    /// User picked a TUI color theme by name
+    ThemeChangeRequested { name: String },
+
+    /// User picked what the progress bar shows inside it
+    ProgressLabelChangeRequested { label: ProgressLabel },
+
     /// User requested a library refresh
     RefreshRequested,
 
@@ -3,7 +3,9 @@ use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use lofty::probe::Probe;
 use lofty::file::{AudioFile, TaggedFileExt};
-use lofty::tag::Accessor;
+use lofty::tag::{Accessor, ItemKey};
+use crate::modules::library::cover_art::{self, CoverArt};
+use crate::modules::playback::replay_gain::parse_gain_db;
 use crate::utils::{format_artists, parse_artists};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -15,11 +17,45 @@ pub struct Song {
     pub track_number: Option<u32>,
     pub duration: Option<std::time::Duration>,
 
+    #[serde(default)]
+    pub genre: Option<String>,
+
+    #[serde(default)]
+    pub year: Option<u32>,
+
+    /// Average audio bitrate in kbps, as reported by the container.
+    #[serde(default)]
+    pub bitrate: Option<u32>,
+
+    /// Sample rate in Hz.
+    #[serde(default)]
+    pub sample_rate: Option<u32>,
+
+    /// Number of audio channels (1 = mono, 2 = stereo).
+    #[serde(default)]
+    pub channels: Option<u8>,
+
+    /// ReplayGain track gain in dB (`REPLAYGAIN_TRACK_GAIN`), used for loudness normalization.
+    #[serde(default)]
+    pub track_gain_db: Option<f32>,
+
     pub search_key: String,
 
+    /// Modification time of the source file when its tags were last read.
+    /// The scanner reuses the cached entry while this still matches the file on disk.
+    #[serde(default)]
+    pub scanned_mtime: Option<std::time::SystemTime>,
+
     /// Stable insertion order from the last scan. Used to restore natural order
     #[serde(default)]
     pub order: usize,
+
+    /// Star rating from 1 to 5; `None` while unrated.
+    #[serde(default)]
+    pub rating: Option<u8>,
+
+    #[serde(default)]
+    pub favorite: bool,
 }
 
 impl Song {
@@ -41,6 +77,55 @@ impl Song {
         format_artists(&self.artists)
     }
 
+    /// Favorite heart and rating stars for display, e.g. "♥ ★★★"; empty for an unmarked song.
+    pub fn rating_marks(&self) -> String {
+        let heart = if self.favorite { "♥" } else { "" };
+        let stars = "★".repeat(self.rating.unwrap_or(0) as usize);
+        match (heart.is_empty(), stars.is_empty()) {
+            (false, false) => format!("{} {}", heart, stars),
+            _ => format!("{}{}", heart, stars),
+        }
+    }
+
+    /// Audio properties for display, e.g. "320 kbps · 44.1 kHz · stereo"; `None` when none are known.
+    pub fn audio_summary(&self) -> Option<String> {
+        let parts: Vec<String> = [
+            self.bitrate.map(|b| format!("{} kbps", b)),
+            self.sample_rate.map(|r| format!("{} kHz", f64::from(r) / 1000.0)),
+            self.channels.map(|c| match c {
+                1 => "mono".to_string(),
+                2 => "stereo".to_string(),
+                n => format!("{} ch", n),
+            }),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        (!parts.is_empty()).then(|| parts.join(" · "))
+    }
+
+    /// The song's embedded cover, cached on disk after the first extraction.
+    pub fn album_art(&self) -> Option<CoverArt> {
+        cover_art::cached_cover(&cover_art::cache_dir(), &self.path, Self::extract_cover)
+    }
+
+    /// Read the first embedded picture from `path`.
+    ///
+    /// Returns `None` when the file has no embedded art or cannot be read.
+    pub fn extract_cover(path: &Path) -> Option<CoverArt> {
+        let tagged_file = Probe::open(path).ok()?.read().ok()?;
+        let picture = tagged_file.tags().iter().flat_map(|t| t.pictures()).next()?;
+
+        Some(CoverArt {
+            data: picture.data().to_vec(),
+            mime_type: picture
+                .mime_type()
+                .map(|m| m.as_str().to_owned())
+                .unwrap_or_else(|| "application/octet-stream".to_owned()),
+        })
+    }
+
     fn generate_search_key(title: &str, artists: &[String], album: Option<&str>) -> String {
         // We combine Title, Artist, and Album into one string.
         // This allows a query like "Pink Floyd Wall" to match effectively.
@@ -62,7 +147,17 @@ impl Song {
             .unwrap_or_default();
         let album = tag.and_then(|t| t.album().map(|s| s.into_owned()));
         let track_number = tag.and_then(|t| t.track());
-        let duration = Some(tagged_file.properties().duration());
+        let genre = tag.and_then(|t| t.genre().map(|s| s.into_owned()));
+        let year = tag.and_then(|t| t.date()).map(|d| u32::from(d.year));
+        let track_gain_db = tag
+            .and_then(|t| t.get_string(ItemKey::ReplayGainTrackGain))
+            .and_then(parse_gain_db);
+
+        let properties = tagged_file.properties();
+        let duration = Some(properties.duration());
+        let bitrate = properties.audio_bitrate().or_else(|| properties.overall_bitrate());
+        let sample_rate = properties.sample_rate();
+        let channels = properties.channels();
 
         let search_key = Self::generate_search_key(&title, &artists, album.as_deref());
 
@@ -73,8 +168,17 @@ impl Song {
             album,
             track_number,
             duration,
+            genre,
+            year,
+            bitrate,
+            sample_rate,
+            channels,
+            track_gain_db,
             search_key,
-            order: 0
+            scanned_mtime: None,
+            order: 0,
+            rating: None,
+            favorite: false,
         })
     }
 
@@ -89,8 +193,17 @@ impl Song {
             album: None,
             track_number: None,
             duration: None,
+            genre: None,
+            year: None,
+            bitrate: None,
+            sample_rate: None,
+            channels: None,
+            track_gain_db: None,
             search_key,
-            order: 0
+            scanned_mtime: None,
+            order: 0,
+            rating: None,
+            favorite: false,
         }
     }
 
@@ -102,6 +215,18 @@ impl Song {
     }
 }
 
+#[cfg(test)]
+impl Song {
+    /// An untagged `<title>.mp3` titled `title`, for tests to fill in with `..`.
+    pub fn test_fixture(title: &str) -> Self {
+        Song {
+            title: title.to_owned(),
+            search_key: title.to_lowercase(),
+            ..Self::fallback(&PathBuf::from(format!("{}.mp3", title)))
+        }
+    }
+}
+
 impl fmt::Display for Song {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let duration_str = self
@@ -122,6 +247,13 @@ impl fmt::Display for Song {
     }
 }
 
+/// How often a song has been played, and when it was last played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayRecord {
+    pub play_count: u32,
+    pub last_played: std::time::SystemTime,
+}
+
 /// Controls how playback behaves when a track finishes.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum RepeatMode {
@@ -165,3 +297,127 @@ impl RepeatMode {
     }
 }
 
+/// What a bare `hextune`, without a subcommand, runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DefaultCommand {
+    #[default]
+    Browse,
+    Playlist,
+    List,
+}
+
+/// What auto-advance plays next when a track ends, before falling back to library order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum RadioMode {
+    /// Follow the library (or shuffle) order.
+    #[default]
+    Off,
+
+    /// Songs sharing an artist with the one that ended, in random order.
+    SameArtist,
+
+    /// Songs from the same album as the one that ended, in random order.
+    SameAlbum,
+}
+
+impl RadioMode {
+    /// Cycle to the next mode in order: Off → SameArtist → SameAlbum → Off.
+    pub fn cycle(&self) -> Self {
+        match self {
+            Self::Off => Self::SameArtist,
+            Self::SameArtist => Self::SameAlbum,
+            Self::SameAlbum => Self::Off,
+        }
+    }
+
+    /// Whether `candidate` belongs to the radio pool seeded by `seed`.
+    /// Songs without artists (or an album) never match.
+    pub fn matches(&self, seed: &Song, candidate: &Song) -> bool {
+        match self {
+            Self::Off => false,
+            Self::SameArtist => candidate.artists.iter().any(|artist| seed.artists.contains(artist)),
+            Self::SameAlbum => seed.album.is_some() && candidate.album == seed.album,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Off => "Off",
+            Self::SameArtist => "Same artist",
+            Self::SameAlbum => "Same album",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn temp_file(name: &str, contents: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("hextune_models_{}_{}", std::process::id(), name));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    /// A second of 16-bit PCM silence, with no tags.
+    fn wav(sample_rate: u32, channels: u16) -> Vec<u8> {
+        let block_align = channels * 2;
+        let data_len = sample_rate * u32::from(block_align);
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        bytes.extend_from_slice(&channels.to_le_bytes());
+        bytes.extend_from_slice(&sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&(sample_rate * u32::from(block_align)).to_le_bytes());
+        bytes.extend_from_slice(&block_align.to_le_bytes());
+        bytes.extend_from_slice(&16u16.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data_len.to_le_bytes());
+        bytes.resize(bytes.len() + data_len as usize, 0);
+        bytes
+    }
+
+    #[test]
+    fn audio_properties_are_read_from_the_file() {
+        let path = temp_file("props.wav", &wav(44_100, 2));
+
+        let song = Song::from_path(&path);
+
+        assert_eq!(song.title, format!("hextune_models_{}_props", std::process::id()));
+        assert_eq!(song.sample_rate, Some(44_100));
+        assert_eq!(song.channels, Some(2));
+        assert_eq!(song.bitrate, Some(1_411));
+        assert_eq!(song.audio_summary().as_deref(), Some("1411 kbps · 44.1 kHz · stereo"));
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn unreadable_file_has_no_audio_properties() {
+        let path = temp_file("broken.mp3", b"not really audio");
+
+        let song = Song::from_path(&path);
+
+        assert_eq!((song.bitrate, song.sample_rate, song.channels), (None, None, None));
+        assert_eq!(song.audio_summary(), None);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn songs_saved_before_ratings_load_unrated() {
+        let mut saved = serde_json::to_value(Song::fallback(Path::new("/music/old.mp3"))).unwrap();
+        let fields = saved.as_object_mut().unwrap();
+        fields.remove("rating");
+        fields.remove("favorite");
+
+        let song: Song = serde_json::from_value(saved).unwrap();
+
+        assert_eq!((song.rating, song.favorite), (None, false));
+        assert_eq!(song.rating_marks(), "");
+        assert_eq!(Song { rating: Some(3), favorite: true, ..song }.rating_marks(), "♥ ★★★");
+    }
+}
@@ -4,6 +4,9 @@ use crate::core::events::UiEvent;
 use crate::core::models::Song;
 use anyhow::Result;
 use crate::modules::input::KeyConfig;
+use crate::modules::playback::equalizer::EqGains;
+use crate::modules::playback::level_meter::LevelMeter;
+use crate::modules::storage::StateFile;
 
 /// Abstraction for audio playback backend
 pub trait PlaybackBackend: Send {
@@ -31,11 +34,47 @@ pub trait PlaybackBackend: Send {
     /// Set volume (0.0 - 1.0)
     fn set_volume(&mut self, volume: f32);
 
+    /// Scale each track by its ReplayGain tag on top of the user volume.
+    /// Backends without normalization support ignore this.
+    fn set_normalization(&mut self, _enabled: bool) {}
+
+    /// Overlap automatic track transitions by `window`; zero disables crossfading.
+    /// Backends without crossfade support ignore this.
+    fn set_crossfade(&mut self, _window: Duration) {}
+
+    /// Equalizer gains for tracks started from now on; flat gains turn it off.
+    /// Backends without an equalizer ignore this.
+    fn set_equalizer(&mut self, _gains: EqGains) {}
+
+    /// Jump to `position` within the current track.
+    /// Backends that cannot seek return an error.
+    fn seek(&mut self, _position: Duration) -> Result<()> {
+        anyhow::bail!("Seeking is not supported by this playback backend")
+    }
+
     /// Get current playback position (elapsed time)
     /// Returns Duration::ZERO if not playing
     fn position(&self) -> Duration {
         Duration::ZERO
     }
+
+    /// Queue `song` to start as soon as the current track ends, without a gap.
+    /// Backends that cannot do this ignore it and rely on `has_finished` instead.
+    /// Calling it again while a song is already queued is a no-op.
+    fn queue_next(&mut self, _song: &Song) -> Result<()> {
+        Ok(())
+    }
+
+    /// Returns `true` (once) when playback has moved on to the queued song.
+    fn take_queued_started(&mut self) -> bool {
+        false
+    }
+
+    /// Levels of the audio being played, for the TUI visualizer.
+    /// Backends that produce no audio return `None`.
+    fn level_meter(&self) -> Option<LevelMeter> {
+        None
+    }
 }
 
 /// Abstraction for persistent storage
@@ -45,6 +84,32 @@ pub trait StorageBackend: Send {
 
     /// Save application state
     fn save(&self, state: &AppState) -> Result<()>;
+
+    /// Save only the settings (`state.config`). Backends that keep everything
+    /// in one place can rely on the default, a full save.
+    fn save_config(&self, state: &AppState) -> Result<()> {
+        self.save(state)
+    }
+
+    /// Save only the library, play queue and history.
+    fn save_library(&self, state: &AppState) -> Result<()> {
+        self.save(state)
+    }
+
+    /// Files the state is kept in, for diagnostics. Empty for backends without files.
+    fn files(&self) -> Vec<StateFile> {
+        Vec::new()
+    }
+}
+
+/// Abstraction for a service that keeps a listening history (Last.fm, ListenBrainz)
+pub trait ScrobbleBackend: Send {
+    /// `song` started playing.
+    fn now_playing(&mut self, song: &Song);
+
+    /// `song`, the last one passed to `now_playing`, has played past the configured
+    /// play threshold. Songs the service does not count are for the backend to skip.
+    fn scrobble(&mut self, song: &Song);
 }
 
 /// Abstraction for UI rendering
@@ -62,9 +127,22 @@ pub trait UiRenderer: Send {
     /// Returns events generated from user input
     fn poll_input(&mut self, config: &KeyConfig) -> Result<Vec<UiEvent>>;
 
+    /// Block until input is ready or `timeout` passes, returning whether input is ready.
+    /// Default implementation just sleeps
+    fn wait_for_input(&mut self, timeout: Duration) -> Result<bool> {
+        std::thread::sleep(timeout);
+        Ok(false)
+    }
+
     /// Update renderer with current app state before rendering
     /// Default implementation does nothing
     fn update_state(&mut self, _state: &AppState) {
         
     }
+
+    /// First song list row on screen, for renderers that show the library;
+    /// saved on exit so the list reopens there. Default: no list
+    fn list_scroll(&self) -> Option<usize> {
+        None
+    }
 }
@@ -11,5 +11,15 @@ use anyhow::Result;
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
-    cli_handlers::from_cli(cli.command).execute()
+    modules::storage::configure(cli.config_format, cli.config);
+    if let Err(e) = modules::logging::init(cli.verbose) {
+        eprintln!("Warning: Logging is off: {:#}", e);
+    }
+    modules::playback::rodio_backend::configure_device(cli.device);
+    modules::playback::configure_null_audio(cli.no_audio);
+    let command = match cli.command {
+        Some(command) => command,
+        None => cli_handlers::default_command()?,
+    };
+    cli_handlers::from_cli(command).execute()
 }
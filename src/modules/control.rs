@@ -0,0 +1,334 @@
+//! Remote control of a running session over a local socket, so one-shot
+//! commands like `pause` can reach the `browse`, `playlist` or `daemon` process.
+//!
+//! The protocol is one request per line (`pause`, `play 3`, `volume 40`, ...),
+//! answered with one line: `ok` once the request is queued as an event, a JSON
+//! object for `status`, or `error: <reason>`.
+
+use crate::application::state::AppState;
+use crate::core::events::{AppEvent, EventSender, UiEvent};
+use crate::utils::{repeat_label, APP_NAME, VOLUME_MAX};
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// What a one-shot command asks the running session to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlRequest {
+    /// Play the library song at this 0-based index
+    Play { index: usize },
+    Next,
+    Previous,
+    NextAlbum,
+    PreviousAlbum,
+    Pause,
+    Resume,
+    Toggle,
+    Volume { percent: u8 },
+    /// Reply with the playback state as JSON
+    Status,
+    /// End the session
+    Quit,
+}
+
+impl ControlRequest {
+    /// The request as sent over the socket.
+    pub fn to_line(self) -> String {
+        match self {
+            ControlRequest::Play { index } => format!("play {}", index),
+            ControlRequest::Next => "next".to_string(),
+            ControlRequest::Previous => "prev".to_string(),
+            ControlRequest::NextAlbum => "next-album".to_string(),
+            ControlRequest::PreviousAlbum => "prev-album".to_string(),
+            ControlRequest::Pause => "pause".to_string(),
+            ControlRequest::Resume => "resume".to_string(),
+            ControlRequest::Toggle => "toggle".to_string(),
+            ControlRequest::Volume { percent } => format!("volume {}", percent),
+            ControlRequest::Status => "status".to_string(),
+            ControlRequest::Quit => "quit".to_string(),
+        }
+    }
+
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let mut words = line.split_whitespace();
+        let name = words.next().unwrap_or_default();
+        let arg = words.next();
+        if words.next().is_some() {
+            return Err(format!("too many arguments in '{}'", line.trim()));
+        }
+
+        let request = match (name, arg) {
+            ("play", Some(index)) => ControlRequest::Play {
+                index: index.parse().map_err(|_| format!("invalid index '{}'", index))?,
+            },
+            ("volume", Some(percent)) => ControlRequest::Volume {
+                percent: percent
+                    .parse()
+                    .ok()
+                    .filter(|p| *p <= VOLUME_MAX)
+                    .ok_or_else(|| format!("volume must be 0-{}, got '{}'", VOLUME_MAX, percent))?,
+            },
+            ("next", None) => ControlRequest::Next,
+            ("prev", None) => ControlRequest::Previous,
+            ("next-album", None) => ControlRequest::NextAlbum,
+            ("prev-album", None) => ControlRequest::PreviousAlbum,
+            ("pause", None) => ControlRequest::Pause,
+            ("resume", None) => ControlRequest::Resume,
+            ("toggle", None) => ControlRequest::Toggle,
+            ("status", None) => ControlRequest::Status,
+            ("quit", None) => ControlRequest::Quit,
+            _ => return Err(format!("unknown request '{}'", line.trim())),
+        };
+        Ok(request)
+    }
+}
+
+/// How long a connected client may stay silent before its connection is dropped.
+#[cfg(unix)]
+const READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Where sessions listen: the per-user runtime directory, else the temp
+/// directory, which every user shares, under a name carrying the uid.
+pub fn socket_path() -> PathBuf {
+    match dirs::runtime_dir() {
+        Some(dir) => dir.join(format!("{}.sock", APP_NAME)),
+        None => std::env::temp_dir().join(format!("{}-{}.sock", APP_NAME, user_id())),
+    }
+}
+
+#[cfg(unix)]
+fn user_id() -> u32 {
+    nix::unistd::getuid().as_raw()
+}
+
+#[cfg(not(unix))]
+fn user_id() -> String {
+    std::env::var("USERNAME").unwrap_or_default()
+}
+
+/// Send `request` to the running session and return its reply, or `None` when
+/// there is no session.
+pub fn send(request: ControlRequest) -> Result<Option<String>> {
+    send_to(&socket_path(), request)
+}
+
+/// Answer one request: queue its events, or describe `state` for `status`.
+fn respond(request: ControlRequest, events: &EventSender, state: &Mutex<AppState>) -> Result<String> {
+    let ui_events = match request {
+        ControlRequest::Status => return Ok(status_json(&state.lock().unwrap()).to_string()),
+        ControlRequest::Quit => {
+            events.send(AppEvent::Shutdown)?;
+            return Ok("ok".to_string());
+        }
+        ControlRequest::Play { index } => {
+            let len = state.lock().unwrap().library.songs.len();
+            if index >= len {
+                return Ok(format!("error: invalid index {}; the library has {} songs", index, len));
+            }
+            vec![UiEvent::SelectionChanged { index }, UiEvent::PlaySelectedRequested]
+        }
+        ControlRequest::Next => vec![UiEvent::NextTrackRequested],
+        ControlRequest::Previous => vec![UiEvent::PreviousTrackRequested],
+        ControlRequest::NextAlbum => vec![UiEvent::AlbumSkipRequested { forward: true }],
+        ControlRequest::PreviousAlbum => vec![UiEvent::AlbumSkipRequested { forward: false }],
+        ControlRequest::Pause => vec![UiEvent::PauseRequested],
+        ControlRequest::Resume => vec![UiEvent::ResumeRequested],
+        ControlRequest::Toggle => vec![UiEvent::TogglePauseRequested],
+        ControlRequest::Volume { percent } => vec![UiEvent::VolumeChangeRequested { volume: percent }],
+    };
+
+    for event in ui_events {
+        events.send(AppEvent::Ui(event))?;
+    }
+    Ok("ok".to_string())
+}
+
+fn status_json(state: &AppState) -> serde_json::Value {
+    let song = state.playback.current_song.as_ref().map(|song| {
+        serde_json::json!({
+            "index": state.playback.current_index,
+            "title": song.title,
+            "artists": song.artists,
+            "album": song.album,
+            "path": song.path,
+            "duration_secs": song.duration.map(|d| d.as_secs()),
+        })
+    });
+
+    serde_json::json!({
+        "playing": state.playback.is_playing,
+        "paused": state.playback.is_paused,
+        "song": song,
+        "elapsed_secs": state.playback.current_elapsed.as_secs(),
+        "volume": state.config.volume_percent,
+        "muted": state.config.is_muted(),
+        "shuffle": state.config.shuffle,
+        "repeat": repeat_label(state.config.repeat).to_lowercase(),
+        "queue_len": state.playback.queue.len(),
+        "library_len": state.library.songs.len(),
+    })
+}
+
+/// Listens for control requests on a background thread while it is alive;
+/// dropping it removes the socket.
+pub struct ControlServer {
+    path: PathBuf,
+}
+
+impl ControlServer {
+    /// Start listening on `socket_path()`. `Ok(None)` when another session already does.
+    pub fn start(events: EventSender, state: Arc<Mutex<AppState>>) -> Result<Option<Self>> {
+        Self::start_at(socket_path(), events, state)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(unix)]
+impl ControlServer {
+    fn start_at(path: PathBuf, events: EventSender, state: Arc<Mutex<AppState>>) -> Result<Option<Self>> {
+        use anyhow::Context;
+        use nix::sys::stat::{umask, Mode};
+        use std::os::unix::net::{UnixListener, UnixStream};
+
+        if path.exists() {
+            if UnixStream::connect(&path).is_ok() {
+                return Ok(None);
+            }
+            // Left behind by a session that did not exit cleanly.
+            std::fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove stale socket '{}'", path.display()))?;
+        }
+
+        // Only the owner may drive the session. The socket is created that way,
+        // so it is never reachable by others, even briefly.
+        let previous_umask = umask(Mode::from_bits_truncate(0o177));
+        let listener = UnixListener::bind(&path);
+        umask(previous_umask);
+        let listener = listener.with_context(|| format!("Failed to listen on '{}'", path.display()))?;
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                // Each client on its own thread, so a silent one holds up nobody else.
+                let events = events.clone();
+                let state = Arc::clone(&state);
+                std::thread::spawn(move || serve(stream, &events, &state));
+            }
+        });
+
+        Ok(Some(Self { path }))
+    }
+}
+
+/// Answer the one request a client sends, or give up on it after `READ_TIMEOUT`.
+#[cfg(unix)]
+fn serve(mut stream: std::os::unix::net::UnixStream, events: &EventSender, state: &Mutex<AppState>) {
+    use std::io::{BufRead, BufReader, Write};
+
+    let mut line = String::new();
+    if stream.set_read_timeout(Some(READ_TIMEOUT)).is_err() || BufReader::new(&stream).read_line(&mut line).is_err() {
+        return;
+    }
+
+    let reply = match ControlRequest::parse(&line) {
+        Ok(request) => match respond(request, events, state) {
+            Ok(reply) => reply,
+            Err(_) => return, // The session is shutting down.
+        },
+        Err(e) => format!("error: {}", e),
+    };
+    let _ = writeln!(stream, "{}", reply);
+}
+
+#[cfg(unix)]
+fn send_to(path: &Path, request: ControlRequest) -> Result<Option<String>> {
+    use std::io::{BufRead, BufReader, ErrorKind, Write};
+    use std::os::unix::net::UnixStream;
+
+    let mut stream = match UnixStream::connect(path) {
+        Ok(stream) => stream,
+        Err(e) if matches!(e.kind(), ErrorKind::NotFound | ErrorKind::ConnectionRefused) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    writeln!(stream, "{}", request.to_line())?;
+
+    let mut reply = String::new();
+    BufReader::new(&stream).read_line(&mut reply)?;
+    match reply.trim().strip_prefix("error: ") {
+        Some(reason) => anyhow::bail!("The running session refused '{}': {}", request.to_line(), reason),
+        None => Ok(Some(reply.trim().to_string())),
+    }
+}
+
+#[cfg(not(unix))]
+impl ControlServer {
+    fn start_at(_path: PathBuf, _events: EventSender, _state: Arc<Mutex<AppState>>) -> Result<Option<Self>> {
+        anyhow::bail!("Remote control needs Unix sockets, which this platform does not have")
+    }
+}
+
+#[cfg(not(unix))]
+fn send_to(_path: &Path, _request: ControlRequest) -> Result<Option<String>> {
+    anyhow::bail!("Controlling a running session is only supported on Unix-like systems")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_survive_the_wire_format() {
+        for request in [
+            ControlRequest::Play { index: 12 },
+            ControlRequest::Previous,
+            ControlRequest::PreviousAlbum,
+            ControlRequest::Volume { percent: 40 },
+            ControlRequest::Quit,
+        ] {
+            assert_eq!(ControlRequest::parse(&request.to_line()), Ok(request));
+        }
+        assert!(ControlRequest::parse("volume 101").is_err());
+        assert!(ControlRequest::parse("play").is_err());
+        assert!(ControlRequest::parse("next 2").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn requests_reach_the_session_as_events() {
+        let path = std::env::temp_dir().join(format!("{}_control_{}.sock", APP_NAME, std::process::id()));
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let state = Arc::new(Mutex::new(AppState::default()));
+        assert_eq!(send_to(&path, ControlRequest::Pause).unwrap(), None, "no session yet");
+
+        let server = ControlServer::start_at(path.clone(), tx.clone(), Arc::clone(&state)).unwrap().unwrap();
+        assert!(
+            ControlServer::start_at(path.clone(), tx, Arc::clone(&state)).unwrap().is_none(),
+            "one session owns the socket"
+        );
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+
+        // Connected but silent; the requests below are still answered, without waiting it out.
+        let _silent = std::os::unix::net::UnixStream::connect(&path).unwrap();
+        let asked = std::time::Instant::now();
+        assert_eq!(send_to(&path, ControlRequest::Toggle).unwrap().as_deref(), Some("ok"));
+        assert!(asked.elapsed() < READ_TIMEOUT);
+        assert!(matches!(rx.recv().unwrap(), AppEvent::Ui(UiEvent::TogglePauseRequested)));
+        assert!(send_to(&path, ControlRequest::Play { index: 0 }).is_err(), "the library is empty");
+
+        let status: serde_json::Value = serde_json::from_str(&send_to(&path, ControlRequest::Status).unwrap().unwrap()).unwrap();
+        assert_eq!(status["volume"], 100);
+        assert_eq!(status["song"], serde_json::Value::Null);
+
+        drop(server);
+        assert!(!path.exists());
+    }
+}
@@ -11,15 +11,47 @@ pub enum InputAction {
     NextTrack,
     PreviousTrack,
     ToggleShuffle,
+    ToggleMute,
     Refresh,
     CycleSort,
+    ToggleAlbumView,
+    EnqueueSelected,
+    RemoveSelected,
+    LoopSetA,
+    LoopSetB,
+    LoopClear,
+    ToggleHelp,
+    JumpToPlaying,
+    VolumeUp,
+    VolumeDown,
+    CycleSleepTimer,
+    ToggleVisualizer,
+    ShowRecent,
+    RestartTrack,
+    ToggleFavorite,
+    SeekForward,
+    SeekBackward,
+    SeekForwardLong,
+    SeekBackwardLong,
+    AddBookmark,
+    NextBookmark,
+    NextAlbum,
+    PreviousAlbum,
 
     // Search mode
     SearchExit,
     SearchClearLine,
+    SearchHistoryPrev,
+    SearchHistoryNext,
+    SearchEnqueueAll,
     SearchBackspace,
     SearchAppend(char),
 
+    // Quick filter (type-ahead in normal mode)
+    QuickFilterAppend(char),
+    QuickFilterBackspace,
+    QuickFilterClear,
+
     // Settings mode
     SettingsClose,
     SettingsNavigateUp,
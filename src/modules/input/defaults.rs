@@ -75,6 +75,8 @@ pub fn default_bindings() -> Vec<(InputMode, KeyBinding, InputAction)> {
 
     push_normal(&mut bindings, "r", InputAction::ToggleShuffle);
 
+    push_normal(&mut bindings, "m", InputAction::ToggleMute);
+
     push_normal_special(
         &mut bindings,
         KeyCode::F(5),
@@ -85,6 +87,50 @@ pub fn default_bindings() -> Vec<(InputMode, KeyBinding, InputAction)> {
 
     push_normal(&mut bindings, "o", InputAction::CycleSort);
 
+    push_normal(&mut bindings, "a", InputAction::ToggleAlbumView);
+
+    push_normal(&mut bindings, "e", InputAction::EnqueueSelected);
+
+    push_normal(&mut bindings, "d", InputAction::RemoveSelected);
+
+    push_normal(&mut bindings, "[", InputAction::LoopSetA);
+
+    push_normal(&mut bindings, "]", InputAction::LoopSetB);
+
+    push_normal(&mut bindings, "\\", InputAction::LoopClear);
+
+    push_normal(&mut bindings, "?", InputAction::ToggleHelp);
+
+    push_normal(&mut bindings, "g", InputAction::JumpToPlaying);
+
+    push_normal(&mut bindings, "+", InputAction::VolumeUp);
+    push_normal(&mut bindings, "=", InputAction::VolumeUp);
+    push_normal(&mut bindings, "0", InputAction::VolumeUp);
+
+    push_normal(&mut bindings, "-", InputAction::VolumeDown);
+    push_normal(&mut bindings, "9", InputAction::VolumeDown);
+
+    push_normal(&mut bindings, "z", InputAction::CycleSleepTimer);
+
+    push_normal(&mut bindings, "v", InputAction::ToggleVisualizer);
+
+    push_normal(&mut bindings, "h", InputAction::ShowRecent);
+
+    push_normal_special(&mut bindings, KeyCode::Home, KeyModifiers::NONE, InputAction::RestartTrack);
+
+    push_normal(&mut bindings, "f", InputAction::ToggleFavorite);
+
+    push_normal(&mut bindings, ".", InputAction::SeekForward);
+    push_normal(&mut bindings, ",", InputAction::SeekBackward);
+    push_normal(&mut bindings, ">", InputAction::SeekForwardLong);
+    push_normal(&mut bindings, "<", InputAction::SeekBackwardLong);
+
+    push_normal(&mut bindings, ";", InputAction::AddBookmark);
+    push_normal(&mut bindings, "'", InputAction::NextBookmark);
+
+    push_normal(&mut bindings, "}", InputAction::NextAlbum);
+    push_normal(&mut bindings, "{", InputAction::PreviousAlbum);
+
     // Search mode (text input actions are structural and intentionally omitted)
     bindings.push((
         InputMode::Search,
@@ -96,6 +142,21 @@ pub fn default_bindings() -> Vec<(InputMode, KeyBinding, InputAction)> {
         KeyBinding::from_str("Ctrl+u").expect("Ctrl+u must parse"),
         InputAction::SearchClearLine,
     ));
+    bindings.push((
+        InputMode::Search,
+        KeyBinding::from_str("Ctrl+p").expect("Ctrl+p must parse"),
+        InputAction::SearchHistoryPrev,
+    ));
+    bindings.push((
+        InputMode::Search,
+        KeyBinding::from_str("Ctrl+n").expect("Ctrl+n must parse"),
+        InputAction::SearchHistoryNext,
+    ));
+    bindings.push((
+        InputMode::Search,
+        KeyBinding::from_str("Ctrl+a").expect("Ctrl+a must parse"),
+        InputAction::SearchEnqueueAll,
+    ));
     bindings.push((
         InputMode::Search,
         KeyBinding::from_str("Up").expect("Up must parse"),
@@ -116,6 +177,11 @@ pub fn default_bindings() -> Vec<(InputMode, KeyBinding, InputAction)> {
         KeyBinding::from_str("Ctrl+Space").expect("Ctrl+Space must parse"),
         InputAction::TogglePause,
     ));
+    bindings.push((
+        InputMode::Search,
+        KeyBinding::from_str("Ctrl+g").expect("Ctrl+g must parse"),
+        InputAction::JumpToPlaying,
+    ));
 
     // Settings mode (structural text input actions are intentionally omitted)
     bindings.push((
@@ -249,10 +315,37 @@ mod tests {
             InputAction::NextTrack,
             InputAction::PreviousTrack,
             InputAction::ToggleShuffle,
+            InputAction::ToggleMute,
             InputAction::Refresh,
             InputAction::CycleSort,
+            InputAction::ToggleAlbumView,
+            InputAction::EnqueueSelected,
+            InputAction::RemoveSelected,
+            InputAction::LoopSetA,
+            InputAction::LoopSetB,
+            InputAction::LoopClear,
+            InputAction::ToggleHelp,
+            InputAction::JumpToPlaying,
+            InputAction::VolumeUp,
+            InputAction::VolumeDown,
+            InputAction::CycleSleepTimer,
+            InputAction::ToggleVisualizer,
+            InputAction::ShowRecent,
+            InputAction::RestartTrack,
+            InputAction::ToggleFavorite,
+            InputAction::SeekForward,
+            InputAction::SeekBackward,
+            InputAction::SeekForwardLong,
+            InputAction::SeekBackwardLong,
+            InputAction::AddBookmark,
+            InputAction::NextBookmark,
+            InputAction::NextAlbum,
+            InputAction::PreviousAlbum,
             InputAction::SearchExit,
             InputAction::SearchClearLine,
+            InputAction::SearchHistoryPrev,
+            InputAction::SearchHistoryNext,
+            InputAction::SearchEnqueueAll,
             InputAction::SettingsClose,
             InputAction::SettingsNavigateUp,
             InputAction::SettingsNavigateDown,
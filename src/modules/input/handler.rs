@@ -1,5 +1,42 @@
 use super::{InputAction, InputMode, KeyBinding, KeyConfig};
-use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crate::core::events::UiEvent;
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use std::time::Duration;
+
+/// Every terminal event queued since the last call, without blocking.
+///
+/// A held key repeats faster than the UI tick; reading one event per tick lets
+/// them back up and keeps the list moving after the key is released.
+pub fn drain_pending() -> Result<Vec<Event>> {
+    let mut pending = Vec::new();
+    while event::poll(Duration::ZERO)? {
+        pending.push(event::read()?);
+    }
+    Ok(pending)
+}
+
+/// Block until a terminal event is queued or `timeout` passes; `true` when one is.
+pub fn wait_for_input(timeout: Duration) -> Result<bool> {
+    Ok(event::poll(timeout)?)
+}
+
+/// Keep only the last of each run of back-to-back `SelectionChanged` events.
+///
+/// The renderer moves its own cursor per key, so the earlier ones are already
+/// stale; an event in between (e.g. play selected) keeps the selection before it.
+pub fn coalesce_selection_changes(events: Vec<UiEvent>) -> Vec<UiEvent> {
+    let mut coalesced: Vec<UiEvent> = Vec::with_capacity(events.len());
+    for event in events {
+        if matches!(event, UiEvent::SelectionChanged { .. })
+            && matches!(coalesced.last(), Some(UiEvent::SelectionChanged { .. }))
+        {
+            coalesced.pop();
+        }
+        coalesced.push(event);
+    }
+    coalesced
+}
 
 pub fn map(mode: InputMode, key: KeyEvent, config: &KeyConfig) -> Option<InputAction> {
     if matches!(key.kind, KeyEventKind::Release) {
@@ -21,6 +58,17 @@ pub fn map(mode: InputMode, key: KeyEvent, config: &KeyConfig) -> Option<InputAc
         InputMode::SettingsTextEntry => {
             return handle_settings_text_entry(key);
         }
+        InputMode::QuickFilter => {
+            // Pause keeps working even though Space now types: fall back to the
+            // search-mode pause binding (Ctrl+Space by default).
+            return handle_quick_filter_input(key)
+                .or_else(|| config.get(InputMode::Normal, &binding))
+                .or_else(|| {
+                    config
+                        .get(InputMode::Search, &binding)
+                        .filter(|action| *action == InputAction::TogglePause)
+                });
+        }
         InputMode::Settings => {
             if matches!(key.code, KeyCode::Backspace) {
                 return Some(InputAction::SettingsBackspace);
@@ -49,6 +97,19 @@ fn handle_search_text_input(key: KeyEvent) -> Option<InputAction> {
     }
 }
 
+fn handle_quick_filter_input(key: KeyEvent) -> Option<InputAction> {
+    match key.code {
+        KeyCode::Esc => Some(InputAction::QuickFilterClear),
+        KeyCode::Backspace => Some(InputAction::QuickFilterBackspace),
+        KeyCode::Char(c)
+            if !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+        {
+            Some(InputAction::QuickFilterAppend(c))
+        }
+        _ => None,
+    }
+}
+
 fn handle_settings_text_entry(key: KeyEvent) -> Option<InputAction> {
     match key.code {
         KeyCode::Esc => Some(InputAction::SettingsClose),
@@ -153,6 +214,10 @@ mod tests {
             map(InputMode::Normal, key(KeyCode::Char('r')), &cfg),
             Some(InputAction::ToggleShuffle)
         );
+        assert_eq!(
+            map(InputMode::Normal, key(KeyCode::Char('m')), &cfg),
+            Some(InputAction::ToggleMute)
+        );
         assert_eq!(
             map(InputMode::Normal, key(KeyCode::F(5)), &cfg),
             Some(InputAction::Refresh)
@@ -161,6 +226,82 @@ mod tests {
             map(InputMode::Normal, key(KeyCode::Char('o')), &cfg),
             Some(InputAction::CycleSort)
         );
+        assert_eq!(
+            map(InputMode::Normal, key(KeyCode::Char('a')), &cfg),
+            Some(InputAction::ToggleAlbumView)
+        );
+        assert_eq!(
+            map(InputMode::Normal, key(KeyCode::Char('e')), &cfg),
+            Some(InputAction::EnqueueSelected)
+        );
+        assert_eq!(
+            map(InputMode::Normal, key(KeyCode::Char('d')), &cfg),
+            Some(InputAction::RemoveSelected)
+        );
+        assert_eq!(
+            map(InputMode::Normal, key(KeyCode::Char('[')), &cfg),
+            Some(InputAction::LoopSetA)
+        );
+        assert_eq!(
+            map(InputMode::Normal, key(KeyCode::Char(']')), &cfg),
+            Some(InputAction::LoopSetB)
+        );
+        assert_eq!(
+            map(InputMode::Normal, key(KeyCode::Char('\\')), &cfg),
+            Some(InputAction::LoopClear)
+        );
+        assert_eq!(
+            map(InputMode::Normal, key(KeyCode::Char('?')), &cfg),
+            Some(InputAction::ToggleHelp)
+        );
+        assert_eq!(
+            map(InputMode::Normal, key(KeyCode::Char('g')), &cfg),
+            Some(InputAction::JumpToPlaying)
+        );
+        assert_eq!(
+            map(InputMode::Normal, key(KeyCode::Char('+')), &cfg),
+            Some(InputAction::VolumeUp)
+        );
+        assert_eq!(
+            map(InputMode::Normal, key(KeyCode::Char('-')), &cfg),
+            Some(InputAction::VolumeDown)
+        );
+        assert_eq!(
+            map(InputMode::Normal, key(KeyCode::Char('z')), &cfg),
+            Some(InputAction::CycleSleepTimer)
+        );
+        assert_eq!(
+            map(InputMode::Normal, key(KeyCode::Char('v')), &cfg),
+            Some(InputAction::ToggleVisualizer)
+        );
+        assert_eq!(
+            map(InputMode::Normal, key(KeyCode::Char('h')), &cfg),
+            Some(InputAction::ShowRecent)
+        );
+        assert_eq!(
+            map(InputMode::Normal, key(KeyCode::Home), &cfg),
+            Some(InputAction::RestartTrack)
+        );
+        assert_eq!(
+            map(InputMode::Normal, key(KeyCode::Char('f')), &cfg),
+            Some(InputAction::ToggleFavorite)
+        );
+        assert_eq!(
+            map(InputMode::Normal, key(KeyCode::Char('.')), &cfg),
+            Some(InputAction::SeekForward)
+        );
+        assert_eq!(
+            map(InputMode::Normal, KeyEvent::new(KeyCode::Char('<'), KeyModifiers::SHIFT), &cfg),
+            Some(InputAction::SeekBackwardLong)
+        );
+        assert_eq!(
+            map(InputMode::Normal, key(KeyCode::Char('\'')), &cfg),
+            Some(InputAction::NextBookmark)
+        );
+        assert_eq!(
+            map(InputMode::Normal, KeyEvent::new(KeyCode::Char('}'), KeyModifiers::SHIFT), &cfg),
+            Some(InputAction::NextAlbum)
+        );
     }
 
     #[test]
@@ -194,12 +335,45 @@ mod tests {
             map(InputMode::Search, ctrl(KeyCode::Char(' ')), &cfg),
             Some(InputAction::TogglePause)
         );
+        assert_eq!(
+            map(InputMode::Search, ctrl(KeyCode::Char('g')), &cfg),
+            Some(InputAction::JumpToPlaying)
+        );
         assert_eq!(
             map(InputMode::Search, key(KeyCode::Char('a')), &cfg),
             Some(InputAction::SearchAppend('a'))
         );
     }
 
+    #[test]
+    fn quick_filter_types_printable_keys_and_keeps_other_bindings() {
+        let cfg = KeyConfig::default();
+        assert_eq!(
+            map(InputMode::QuickFilter, key(KeyCode::Char('q')), &cfg),
+            Some(InputAction::QuickFilterAppend('q'))
+        );
+        assert_eq!(
+            map(InputMode::QuickFilter, key(KeyCode::Backspace), &cfg),
+            Some(InputAction::QuickFilterBackspace)
+        );
+        assert_eq!(
+            map(InputMode::QuickFilter, key(KeyCode::Esc), &cfg),
+            Some(InputAction::QuickFilterClear)
+        );
+        assert_eq!(
+            map(InputMode::QuickFilter, key(KeyCode::Down), &cfg),
+            Some(InputAction::NavigateDown)
+        );
+        assert_eq!(
+            map(InputMode::QuickFilter, ctrl(KeyCode::Char('c')), &cfg),
+            Some(InputAction::Quit)
+        );
+        assert_eq!(
+            map(InputMode::QuickFilter, ctrl(KeyCode::Char(' ')), &cfg),
+            Some(InputAction::TogglePause)
+        );
+    }
+
     #[test]
     fn settings_mode_bindings() {
         let cfg = KeyConfig::default();
@@ -326,4 +500,24 @@ mod tests {
             Some(InputAction::SettingsNavigateUp)
         );
     }
+
+    #[test]
+    fn only_back_to_back_selection_changes_are_collapsed() {
+        let events = coalesce_selection_changes(vec![
+            UiEvent::SelectionChanged { index: 1 },
+            UiEvent::SelectionChanged { index: 2 },
+            UiEvent::PlaySelectedRequested,
+            UiEvent::SelectionChanged { index: 3 },
+            UiEvent::SelectionChanged { index: 4 },
+        ]);
+
+        assert!(matches!(
+            events[..],
+            [
+                UiEvent::SelectionChanged { index: 2 },
+                UiEvent::PlaySelectedRequested,
+                UiEvent::SelectionChanged { index: 4 },
+            ]
+        ));
+    }
 }
@@ -30,21 +30,38 @@ impl KeyBinding {
             return Err("Key binding cannot be empty".to_string());
         }
 
-        let parts: Vec<&str> = raw.split('+').map(|p| p.trim()).collect();
+        // A literal plus is the key itself, alone or after a modifier: `+`, `Ctrl++`.
+        let (raw_keys, plus_key) = if raw == "+" {
+            ("", true)
+        } else if let Some(mods) = raw.strip_suffix("++") {
+            (mods, true)
+        } else {
+            (raw, false)
+        };
+
+        let parts: Vec<&str> = if raw_keys.is_empty() {
+            Vec::new()
+        } else {
+            raw_keys.split('+').map(|p| p.trim()).collect()
+        };
         if parts.iter().any(|p| p.is_empty()) {
             return Err(format!("Invalid key binding '{raw}': empty segment"));
         }
 
-        let (key_part, modifier_parts) = parts
-            .split_last()
-            .ok_or_else(|| "Key binding cannot be empty".to_string())?;
+        let (code, modifier_parts) = if plus_key {
+            (KeyCode::Char('+'), parts.as_slice())
+        } else {
+            let (key_part, modifier_parts) = parts
+                .split_last()
+                .ok_or_else(|| "Key binding cannot be empty".to_string())?;
+            (parse_key_code(key_part)?, modifier_parts)
+        };
 
         let mut modifiers = KeyModifiers::NONE;
         for m in modifier_parts {
             modifiers |= parse_modifier(m)?;
         }
 
-        let code = parse_key_code(key_part)?;
         let (code, modifiers) = normalize_char_key(code, modifiers);
 
         Ok(Self { code, modifiers })
@@ -224,6 +241,20 @@ mod tests {
         assert_eq!(backspace.modifiers, KeyModifiers::NONE);
     }
 
+    #[test]
+    fn plus_key_parses_alone_and_after_a_modifier() {
+        let plus = KeyBinding::from_str("+").unwrap();
+        assert_eq!(plus.code, KeyCode::Char('+'));
+        assert_eq!(plus.modifiers, KeyModifiers::NONE);
+
+        let ctrl_plus = KeyBinding::from_str("Ctrl++").unwrap();
+        assert_eq!(ctrl_plus.code, KeyCode::Char('+'));
+        assert_eq!(ctrl_plus.modifiers, KeyModifiers::CONTROL);
+        assert_eq!(KeyBinding::from_str(&ctrl_plus.to_string()).unwrap(), ctrl_plus);
+
+        assert!(KeyBinding::from_str("Ctrl+").is_err());
+    }
+
     #[test]
     fn unknown_key_name_is_error() {
         let err = KeyBinding::from_str("NotAKey").unwrap_err();
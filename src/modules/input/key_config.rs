@@ -152,19 +152,30 @@ impl KeyConfig {
                     }
                 };
 
-                config.clear_action(mode, action);
-
-                for key_str in key_strings {
-                    let binding = match KeyBinding::from_str(&key_str) {
-                        Ok(b) => b,
+                let bindings: Vec<KeyBinding> = key_strings
+                    .iter()
+                    .filter_map(|key_str| match KeyBinding::from_str(key_str) {
+                        Ok(b) => Some(b),
                         Err(err) => {
                             eprintln!(
                                 "Warning: Invalid binding '{key_str}' for '{section_name}.{key_name}': {err}. Ignoring."
                             );
-                            continue;
+                            None
                         }
-                    };
+                    })
+                    .collect();
+
+                // Only replace the defaults when at least one key was usable,
+                // so a typo never leaves an action without any binding.
+                if bindings.is_empty() {
+                    eprintln!(
+                        "Warning: No valid keys for '{section_name}.{key_name}'; keeping the default binding."
+                    );
+                    continue;
+                }
 
+                config.clear_action(mode, action);
+                for binding in bindings {
                     config.bindings.insert((mode, binding), action);
                 }
             }
@@ -212,17 +223,45 @@ fn action_for_key(section: SectionKind, key: &str) -> Option<InputAction> {
             "next_track" => Some(InputAction::NextTrack),
             "prev_track" => Some(InputAction::PreviousTrack),
             "toggle_shuffle" => Some(InputAction::ToggleShuffle),
+            "toggle_mute" => Some(InputAction::ToggleMute),
             "refresh" => Some(InputAction::Refresh),
             "cycle_sort" => Some(InputAction::CycleSort),
+            "toggle_album_view" => Some(InputAction::ToggleAlbumView),
+            "enqueue" => Some(InputAction::EnqueueSelected),
+            "remove" => Some(InputAction::RemoveSelected),
+            "loop_set_a" => Some(InputAction::LoopSetA),
+            "loop_set_b" => Some(InputAction::LoopSetB),
+            "loop_clear" => Some(InputAction::LoopClear),
+            "show_help" => Some(InputAction::ToggleHelp),
+            "jump_to_playing" => Some(InputAction::JumpToPlaying),
+            "volume_up" => Some(InputAction::VolumeUp),
+            "volume_down" => Some(InputAction::VolumeDown),
+            "sleep_timer" => Some(InputAction::CycleSleepTimer),
+            "toggle_visualizer" => Some(InputAction::ToggleVisualizer),
+            "show_recent" => Some(InputAction::ShowRecent),
+            "restart_track" => Some(InputAction::RestartTrack),
+            "toggle_favorite" => Some(InputAction::ToggleFavorite),
+            "seek_forward" => Some(InputAction::SeekForward),
+            "seek_backward" => Some(InputAction::SeekBackward),
+            "seek_forward_long" => Some(InputAction::SeekForwardLong),
+            "seek_backward_long" => Some(InputAction::SeekBackwardLong),
+            "add_bookmark" => Some(InputAction::AddBookmark),
+            "next_bookmark" => Some(InputAction::NextBookmark),
+            "next_album" => Some(InputAction::NextAlbum),
+            "prev_album" => Some(InputAction::PreviousAlbum),
             _ => None,
         },
         SectionKind::Search => match key {
             "search_exit" => Some(InputAction::SearchExit),
             "toggle_pause" => Some(InputAction::TogglePause),
             "clear_line" => Some(InputAction::SearchClearLine),
+            "history_prev" => Some(InputAction::SearchHistoryPrev),
+            "history_next" => Some(InputAction::SearchHistoryNext),
+            "enqueue_all" => Some(InputAction::SearchEnqueueAll),
             "navigate_up" => Some(InputAction::NavigateUp),
             "navigate_down" => Some(InputAction::NavigateDown),
             "play_selected" => Some(InputAction::PlaySelected),
+            "jump_to_playing" => Some(InputAction::JumpToPlaying),
             _ => None,
         },
         SectionKind::Settings => match key {
@@ -256,7 +295,7 @@ fn parse_key_list(value: &toml::Value) -> Result<Vec<String>, String> {
 }
 
 fn keymap_path(config_dir: &Path) -> PathBuf {
-    config_dir.join(&format!("{}", APP_NAME)).join("keymap.toml")
+    config_dir.join(APP_NAME).join("keymap.toml")
 }
 
 fn write_default_keymap_if_missing(file_path: &Path) -> io::Result<()> {
@@ -318,8 +357,32 @@ fn default_keymap_toml() -> String {
             (InputAction::NextTrack, "next_track"),
             (InputAction::PreviousTrack, "prev_track"),
             (InputAction::ToggleShuffle, "toggle_shuffle"),
+            (InputAction::ToggleMute, "toggle_mute"),
             (InputAction::Refresh, "refresh"),
             (InputAction::CycleSort, "cycle_sort"),
+            (InputAction::ToggleAlbumView, "toggle_album_view"),
+            (InputAction::EnqueueSelected, "enqueue"),
+            (InputAction::RemoveSelected, "remove"),
+            (InputAction::LoopSetA, "loop_set_a"),
+            (InputAction::LoopSetB, "loop_set_b"),
+            (InputAction::LoopClear, "loop_clear"),
+            (InputAction::ToggleHelp, "show_help"),
+            (InputAction::JumpToPlaying, "jump_to_playing"),
+            (InputAction::VolumeUp, "volume_up"),
+            (InputAction::VolumeDown, "volume_down"),
+            (InputAction::CycleSleepTimer, "sleep_timer"),
+            (InputAction::ToggleVisualizer, "toggle_visualizer"),
+            (InputAction::ShowRecent, "show_recent"),
+            (InputAction::RestartTrack, "restart_track"),
+            (InputAction::ToggleFavorite, "toggle_favorite"),
+            (InputAction::SeekForward, "seek_forward"),
+            (InputAction::SeekBackward, "seek_backward"),
+            (InputAction::SeekForwardLong, "seek_forward_long"),
+            (InputAction::SeekBackwardLong, "seek_backward_long"),
+            (InputAction::AddBookmark, "add_bookmark"),
+            (InputAction::NextBookmark, "next_bookmark"),
+            (InputAction::NextAlbum, "next_album"),
+            (InputAction::PreviousAlbum, "prev_album"),
         ],
         &bindings,
     );
@@ -333,9 +396,13 @@ fn default_keymap_toml() -> String {
             (InputAction::SearchExit, "search_exit"),
             (InputAction::TogglePause, "toggle_pause"),
             (InputAction::SearchClearLine, "clear_line"),
+            (InputAction::SearchHistoryPrev, "history_prev"),
+            (InputAction::SearchHistoryNext, "history_next"),
+            (InputAction::SearchEnqueueAll, "enqueue_all"),
             (InputAction::NavigateUp, "navigate_up"),
             (InputAction::NavigateDown, "navigate_down"),
             (InputAction::PlaySelected, "play_selected"),
+            (InputAction::JumpToPlaying, "jump_to_playing"),
         ],
         &bindings,
     );
@@ -520,6 +587,29 @@ quit = "x"
         assert_eq!(cfg.get(InputMode::Normal, &b(KeyCode::Char('q'))), None);
     }
 
+    #[test]
+    fn malformed_binding_keeps_the_valid_ones() {
+        let toml_str = r#"
+[normal]
+quit = ["Hyper+q", "x"]
+"#;
+
+        let cfg = KeyConfig::parse(toml_str).unwrap();
+        assert_eq!(cfg.get(InputMode::Normal, &b(KeyCode::Char('x'))), Some(InputAction::Quit));
+        assert_eq!(cfg.get(InputMode::Normal, &b(KeyCode::Char('q'))), None);
+    }
+
+    #[test]
+    fn action_with_only_malformed_bindings_keeps_defaults() {
+        let toml_str = r#"
+[normal]
+quit = ["Hyper+q", ""]
+"#;
+
+        let cfg = KeyConfig::parse(toml_str).unwrap();
+        assert_eq!(cfg.get(InputMode::Normal, &b(KeyCode::Char('q'))), Some(InputAction::Quit));
+    }
+
     #[test]
     fn user_entry_does_not_affect_other_modes() {
         let toml_str = r#"
@@ -6,7 +6,7 @@ pub mod key_config;
 pub mod mode;
 
 pub use action::InputAction;
-pub use handler::map as map_key;
+pub use handler::{coalesce_selection_changes, drain_pending, map as map_key, wait_for_input};
 pub use key_binding::KeyBinding;
 pub use key_config::KeyConfig;
 pub use mode::InputMode;
@@ -9,4 +9,9 @@ pub enum InputMode {
     /// In this mode, character keys should insert text instead of triggering
     /// modal-level shortcuts (e.g. the "close settings" toggle key).
     SettingsTextEntry,
+    /// Normal mode with type-ahead filtering enabled (`browse --quick-filter`).
+    ///
+    /// Printable keys extend the filter instead of triggering shortcuts; every
+    /// other key keeps its normal-mode binding.
+    QuickFilter,
 }
@@ -0,0 +1,92 @@
+use crate::core::models::Song;
+
+/// First library index of the album after (or before) the one holding `current`.
+///
+/// Albums are runs of consecutive songs with the same album tag (ignoring case),
+/// so they follow whatever order the library is in; sorting by album groups
+/// each album into a single run. Songs without an album tag form runs of their own.
+///
+/// Past the last album (or before the first), `wrap` goes round to the other
+/// end; otherwise there is nowhere to go. Without a current song, "next" starts
+/// at the first album.
+pub fn adjacent_album_start(songs: &[Song], current: Option<usize>, forward: bool, wrap: bool) -> Option<usize> {
+    let Some(current) = current.filter(|&i| i < songs.len()) else {
+        return (forward && !songs.is_empty()).then_some(0);
+    };
+
+    if forward {
+        let next = (current + 1..songs.len()).find(|&i| !same_album(&songs[i], &songs[current]));
+        next.or_else(|| wrap.then_some(0))
+    } else {
+        match album_start(songs, current) {
+            0 if wrap => Some(album_start(songs, songs.len() - 1)),
+            0 => None,
+            start => Some(album_start(songs, start - 1)),
+        }
+    }
+}
+
+/// Index of the first song of the run `index` belongs to.
+fn album_start(songs: &[Song], index: usize) -> usize {
+    let mut start = index;
+    while start > 0 && same_album(&songs[start - 1], &songs[index]) {
+        start -= 1;
+    }
+    start
+}
+
+fn same_album(a: &Song, b: &Song) -> bool {
+    match (&a.album, &b.album) {
+        (Some(a), Some(b)) => a.to_lowercase() == b.to_lowercase(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    /// Three albums of 3, 2 and 2 songs, in that order.
+    fn library() -> Vec<Song> {
+        [("A", 3), ("B", 2), ("c", 2)]
+            .into_iter()
+            .flat_map(|(album, tracks)| {
+                (0..tracks).map(move |track| Song {
+                    album: Some(album.to_owned()),
+                    ..Song::from_path(Path::new(&format!("/m/{album}{track}.mp3")))
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn next_album_jumps_to_the_first_track_of_the_following_album() {
+        let songs = library();
+        assert_eq!(adjacent_album_start(&songs, Some(0), true, false), Some(3));
+        assert_eq!(adjacent_album_start(&songs, Some(2), true, false), Some(3));
+        assert_eq!(adjacent_album_start(&songs, Some(4), true, false), Some(5));
+        assert_eq!(adjacent_album_start(&songs, Some(6), true, false), None, "last album");
+        assert_eq!(adjacent_album_start(&songs, Some(5), true, true), Some(0), "wraps with repeat all");
+        assert_eq!(adjacent_album_start(&songs, None, true, false), Some(0));
+    }
+
+    #[test]
+    fn previous_album_jumps_to_the_first_track_of_the_album_before() {
+        let songs = library();
+        assert_eq!(adjacent_album_start(&songs, Some(4), false, false), Some(0));
+        assert_eq!(adjacent_album_start(&songs, Some(6), false, false), Some(3));
+        assert_eq!(adjacent_album_start(&songs, Some(1), false, false), None, "first album");
+        assert_eq!(adjacent_album_start(&songs, Some(1), false, true), Some(5), "wraps with repeat all");
+        assert_eq!(adjacent_album_start(&songs, None, false, true), None);
+    }
+
+    #[test]
+    fn untagged_songs_are_albums_of_one() {
+        let mut songs = library();
+        songs[1].album = None;
+        assert_eq!(adjacent_album_start(&songs, Some(0), true, false), Some(1));
+        assert_eq!(adjacent_album_start(&songs, Some(1), true, false), Some(2));
+        assert_eq!(adjacent_album_start(&songs, Some(2), false, false), Some(1));
+    }
+}
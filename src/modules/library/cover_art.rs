@@ -0,0 +1,158 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use crate::utils::APP_NAME;
+
+/// An embedded cover image: raw encoded bytes plus their MIME type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoverArt {
+    pub data: Vec<u8>,
+    pub mime_type: String,
+}
+
+/// File extensions used for cached covers, paired with the MIME type they stand for.
+const CACHE_EXTENSIONS: &[(&str, &str)] = &[
+    ("jpg", "image/jpeg"),
+    ("png", "image/png"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("bmp", "image/bmp"),
+    ("tif", "image/tiff"),
+    ("bin", "application/octet-stream"),
+];
+
+impl CoverArt {
+    /// Extension to store this cover under; unknown types fall back to `bin`.
+    pub fn extension(&self) -> &'static str {
+        CACHE_EXTENSIONS
+            .iter()
+            .find(|(_, mime)| mime.eq_ignore_ascii_case(&self.mime_type))
+            .map(|(ext, _)| *ext)
+            .unwrap_or("bin")
+    }
+}
+
+/// Directory extracted covers are cached in.
+pub fn cache_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("{}-covers", APP_NAME))
+}
+
+/// Return the cover of `source`, reading it from `dir` when cached.
+///
+/// On a cache miss, `extract` decodes the cover from the audio file and the
+/// result is written to `dir` for next time. Entries are keyed by the file path
+/// and its modification time, so retagging a file invalidates its cover.
+/// Files without embedded art return `None` and leave no cache entry.
+pub fn cached_cover(
+    dir: &Path,
+    source: &Path,
+    extract: impl FnOnce(&Path) -> Option<CoverArt>,
+) -> Option<CoverArt> {
+    let key = cache_key(source);
+
+    for (ext, mime) in CACHE_EXTENSIONS {
+        if let Ok(data) = fs::read(dir.join(format!("{}.{}", key, ext))) {
+            return Some(CoverArt { data, mime_type: mime.to_string() });
+        }
+    }
+
+    let cover = extract(source)?;
+
+    // Caching is best-effort: a read-only temp dir just means decoding again next time.
+    if fs::create_dir_all(dir).is_ok() {
+        let _ = fs::write(dir.join(format!("{}.{}", key, cover.extension())), &cover.data);
+    }
+
+    Some(cover)
+}
+
+fn cache_key(source: &Path) -> String {
+    let mtime = source.metadata().and_then(|m| m.modified()).ok();
+
+    let mut hasher = DefaultHasher::new();
+    source.hash(&mut hasher);
+    mtime.unwrap_or(SystemTime::UNIX_EPOCH).hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "hextune_covers_{}_{}_{}",
+            name,
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn png() -> CoverArt {
+        CoverArt { data: b"\x89PNG fake".to_vec(), mime_type: "image/png".to_owned() }
+    }
+
+    #[test]
+    fn second_lookup_is_served_from_cache() {
+        let dir = temp_dir("hit");
+        let source = dir.join("song.mp3");
+        fs::write(&source, b"audio").unwrap();
+        let calls = Cell::new(0);
+        let extract = |_: &Path| {
+            calls.set(calls.get() + 1);
+            Some(png())
+        };
+
+        let first = cached_cover(&dir, &source, extract);
+        let second = cached_cover(&dir, &source, extract);
+
+        assert_eq!(first, Some(png()));
+        assert_eq!(second, Some(png()), "cached copy keeps its mime type");
+        assert_eq!(calls.get(), 1);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn webp_and_gif_covers_keep_their_mime_type_in_the_cache() {
+        let dir = temp_dir("formats");
+        for mime in ["image/webp", "image/gif"] {
+            let source = dir.join(format!("{}.mp3", mime.replace('/', "_")));
+            fs::write(&source, b"audio").unwrap();
+            let art = CoverArt { data: b"fake image".to_vec(), mime_type: mime.to_owned() };
+
+            assert_eq!(cached_cover(&dir, &source, |_| Some(art.clone())).as_ref(), Some(&art));
+            assert_eq!(cached_cover(&dir, &source, |_| None), Some(art), "served from the cache");
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn missing_art_returns_none_and_is_not_cached() {
+        let dir = temp_dir("none");
+        let source = dir.join("song.mp3");
+        fs::write(&source, b"audio").unwrap();
+
+        assert_eq!(cached_cover(&dir, &source, |_| None), None);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1, "only the source file remains");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn unknown_mime_type_is_stored_as_bin() {
+        let art = CoverArt { data: vec![1, 2, 3], mime_type: "image/heic".to_owned() };
+        assert_eq!(art.extension(), "bin");
+        assert_eq!(png().extension(), "png");
+    }
+}
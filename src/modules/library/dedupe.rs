@@ -0,0 +1,129 @@
+use crate::core::models::Song;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Group library entries that are the same track.
+///
+/// Two songs are duplicates when they share a path, or when they have the same
+/// title, the same artists (ignoring case and order) and a known duration equal
+/// to the second. Songs without a duration only match by path.
+///
+/// Returns groups of library indices with at least two members. Each group is
+/// in ascending order and groups are ordered by their first index, so keeping
+/// `group[0]` keeps the copy that appears first in the library.
+pub fn duplicate_groups(songs: &[Song]) -> Vec<Vec<usize>> {
+    let mut groups = DisjointSet::new(songs.len());
+    let mut by_path: HashMap<&Path, usize> = HashMap::new();
+    let mut by_tags: HashMap<(String, Vec<String>, u64), usize> = HashMap::new();
+
+    for (index, song) in songs.iter().enumerate() {
+        if let Some(&first) = by_path.get(song.path.as_path()) {
+            groups.union(first, index);
+        } else {
+            by_path.insert(&song.path, index);
+        }
+
+        if let Some(key) = tag_key(song) {
+            match by_tags.get(&key) {
+                Some(&first) => groups.union(first, index),
+                None => {
+                    by_tags.insert(key, index);
+                }
+            }
+        }
+    }
+
+    let mut members: HashMap<usize, Vec<usize>> = HashMap::new();
+    for index in 0..songs.len() {
+        members.entry(groups.find(index)).or_default().push(index);
+    }
+
+    let mut duplicates: Vec<Vec<usize>> = members.into_values().filter(|g| g.len() > 1).collect();
+    duplicates.sort_by_key(|group| group[0]);
+    duplicates
+}
+
+fn tag_key(song: &Song) -> Option<(String, Vec<String>, u64)> {
+    let secs = song.duration?.as_secs();
+    let mut artists: Vec<String> = song.artists.iter().map(|a| a.trim().to_lowercase()).collect();
+    artists.sort();
+    Some((song.title.trim().to_lowercase(), artists, secs))
+}
+
+/// Union–find over library indices; the smaller index becomes the root.
+struct DisjointSet {
+    parent: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(len: usize) -> Self {
+        Self { parent: (0..len).collect() }
+    }
+
+    fn find(&mut self, index: usize) -> usize {
+        let parent = self.parent[index];
+        if parent == index {
+            return index;
+        }
+        let root = self.find(parent);
+        self.parent[index] = root;
+        root
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        self.parent[a.max(b)] = a.min(b);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    fn make_song(path: &str, title: &str, artists: &[&str], secs: Option<u64>) -> Song {
+        Song {
+            path: PathBuf::from(path),
+            artists: artists.iter().map(|a| a.to_string()).collect(),
+            duration: secs.map(Duration::from_secs),
+            ..Song::test_fixture(title)
+        }
+    }
+
+    #[test]
+    fn same_tags_and_duration_are_duplicates() {
+        let songs = vec![
+            make_song("a/one.mp3", "Song", &["Queen", "Bowie"], Some(200)),
+            make_song("b/other.mp3", "Other", &["Queen"], Some(200)),
+            make_song("c/one.flac", " song ", &["bowie", "QUEEN"], Some(200)),
+        ];
+
+        assert_eq!(duplicate_groups(&songs), [vec![0, 2]]);
+    }
+
+    #[test]
+    fn different_duration_or_unknown_duration_is_not_a_tag_match() {
+        let songs = vec![
+            make_song("1.mp3", "Song", &["Queen"], Some(200)),
+            make_song("2.mp3", "Song", &["Queen"], Some(245)),
+            make_song("3.mp3", "Song", &["Queen"], None),
+            make_song("4.mp3", "Song", &["Queen"], None),
+        ];
+
+        assert!(duplicate_groups(&songs).is_empty());
+    }
+
+    #[test]
+    fn identical_paths_are_duplicates_and_groups_merge() {
+        let songs = vec![
+            make_song("x.mp3", "Intro", &[], None),
+            make_song("y.mp3", "Song", &["Queen"], Some(200)),
+            make_song("x.mp3", "Intro", &[], None),
+            make_song("z.mp3", "Song", &["Queen"], Some(200)),
+            make_song("y.mp3", "Song (retagged)", &["Queen"], Some(201)),
+        ];
+
+        assert_eq!(duplicate_groups(&songs), [vec![0, 2], vec![1, 3, 4]]);
+    }
+}
@@ -0,0 +1,433 @@
+use crate::core::models::Song;
+use std::fmt;
+
+/// A parsed filter query such as `artist=Queen and duration>180`.
+///
+/// `and` binds tighter than `or`; parentheses group. String fields compare
+/// case-insensitively, and `artist` matches when any of a song's artists does.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+    Compare { field: Field, op: Op, value: Value },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Title,
+    Artist,
+    Album,
+    Genre,
+    /// Length in seconds.
+    Duration,
+    Year,
+    Track,
+    /// Stars, 1 to 5.
+    Rating,
+    /// `yes` or `no`.
+    Favorite,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    /// Substring match, for string fields only.
+    Contains,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Text(String),
+    Number(u64),
+}
+
+impl Field {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name.to_lowercase().as_str() {
+            "title" => Self::Title,
+            "artist" => Self::Artist,
+            "album" => Self::Album,
+            "genre" => Self::Genre,
+            "duration" => Self::Duration,
+            "year" => Self::Year,
+            "track" => Self::Track,
+            "rating" => Self::Rating,
+            "favorite" => Self::Favorite,
+            _ => return None,
+        })
+    }
+
+    fn is_numeric(self) -> bool {
+        matches!(self, Self::Duration | Self::Year | Self::Track | Self::Rating | Self::Favorite)
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Title => "title",
+            Self::Artist => "artist",
+            Self::Album => "album",
+            Self::Genre => "genre",
+            Self::Duration => "duration",
+            Self::Year => "year",
+            Self::Track => "track",
+            Self::Rating => "rating",
+            Self::Favorite => "favorite",
+        })
+    }
+}
+
+impl Op {
+    fn parse(symbol: &str) -> Option<Self> {
+        Some(match symbol {
+            "=" | "==" => Self::Eq,
+            "!=" => Self::Ne,
+            "<" => Self::Lt,
+            "<=" => Self::Le,
+            ">" => Self::Gt,
+            ">=" => Self::Ge,
+            "~" => Self::Contains,
+            _ => return None,
+        })
+    }
+
+    fn holds<T: Ord>(self, left: T, right: T) -> bool {
+        match self {
+            Self::Eq | Self::Contains => left == right,
+            Self::Ne => left != right,
+            Self::Lt => left < right,
+            Self::Le => left <= right,
+            Self::Gt => left > right,
+            Self::Ge => left >= right,
+        }
+    }
+}
+
+impl Filter {
+    /// Parse a filter query; the error names the part that could not be understood.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser { tokens: &tokens, pos: 0 };
+        let filter = parser.or_expr()?;
+        match parser.peek() {
+            None => Ok(filter),
+            Some(token) => Err(format!("unexpected '{}'", token)),
+        }
+    }
+
+    pub fn matches(&self, song: &Song) -> bool {
+        match self {
+            Self::And(left, right) => left.matches(song) && right.matches(song),
+            Self::Or(left, right) => left.matches(song) || right.matches(song),
+            Self::Compare { field, op, value } => compare(song, *field, *op, value),
+        }
+    }
+}
+
+/// Songs without the compared tag never match, whatever the operator.
+fn compare(song: &Song, field: Field, op: Op, value: &Value) -> bool {
+    match (field, value) {
+        (Field::Duration, Value::Number(n)) => song.duration.is_some_and(|d| op.holds(d.as_secs(), *n)),
+        (Field::Year, Value::Number(n)) => song.year.is_some_and(|y| op.holds(u64::from(y), *n)),
+        (Field::Track, Value::Number(n)) => song.track_number.is_some_and(|t| op.holds(u64::from(t), *n)),
+        (Field::Rating, Value::Number(n)) => song.rating.is_some_and(|r| op.holds(u64::from(r), *n)),
+        (Field::Favorite, Value::Number(n)) => op.holds(u64::from(song.favorite), *n),
+        (Field::Artist, Value::Text(text)) => match op {
+            // `artist!=X` means none of the artists is X.
+            Op::Ne => {
+                !song.artists.is_empty() && !song.artists.iter().any(|artist| text_matches(artist, Op::Eq, text))
+            }
+            _ => song.artists.iter().any(|artist| text_matches(artist, op, text)),
+        },
+        (Field::Title, Value::Text(text)) => text_matches(&song.title, op, text),
+        (Field::Album, Value::Text(text)) => song.album.as_deref().is_some_and(|a| text_matches(a, op, text)),
+        (Field::Genre, Value::Text(text)) => song.genre.as_deref().is_some_and(|g| text_matches(g, op, text)),
+        _ => false,
+    }
+}
+
+fn text_matches(actual: &str, op: Op, wanted: &str) -> bool {
+    let actual = actual.to_lowercase();
+    let wanted = wanted.to_lowercase();
+    match op {
+        Op::Contains => actual.contains(&wanted),
+        _ => op.holds(actual.as_str(), wanted.as_str()),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Op(String),
+    Open,
+    Close,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Word(word) | Self::Op(word) => f.write_str(word),
+            Self::Quoted(text) => write!(f, "\"{}\"", text),
+            Self::Open => f.write_str("("),
+            Self::Close => f.write_str(")"),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    const OP_CHARS: &[char] = &['=', '!', '<', '>', '~'];
+
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '(' || c == ')' {
+            chars.next();
+            tokens.push(if c == '(' { Token::Open } else { Token::Close });
+        } else if c == '"' {
+            chars.next();
+            let mut text = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some(c) => text.push(c),
+                    None => return Err(format!("unterminated quote in \"{}", text)),
+                }
+            }
+            tokens.push(Token::Quoted(text));
+        } else if OP_CHARS.contains(&c) {
+            let mut op = String::new();
+            while let Some(&c) = chars.peek().filter(|c| OP_CHARS.contains(c)) {
+                op.push(c);
+                chars.next();
+            }
+            tokens.push(Token::Op(op));
+        } else {
+            let mut word = String::new();
+            while let Some(&c) = chars
+                .peek()
+                .filter(|c| !c.is_whitespace() && !OP_CHARS.contains(c) && !matches!(c, '(' | ')' | '"'))
+            {
+                word.push(c);
+                chars.next();
+            }
+            tokens.push(Token::Word(word));
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.pos);
+        self.pos += 1;
+        token
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword));
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn or_expr(&mut self) -> Result<Filter, String> {
+        let mut left = self.and_expr()?;
+        while self.keyword("or") {
+            left = Filter::Or(Box::new(left), Box::new(self.and_expr()?));
+        }
+        Ok(left)
+    }
+
+    fn and_expr(&mut self) -> Result<Filter, String> {
+        let mut left = self.primary()?;
+        while self.keyword("and") {
+            left = Filter::And(Box::new(left), Box::new(self.primary()?));
+        }
+        Ok(left)
+    }
+
+    fn primary(&mut self) -> Result<Filter, String> {
+        match self.next() {
+            Some(Token::Open) => {
+                let inner = self.or_expr()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(inner),
+                    _ => Err("missing ')'".to_string()),
+                }
+            }
+            Some(Token::Word(name)) => {
+                let field = Field::parse(name).ok_or_else(|| {
+                    format!(
+                        "unknown field '{}' (expected title, artist, album, genre, duration, year, track, rating or favorite)",
+                        name
+                    )
+                })?;
+                self.comparison(field)
+            }
+            Some(token) => Err(format!("expected a field, found '{}'", token)),
+            None => Err("expected a field, found the end of the query".to_string()),
+        }
+    }
+
+    fn comparison(&mut self, field: Field) -> Result<Filter, String> {
+        let op = match self.next() {
+            Some(Token::Op(symbol)) => Op::parse(symbol).ok_or_else(|| format!("unknown operator '{}'", symbol))?,
+            _ => return Err(format!("expected an operator after '{}'", field)),
+        };
+
+        let raw = match self.next() {
+            Some(Token::Word(text) | Token::Quoted(text)) => text.clone(),
+            _ => return Err(format!("expected a value after '{}'", field)),
+        };
+
+        let value = if field.is_numeric() {
+            if op == Op::Contains {
+                return Err(format!("'~' only works on text fields, not '{}'", field));
+            }
+            if field == Field::Favorite && !matches!(op, Op::Eq | Op::Ne) {
+                return Err("'favorite' only takes '=' and '!='".to_string());
+            }
+            Value::Number(parse_number(field, &raw)?)
+        } else {
+            Value::Text(raw)
+        };
+
+        Ok(Filter::Compare { field, op, value })
+    }
+}
+
+/// Durations also accept `m:ss`; `favorite` takes yes/no and is stored as 1/0.
+fn parse_number(field: Field, raw: &str) -> Result<u64, String> {
+    if field == Field::Favorite {
+        return match raw.to_lowercase().as_str() {
+            "yes" | "true" => Ok(1),
+            "no" | "false" => Ok(0),
+            _ => Err(format!("'{}' is not yes or no", raw)),
+        };
+    }
+
+    let parsed = match raw.split_once(':') {
+        Some((mins, secs)) if field == Field::Duration => mins
+            .parse::<u64>()
+            .ok()
+            .zip(secs.parse::<u64>().ok().filter(|s| *s < 60))
+            .map(|(m, s)| m * 60 + s),
+        _ => raw.parse().ok(),
+    };
+    parsed.ok_or_else(|| format!("'{}' is not a valid {}", raw, field))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn song(title: &str, artists: &[&str], secs: u64, year: Option<u32>) -> Song {
+        Song {
+            artists: artists.iter().map(|a| a.to_string()).collect(),
+            album: Some("Greatest Hits".to_owned()),
+            track_number: Some(1),
+            duration: Some(Duration::from_secs(secs)),
+            year,
+            ..Song::test_fixture(title)
+        }
+    }
+
+    fn cmp(field: Field, op: Op, value: Value) -> Filter {
+        Filter::Compare { field, op, value }
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let parsed = Filter::parse("year>=1990 or artist=Queen AND duration>3:00").unwrap();
+
+        assert_eq!(
+            parsed,
+            Filter::Or(
+                Box::new(cmp(Field::Year, Op::Ge, Value::Number(1990))),
+                Box::new(Filter::And(
+                    Box::new(cmp(Field::Artist, Op::Eq, Value::Text("Queen".into()))),
+                    Box::new(cmp(Field::Duration, Op::Gt, Value::Number(180))),
+                )),
+            )
+        );
+    }
+
+    #[test]
+    fn quotes_and_parentheses_parse() {
+        let parsed = Filter::parse(r#"(album~"greatest hits" or genre=rock) and track<=3"#).unwrap();
+
+        assert!(matches!(parsed, Filter::And(left, _) if matches!(*left, Filter::Or(..))));
+    }
+
+    #[test]
+    fn malformed_queries_are_rejected_with_a_reason() {
+        for (query, reason) in [
+            ("colour=red", "unknown field"),
+            ("year=", "expected a value"),
+            ("year 1990", "expected an operator"),
+            ("year=nineteen", "not a valid year"),
+            ("duration~3", "only works on text"),
+            ("(year=1990", "missing ')'"),
+            ("year=1990 artist=Queen", "unexpected 'artist'"),
+            ("title=\"open", "unterminated quote"),
+            ("year=>1990", "unknown operator"),
+            ("favorite=maybe", "not yes or no"),
+            ("favorite>no", "only takes"),
+        ] {
+            let err = Filter::parse(query).unwrap_err();
+            assert!(err.contains(reason), "{query}: {err}");
+        }
+    }
+
+    #[test]
+    fn comparisons_evaluate_against_song_fields() {
+        let bohemian = song("Bohemian Rhapsody", &["Queen"], 354, Some(1975));
+        let duet = song("Under Pressure", &["Queen", "David Bowie"], 248, None);
+
+        let matches = |query: &str, song: &Song| Filter::parse(query).unwrap().matches(song);
+
+        assert!(matches("artist=queen and duration>300", &bohemian));
+        assert!(!matches("artist=queen and duration>300", &duet));
+        assert!(matches("artist=\"david bowie\"", &duet), "any artist matches");
+        assert!(!matches("artist!=\"david bowie\"", &duet));
+        assert!(matches("artist!=\"david bowie\"", &bohemian));
+        assert!(matches("title~rhapsody", &bohemian));
+        assert!(matches("year<1980", &bohemian));
+        assert!(!matches("year<1980 or year>=1980", &duet), "a missing tag never matches");
+        assert!(!matches("genre!=rock", &bohemian));
+    }
+
+    #[test]
+    fn ratings_and_favorites_filter() {
+        let loved = Song { rating: Some(5), favorite: true, ..song("Bohemian Rhapsody", &["Queen"], 354, None) };
+        let unrated = song("Under Pressure", &["Queen"], 248, None);
+
+        let matches = |query: &str, song: &Song| Filter::parse(query).unwrap().matches(song);
+
+        assert!(matches("favorite=yes and rating>=4", &loved));
+        assert!(matches("favorite=no", &unrated));
+        assert!(!matches("rating<3", &unrated), "unrated songs never match a rating");
+    }
+}
@@ -1,3 +1,8 @@
+pub mod albums;
+pub mod cover_art;
+pub mod dedupe;
+pub mod filter;
 pub mod scanner;
 pub mod search_engine;
 pub mod sorter;
+pub mod stats;
@@ -1,34 +1,288 @@
 use crate::core::models::Song;
-use anyhow::Result;
+use anyhow::{Context, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use walkdir::WalkDir;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 use crate::utils::{SCAN_PROGRESS_INTERVAL, SUPPORTED_EXTENSIONS};
 
-/// Scan `root` recursively for audio files and return them as a `Vec<Song>`
+/// Include/exclude glob patterns applied to paths relative to the scan root
+///
+/// A file is scanned if it matches any include pattern (or there are none)
+/// and no exclude pattern. A directory matching an exclude pattern, either by
+/// itself (`samples`, `**/samples`) or as a prefix (`samples/**`), is not
+/// descended into at all. The default filter lets every audio file through.
+#[derive(Debug, Clone, Default)]
+pub struct ScanFilter {
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+}
+
+impl ScanFilter {
+    /// Compile the patterns; empty strings are ignored.
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self> {
+        let include = if include.iter().any(|p| !p.is_empty()) {
+            Some(build_glob_set(include)?)
+        } else {
+            None
+        };
+
+        Ok(Self { include, exclude: build_glob_set(exclude)? })
+    }
+
+    fn allows_file(&self, relative: &Path) -> bool {
+        self.include.as_ref().is_none_or(|set| set.is_match(relative))
+            && !self.exclude.is_match(relative)
+    }
+
+    fn excludes_dir(&self, relative: &Path) -> bool {
+        // "dir/" lets prefix patterns such as `dir/**` match the directory itself.
+        let mut with_slash = relative.as_os_str().to_owned();
+        with_slash.push("/");
+
+        self.exclude.is_match(relative) || self.exclude.is_match(PathBuf::from(with_slash))
+    }
+}
+
+fn build_glob_set(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns.iter().filter(|p| !p.is_empty()) {
+        builder.add(Glob::new(pattern).with_context(|| format!("Invalid glob '{}'", pattern))?);
+    }
+    Ok(builder.build()?)
+}
+
+/// Scan each of `roots` recursively for audio files and return them as a `Vec<Song>`
+///
+/// A root inside another one is skipped, so overlapping trees are read once.
+/// Only files let through by `filter` (matched relative to their root) are read;
+/// excluded directories are skipped whole.
+///
+/// `previous` is the library from the last scan. A file whose modification
+/// time still matches the cached `scanned_mtime` is reused as-is instead of
+/// re-reading its tags, which keeps refreshing a large, unchanged library cheap.
+/// Pass `&[]` to force every file to be read.
 ///
 /// `on_progress` is called every [`SCAN_PROGRESS_INTERVAL`] songs with the
 /// running count, so callers can surface progress to the user without flooding
 /// the event channel on large libraries.  Pass `|_| {}` to ignore progress
-pub fn scan_directory(root: &Path, on_progress: impl Fn(usize)) -> Result<Vec<Song>> {
-    let songs = WalkDir::new(root)
+pub fn scan_roots(
+    roots: &[impl AsRef<Path>],
+    previous: &[Song],
+    filter: &ScanFilter,
+    on_progress: impl Fn(usize),
+) -> Result<Vec<Song>> {
+    let cache: HashMap<&Path, &Song> = previous
+        .iter()
+        .map(|song| (song.path.as_path(), song))
+        .collect();
+
+    let started = std::time::Instant::now();
+    let mut songs = Vec::new();
+    for root in distinct_roots(roots) {
+        let relative = |path: &Path| path.strip_prefix(root).unwrap_or(path).to_path_buf();
+
+        let entries = WalkDir::new(root)
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !e.file_type().is_dir() || !filter.excludes_dir(&relative(e.path())))
+            .filter_map(|e| e.ok())
+            .filter(|e| {
+                e.path().is_file() && is_audio_file(e.path()) && filter.allows_file(&relative(e.path()))
+            });
+
+        for entry in entries {
+            songs.push(load_song(entry.path(), &cache));
+            if songs.len() % SCAN_PROGRESS_INTERVAL == 0 {
+                on_progress(songs.len());
+            }
+        }
+    }
+
+    let reused = songs.iter().filter(|song| cache.contains_key(song.path.as_path())).count();
+    log::info!(
+        "Scanned {} songs ({} new) in {:.1}s",
+        songs.len(),
+        songs.len() - reused,
+        started.elapsed().as_secs_f64()
+    );
+    Ok(order_album_tracks(songs))
+}
+
+/// `roots` without repeats and without any that lie inside another root.
+fn distinct_roots(roots: &[impl AsRef<Path>]) -> Vec<&Path> {
+    let mut distinct: Vec<&Path> = Vec::new();
+    for root in roots.iter().map(AsRef::as_ref) {
+        if distinct.iter().any(|kept| root.starts_with(kept)) {
+            continue;
+        }
+        distinct.retain(|kept| !kept.starts_with(root));
+        distinct.push(root);
+    }
+    distinct
+}
+
+/// Put the tracks of each album in track-number order (then title), since
+/// directory traversal order is arbitrary, and number `order` to match.
+///
+/// Songs are grouped by parent directory plus album tag; each group keeps the
+/// positions its songs already had, so albums do not move relative to each other.
+/// Tracks without a number follow the numbered ones.
+fn order_album_tracks(songs: Vec<Song>) -> Vec<Song> {
+    let mut groups: HashMap<(Option<&Path>, Option<&str>), Vec<usize>> = HashMap::new();
+    for (i, song) in songs.iter().enumerate() {
+        groups.entry((song.path.parent(), song.album.as_deref())).or_default().push(i);
+    }
+
+    // `source[slot]` is the index of the song that ends up at `slot`.
+    let mut source: Vec<usize> = (0..songs.len()).collect();
+    for slots in groups.values() {
+        let mut sorted = slots.clone();
+        sorted.sort_by(|&a, &b| {
+            let (a, b) = (&songs[a], &songs[b]);
+            (a.track_number.is_none(), a.track_number, &a.title)
+                .cmp(&(b.track_number.is_none(), b.track_number, &b.title))
+        });
+        for (&slot, &from) in slots.iter().zip(&sorted) {
+            source[slot] = from;
+        }
+    }
+
+    let mut songs: Vec<Option<Song>> = songs.into_iter().map(Some).collect();
+    source
         .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().is_file() && is_audio_file(e.path()))
         .enumerate()
-        .map(|(i, entry)| {
-            let mut song = Song::from_path(entry.path());
-            song.order = i;
+        .map(|(order, from)| {
+            let mut song = songs[from].take().expect("each song is placed once");
+            song.order = order;
+            song
+        })
+        .collect()
+}
+
+/// Result of merging a fresh scan into the existing library.
+#[derive(Debug, Clone)]
+pub struct LibraryMerge {
+    pub songs: Vec<Song>,
+    /// Files in the scan that were not in the library.
+    pub added: usize,
+    /// Library entries the scan found again.
+    pub unchanged: usize,
+}
+
+/// Union `scanned` into `existing` by path. Existing entries keep their
+/// position and data, even when the scan did not find them; files not seen
+/// before are appended in scan order.
+pub fn merge_scan(existing: &[Song], scanned: Vec<Song>) -> LibraryMerge {
+    let known: HashSet<&Path> = existing.iter().map(|song| song.path.as_path()).collect();
+    let found: HashSet<&Path> = scanned.iter().map(|song| song.path.as_path()).collect();
+
+    let unchanged = existing.iter().filter(|song| found.contains(song.path.as_path())).count();
+
+    let next_order = existing.iter().map(|song| song.order + 1).max().unwrap_or(0);
+    let new_songs: Vec<Song> = scanned
+        .iter()
+        .filter(|song| !known.contains(song.path.as_path()))
+        .cloned()
+        .collect();
+    let added = new_songs.len();
 
-            let count = i + 1;
-            if count % SCAN_PROGRESS_INTERVAL == 0 {
-                on_progress(count);
+    let mut songs = existing.to_vec();
+    songs.extend(new_songs.into_iter().enumerate().map(|(i, mut song)| {
+        song.order = next_order + i;
+        song
+    }));
+
+    LibraryMerge { songs, added, unchanged }
+}
+
+/// Split `songs` into those whose file still exists and those whose file is gone.
+pub fn split_missing(songs: Vec<Song>) -> (Vec<Song>, Vec<Song>) {
+    songs.into_iter().partition(|song| song.path.exists())
+}
+
+/// Result of re-reading the tags of the library's files in place.
+#[derive(Debug, Clone)]
+pub struct MetadataRefresh {
+    pub songs: Vec<Song>,
+    /// Entries whose title, artists, album, track, duration, genre or year changed.
+    pub changed: usize,
+    /// Entries whose file is gone; they are kept as they were.
+    pub missing: usize,
+}
+
+/// Re-read the tags of every library file that still exists, without looking
+/// for new files. Entries keep their position and everything that is not a tag.
+pub fn refresh_metadata(songs: &[Song]) -> MetadataRefresh {
+    let mut changed = 0;
+    let mut missing = 0;
+
+    let songs = songs
+        .iter()
+        .map(|old| {
+            if !old.path.exists() {
+                missing += 1;
+                return old.clone();
             }
 
-            song
+            let mut fresh = Song::from_path(&old.path);
+            fresh.scanned_mtime = file_mtime(&old.path);
+            let merged = with_fresh_tags(old, fresh);
+            if !same_tags(old, &merged) {
+                changed += 1;
+            }
+            merged
         })
         .collect();
 
-    Ok(songs)
+    MetadataRefresh { songs, changed, missing }
+}
+
+/// `old` with the tags of `fresh`, a new read of the same file. The position and
+/// the user's rating and favorite mark stay; play counts are kept by path elsewhere.
+fn with_fresh_tags(old: &Song, fresh: Song) -> Song {
+    Song {
+        path: old.path.clone(),
+        order: old.order,
+        rating: old.rating,
+        favorite: old.favorite,
+        ..fresh
+    }
+}
+
+fn same_tags(a: &Song, b: &Song) -> bool {
+    a.title == b.title
+        && a.artists == b.artists
+        && a.album == b.album
+        && a.track_number == b.track_number
+        && a.duration == b.duration
+        && a.genre == b.genre
+        && a.year == b.year
+}
+
+/// Reuse the cached song when the file is unchanged, otherwise read its tags.
+fn load_song(path: &Path, cache: &HashMap<&Path, &Song>) -> Song {
+    let mtime = file_mtime(path);
+
+    if let Some(cached) = cache.get(path)
+        && mtime.is_some()
+        && cached.scanned_mtime == mtime
+    {
+        return (*cached).clone();
+    }
+
+    let mut song = Song::from_path(path);
+    song.scanned_mtime = mtime;
+    // Ratings are the user's, not the file's; keep them when the tags are re-read.
+    if let Some(cached) = cache.get(path) {
+        song.rating = cached.rating;
+        song.favorite = cached.favorite;
+    }
+    song
+}
+
+fn file_mtime(path: &Path) -> Option<SystemTime> {
+    path.metadata().and_then(|m| m.modified()).ok()
 }
 
 fn is_audio_file(path: &Path) -> bool {
@@ -37,3 +291,388 @@ fn is_audio_file(path: &Path) -> bool {
         .map(|e| SUPPORTED_EXTENSIONS.contains(&e.to_lowercase().as_str()))
         .unwrap_or(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, File};
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    // ── Helpers ───────────────────────────────────────────────────────────────
+
+    fn temp_library(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "hextune_scanner_{}_{}_{}",
+            name,
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Write a file that lofty cannot parse, so a fresh read falls back to the filename title.
+    fn write_track(dir: &Path, name: &str) -> PathBuf {
+        let path = dir.join(name);
+        fs::write(&path, b"not really audio").unwrap();
+        path
+    }
+
+    fn touch(path: &Path, offset_secs: u64) {
+        let file = File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(offset_secs))
+            .unwrap();
+    }
+
+    /// Scan once, then rename the cached title so a reuse is distinguishable from a re-read.
+    fn scan_and_mark_cached(dir: &Path) -> Vec<Song> {
+        let mut songs = scan_roots(&[dir], &[], &ScanFilter::default(), |_| {}).unwrap();
+        for song in &mut songs {
+            song.title = "Cached".to_owned();
+        }
+        songs
+    }
+
+    // ── Cache behaviour ───────────────────────────────────────────────────────
+
+    #[test]
+    fn fresh_scan_records_file_mtime() {
+        let dir = temp_library("records_mtime");
+        let path = write_track(&dir, "one.mp3");
+
+        let songs = scan_roots(&[&dir], &[], &ScanFilter::default(), |_| {}).unwrap();
+
+        assert_eq!(songs.len(), 1);
+        assert_eq!(songs[0].title, "one");
+        assert_eq!(songs[0].scanned_mtime, file_mtime(&path));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn unchanged_file_reuses_cached_song() {
+        let dir = temp_library("reuse");
+        write_track(&dir, "one.mp3");
+        let previous = scan_and_mark_cached(&dir);
+
+        let songs = scan_roots(&[&dir], &previous, &ScanFilter::default(), |_| {}).unwrap();
+
+        assert_eq!(songs[0].title, "Cached", "unchanged file must not be re-read");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn touched_file_is_re_read() {
+        let dir = temp_library("touched");
+        let path = write_track(&dir, "one.mp3");
+        let previous = scan_and_mark_cached(&dir);
+
+        touch(&path, 60);
+        let songs = scan_roots(&[&dir], &previous, &ScanFilter::default(), |_| {}).unwrap();
+
+        assert_eq!(songs[0].title, "one", "modified file must be re-read");
+        assert_eq!(songs[0].scanned_mtime, file_mtime(&path));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn re_read_file_keeps_its_rating() {
+        let dir = temp_library("rated");
+        let path = write_track(&dir, "one.mp3");
+        let mut previous = scan_and_mark_cached(&dir);
+        previous[0].rating = Some(4);
+        previous[0].favorite = true;
+
+        touch(&path, 60);
+        let songs = scan_roots(&[&dir], &previous, &ScanFilter::default(), |_| {}).unwrap();
+
+        assert_eq!(songs[0].title, "one");
+        assert_eq!((songs[0].rating, songs[0].favorite), (Some(4), true));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn only_touched_files_are_re_read() {
+        let dir = temp_library("mixed");
+        write_track(&dir, "a.mp3");
+        let b = write_track(&dir, "b.mp3");
+        let previous = scan_and_mark_cached(&dir);
+
+        touch(&b, 60);
+        let songs = scan_roots(&[&dir], &previous, &ScanFilter::default(), |_| {}).unwrap();
+
+        let title_of = |file: &str| {
+            songs.iter()
+                .find(|s| s.path.file_name().unwrap() == file)
+                .map(|s| s.title.clone())
+                .unwrap()
+        };
+        assert_eq!(title_of("a.mp3"), "Cached");
+        assert_eq!(title_of("b.mp3"), "b");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn cached_song_without_mtime_is_re_read() {
+        let dir = temp_library("legacy");
+        write_track(&dir, "one.mp3");
+        let mut previous = scan_and_mark_cached(&dir);
+        previous[0].scanned_mtime = None; // library saved before mtimes were tracked
+
+        let songs = scan_roots(&[&dir], &previous, &ScanFilter::default(), |_| {}).unwrap();
+
+        assert_eq!(songs[0].title, "one");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn reused_song_gets_order_from_current_scan() {
+        let dir = temp_library("order");
+        write_track(&dir, "one.mp3");
+        let mut previous = scan_and_mark_cached(&dir);
+        previous[0].order = 99;
+
+        let songs = scan_roots(&[&dir], &previous, &ScanFilter::default(), |_| {}).unwrap();
+
+        assert_eq!(songs[0].order, 0);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    // ── Metadata-only refresh ─────────────────────────────────────────────────
+
+    #[test]
+    fn fresh_tags_replace_old_ones_but_user_data_stays() {
+        let old = Song {
+            title: "Old Title".to_owned(),
+            album: Some("Old Album".to_owned()),
+            order: 7,
+            rating: Some(3),
+            favorite: true,
+            ..Song::from_path(Path::new("/m/one.mp3"))
+        };
+        let fresh = Song {
+            title: "New Title".to_owned(),
+            artists: vec!["New Artist".to_owned()],
+            duration: Some(Duration::from_secs(200)),
+            ..Song::from_path(Path::new("/m/one.mp3"))
+        };
+
+        let merged = with_fresh_tags(&old, fresh);
+
+        assert_eq!(merged.title, "New Title");
+        assert_eq!(merged.artists, ["New Artist"]);
+        assert_eq!(merged.album, None, "a removed tag is removed");
+        assert_eq!(merged.duration, Some(Duration::from_secs(200)));
+        assert_eq!((merged.order, merged.rating, merged.favorite), (7, Some(3), true));
+        assert!(!same_tags(&old, &merged));
+    }
+
+    #[test]
+    fn metadata_refresh_re_reads_files_in_place_and_keeps_missing_ones() {
+        let dir = temp_library("metadata_only");
+        write_track(&dir, "a.mp3");
+        write_track(&dir, "b.mp3");
+        let mut songs = scan_roots(&[&dir], &[], &ScanFilter::default(), |_| {}).unwrap();
+        songs.reverse();
+        songs[0].title = "Edited elsewhere".to_owned();
+        songs[0].rating = Some(5);
+        let gone = Song::from_path(&dir.join("gone.mp3"));
+        songs.push(Song { title: "Gone".to_owned(), ..gone });
+        write_track(&dir, "new.mp3");
+
+        let refresh = refresh_metadata(&songs);
+
+        assert_eq!(titles(&refresh.songs), ["b", "a", "Gone"], "same entries in the same order");
+        assert_eq!(refresh.songs[0].rating, Some(5));
+        assert_eq!((refresh.changed, refresh.missing), (1, 1));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    // ── Extensions ────────────────────────────────────────────────────────────
+
+    #[test]
+    fn audio_extensions_are_recognised_case_insensitively() {
+        for name in ["a.mp3", "a.FLAC", "a.m4a", "a.aac", "a.Opus", "a.ogg", "a.wav"] {
+            assert!(is_audio_file(Path::new(name)), "{name} should be scanned");
+        }
+        for name in ["a.txt", "a.jpg", "a.m4", "no_extension"] {
+            assert!(!is_audio_file(Path::new(name)), "{name} should be skipped");
+        }
+    }
+
+    // ── Multiple roots ────────────────────────────────────────────────────────
+
+    #[test]
+    fn every_root_is_scanned() {
+        let (first, second) = (temp_library("root_a"), temp_library("root_b"));
+        write_track(&first, "a.mp3");
+        write_track(&second, "b.mp3");
+
+        let songs = scan_roots(&[&first, &second], &[], &ScanFilter::default(), |_| {}).unwrap();
+
+        assert_eq!(titles(&songs), ["a", "b"]);
+        let _ = fs::remove_dir_all(&first);
+        let _ = fs::remove_dir_all(&second);
+    }
+
+    #[test]
+    fn overlapping_roots_are_read_once() {
+        let dir = temp_library("overlap");
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        write_track(&dir, "top.mp3");
+        write_track(&dir.join("sub"), "nested.mp3");
+
+        let songs = scan_roots(&[dir.join("sub"), dir.clone(), dir.clone()], &[], &ScanFilter::default(), |_| {}).unwrap();
+
+        let mut found = titles(&songs);
+        found.sort();
+        assert_eq!(found, ["nested", "top"]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    // ── Include / exclude filters ─────────────────────────────────────────────
+
+    fn patterns(list: &[&str]) -> Vec<String> {
+        list.iter().map(|p| p.to_string()).collect()
+    }
+
+    fn scanned_titles(dir: &Path, filter: &ScanFilter) -> Vec<String> {
+        let mut titles: Vec<String> = scan_roots(&[dir], &[], filter, |_| {})
+            .unwrap()
+            .into_iter()
+            .map(|s| s.title)
+            .collect();
+        titles.sort();
+        titles
+    }
+
+    #[test]
+    fn exclude_wins_over_include() {
+        let dir = temp_library("precedence");
+        fs::create_dir_all(dir.join("rock/live")).unwrap();
+        write_track(&dir, "top.mp3");
+        write_track(&dir.join("rock"), "studio.flac");
+        write_track(&dir.join("rock/live"), "concert.flac");
+
+        let filter = ScanFilter::new(&patterns(&["rock/**"]), &patterns(&["**/live/**"])).unwrap();
+
+        assert_eq!(scanned_titles(&dir, &filter), ["studio"]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn empty_include_list_keeps_everything_not_excluded() {
+        let dir = temp_library("no_include");
+        write_track(&dir, "keep.mp3");
+        write_track(&dir, "drop.wav");
+
+        let filter = ScanFilter::new(&patterns(&[""]), &patterns(&["*.wav"])).unwrap();
+
+        assert_eq!(scanned_titles(&dir, &filter), ["keep"]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn excluded_directory_is_not_descended_into() {
+        let dir = temp_library("prune");
+        fs::create_dir_all(dir.join("samples/kicks")).unwrap();
+        write_track(&dir, "song.mp3");
+        write_track(&dir.join("samples/kicks"), "kick.wav");
+
+        // The pattern names only the directory; its files are skipped because
+        // the walk never enters it.
+        let filter = ScanFilter::new(&[], &patterns(&["samples"])).unwrap();
+
+        assert_eq!(scanned_titles(&dir, &filter), ["song"]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn invalid_glob_is_an_error() {
+        assert!(ScanFilter::new(&[], &patterns(&["[unclosed"])).is_err());
+    }
+
+    // ── Merging ───────────────────────────────────────────────────────────────
+
+    fn songs(names: &[&str]) -> Vec<Song> {
+        names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let mut song = Song::from_path(Path::new(&format!("/music/{name}.mp3")));
+                song.order = i;
+                song
+            })
+            .collect()
+    }
+
+    fn titles(songs: &[Song]) -> Vec<&str> {
+        songs.iter().map(|s| s.title.as_str()).collect()
+    }
+
+    #[test]
+    fn merge_keeps_existing_entries_and_appends_new_files() {
+        let mut existing = songs(&["b", "a", "gone"]);
+        existing[1].title = "Edited by hand".to_owned();
+
+        let merge = merge_scan(&existing, songs(&["a", "b", "c", "d"]));
+
+        assert_eq!(titles(&merge.songs), ["b", "Edited by hand", "gone", "c", "d"]);
+        assert_eq!((merge.added, merge.unchanged), (2, 2));
+        assert_eq!(merge.songs[3].order, 3, "new songs sort after the existing ones");
+        assert_eq!(merge.songs[4].order, 4);
+    }
+
+    #[test]
+    fn album_tracks_are_put_in_track_number_order() {
+        let mut scanned = songs(&["three", "loose", "one", "two"]);
+        for (song, track) in scanned.iter_mut().zip([Some(3), None, Some(1), Some(2)]) {
+            song.track_number = track;
+            song.album = track.map(|_| "Album".to_owned());
+        }
+
+        let ordered = order_album_tracks(scanned);
+
+        assert_eq!(titles(&ordered), ["one", "loose", "two", "three"], "the untagged song keeps its slot");
+        assert_eq!(ordered.iter().map(|s| s.order).collect::<Vec<_>>(), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn merge_into_empty_library_adds_everything() {
+        let merge = merge_scan(&[], songs(&["a", "b"]));
+
+        assert_eq!(titles(&merge.songs), ["a", "b"]);
+        assert_eq!((merge.added, merge.unchanged), (2, 0));
+    }
+
+    #[test]
+    fn deleted_file_is_split_off_as_missing() {
+        let dir = temp_library("missing");
+        write_track(&dir, "kept.mp3");
+        let deleted = write_track(&dir, "deleted.mp3");
+        let songs = scan_roots(&[&dir], &[], &ScanFilter::default(), |_| {}).unwrap();
+        fs::remove_file(&deleted).unwrap();
+
+        let (present, missing) = split_missing(songs);
+
+        assert_eq!(titles(&present), ["kept"]);
+        assert_eq!(titles(&missing), ["deleted"]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::core::models::Song;
+use crate::utils::{SEARCH_ALBUM_WEIGHT, SEARCH_ARTIST_WEIGHT, SEARCH_TITLE_WEIGHT};
 use fuzzy_matcher::skim::SkimMatcherV2;
 use fuzzy_matcher::FuzzyMatcher;
 
@@ -13,18 +14,62 @@ pub struct SearchResult<'a> {
     pub score: i64,
 }
 
+/// Percent of each field's fuzzy score that counts towards a song's score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldWeights {
+    pub title: i64,
+    pub artist: i64,
+    pub album: i64,
+}
+
+impl Default for FieldWeights {
+    fn default() -> Self {
+        Self { title: SEARCH_TITLE_WEIGHT, artist: SEARCH_ARTIST_WEIGHT, album: SEARCH_ALBUM_WEIGHT }
+    }
+}
+
+/// How a query is matched against the song fields.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchOptions {
+    /// Match the query as a plain substring instead of fuzzily
+    pub exact: bool,
+    /// Tell upper and lower case apart
+    pub case_sensitive: bool,
+}
+
+/// Score of an exact field match, before the field weight is applied.
+const EXACT_MATCH_SCORE: i64 = 100;
+
 /// Search engine for finding songs with fuzzy matching
 pub struct SearchEngine {
     matcher: SkimMatcherV2,
+    weights: FieldWeights,
+    options: SearchOptions,
 }
 
 impl SearchEngine {
     pub fn new() -> Self {
+        Self::with_weights(FieldWeights::default())
+    }
+
+    pub fn with_weights(weights: FieldWeights) -> Self {
         Self {
-            matcher: SkimMatcherV2::default(),
+            matcher: SkimMatcherV2::default().ignore_case(),
+            weights,
+            options: SearchOptions::default(),
         }
     }
 
+    pub fn with_options(mut self, options: SearchOptions) -> Self {
+        self.matcher = if options.case_sensitive {
+            SkimMatcherV2::default().respect_case()
+        } else {
+            SkimMatcherV2::default().ignore_case()
+        };
+        self.options = options;
+        self
+    }
+
     /// Perform a fuzzy search across the library
     ///
     /// Returns results sorted by relevance (best matches first)
@@ -40,51 +85,79 @@ impl SearchEngine {
             return Vec::new();
         }
 
-        let query_lower = query.to_lowercase();
+        let query = if self.options.case_sensitive { query.to_string() } else { query.to_lowercase() };
 
-        let mut results: Vec<SearchResult> = library
+        let mut scored: Vec<(SearchResult, i64)> = library
             .iter()
             .enumerate()
             .filter_map(|(index, song)| {
-                self.score_song(song, &query_lower).map(|score| SearchResult {
-                    index,
-                    song, 
-                    score,
+                self.score_song(song, &query).map(|(score, tie_break)| {
+                    (SearchResult { index, song, score }, tie_break)
                 })
             })
             .collect();
 
-        // Sort by score descending (best matches first)
-        results.sort_by(|a, b| b.score.cmp(&a.score));
+        // Sort by score descending (best matches first), then by the combined score
+        scored.sort_by_key(|(r, tie_break)| std::cmp::Reverse((r.score, *tie_break)));
 
-        results
+        scored.into_iter().map(|(result, _)| result).collect()
     }
 
-    /// Calculate a match score for a single song
+    /// Calculate a match score for a single song, plus a tie-breaker
     ///
-    /// Searches across title, artist, and album fields
+    /// The score is the best weighted match across title, artist, and album;
+    /// the tie-breaker is the match against all three combined. A song only the
+    /// combined text matches (e.g. "floyd wall") scores 0 and ranks by the tie-breaker.
     /// Returns None if no match found
-    fn score_song(&self, song: &Song, query: &str) -> Option<i64> {
-        // Try matching against individual fields first (higher weight)
-        let title_score = self.matcher.fuzzy_match(&song.title, query);
+    fn score_song(&self, song: &Song, query: &str) -> Option<(i64, i64)> {
+        let title_score = self.field_score(&song.title, query);
 
         // Score each individual artist and take the best one.
         let artist_score = song
             .artists
             .iter()
-            .filter_map(|a| self.matcher.fuzzy_match(a, query))
+            .filter_map(|a| self.field_score(a, query))
             .max();
 
         let album_score = song.album.as_ref()
-            .and_then(|a| self.matcher.fuzzy_match(a, query));
+            .and_then(|a| self.field_score(a, query));
+
+        // `search_key` is lowercased, so a case-sensitive search rebuilds it from the fields.
+        let combined_score = if self.options.case_sensitive {
+            let combined = format!("{} {} {}", song.title, song.artists.join(" "), song.album.as_deref().unwrap_or_default());
+            self.field_score(&combined, query)
+        } else {
+            self.field_score(&song.search_key, query)
+        };
+
+        let weighted = [
+            (title_score, self.weights.title),
+            (artist_score, self.weights.artist),
+            (album_score, self.weights.album),
+        ]
+        .into_iter()
+        .filter_map(|(score, weight)| Some(score? * weight / 100))
+        .max();
 
-        let combined_score = self.matcher.fuzzy_match(&song.search_key, query);
+        match (weighted, combined_score) {
+            (None, None) => None,
+            (weighted, combined) => Some((weighted.unwrap_or(0), combined.unwrap_or(0))),
+        }
+    }
 
-        // Extract the absolute maximum score across all fields and the combined search_key.
-        [title_score, artist_score, album_score, combined_score]
-            .into_iter()
-            .flatten() // Automatically drops None values and unwraps Some(i64)
-            .max()     // Grabs the highest score
+    /// Match score of `query` against one field; `query` is already lowercased
+    /// unless the search is case-sensitive.
+    fn field_score(&self, text: &str, query: &str) -> Option<i64> {
+        if !self.options.exact {
+            return self.matcher.fuzzy_match(text, query);
+        }
+
+        let found = if self.options.case_sensitive {
+            text.contains(query)
+        } else {
+            text.to_lowercase().contains(query)
+        };
+        found.then_some(EXACT_MATCH_SCORE)
     }
 
     /// Converts SearchResult to (index, Song) tuples by cloning
@@ -106,7 +179,6 @@ impl Default for SearchEngine {
 mod tests {
     use super::*;
     use crate::core::models::Song;
-    use std::path::PathBuf;
 
     // ── Helpers ───────────────────────────────────────────────────────────────
 
@@ -122,14 +194,10 @@ mod tests {
             .to_lowercase();
 
         Song {
-            path: PathBuf::from(format!("{}.mp3", title)),
-            title: title.to_owned(),
             artists,
             album: album_str,
-            track_number: None,
-            duration: None,
             search_key,
-            order: 0,
+            ..Song::test_fixture(title)
         }
     }
 
@@ -263,4 +331,77 @@ mod tests {
         let indexed = engine.search_result_to_song_index(vec![]);
         assert!(indexed.is_empty());
     }
-}
\ No newline at end of file
+
+    // ── Field weights ─────────────────────────────────────────────────────────
+
+    fn title_and_artist_named_alike() -> Vec<Song> {
+        vec![
+            make_song("Borealis", &["Aurora"], None),
+            make_song("Aurora", &["Lights"], None),
+        ]
+    }
+
+    #[test]
+    fn title_match_outranks_an_equally_good_artist_match() {
+        let engine = SearchEngine::new();
+        let lib = title_and_artist_named_alike();
+
+        let results = engine.search(&lib, "aurora");
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].song.title, "Aurora");
+        assert!(results[0].score > results[1].score);
+    }
+
+    #[test]
+    fn custom_weights_change_the_ranking() {
+        let engine = SearchEngine::with_weights(FieldWeights { title: 50, artist: 100, album: 50 });
+        let lib = title_and_artist_named_alike();
+
+        let results = engine.search(&lib, "aurora");
+
+        assert_eq!(results[0].song.title, "Borealis");
+    }
+
+    #[test]
+    fn match_across_fields_still_counts() {
+        let engine = SearchEngine::new();
+        let lib = library();
+
+        let results = engine.search(&lib, "floyd wall");
+
+        assert_eq!(results.first().map(|r| r.song.title.as_str()), Some("Comfortably Numb"));
+    }
+
+    // ── Search options ────────────────────────────────────────────────────────
+
+    #[test]
+    fn exact_search_only_returns_substring_matches() {
+        let lib = library();
+
+        let fuzzy = SearchEngine::new().search(&lib, "ere");
+        let exact = SearchEngine::new()
+            .with_options(SearchOptions { exact: true, case_sensitive: false })
+            .search(&lib, "ere");
+
+        assert!(fuzzy.len() > 1, "'ere' fuzzily matches several songs");
+        let titles: Vec<&str> = exact.iter().map(|r| r.song.title.as_str()).collect();
+        assert_eq!(titles, vec!["Wish You Were Here"]);
+    }
+
+    #[test]
+    fn case_sensitive_search_tells_case_apart() {
+        let lib = library();
+        let exact = |case_sensitive| {
+            SearchEngine::new()
+                .with_options(SearchOptions { exact: true, case_sensitive })
+                .search(&lib, "queen")
+                .len()
+        };
+
+        assert_eq!(exact(false), 2);
+        assert_eq!(exact(true), 0);
+        let sensitive = SearchEngine::new().with_options(SearchOptions { exact: false, case_sensitive: true });
+        assert_eq!(sensitive.search(&lib, "Queen").len(), 2);
+    }
+}
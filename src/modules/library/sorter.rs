@@ -2,6 +2,7 @@ use clap::builder::PossibleValue;
 use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 use crate::core::models::Song;
+use std::cmp::Ordering;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum SortField {
@@ -10,34 +11,38 @@ pub enum SortField {
     Title,
     /// Alphabetical by the first credited artist.
     Artist,
-    /// Alphabetical by album name; tracks without an album sort last.
+    /// Alphabetical by album name, then track number; tracks without an album sort last.
     Album,
+    /// Ascending track number; tracks without a number sort last.
+    Track,
     /// Shortest to longest; tracks without duration sort last.
     Duration,
 }
 
 impl ValueEnum for SortField {
     fn value_variants<'a>() -> &'a [Self] {
-        &[Self::Title, Self::Artist, Self::Album, Self::Duration]
+        &[Self::Title, Self::Artist, Self::Album, Self::Track, Self::Duration]
     }
 
     fn to_possible_value(&self) -> Option<PossibleValue> {
         match self {
             Self::Title    => Some(PossibleValue::new("title").help("Sort alphabetically by title (default)")),
             Self::Artist   => Some(PossibleValue::new("artist").help("Sort alphabetically by artist")),
-            Self::Album    => Some(PossibleValue::new("album").help("Sort alphabetically by album")),
+            Self::Album    => Some(PossibleValue::new("album").help("Sort alphabetically by album, then track number")),
+            Self::Track    => Some(PossibleValue::new("track").help("Sort by track number")),
             Self::Duration => Some(PossibleValue::new("duration").help("Sort shortest to longest")),
         }
     }
 }
 
 impl SortField {
-    /// Cycle to the next field: Title → Artist → Album → Duration → Title
+    /// Cycle to the next field: Title → Artist → Album → Track → Duration → Title
     pub fn next(self) -> Self {
         match self {
             Self::Title    => Self::Artist,
             Self::Artist   => Self::Album,
-            Self::Album    => Self::Duration,
+            Self::Album    => Self::Track,
+            Self::Track    => Self::Duration,
             Self::Duration => Self::Title,
         }
     }
@@ -46,44 +51,139 @@ impl SortField {
 /// Return a sorted copy of `songs` according to `field`.
 ///
 /// The original slice is never mutated — callers decide what to do with
-/// the sorted view (print it, pass it to the TUI, etc.).
+/// the sorted view (print it, pass it to the TUI, etc.). Songs with equal
+/// keys keep their scan order, however the input happens to be ordered.
 pub fn sort_songs(songs: &[Song], field: SortField) -> Vec<&Song> {
     let mut sorted: Vec<&Song> = songs.iter().collect();
 
     match field {
         SortField::Title => {
-            sorted.sort_by(|a, b| a.title.to_lowercase().cmp(&b.title.to_lowercase()));
+            sorted.sort_by(|a, b| {
+                a.title.to_lowercase().cmp(&b.title.to_lowercase())
+                    .then(a.order.cmp(&b.order))
+            });
         }
         SortField::Artist => {
             sorted.sort_by(|a, b| {
                 let a_artist = a.artists.first().map(|s| s.to_lowercase()).unwrap_or_default();
                 let b_artist = b.artists.first().map(|s| s.to_lowercase()).unwrap_or_default();
-                a_artist.cmp(&b_artist)
+                a_artist.cmp(&b_artist).then(a.order.cmp(&b.order))
             });
         }
         SortField::Album => {
             sorted.sort_by(|a, b| {
+                let a_album = a.album.as_ref().map(|s| s.to_lowercase());
+                let b_album = b.album.as_ref().map(|s| s.to_lowercase());
                 // Songs without an album float to the bottom.
-                match (&a.album, &b.album) {
-                    (None, None)       => std::cmp::Ordering::Equal,
-                    (None, Some(_))    => std::cmp::Ordering::Greater,
-                    (Some(_), None)    => std::cmp::Ordering::Less,
-                    (Some(a), Some(b)) => a.to_lowercase().cmp(&b.to_lowercase()),
-                }
+                missing_last(a_album, b_album)
+                    .then(missing_last(a.track_number, b.track_number))
+                    .then(a.order.cmp(&b.order))
+            });
+        }
+        SortField::Track => {
+            sorted.sort_by(|a, b| {
+                missing_last(a.track_number, b.track_number).then(a.order.cmp(&b.order))
             });
         }
         SortField::Duration => {
             sorted.sort_by(|a, b| {
                 // Songs without a known duration float to the bottom.
-                match (a.duration, b.duration) {
-                    (None, None)       => std::cmp::Ordering::Equal,
-                    (None, Some(_))    => std::cmp::Ordering::Greater,
-                    (Some(_), None)    => std::cmp::Ordering::Less,
-                    (Some(a), Some(b)) => a.cmp(&b),
-                }
+                missing_last(a.duration, b.duration).then(a.order.cmp(&b.order))
             });
         }
     }
 
     sorted
-}
\ No newline at end of file
+}
+
+/// Ascending order with `None` after every `Some`.
+fn missing_last<T: Ord>(a: Option<T>, b: Option<T>) -> Ordering {
+    match (a, b) {
+        (None, None)       => Ordering::Equal,
+        (None, Some(_))    => Ordering::Greater,
+        (Some(_), None)    => Ordering::Less,
+        (Some(a), Some(b)) => a.cmp(&b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn make_song(title: &str, album: Option<&str>, track: Option<u32>, order: usize) -> Song {
+        Song {
+            album: album.map(str::to_owned),
+            track_number: track,
+            order,
+            ..Song::test_fixture(title)
+        }
+    }
+
+    fn titles(sorted: &[&Song]) -> Vec<String> {
+        sorted.iter().map(|s| s.title.clone()).collect()
+    }
+
+    #[test]
+    fn title_sort_is_case_insensitive() {
+        let songs = vec![
+            make_song("banana", None, None, 0),
+            make_song("Apple", None, None, 1),
+            make_song("cherry", None, None, 2),
+        ];
+
+        assert_eq!(titles(&sort_songs(&songs, SortField::Title)), ["Apple", "banana", "cherry"]);
+    }
+
+    #[test]
+    fn album_sort_orders_tracks_within_each_album() {
+        let songs = vec![
+            make_song("B2", Some("Beta"), Some(2), 0),
+            make_song("Loose", None, None, 1),
+            make_song("A2", Some("alpha"), Some(2), 2),
+            make_song("B1", Some("Beta"), Some(1), 3),
+            make_song("A1", Some("Alpha"), Some(1), 4),
+        ];
+
+        assert_eq!(
+            titles(&sort_songs(&songs, SortField::Album)),
+            ["A1", "A2", "B1", "B2", "Loose"]
+        );
+    }
+
+    #[test]
+    fn track_sort_puts_unnumbered_songs_last() {
+        let songs = vec![
+            make_song("None", None, None, 0),
+            make_song("Three", None, Some(3), 1),
+            make_song("One", None, Some(1), 2),
+        ];
+
+        assert_eq!(titles(&sort_songs(&songs, SortField::Track)), ["One", "Three", "None"]);
+    }
+
+    #[test]
+    fn duration_sort_is_ascending() {
+        let mut long = make_song("Long", None, None, 0);
+        long.duration = Some(Duration::from_secs(300));
+        let mut short = make_song("Short", None, None, 1);
+        short.duration = Some(Duration::from_secs(90));
+        let unknown = make_song("Unknown", None, None, 2);
+
+        let songs = vec![unknown, long, short];
+        assert_eq!(titles(&sort_songs(&songs, SortField::Duration)), ["Short", "Long", "Unknown"]);
+    }
+
+    #[test]
+    fn equal_keys_keep_scan_order_regardless_of_input_order() {
+        // Same title; input is in reverse scan order (e.g. left over from a previous sort).
+        let songs = vec![
+            make_song("Same", None, None, 2),
+            make_song("Same", None, None, 0),
+            make_song("Same", None, None, 1),
+        ];
+
+        let orders: Vec<usize> = sort_songs(&songs, SortField::Title).iter().map(|s| s.order).collect();
+        assert_eq!(orders, [0, 1, 2]);
+    }
+}
@@ -0,0 +1,217 @@
+use crate::core::models::{PlayRecord, Song};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+/// Listening statistics derived from the play history.
+///
+/// Only songs still in the library are counted; indices refer to `songs`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListeningStats {
+    pub total_plays: u32,
+    /// Sum of each play's track length, for tracks with a known duration.
+    pub total_time: Duration,
+    /// `(library index, plays)`, most played first.
+    pub top_tracks: Vec<(usize, u32)>,
+    /// `(artist, plays)`, most played first. A song counts for each of its artists.
+    pub top_artists: Vec<(String, u32)>,
+    /// `(library index, last played)`, newest first.
+    pub recent: Vec<(usize, SystemTime)>,
+}
+
+/// Aggregate `plays` over `songs`, keeping the first `top` entries of each ranking.
+pub fn listening_stats(
+    songs: &[Song],
+    plays: &BTreeMap<PathBuf, PlayRecord>,
+    top: usize,
+) -> ListeningStats {
+    let played: Vec<(usize, &Song, &PlayRecord)> = songs
+        .iter()
+        .enumerate()
+        .filter_map(|(i, song)| plays.get(&song.path).map(|record| (i, song, record)))
+        .collect();
+
+    let total_plays = played.iter().map(|(_, _, r)| r.play_count).sum();
+    let total_time = played
+        .iter()
+        .filter_map(|(_, song, r)| song.duration.map(|d| d * r.play_count))
+        .sum();
+
+    let mut top_tracks: Vec<(usize, u32)> = played.iter().map(|(i, _, r)| (*i, r.play_count)).collect();
+    top_tracks.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    top_tracks.truncate(top);
+
+    let mut by_artist: HashMap<&str, u32> = HashMap::new();
+    for (_, song, record) in &played {
+        for artist in &song.artists {
+            *by_artist.entry(artist.as_str()).or_default() += record.play_count;
+        }
+    }
+    let mut top_artists: Vec<(String, u32)> = by_artist
+        .into_iter()
+        .map(|(artist, count)| (artist.to_owned(), count))
+        .collect();
+    top_artists.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    top_artists.truncate(top);
+
+    let mut recent: Vec<(usize, SystemTime)> = played.iter().map(|(i, _, r)| (*i, r.last_played)).collect();
+    recent.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    recent.truncate(top);
+
+    ListeningStats { total_plays, total_time, top_tracks, top_artists, recent }
+}
+
+/// `(library index, skips)` of the `top` most skipped songs still in the library.
+pub fn most_skipped(songs: &[Song], skips: &BTreeMap<PathBuf, u32>, top: usize) -> Vec<(usize, u32)> {
+    let mut skipped: Vec<(usize, u32)> = songs
+        .iter()
+        .enumerate()
+        .filter_map(|(i, song)| skips.get(&song.path).map(|&count| (i, count)))
+        .filter(|&(_, count)| count > 0)
+        .collect();
+    skipped.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    skipped.truncate(top);
+    skipped
+}
+
+/// One entry of the recently played list.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecentPlay {
+    /// Library index of the song; `None` when it is no longer in the library.
+    pub index: Option<usize>,
+    pub path: PathBuf,
+    pub played_at: SystemTime,
+}
+
+/// The last `limit` plays in `recent` (stored oldest first), newest first.
+pub fn recent_plays<'a>(
+    songs: &[Song],
+    recent: impl DoubleEndedIterator<Item = &'a (PathBuf, SystemTime)>,
+    limit: usize,
+) -> Vec<RecentPlay> {
+    let index_of: HashMap<&PathBuf, usize> = songs.iter().enumerate().map(|(i, song)| (&song.path, i)).collect();
+
+    recent
+        .rev()
+        .take(limit)
+        .map(|(path, played_at)| RecentPlay {
+            index: index_of.get(path).copied(),
+            path: path.clone(),
+            played_at: *played_at,
+        })
+        .collect()
+}
+
+/// Describe how long before `now` the moment `then` was, e.g. "5 min ago".
+pub fn format_ago(then: SystemTime, now: SystemTime) -> String {
+    let secs = now.duration_since(then).unwrap_or_default().as_secs();
+
+    match secs {
+        0..60 => "just now".to_string(),
+        60..3_600 => format!("{} min ago", secs / 60),
+        3_600..86_400 => format!("{} h ago", secs / 3_600),
+        _ => {
+            let days = secs / 86_400;
+            format!("{} day{} ago", days, if days == 1 { "" } else { "s" })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_song(title: &str, artists: &[&str], secs: Option<u64>) -> Song {
+        Song {
+            artists: artists.iter().map(|a| a.to_string()).collect(),
+            duration: secs.map(Duration::from_secs),
+            ..Song::test_fixture(title)
+        }
+    }
+
+    fn at(secs: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    fn record(title: &str, play_count: u32, last_played: u64) -> (PathBuf, PlayRecord) {
+        (
+            PathBuf::from(format!("{}.mp3", title)),
+            PlayRecord { play_count, last_played: at(last_played) },
+        )
+    }
+
+    #[test]
+    fn ranks_tracks_artists_and_recent_plays() {
+        let songs = vec![
+            make_song("A", &["Queen"], Some(100)),
+            make_song("B", &["Queen", "Bowie"], Some(200)),
+            make_song("C", &["Bowie"], None),
+        ];
+        let plays = BTreeMap::from([record("A", 2, 50), record("B", 3, 10), record("C", 1, 90)]);
+
+        let stats = listening_stats(&songs, &plays, 10);
+
+        assert_eq!(stats.total_plays, 6);
+        assert_eq!(stats.total_time, Duration::from_secs(2 * 100 + 3 * 200), "unknown durations are skipped");
+        assert_eq!(stats.top_tracks, [(1, 3), (0, 2), (2, 1)]);
+        assert_eq!(stats.top_artists, [("Queen".to_owned(), 5), ("Bowie".to_owned(), 4)]);
+        assert_eq!(stats.recent, [(2, at(90)), (0, at(50)), (1, at(10))]);
+    }
+
+    #[test]
+    fn rankings_are_limited_to_top_n() {
+        let songs = vec![make_song("A", &[], None), make_song("B", &[], None)];
+        let plays = BTreeMap::from([record("A", 1, 1), record("B", 2, 2)]);
+
+        let stats = listening_stats(&songs, &plays, 1);
+
+        assert_eq!(stats.top_tracks, [(1, 2)]);
+        assert_eq!(stats.recent, [(1, at(2))]);
+    }
+
+    #[test]
+    fn history_for_songs_no_longer_in_library_is_ignored() {
+        let songs = vec![make_song("A", &[], None)];
+        let plays = BTreeMap::from([record("Gone", 7, 1)]);
+
+        assert_eq!(listening_stats(&songs, &plays, 10).total_plays, 0);
+    }
+
+    #[test]
+    fn most_skipped_ranks_library_songs_only() {
+        let songs = vec![make_song("A", &[], None), make_song("B", &[], None), make_song("C", &[], None)];
+        let skips = BTreeMap::from([
+            (PathBuf::from("A.mp3"), 2),
+            (PathBuf::from("C.mp3"), 5),
+            (PathBuf::from("Gone.mp3"), 9),
+        ]);
+
+        assert_eq!(most_skipped(&songs, &skips, 10), [(2, 5), (0, 2)]);
+        assert_eq!(most_skipped(&songs, &skips, 1), [(2, 5)]);
+    }
+
+    #[test]
+    fn ago_uses_the_largest_whole_unit() {
+        let now = at(1_000_000);
+        assert_eq!(format_ago(at(1_000_000 - 30), now), "just now");
+        assert_eq!(format_ago(at(1_000_000 - 300), now), "5 min ago");
+        assert_eq!(format_ago(at(1_000_000 - 7_200), now), "2 h ago");
+        assert_eq!(format_ago(at(1_000_000 - 86_400), now), "1 day ago");
+    }
+
+    #[test]
+    fn recent_plays_are_newest_first_and_keep_missing_songs() {
+        let songs = vec![make_song("A", &[], None), make_song("B", &[], None)];
+        let recent = [
+            (PathBuf::from("A.mp3"), at(10)),
+            (PathBuf::from("Gone.mp3"), at(20)),
+            (PathBuf::from("B.mp3"), at(30)),
+        ];
+
+        let plays = recent_plays(&songs, recent.iter(), 2);
+
+        assert_eq!(plays.len(), 2);
+        assert_eq!((plays[0].index, plays[0].played_at), (Some(1), at(30)));
+        assert_eq!((plays[1].index, &plays[1].path), (None, &PathBuf::from("Gone.mp3")));
+    }
+}
@@ -0,0 +1,39 @@
+//! Optional log of track changes, playback errors and scans, for debugging.
+//!
+//! Records only ever go to the log file: the terminal belongs to the UI.
+
+use crate::modules::storage;
+use crate::utils::APP_NAME;
+use anyhow::{Context, Result};
+use std::fs::{self, OpenOptions};
+use std::path::PathBuf;
+
+/// Environment variable with `env_logger` filters, e.g. `debug` or `hextune=warn`.
+pub const LOG_FILTER_ENV: &str = "RUST_LOG";
+
+/// Start logging to `storage::log_path()` when `verbose` is set or
+/// `LOG_FILTER_ENV` is. Its filters pick the levels; `--verbose` alone logs
+/// this app at `info`. Returns the log file, if logging is on.
+pub fn init(verbose: bool) -> Result<Option<PathBuf>> {
+    let filters = std::env::var(LOG_FILTER_ENV).ok().filter(|filters| !filters.trim().is_empty());
+    if !verbose && filters.is_none() {
+        return Ok(None);
+    }
+
+    let path = storage::log_path()?;
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create log directory '{}'", dir.display()))?;
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open log file '{}'", path.display()))?;
+
+    env_logger::Builder::new()
+        .parse_filters(&filters.unwrap_or_else(|| format!("{}=info", APP_NAME)))
+        .target(env_logger::Target::Pipe(Box::new(file)))
+        .try_init()?;
+
+    Ok(Some(path))
+}
@@ -1,4 +1,10 @@
+pub mod control;
+pub mod logging;
+#[cfg(all(target_os = "linux", feature = "mpris"))]
+pub mod mpris;
 pub mod playback;
+#[cfg(feature = "scrobble")]
+pub mod scrobble;
 pub mod library;
 pub mod storage;
 pub mod input;
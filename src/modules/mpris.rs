@@ -0,0 +1,431 @@
+//! MPRIS (`org.mpris.MediaPlayer2`) service on the D-Bus session bus, so media
+//! keys, desktop widgets and `playerctl` can drive a running session.
+//!
+//! Method calls become the same `UiEvent`s as keys and `remote` requests do, and
+//! properties are read from the shared `AppState`. The application hands every
+//! playback event to `MprisServer::announce`, which tells clients what it changed
+//! with `PropertiesChanged`.
+
+use crate::application::state::AppState;
+use crate::core::events::{AppEvent, EventSender, PlaybackEvent, UiEvent};
+use crate::utils::{APP_NAME, VOLUME_MAX};
+use anyhow::Result;
+use crossbeam_channel::{unbounded, Sender};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use zbus::fdo;
+use zbus::names::BusName;
+use zbus::zvariant::{ObjectPath, Value};
+
+const OBJECT_PATH: &str = "/org/mpris/MediaPlayer2";
+const PLAYER_INTERFACE: &str = "org.mpris.MediaPlayer2.Player";
+
+/// Track id the specification reserves for "nothing is playing".
+const NO_TRACK: &str = "/org/mpris/MediaPlayer2/TrackList/NoTrack";
+
+/// Property values as D-Bus sends them, keyed by property name.
+type Properties = HashMap<&'static str, Value<'static>>;
+
+/// Serves MPRIS while it is alive; dropping it releases the bus name.
+pub struct MprisServer {
+    /// Changed properties, for the thread that signals them.
+    changes: Sender<Properties>,
+}
+
+impl MprisServer {
+    /// Claim `org.mpris.MediaPlayer2.hextune` on the session bus. Fails when
+    /// there is no session bus or another session already holds the name.
+    pub fn start(events: EventSender, state: Arc<Mutex<AppState>>) -> Result<Self> {
+        let connection = zbus::blocking::connection::Builder::session()?
+            .name(format!("org.mpris.MediaPlayer2.{}", APP_NAME))?
+            .serve_at(OBJECT_PATH, Root { events: events.clone() })?
+            .serve_at(OBJECT_PATH, Player { events, state: Arc::clone(&state) })?
+            .build()?;
+
+        let (changes, rx) = unbounded::<Properties>();
+        // Owns the connection, so the name goes away with the thread, which ends
+        // once the server is dropped. Signals are sent from here so a slow bus
+        // never holds up the event loop.
+        std::thread::spawn(move || {
+            for changed in rx {
+                let body = (PLAYER_INTERFACE, changed, Vec::<&str>::new());
+                let _ = connection.emit_signal(
+                    None::<BusName>,
+                    OBJECT_PATH,
+                    "org.freedesktop.DBus.Properties",
+                    "PropertiesChanged",
+                    &body,
+                );
+            }
+        });
+
+        Ok(Self { changes })
+    }
+
+    /// Tell clients about the properties `event` changed; `state` has already applied it.
+    pub fn announce(&self, event: &PlaybackEvent, state: &AppState) {
+        let changed = changes(event, state);
+        if !changed.is_empty() {
+            let _ = self.changes.send(changed);
+        }
+    }
+}
+
+/// `org.mpris.MediaPlayer2`: what the player is and whether it can be closed.
+struct Root {
+    events: EventSender,
+}
+
+#[zbus::interface(name = "org.mpris.MediaPlayer2")]
+impl Root {
+    /// There is no window to bring forward.
+    fn raise(&self) {}
+
+    fn quit(&self) {
+        let _ = self.events.send(AppEvent::Shutdown);
+    }
+
+    #[zbus(property)]
+    fn can_quit(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_raise(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn has_track_list(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn identity(&self) -> String {
+        APP_NAME.to_string()
+    }
+
+    #[zbus(property)]
+    fn supported_uri_schemes(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    #[zbus(property)]
+    fn supported_mime_types(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// `org.mpris.MediaPlayer2.Player`: transport controls and what is playing.
+struct Player {
+    events: EventSender,
+    state: Arc<Mutex<AppState>>,
+}
+
+impl Player {
+    fn send(&self, event: UiEvent) {
+        // Only fails while the session shuts down.
+        let _ = self.events.send(AppEvent::Ui(event));
+    }
+}
+
+#[zbus::interface(name = "org.mpris.MediaPlayer2.Player")]
+impl Player {
+    fn next(&self) {
+        self.send(UiEvent::NextTrackRequested);
+    }
+
+    fn previous(&self) {
+        self.send(UiEvent::PreviousTrackRequested);
+    }
+
+    fn pause(&self) {
+        self.send(UiEvent::PauseRequested);
+    }
+
+    fn play_pause(&self) {
+        self.send(UiEvent::TogglePauseRequested);
+    }
+
+    /// The player has no stopped state to return to, so stopping pauses.
+    fn stop(&self) {
+        self.send(UiEvent::PauseRequested);
+    }
+
+    /// Resume the current track, or start the selected one when nothing is loaded.
+    fn play(&self) {
+        let loaded = self.state.lock().unwrap().playback.current_song.is_some();
+        self.send(if loaded { UiEvent::ResumeRequested } else { UiEvent::PlaySelectedRequested });
+    }
+
+    /// `offset` is in microseconds, backwards when negative. Seeking past the end
+    /// moves on to the next track, as the specification asks.
+    fn seek(&self, offset: i64) {
+        let (elapsed, duration) = {
+            let state = self.state.lock().unwrap();
+            match &state.playback.current_song {
+                Some(song) => (state.playback.current_elapsed, song.duration),
+                None => return,
+            }
+        };
+        match seek_position(elapsed, duration, offset) {
+            Some(position) => self.send(UiEvent::SeekRequested { position }),
+            None => self.send(UiEvent::NextTrackRequested),
+        }
+    }
+
+    /// Ignored unless `track_id` is still the current track, as the specification asks.
+    fn set_position(&self, track_id: ObjectPath<'_>, position: i64) {
+        let current = self.state.lock().unwrap().playback.current_index.map(track_path);
+        if current.as_deref() == Some(track_id.as_str()) && position >= 0 {
+            self.send(UiEvent::SeekRequested { position: Duration::from_micros(position as u64) });
+        }
+    }
+
+    fn open_uri(&self, _uri: &str) -> fdo::Result<()> {
+        Err(fdo::Error::NotSupported("Only library songs can be played".to_string()))
+    }
+
+    #[zbus(property)]
+    fn playback_status(&self) -> String {
+        playback_status(&self.state.lock().unwrap()).to_string()
+    }
+
+    #[zbus(property)]
+    fn metadata(&self) -> Properties {
+        metadata(&self.state.lock().unwrap())
+    }
+
+    #[zbus(property)]
+    fn volume(&self) -> f64 {
+        volume(&self.state.lock().unwrap())
+    }
+
+    #[zbus(property)]
+    fn set_volume(&mut self, volume: f64) {
+        let percent = (volume.clamp(0.0, 1.0) * f64::from(VOLUME_MAX)).round() as u8;
+        self.send(UiEvent::VolumeChangeRequested { volume: percent });
+    }
+
+    /// Changes every moment, so clients poll it instead of waiting for a signal.
+    #[zbus(property(emits_changed_signal = "false"))]
+    fn position(&self) -> i64 {
+        micros(self.state.lock().unwrap().playback.current_elapsed)
+    }
+
+    #[zbus(property)]
+    fn rate(&self) -> f64 {
+        1.0
+    }
+
+    #[zbus(property)]
+    fn minimum_rate(&self) -> f64 {
+        1.0
+    }
+
+    #[zbus(property)]
+    fn maximum_rate(&self) -> f64 {
+        1.0
+    }
+
+    #[zbus(property)]
+    fn can_go_next(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_go_previous(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_play(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_pause(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_seek(&self) -> bool {
+        true
+    }
+
+    #[zbus(property(emits_changed_signal = "const"))]
+    fn can_control(&self) -> bool {
+        true
+    }
+}
+
+/// The properties `event` changes, with their values in `state`. Every `Started`
+/// announces its metadata, so a restarted or repeated track is announced too.
+fn changes(event: &PlaybackEvent, state: &AppState) -> Properties {
+    let mut changed = Properties::new();
+    match event {
+        PlaybackEvent::Started { .. } => {
+            changed.insert("Metadata", Value::from(metadata(state)));
+            changed.insert("PlaybackStatus", Value::from(playback_status(state)));
+        }
+        PlaybackEvent::Paused | PlaybackEvent::Resumed | PlaybackEvent::TrackFinished | PlaybackEvent::Stopped => {
+            changed.insert("PlaybackStatus", Value::from(playback_status(state)));
+        }
+        PlaybackEvent::VolumeChanged { .. } | PlaybackEvent::MuteChanged { .. } => {
+            changed.insert("Volume", Value::from(volume(state)));
+        }
+        _ => {}
+    }
+    changed
+}
+
+fn playback_status(state: &AppState) -> &'static str {
+    match (state.playback.is_playing, state.playback.is_paused) {
+        (false, _) => "Stopped",
+        (true, true) => "Paused",
+        (true, false) => "Playing",
+    }
+}
+
+/// Linear 0.0–1.0 volume; muted reads as silent.
+fn volume(state: &AppState) -> f64 {
+    if state.config.is_muted() {
+        0.0
+    } else {
+        f64::from(state.config.volume_percent) / f64::from(VOLUME_MAX)
+    }
+}
+
+/// Object path naming the library song at `index`.
+fn track_path(index: usize) -> String {
+    format!("/org/{}/track/{}", APP_NAME, index)
+}
+
+/// Where seeking `offset` microseconds from `elapsed` lands: never before the
+/// start, and `None` at or past the end of a track whose `duration` is known.
+fn seek_position(elapsed: Duration, duration: Option<Duration>, offset: i64) -> Option<Duration> {
+    let step = Duration::from_micros(offset.unsigned_abs());
+    let target = if offset < 0 { elapsed.saturating_sub(step) } else { elapsed + step };
+    match duration {
+        Some(duration) if target >= duration => None,
+        _ => Some(target),
+    }
+}
+
+fn micros(duration: Duration) -> i64 {
+    i64::try_from(duration.as_micros()).unwrap_or(i64::MAX)
+}
+
+/// `xesam`/`mpris` metadata of the playing song; only the track id when nothing plays.
+fn metadata(state: &AppState) -> Properties {
+    let mut map = Properties::new();
+    let (Some(song), Some(index)) = (&state.playback.current_song, state.playback.current_index) else {
+        map.insert("mpris:trackid", Value::from(ObjectPath::from_static_str_unchecked(NO_TRACK)));
+        return map;
+    };
+
+    let track_id = ObjectPath::try_from(track_path(index)).expect("track paths are valid object paths");
+    map.insert("mpris:trackid", Value::from(track_id));
+    map.insert("xesam:title", Value::from(song.title.clone()));
+    map.insert("xesam:artist", Value::from(song.artists.clone()));
+    if let Some(album) = &song.album {
+        map.insert("xesam:album", Value::from(album.clone()));
+    }
+    if let Some(genre) = &song.genre {
+        map.insert("xesam:genre", Value::from(vec![genre.clone()]));
+    }
+    if let Some(track) = song.track_number.and_then(|n| i32::try_from(n).ok()) {
+        map.insert("xesam:trackNumber", Value::from(track));
+    }
+    if let Some(duration) = song.duration {
+        map.insert("mpris:length", Value::from(micros(duration)));
+    }
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::models::Song;
+    use std::path::PathBuf;
+
+    fn playing(title: &str, index: usize) -> AppState {
+        let mut state = AppState::default();
+        state.playback.current_song = Some(Song {
+            title: title.to_string(),
+            artists: vec!["Artist A".to_string(), "Artist B".to_string()],
+            album: Some("Album".to_string()),
+            track_number: Some(3),
+            duration: Some(Duration::from_secs(90)),
+            ..Song::from_path(&PathBuf::from(format!("/music/{}.mp3", title)))
+        });
+        state.playback.current_index = Some(index);
+        state.playback.is_playing = true;
+        state
+    }
+
+    #[test]
+    fn metadata_describes_the_playing_song() {
+        let map = metadata(&playing("Song", 7));
+
+        assert_eq!(map["mpris:trackid"], Value::from(ObjectPath::try_from("/org/hextune/track/7").unwrap()));
+        assert_eq!(map["xesam:title"], Value::from("Song"));
+        assert_eq!(map["xesam:artist"], Value::from(vec!["Artist A", "Artist B"]));
+        assert_eq!(map["xesam:trackNumber"], Value::from(3));
+        assert_eq!(map["mpris:length"], Value::from(90_000_000i64));
+        assert!(!map.contains_key("xesam:genre"));
+    }
+
+    #[test]
+    fn metadata_without_a_song_is_only_the_no_track_id() {
+        let map = metadata(&AppState::default());
+
+        assert_eq!(map.len(), 1);
+        assert_eq!(map["mpris:trackid"], Value::from(ObjectPath::try_from(NO_TRACK).unwrap()));
+    }
+
+    #[test]
+    fn status_and_volume_follow_the_session() {
+        let mut state = playing("Song", 0);
+        assert_eq!(playback_status(&state), "Playing");
+        state.playback.is_paused = true;
+        assert_eq!(playback_status(&state), "Paused");
+        assert_eq!(playback_status(&AppState::default()), "Stopped");
+
+        state.config.volume_percent = 40;
+        assert_eq!(volume(&state), 0.4);
+    }
+
+    #[test]
+    fn seeks_keep_sub_second_offsets() {
+        let ms = Duration::from_millis;
+
+        assert_eq!(seek_position(ms(10_000), Some(ms(90_000)), 250_000), Some(ms(10_250)));
+        assert_eq!(seek_position(ms(10_000), Some(ms(90_000)), -1_500_000), Some(ms(8_500)));
+        assert_eq!(seek_position(ms(400), Some(ms(90_000)), -1_000_000), Some(Duration::ZERO), "clamped at the start");
+        assert_eq!(seek_position(ms(89_900), Some(ms(90_000)), 200_000), None, "the end means the next track");
+    }
+
+    #[test]
+    fn events_announce_the_properties_they_change() {
+        let mut state = playing("Song", 0);
+        let started = PlaybackEvent::Started { song: state.playback.current_song.clone().unwrap() };
+
+        // The same track again, as repeat-one plays it, is still announced.
+        for _ in 0..2 {
+            let changed = changes(&started, &state);
+            assert_eq!(changed["Metadata"], Value::from(metadata(&state)));
+            assert_eq!(changed["PlaybackStatus"], Value::from("Playing"));
+        }
+
+        state.playback.is_paused = true;
+        let changed = changes(&PlaybackEvent::Paused, &state);
+        assert_eq!(changed.keys().collect::<Vec<_>>(), vec![&"PlaybackStatus"]);
+        assert_eq!(changed["PlaybackStatus"], Value::from("Paused"));
+
+        state.config.volume_percent = 40;
+        assert_eq!(changes(&PlaybackEvent::VolumeChanged { volume: 0.0 }, &state)["Volume"], Value::from(0.4));
+        assert!(changes(&PlaybackEvent::NearingEnd, &state).is_empty());
+    }
+}
@@ -0,0 +1,124 @@
+use rodio::source::SeekError;
+use rodio::{ChannelCount, Sample, SampleRate, Source};
+use std::time::Duration;
+
+/// Position in a track of length `total` at which a crossfade of `window` should begin.
+pub fn crossfade_start(total: Duration, window: Duration) -> Duration {
+    total.saturating_sub(window)
+}
+
+/// Gain applied to the outgoing track at `position`.
+///
+/// Stays at 1.0 until the last `window` of the track, then ramps linearly down to
+/// 0.0 at `total`. A zero window never fades.
+pub fn fade_out_gain(position: Duration, total: Duration, window: Duration) -> f32 {
+    if window.is_zero() {
+        return 1.0;
+    }
+
+    let start = crossfade_start(total, window);
+    if position <= start {
+        return 1.0;
+    }
+
+    let into_fade = (position - start).as_secs_f32();
+    (1.0 - into_fade / window.as_secs_f32()).clamp(0.0, 1.0)
+}
+
+/// Source adapter that fades out the last `window` of a track of known length.
+///
+/// Paired with rodio's `fade_in` on the following track, this produces a
+/// crossfade when both play at once on separate players.
+pub struct FadeOutTail<S> {
+    input: S,
+    total: Duration,
+    window: Duration,
+    elapsed_secs: f64,
+}
+
+impl<S: Source> FadeOutTail<S> {
+    pub fn new(input: S, total: Duration, window: Duration) -> Self {
+        Self {
+            input,
+            total,
+            window,
+            elapsed_secs: 0.0,
+        }
+    }
+}
+
+impl<S: Source> Iterator for FadeOutTail<S> {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Sample> {
+        let samples_per_sec = self.input.sample_rate().get() as f64 * self.input.channels().get() as f64;
+        let sample = self.input.next()?;
+
+        let gain = fade_out_gain(Duration::from_secs_f64(self.elapsed_secs), self.total, self.window);
+        self.elapsed_secs += 1.0 / samples_per_sec;
+
+        Some(sample * gain)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<S: Source> Source for FadeOutTail<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.elapsed_secs = pos.as_secs_f64();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOTAL: Duration = Duration::from_secs(100);
+    const WINDOW: Duration = Duration::from_secs(4);
+
+    #[test]
+    fn full_gain_before_the_fade_window() {
+        assert_eq!(fade_out_gain(Duration::ZERO, TOTAL, WINDOW), 1.0);
+        assert_eq!(fade_out_gain(Duration::from_secs(96), TOTAL, WINDOW), 1.0);
+    }
+
+    #[test]
+    fn gain_ramps_linearly_to_silence() {
+        assert_eq!(fade_out_gain(Duration::from_secs(97), TOTAL, WINDOW), 0.75);
+        assert_eq!(fade_out_gain(Duration::from_secs(98), TOTAL, WINDOW), 0.5);
+        assert_eq!(fade_out_gain(Duration::from_secs(100), TOTAL, WINDOW), 0.0);
+        assert_eq!(fade_out_gain(Duration::from_secs(105), TOTAL, WINDOW), 0.0, "clamped past the end");
+    }
+
+    #[test]
+    fn zero_window_never_fades() {
+        assert_eq!(fade_out_gain(TOTAL, TOTAL, Duration::ZERO), 1.0);
+    }
+
+    #[test]
+    fn window_longer_than_track_starts_at_zero() {
+        let short = Duration::from_secs(2);
+        assert_eq!(crossfade_start(short, WINDOW), Duration::ZERO);
+        assert_eq!(fade_out_gain(Duration::from_secs(2), short, WINDOW), 0.5);
+    }
+}
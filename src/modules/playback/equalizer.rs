@@ -0,0 +1,322 @@
+use crate::utils::EQ_MAX_DB;
+use rodio::source::SeekError;
+use rodio::{ChannelCount, Sample, SampleRate, Source};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+use std::fmt;
+use std::time::Duration;
+
+/// Corner of the bass low shelf.
+const BASS_HZ: f64 = 200.0;
+/// Centre of the mid peaking band.
+const MID_HZ: f64 = 1_000.0;
+const MID_Q: f64 = 0.7;
+/// Corner of the treble high shelf.
+const TREBLE_HZ: f64 = 4_000.0;
+
+/// Band names, in the order `EqGains::band` indexes them.
+pub const EQ_BANDS: [&str; 3] = ["bass", "mid", "treble"];
+
+/// Gains of the three equalizer bands in dB, each within ±`EQ_MAX_DB`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EqGains {
+    #[serde(default)]
+    pub bass: i8,
+    #[serde(default)]
+    pub mid: i8,
+    #[serde(default)]
+    pub treble: i8,
+}
+
+impl EqGains {
+    pub fn new(bass: i8, mid: i8, treble: i8) -> Self {
+        let clamp = |db: i8| db.clamp(-EQ_MAX_DB, EQ_MAX_DB);
+        Self { bass: clamp(bass), mid: clamp(mid), treble: clamp(treble) }
+    }
+
+    /// Gain of band `index` (see `EQ_BANDS`).
+    pub fn band(&self, index: usize) -> i8 {
+        match index {
+            0 => self.bass,
+            1 => self.mid,
+            _ => self.treble,
+        }
+    }
+
+    /// Copy with band `index` set to `db`, clamped like `new`.
+    pub fn with_band(self, index: usize, db: i8) -> Self {
+        match index {
+            0 => Self::new(db, self.mid, self.treble),
+            1 => Self::new(self.bass, db, self.treble),
+            _ => Self::new(self.bass, self.mid, db),
+        }
+    }
+
+    /// All bands at 0 dB: playback skips the filters entirely.
+    pub fn is_flat(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl fmt::Display for EqGains {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "bass {:+} dB, mid {:+} dB, treble {:+} dB", self.bass, self.mid, self.treble)
+    }
+}
+
+/// Normalized biquad coefficients (`a0` = 1), from the RBJ audio EQ cookbook.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+}
+
+impl Biquad {
+    /// Boost or cut everything below `freq` by `gain_db` (shelf slope 1).
+    pub fn low_shelf(sample_rate: f64, freq: f64, gain_db: f64) -> Self {
+        let (a, cos, alpha) = shelf_terms(sample_rate, freq, gain_db);
+        let sqrt_a_alpha = 2.0 * a.sqrt() * alpha;
+
+        Self::normalized(
+            a * ((a + 1.0) - (a - 1.0) * cos + sqrt_a_alpha),
+            2.0 * a * ((a - 1.0) - (a + 1.0) * cos),
+            a * ((a + 1.0) - (a - 1.0) * cos - sqrt_a_alpha),
+            (a + 1.0) + (a - 1.0) * cos + sqrt_a_alpha,
+            -2.0 * ((a - 1.0) + (a + 1.0) * cos),
+            (a + 1.0) + (a - 1.0) * cos - sqrt_a_alpha,
+        )
+    }
+
+    /// Boost or cut everything above `freq` by `gain_db` (shelf slope 1).
+    pub fn high_shelf(sample_rate: f64, freq: f64, gain_db: f64) -> Self {
+        let (a, cos, alpha) = shelf_terms(sample_rate, freq, gain_db);
+        let sqrt_a_alpha = 2.0 * a.sqrt() * alpha;
+
+        Self::normalized(
+            a * ((a + 1.0) + (a - 1.0) * cos + sqrt_a_alpha),
+            -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+            a * ((a + 1.0) + (a - 1.0) * cos - sqrt_a_alpha),
+            (a + 1.0) - (a - 1.0) * cos + sqrt_a_alpha,
+            2.0 * ((a - 1.0) - (a + 1.0) * cos),
+            (a + 1.0) - (a - 1.0) * cos - sqrt_a_alpha,
+        )
+    }
+
+    /// Boost or cut a band around `freq` by `gain_db`; higher `q` narrows it.
+    pub fn peaking(sample_rate: f64, freq: f64, q: f64, gain_db: f64) -> Self {
+        let a = 10f64.powf(gain_db / 40.0);
+        let w0 = 2.0 * PI * nyquist_safe(sample_rate, freq) / sample_rate;
+        let (sin, cos) = w0.sin_cos();
+        let alpha = sin / (2.0 * q);
+
+        Self::normalized(
+            1.0 + alpha * a,
+            -2.0 * cos,
+            1.0 - alpha * a,
+            1.0 + alpha / a,
+            -2.0 * cos,
+            1.0 - alpha / a,
+        )
+    }
+
+    fn normalized(b0: f64, b1: f64, b2: f64, a0: f64, a1: f64, a2: f64) -> Self {
+        Self {
+            b0: (b0 / a0) as f32,
+            b1: (b1 / a0) as f32,
+            b2: (b2 / a0) as f32,
+            a1: (a1 / a0) as f32,
+            a2: (a2 / a0) as f32,
+        }
+    }
+
+    /// Filter one sample (transposed direct form II).
+    fn process(&self, x: f32, state: &mut [f32; 2]) -> f32 {
+        let y = self.b0 * x + state[0];
+        state[0] = self.b1 * x - self.a1 * y + state[1];
+        state[1] = self.b2 * x - self.a2 * y;
+        y
+    }
+}
+
+/// `A`, `cos(w0)` and `alpha` shared by both shelf filters.
+fn shelf_terms(sample_rate: f64, freq: f64, gain_db: f64) -> (f64, f64, f64) {
+    let a = 10f64.powf(gain_db / 40.0);
+    let w0 = 2.0 * PI * nyquist_safe(sample_rate, freq) / sample_rate;
+    let (sin, cos) = w0.sin_cos();
+    // Shelf slope S = 1: alpha = sin/2 * sqrt((A + 1/A)(1/S - 1) + 2).
+    (a, cos, sin / 2.0 * 2f64.sqrt())
+}
+
+/// Keep a band below Nyquist on low sample-rate files.
+fn nyquist_safe(sample_rate: f64, freq: f64) -> f64 {
+    freq.min(sample_rate * 0.45)
+}
+
+/// The three band filters for `gains` at `sample_rate`.
+fn stages(gains: EqGains, sample_rate: SampleRate) -> [Biquad; 3] {
+    let rate = f64::from(sample_rate.get());
+    [
+        Biquad::low_shelf(rate, BASS_HZ, f64::from(gains.bass)),
+        Biquad::peaking(rate, MID_HZ, MID_Q, f64::from(gains.mid)),
+        Biquad::high_shelf(rate, TREBLE_HZ, f64::from(gains.treble)),
+    ]
+}
+
+/// Source adapter running every channel through the bass, mid and treble filters.
+pub struct Equalizer<S> {
+    input: S,
+    gains: EqGains,
+    stages: [Biquad; 3],
+    /// Filter memory per channel and stage.
+    history: Vec<[[f32; 2]; 3]>,
+    /// Format the stages were built for; rebuilt when a new span changes it.
+    format: (ChannelCount, SampleRate),
+    channel: usize,
+}
+
+impl<S: Source> Equalizer<S> {
+    pub fn new(input: S, gains: EqGains) -> Self {
+        let format = (input.channels(), input.sample_rate());
+        Self {
+            stages: stages(gains, format.1),
+            history: vec![[[0.0; 2]; 3]; usize::from(format.0.get())],
+            input,
+            gains,
+            format,
+            channel: 0,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.format = (self.input.channels(), self.input.sample_rate());
+        self.stages = stages(self.gains, self.format.1);
+        self.history = vec![[[0.0; 2]; 3]; usize::from(self.format.0.get())];
+        self.channel = 0;
+    }
+}
+
+impl<S: Source> Iterator for Equalizer<S> {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Sample> {
+        if self.channel == 0 && (self.input.channels(), self.input.sample_rate()) != self.format {
+            self.reset();
+        }
+
+        let sample = self.input.next()?;
+        let history = &mut self.history[self.channel];
+        let out = self
+            .stages
+            .iter()
+            .zip(history.iter_mut())
+            .fold(sample, |x, (stage, state)| stage.process(x, state));
+
+        self.channel = (self.channel + 1) % self.history.len();
+        Some(out)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<S: Source> Source for Equalizer<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.reset();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: f64 = 44_100.0;
+
+    /// Magnitude of the filter's response at `freq`.
+    fn gain_at(filter: &Biquad, freq: f64) -> f64 {
+        let w = 2.0 * PI * freq / RATE;
+        let (b0, b1, b2) = (f64::from(filter.b0), f64::from(filter.b1), f64::from(filter.b2));
+        let (a1, a2) = (f64::from(filter.a1), f64::from(filter.a2));
+
+        // |b0 + b1 z^-1 + b2 z^-2| / |1 + a1 z^-1 + a2 z^-2| with z = e^{jw}
+        let num = ((b0 + b1 * w.cos() + b2 * (2.0 * w).cos()).powi(2)
+            + (b1 * w.sin() + b2 * (2.0 * w).sin()).powi(2))
+        .sqrt();
+        let den = ((1.0 + a1 * w.cos() + a2 * (2.0 * w).cos()).powi(2)
+            + (a1 * w.sin() + a2 * (2.0 * w).sin()).powi(2))
+        .sqrt();
+        num / den
+    }
+
+    fn db(gain: f64) -> f64 {
+        20.0 * gain.log10()
+    }
+
+    #[test]
+    fn zero_gain_filters_pass_everything_unchanged() {
+        for filter in [
+            Biquad::low_shelf(RATE, BASS_HZ, 0.0),
+            Biquad::peaking(RATE, MID_HZ, MID_Q, 0.0),
+            Biquad::high_shelf(RATE, TREBLE_HZ, 0.0),
+        ] {
+            for freq in [20.0, 1_000.0, 15_000.0] {
+                assert!((gain_at(&filter, freq) - 1.0).abs() < 1e-5, "{filter:?} at {freq} Hz");
+            }
+        }
+    }
+
+    #[test]
+    fn low_shelf_boosts_bass_and_leaves_treble() {
+        let filter = Biquad::low_shelf(RATE, BASS_HZ, 6.0);
+
+        assert!((db(gain_at(&filter, 10.0)) - 6.0).abs() < 0.1);
+        assert!(db(gain_at(&filter, 10_000.0)).abs() < 0.1);
+    }
+
+    #[test]
+    fn high_shelf_cuts_treble_and_leaves_bass() {
+        let filter = Biquad::high_shelf(RATE, TREBLE_HZ, -9.0);
+
+        assert!((db(gain_at(&filter, 20_000.0)) + 9.0).abs() < 0.2);
+        assert!(db(gain_at(&filter, 50.0)).abs() < 0.1);
+    }
+
+    #[test]
+    fn peaking_hits_its_gain_at_the_centre_only() {
+        let filter = Biquad::peaking(RATE, MID_HZ, MID_Q, 4.0);
+
+        assert!((db(gain_at(&filter, MID_HZ)) - 4.0).abs() < 0.01);
+        assert!(db(gain_at(&filter, 20.0)).abs() < 0.1);
+        assert!(db(gain_at(&filter, 20_000.0)).abs() < 0.1);
+    }
+
+    #[test]
+    fn gains_are_clamped_and_flat_only_at_zero() {
+        let gains = EqGains::new(40, -40, 0);
+
+        assert_eq!(gains, EqGains { bass: EQ_MAX_DB, mid: -EQ_MAX_DB, treble: 0 });
+        assert!(!gains.is_flat());
+        assert!(EqGains::new(0, 0, 0).is_flat());
+    }
+}
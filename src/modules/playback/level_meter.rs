@@ -0,0 +1,183 @@
+use rodio::source::SeekError;
+use rodio::{ChannelCount, Sample, SampleRate, Source};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Level updates per second; each one is the RMS of the frames since the last.
+const UPDATES_PER_SEC: u32 = 30;
+
+/// Quietest level the meter shows; anything below reads as empty.
+const FLOOR_DB: f32 = -48.0;
+
+/// Per-channel loudness of the audio being decoded, shared between the
+/// playback thread (which measures) and the TUI (which draws).
+///
+/// Measuring is off until `set_enabled(true)`, so an unseen meter costs one
+/// atomic load per sample.
+#[derive(Debug, Clone, Default)]
+pub struct LevelMeter {
+    enabled: Arc<AtomicBool>,
+    levels: Arc<Mutex<Vec<f32>>>,
+}
+
+impl LevelMeter {
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+        if !enabled {
+            self.clear();
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Latest RMS level per channel (0.0 - 1.0); empty when nothing was measured.
+    pub fn levels(&self) -> Vec<f32> {
+        self.levels.lock().unwrap().clone()
+    }
+
+    /// Forget the last levels, e.g. when playback stops.
+    pub fn clear(&self) {
+        self.levels.lock().unwrap().clear();
+    }
+
+    fn publish(&self, levels: Vec<f32>) {
+        *self.levels.lock().unwrap() = levels;
+    }
+}
+
+/// Fraction of a meter bar to fill for an RMS `level`, on a dB scale from
+/// `FLOOR_DB` (empty) to 0 dB (full).
+pub fn bar_fraction(level: f32) -> f64 {
+    if level <= 0.0 {
+        return 0.0;
+    }
+    let db = 20.0 * level.log10();
+    f64::from(((db - FLOOR_DB) / -FLOOR_DB).clamp(0.0, 1.0))
+}
+
+/// Source adapter that passes samples through unchanged while feeding a `LevelMeter`.
+pub struct LevelTap<S> {
+    input: S,
+    meter: LevelMeter,
+    /// Sum of squares per channel over the current window.
+    sums: Vec<f32>,
+    frames: u32,
+    window: u32,
+    channel: usize,
+}
+
+impl<S: Source> LevelTap<S> {
+    pub fn new(input: S, meter: LevelMeter) -> Self {
+        let mut tap = Self { input, meter, sums: Vec::new(), frames: 0, window: 1, channel: 0 };
+        tap.reset();
+        tap
+    }
+
+    fn reset(&mut self) {
+        self.sums = vec![0.0; usize::from(self.input.channels().get())];
+        self.window = (self.input.sample_rate().get() / UPDATES_PER_SEC).max(1);
+        self.frames = 0;
+        self.channel = 0;
+    }
+}
+
+impl<S: Source> Iterator for LevelTap<S> {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Sample> {
+        if self.channel == 0 && usize::from(self.input.channels().get()) != self.sums.len() {
+            self.reset();
+        }
+
+        let sample = self.input.next()?;
+        if !self.meter.is_enabled() {
+            return Some(sample);
+        }
+
+        self.sums[self.channel] += sample * sample;
+        self.channel = (self.channel + 1) % self.sums.len();
+
+        if self.channel == 0 {
+            self.frames += 1;
+            if self.frames >= self.window {
+                let frames = self.frames as f32;
+                self.meter.publish(self.sums.iter().map(|sum| (sum / frames).sqrt()).collect());
+                self.sums.iter_mut().for_each(|sum| *sum = 0.0);
+                self.frames = 0;
+            }
+        }
+
+        Some(sample)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<S: Source> Source for LevelTap<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.reset();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rodio::buffer::SamplesBuffer;
+
+    fn stereo(left: f32, right: f32, frames: usize) -> SamplesBuffer {
+        let data: Vec<f32> = (0..frames).flat_map(|_| [left, right]).collect();
+        SamplesBuffer::new(ChannelCount::new(2).unwrap(), SampleRate::new(3_000).unwrap(), data)
+    }
+
+    #[test]
+    fn tap_measures_each_channel_and_passes_samples_through() {
+        let meter = LevelMeter::default();
+        meter.set_enabled(true);
+
+        let samples: Vec<f32> = LevelTap::new(stereo(0.5, -0.25, 100), meter.clone()).collect();
+
+        assert_eq!(samples.len(), 200);
+        assert_eq!(&samples[..2], &[0.5, -0.25]);
+        assert_eq!(meter.levels(), vec![0.5, 0.25]);
+    }
+
+    #[test]
+    fn disabled_meter_measures_nothing() {
+        let meter = LevelMeter::default();
+
+        LevelTap::new(stereo(0.5, 0.5, 100), meter.clone()).for_each(drop);
+
+        assert!(meter.levels().is_empty());
+    }
+
+    #[test]
+    fn bar_fraction_follows_the_db_scale() {
+        assert_eq!(bar_fraction(0.0), 0.0);
+        assert_eq!(bar_fraction(1.0), 1.0);
+        assert!((bar_fraction(10f32.powf(FLOOR_DB / 40.0)) - 0.5).abs() < 1e-6);
+        assert_eq!(bar_fraction(1e-6), 0.0);
+    }
+}
@@ -1,3 +1,33 @@
+pub mod crossfade;
+pub mod equalizer;
+pub mod level_meter;
+pub mod null_backend;
+pub mod replay_gain;
 pub mod rodio_backend;
 pub mod shuffle_manager;
 pub mod playback_progress;
+
+use crate::core::traits::PlaybackBackend;
+use anyhow::Result;
+use null_backend::NullPlaybackBackend;
+use rodio_backend::RodioBackend;
+use std::sync::OnceLock;
+
+/// Environment variable that turns on `--no-audio` when set to `1`, e.g. in CI.
+pub const NULL_AUDIO_ENV: &str = "HEXTUNE_NULL_AUDIO";
+
+static NULL_AUDIO: OnceLock<bool> = OnceLock::new();
+
+/// Record whether `--no-audio` was given; without it, `NULL_AUDIO_ENV` decides.
+/// Only the first call has an effect.
+pub fn configure_null_audio(flag: bool) {
+    NULL_AUDIO.get_or_init(|| flag || std::env::var(NULL_AUDIO_ENV).is_ok_and(|value| value == "1"));
+}
+
+/// Open the audio output on `device`, or the silent backend under `--no-audio`.
+pub fn open_backend(device: Option<&str>) -> Result<Box<dyn PlaybackBackend>> {
+    if *NULL_AUDIO.get_or_init(|| false) {
+        return Ok(Box::new(NullPlaybackBackend::new()));
+    }
+    Ok(Box::new(RodioBackend::new(device)?))
+}
@@ -0,0 +1,131 @@
+use crate::core::models::Song;
+use crate::core::traits::PlaybackBackend;
+use anyhow::Result;
+use std::time::{Duration, Instant};
+
+/// Backend that plays nothing: it keeps the clock a real track would, so the
+/// rest of the app (progress, auto-advance, repeat) behaves as usual on
+/// machines without an audio device. Selected with `--no-audio`.
+///
+/// Songs without a known duration never finish on their own.
+#[derive(Debug, Default)]
+pub struct NullPlaybackBackend {
+    current_song: Option<Song>,
+    /// Position reached before the clock last stopped (pause or seek).
+    played: Duration,
+    /// When the clock last started; `None` while paused or stopped.
+    resumed_at: Option<Instant>,
+}
+
+impl NullPlaybackBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.played + self.resumed_at.map_or(Duration::ZERO, |at| at.elapsed())
+    }
+}
+
+impl PlaybackBackend for NullPlaybackBackend {
+    fn play(&mut self, song: &Song) -> Result<()> {
+        self.current_song = Some(song.clone());
+        self.played = Duration::ZERO;
+        self.resumed_at = Some(Instant::now());
+        Ok(())
+    }
+
+    fn stop(&mut self) {
+        self.current_song = None;
+        self.played = Duration::ZERO;
+        self.resumed_at = None;
+    }
+
+    fn pause(&mut self) {
+        if self.current_song.is_some() {
+            self.played = self.elapsed();
+            self.resumed_at = None;
+        }
+    }
+
+    fn resume(&mut self) {
+        if self.current_song.is_some() && self.resumed_at.is_none() {
+            self.resumed_at = Some(Instant::now());
+        }
+    }
+
+    fn is_playing(&self) -> bool {
+        self.current_song.is_some() && !self.has_finished()
+    }
+
+    fn is_paused(&self) -> bool {
+        self.current_song.is_some() && self.resumed_at.is_none()
+    }
+
+    fn has_finished(&self) -> bool {
+        self.current_song
+            .as_ref()
+            .and_then(|song| song.duration)
+            .is_some_and(|duration| self.elapsed() >= duration)
+    }
+
+    fn set_volume(&mut self, _volume: f32) {}
+
+    fn seek(&mut self, position: Duration) -> Result<()> {
+        if self.current_song.is_some() {
+            self.played = position;
+            if self.resumed_at.is_some() {
+                self.resumed_at = Some(Instant::now());
+            }
+        }
+        Ok(())
+    }
+
+    fn position(&self) -> Duration {
+        match self.current_song.as_ref().and_then(|song| song.duration) {
+            Some(duration) => self.elapsed().min(duration),
+            None if self.current_song.is_some() => self.elapsed(),
+            None => Duration::ZERO,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn song(duration: Duration) -> Song {
+        Song { duration: Some(duration), ..Song::from_path(Path::new("/m/silent.mp3")) }
+    }
+
+    #[test]
+    fn the_clock_runs_only_while_playing() {
+        let mut backend = NullPlaybackBackend::new();
+        backend.play(&song(Duration::from_secs(60))).unwrap();
+        backend.seek(Duration::from_secs(10)).unwrap();
+        backend.pause();
+        let paused_at = backend.position();
+        std::thread::sleep(Duration::from_millis(5));
+
+        assert!(backend.is_playing() && backend.is_paused());
+        assert_eq!(backend.position(), paused_at);
+        assert!(paused_at >= Duration::from_secs(10));
+
+        backend.stop();
+        assert!(!backend.is_playing());
+        assert_eq!(backend.position(), Duration::ZERO);
+    }
+
+    #[test]
+    fn tracks_finish_once_their_duration_has_passed() {
+        let mut backend = NullPlaybackBackend::new();
+        backend.play(&song(Duration::from_secs(60))).unwrap();
+        assert!(!backend.has_finished());
+
+        backend.seek(Duration::from_secs(60)).unwrap();
+        assert!(backend.has_finished());
+        assert!(!backend.is_playing());
+        assert_eq!(backend.position(), Duration::from_secs(60));
+    }
+}
@@ -1,10 +1,14 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+/// Longest stretch `ElapsedClock` runs ahead of the last synced position.
+const MAX_INTERPOLATION: Duration = Duration::from_secs(1);
 
 /// Represents the current state of song playback progress
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct PlaybackProgress {
     elapsed: Duration,
-    total: Duration,
+    /// `None` when the length is unknown (streams, files lofty could not read)
+    total: Option<Duration>,
 }
 
 impl PlaybackProgress {
@@ -24,13 +28,34 @@ impl PlaybackProgress {
 
         Some(Self {
             elapsed: elapsed.min(total), // Clamp elapsed to total
-            total,
+            total: Some(total),
         })
     }
 
-    /// Returns the ratio of progress (0.0 to 1.0)
+    /// Progress of a song whose length is unknown; only elapsed time is tracked
+    pub fn unknown_total(elapsed: Duration) -> Self {
+        Self { elapsed, total: None }
+    }
+
+    /// Progress for a song's optional duration, falling back to
+    /// `unknown_total` when it is missing or zero
+    pub fn for_duration(elapsed: Duration, total: Option<Duration>) -> Self {
+        total
+            .and_then(|total| Self::new(elapsed, total))
+            .unwrap_or_else(|| Self::unknown_total(elapsed))
+    }
+
+    /// Returns the ratio of progress (0.0 to 1.0); always 0.0 when the total is unknown
     pub fn ratio(&self) -> f64 {
-        (self.elapsed.as_secs_f64() / self.total.as_secs_f64()).clamp(0.0, 1.0)
+        self.ratio_at(self.elapsed)
+    }
+
+    /// Returns where `position` falls within the track (0.0 to 1.0)
+    pub fn ratio_at(&self, position: Duration) -> f64 {
+        match self.total {
+            Some(total) => (position.as_secs_f64() / total.as_secs_f64()).clamp(0.0, 1.0),
+            None => 0.0,
+        }
     }
 
     /// Returns the elapsed duration
@@ -38,8 +63,81 @@ impl PlaybackProgress {
         self.elapsed
     }
 
-    /// Returns the total duration
-    pub fn total(&self) -> Duration {
+    /// Returns the total duration, if known
+    pub fn total(&self) -> Option<Duration> {
         self.total
     }
-}
\ No newline at end of file
+}
+
+/// Elapsed time that keeps advancing between position syncs, so the progress
+/// bar moves smoothly even when state updates lag behind rendering.
+#[derive(Debug, Clone, Copy)]
+pub struct ElapsedClock {
+    synced: Duration,
+    synced_at: Instant,
+    running: bool,
+}
+
+impl ElapsedClock {
+    pub fn new(now: Instant) -> Self {
+        Self { synced: Duration::ZERO, synced_at: now, running: false }
+    }
+
+    /// Take a position from the playback state. The baseline only moves when
+    /// the position or the running state changes, so repeated syncs of a stale
+    /// position keep interpolating.
+    pub fn sync(&mut self, elapsed: Duration, running: bool, now: Instant) {
+        if elapsed != self.synced || running != self.running {
+            self.synced = elapsed;
+            self.synced_at = now;
+            self.running = running;
+        }
+    }
+
+    /// Estimated position at `now`, never past `total` and never more than
+    /// `MAX_INTERPOLATION` ahead of the last sync. Stands still while paused.
+    pub fn elapsed(&self, now: Instant, total: Option<Duration>) -> Duration {
+        let elapsed = if self.running {
+            self.synced + now.saturating_duration_since(self.synced_at).min(MAX_INTERPOLATION)
+        } else {
+            self.synced
+        };
+        total.map_or(elapsed, |total| elapsed.min(total.max(self.synced)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_or_zero_duration_falls_back_to_unknown_total() {
+        let elapsed = Duration::from_secs(30);
+
+        for total in [None, Some(Duration::ZERO)] {
+            let progress = PlaybackProgress::for_duration(elapsed, total);
+            assert_eq!(progress.total(), None);
+            assert_eq!(progress.elapsed(), elapsed, "elapsed is not clamped without a total");
+            assert_eq!(progress.ratio(), 0.0);
+        }
+
+        let known = PlaybackProgress::for_duration(elapsed, Some(Duration::from_secs(60)));
+        assert_eq!(known.ratio(), 0.5);
+    }
+
+    #[test]
+    fn clock_interpolates_between_syncs_up_to_the_song_duration() {
+        let start = Instant::now();
+        let total = Some(Duration::from_secs(10));
+        let mut clock = ElapsedClock::new(start);
+
+        clock.sync(Duration::from_millis(9_500), true, start);
+        let later = start + Duration::from_millis(300);
+        clock.sync(Duration::from_millis(9_500), true, later);
+        assert_eq!(clock.elapsed(later, total), Duration::from_millis(9_800), "a stale sync keeps the baseline");
+        assert_eq!(clock.elapsed(start + Duration::from_millis(900), total), Duration::from_secs(10));
+
+        clock.sync(Duration::from_millis(9_500), false, later);
+        assert_eq!(clock.elapsed(later + Duration::from_secs(5), total), Duration::from_millis(9_500));
+    }
+}
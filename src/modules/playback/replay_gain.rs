@@ -0,0 +1,65 @@
+/// Parse a ReplayGain tag value such as `"-6.48 dB"` into decibels.
+///
+/// Returns `None` for blank or malformed values so the track plays at the user volume.
+pub fn parse_gain_db(raw: &str) -> Option<f32> {
+    let trimmed = raw.trim();
+    let number = trimmed
+        .strip_suffix("dB")
+        .or_else(|| trimmed.strip_suffix("db"))
+        .unwrap_or(trimmed)
+        .trim();
+
+    number.parse::<f32>().ok().filter(|gain| gain.is_finite())
+}
+
+/// Amplitude multiplier equivalent to a gain of `gain_db` decibels.
+pub fn gain_to_multiplier(gain_db: f32) -> f32 {
+    10f32.powf(gain_db / 20.0)
+}
+
+/// Amplitude to play a track at: the user `volume` adjusted by the track's gain.
+///
+/// Tracks without a gain tag keep the user volume unchanged. The result is
+/// clamped to 1.0 so positive gains cannot push the output into clipping.
+pub fn track_amplitude(volume: f32, gain_db: Option<f32>) -> f32 {
+    match gain_db {
+        Some(gain) => (volume * gain_to_multiplier(gain)).clamp(0.0, 1.0),
+        None => volume,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_common_tag_formats() {
+        assert_eq!(parse_gain_db("-6.48 dB"), Some(-6.48));
+        assert_eq!(parse_gain_db("+2.5 dB"), Some(2.5));
+        assert_eq!(parse_gain_db("-3.1dB"), Some(-3.1));
+        assert_eq!(parse_gain_db(" 0.00 "), Some(0.0));
+    }
+
+    #[test]
+    fn malformed_values_are_ignored() {
+        assert_eq!(parse_gain_db(""), None);
+        assert_eq!(parse_gain_db("loud"), None);
+        assert_eq!(parse_gain_db("NaN dB"), None);
+    }
+
+    #[test]
+    fn missing_gain_keeps_user_volume() {
+        assert_eq!(track_amplitude(0.4, None), 0.4);
+    }
+
+    #[test]
+    fn negative_gain_attenuates() {
+        let amplitude = track_amplitude(1.0, Some(-20.0));
+        assert!((amplitude - 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn combined_amplitude_is_clamped_to_one() {
+        assert_eq!(track_amplitude(0.9, Some(12.0)), 1.0);
+    }
+}
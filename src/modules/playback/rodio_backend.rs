@@ -1,21 +1,56 @@
 use crate::core::traits::PlaybackBackend;
 use crate::core::models::Song;
-use anyhow::{Result, Context};
-use rodio::{Decoder, DeviceSinkBuilder, MixerDeviceSink, Player};
+use crate::modules::playback::crossfade::{crossfade_start, FadeOutTail};
+use crate::modules::playback::equalizer::{EqGains, Equalizer};
+use crate::modules::playback::level_meter::{LevelMeter, LevelTap};
+use crate::modules::playback::replay_gain::track_amplitude;
+use crate::utils::UNPLAYABLE_EXTENSIONS;
+use anyhow::{bail, Context, Result};
+use rodio::cpal::traits::HostTrait;
+use rodio::{Decoder, Device, DeviceSinkBuilder, DeviceTrait, MixerDeviceSink, Player, Source};
 use std::fs::File;
 use std::io::BufReader;
+use std::sync::OnceLock;
 use std::time::Duration;
 
 pub struct RodioBackend {
+    /// Owns the output stream: it stays open exactly as long as the backend,
+    /// so dropping one backend and opening another switches devices.
     device_sink: MixerDeviceSink,
     player: Player,
     current_song: Option<Song>,
+    /// Song handed over by `queue_next`, not yet playing.
+    queued_song: Option<Song>,
+    /// User volume (amplitude) before any per-track gain.
+    volume: f32,
+    /// Apply each track's ReplayGain on top of `volume`.
+    normalize: bool,
+    /// Crossfade window; zero means plain gapless transitions.
+    crossfade: Duration,
+    /// Band gains applied to each track as it is decoded.
+    eq: EqGains,
+    /// Fed by every track as it plays, for the TUI visualizer.
+    meter: LevelMeter,
+    /// Paused player holding the queued song, started when the crossfade begins.
+    incoming: Option<Player>,
+    /// Previous track's player, kept alive while it fades out.
+    outgoing: Option<Player>,
+}
+
+/// Output device picked with `--device`, which wins over the saved one.
+static DEVICE_FLAG: OnceLock<Option<String>> = OnceLock::new();
+
+/// Record the output device given on the command line. Only the first call has an effect.
+pub fn configure_device(device: Option<String>) {
+    DEVICE_FLAG.get_or_init(|| device);
 }
 
 impl RodioBackend {
-    pub fn new() -> Result<Self> {
-        let mut device_sink = DeviceSinkBuilder::open_default_sink()
-            .context("Failed to open default audio output device")?;
+    /// Open `device` (or the `--device` one) by name, falling back to the
+    /// system default with a warning when it is missing or cannot be opened.
+    pub fn new(device: Option<&str>) -> Result<Self> {
+        let device = DEVICE_FLAG.get().and_then(|flag| flag.as_deref()).or(device);
+        let mut device_sink = open_sink(device)?;
         device_sink.log_on_drop(false);
         let player = Player::connect_new(device_sink.mixer());
 
@@ -23,21 +58,133 @@ impl RodioBackend {
             device_sink,
             player,
             current_song: None,
+            queued_song: None,
+            volume: 1.0,
+            normalize: false,
+            crossfade: Duration::ZERO,
+            eq: EqGains::default(),
+            meter: LevelMeter::default(),
+            incoming: None,
+            outgoing: None,
         })
     }
+
+    /// Name of the system's default output device, if there is one.
+    pub fn default_device_name() -> Option<String> {
+        rodio::cpal::default_host().default_output_device().and_then(|d| device_name(&d))
+    }
+
+    /// Names of every output device, in the order the system lists them.
+    pub fn output_device_names() -> Result<Vec<String>> {
+        let devices = rodio::cpal::default_host()
+            .output_devices()
+            .context("Failed to list audio output devices")?;
+        Ok(devices.filter_map(|d| device_name(&d)).collect())
+    }
+
+    /// Amplitude for `song`: the user volume, adjusted by its track gain when normalizing.
+    fn volume_for(&self, song: Option<&Song>) -> f32 {
+        match song {
+            Some(song) if self.normalize => track_amplitude(self.volume, song.track_gain_db),
+            _ => self.volume,
+        }
+    }
+
+    /// Push the current volume to every live player.
+    fn apply_volume(&self) {
+        self.player.set_volume(self.volume_for(self.current_song.as_ref()));
+        if let Some(incoming) = &self.incoming {
+            incoming.set_volume(self.volume_for(self.queued_song.as_ref()));
+        }
+        if let Some(outgoing) = &self.outgoing {
+            outgoing.set_volume(self.volume);
+        }
+    }
+
+    /// Decode `song` onto `player`, fading out its tail when crossfading is on.
+    /// `fade_in` is only requested for tracks entering through a crossfade.
+    fn append_track(&self, player: &Player, song: &Song, fade_in: bool) -> Result<()> {
+        let decoder = open_decoder(song)?;
+
+        // A flat equalizer keeps the decoder unwrapped; the meter sees the equalized signal.
+        if self.eq.is_flat() {
+            self.append_source(player, LevelTap::new(decoder, self.meter.clone()), song, fade_in);
+        } else {
+            let source = Equalizer::new(decoder, self.eq);
+            self.append_source(player, LevelTap::new(source, self.meter.clone()), song, fade_in);
+        }
+
+        Ok(())
+    }
+
+    fn append_source<S: Source + Send + 'static>(&self, player: &Player, source: S, song: &Song, fade_in: bool) {
+        match song.duration.filter(|_| !self.crossfade.is_zero()) {
+            Some(total) => {
+                let source = FadeOutTail::new(source, total, self.crossfade);
+                if fade_in {
+                    player.append(source.fade_in(self.crossfade));
+                } else {
+                    player.append(source);
+                }
+            }
+            None => player.append(source),
+        }
+    }
+
+    /// Whether the current track has reached the point where the crossfade should start.
+    fn crossfade_due(&self) -> bool {
+        let start = self
+            .current_song
+            .as_ref()
+            .and_then(|song| song.duration)
+            .map(|total| crossfade_start(total, self.crossfade));
+
+        self.player.empty() || start.is_some_and(|start| self.player.get_pos() >= start)
+    }
+}
+
+fn open_decoder(song: &Song) -> Result<Decoder<BufReader<File>>> {
+    let extension = song.path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_lowercase)
+        .unwrap_or_default();
+
+    if UNPLAYABLE_EXTENSIONS.contains(&extension.as_str()) {
+        bail!("{} files cannot be played yet: {}", extension, song.path.display());
+    }
+
+    let file = File::open(&song.path)
+        .with_context(|| format!("Failed to open audio file: {}", song.path.display()))?;
+    let len = file.metadata()?.len();
+
+    // Seekable with a known length: MP4 containers may keep their index at the end.
+    Decoder::builder()
+        .with_data(BufReader::new(file))
+        .with_byte_len(len)
+        .with_seekable(true)
+        .with_hint(&extension)
+        .build()
+        .with_context(|| format!("Failed to decode audio file: {}", song.path.display()))
 }
 
 impl PlaybackBackend for RodioBackend {
     fn play(&mut self, song: &Song) -> Result<()> {
-        let volume = self.player.volume();
-        self.player = Player::connect_new(self.device_sink.mixer());
-        self.player.set_volume(volume);
+        // Decode before replacing the player, so a file that fails to open
+        // leaves the current track playing.
+        let player = Player::connect_new(self.device_sink.mixer());
+        player.set_volume(self.volume_for(Some(song)));
+        if let Err(e) = self.append_track(&player, song, false) {
+            log::error!("Failed to open '{}' for playback: {:#}", song.path.display(), e);
+            return Err(e);
+        }
+        self.player = player;
 
-        let file = File::open(&song.path)?;
-        let source = Decoder::new(BufReader::new(file))
-            .with_context(|| format!("Failed to decode audio file: {}", song.path.display()))?;
+        // Manual plays cut straight over: drop anything queued or still fading.
+        self.queued_song = None;
+        self.incoming = None;
+        self.outgoing = None;
 
-        self.player.append(source);
         self.current_song = Some(song.clone());
         self.player.play();
 
@@ -47,17 +194,27 @@ impl PlaybackBackend for RodioBackend {
     fn stop(&mut self) {
         self.player.stop();
         self.current_song = None;
+        self.queued_song = None;
+        self.incoming = None;
+        self.outgoing = None;
+        self.meter.clear();
     }
 
     fn pause(&mut self) {
         if self.current_song.is_some() {
             self.player.pause();
+            if let Some(outgoing) = &self.outgoing {
+                outgoing.pause();
+            }
         }
     }
 
     fn resume(&mut self) {
         if self.current_song.is_some() {
             self.player.play();
+            if let Some(outgoing) = &self.outgoing {
+                outgoing.play();
+            }
         }
     }
 
@@ -74,7 +231,21 @@ impl PlaybackBackend for RodioBackend {
     }
 
     fn set_volume(&mut self, volume: f32) {
-        self.player.set_volume(volume.clamp(0.0, 1.0));
+        self.volume = volume.clamp(0.0, 1.0);
+        self.apply_volume();
+    }
+
+    fn set_normalization(&mut self, enabled: bool) {
+        self.normalize = enabled;
+        self.apply_volume();
+    }
+
+    fn set_crossfade(&mut self, window: Duration) {
+        self.crossfade = window;
+    }
+
+    fn set_equalizer(&mut self, gains: EqGains) {
+        self.eq = gains;
     }
 
     fn position(&self) -> Duration {
@@ -84,4 +255,126 @@ impl PlaybackBackend for RodioBackend {
             Duration::ZERO
         }
     }
+
+    fn seek(&mut self, position: Duration) -> Result<()> {
+        if self.current_song.is_none() {
+            return Ok(());
+        }
+
+        self.player
+            .try_seek(position)
+            .map_err(|e| anyhow::anyhow!("{}", e))
+    }
+
+    fn queue_next(&mut self, song: &Song) -> Result<()> {
+        let Some(current) = &self.current_song else {
+            return Ok(());
+        };
+        if self.queued_song.is_some() {
+            return Ok(());
+        }
+
+        if self.crossfade.is_zero() || current.duration.is_none() {
+            // Gapless: rodio moves straight on to the next sound in the same player.
+            self.append_track(&self.player, song, false)?;
+        } else {
+            // Crossfade: the next track needs its own player so the two can overlap.
+            let incoming = Player::connect_new(self.device_sink.mixer());
+            incoming.pause();
+            incoming.set_volume(self.volume_for(Some(song)));
+            self.append_track(&incoming, song, true)?;
+            self.incoming = Some(incoming);
+        }
+        self.queued_song = Some(song.clone());
+
+        Ok(())
+    }
+
+    fn take_queued_started(&mut self) -> bool {
+        if self.outgoing.as_ref().is_some_and(|p| p.empty()) {
+            self.outgoing = None;
+        }
+
+        if self.queued_song.is_none() {
+            return false;
+        }
+
+        if self.incoming.is_some() {
+            if !self.crossfade_due() {
+                return false;
+            }
+            if let Some(incoming) = self.incoming.take() {
+                incoming.play();
+                self.outgoing = Some(std::mem::replace(&mut self.player, incoming));
+            }
+        } else if self.player.len() > 1 {
+            // The queue shrinks back to one sound once the previous track is done.
+            return false;
+        }
+
+        self.current_song = self.queued_song.take();
+        // A gapless handover shares one player, so switch to the new track's gain.
+        self.player.set_volume(self.volume_for(self.current_song.as_ref()));
+        true
+    }
+
+    /// Measuring starts once the meter is enabled.
+    fn level_meter(&self) -> Option<LevelMeter> {
+        Some(self.meter.clone())
+    }
+}
+
+fn device_name(device: &Device) -> Option<String> {
+    device.description().ok().map(|description| description.name().to_string())
+}
+
+/// Whether a listed device `name` is the one the user asked for. Case is
+/// ignored, since names are typed by hand.
+pub fn is_named(name: &str, wanted: &str) -> bool {
+    name.trim().eq_ignore_ascii_case(wanted.trim())
+}
+
+fn open_sink(device: Option<&str>) -> Result<MixerDeviceSink> {
+    if let Some(wanted) = device {
+        let found = rodio::cpal::default_host()
+            .output_devices()
+            .ok()
+            .and_then(|mut devices| devices.find(|d| device_name(d).is_some_and(|n| is_named(&n, wanted))));
+
+        match found.map(|d| DeviceSinkBuilder::from_device(d).and_then(|b| b.open_stream())) {
+            Some(Ok(sink)) => return Ok(sink),
+            Some(Err(e)) => eprintln!(
+                "Warning: Could not open output device '{}' ({}). Using the default device.",
+                wanted, e
+            ),
+            None => eprintln!(
+                "Warning: Output device '{}' not found. Using the default device.",
+                wanted
+            ),
+        }
+    }
+
+    DeviceSinkBuilder::open_default_sink().context("Failed to open default audio output device")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn unplayable_format_is_reported_before_opening_the_file() {
+        let song = Song::from_path(Path::new("/nonexistent/hextune/track.OPUS"));
+
+        let err = open_decoder(&song).err().expect("opus must be rejected");
+
+        assert!(err.to_string().starts_with("opus files cannot be played"), "{err}");
+    }
+
+    #[test]
+    fn device_names_match_ignoring_case_and_padding() {
+        assert!(is_named("USB Audio", "usb audio"));
+        assert!(is_named("USB Audio", " USB Audio "));
+        assert!(!is_named("USB Audio", "USB"));
+    }
 }
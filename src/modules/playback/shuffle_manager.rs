@@ -1,4 +1,8 @@
 use rand::seq::SliceRandom;
+use std::collections::VecDeque;
+
+/// How many played songs "previous" can walk back through.
+const MAX_HISTORY: usize = 500;
 
 /// Manages shuffle state and provides smart randomization without repetition
 ///
@@ -7,6 +11,9 @@ use rand::seq::SliceRandom;
 /// - Plays through the queue in order
 /// - When queue is exhausted, reshuffles and starts over
 /// - This guarantees no repetition within a single pass through the playlist
+///
+/// "Previous" walks back through the songs that actually played (including
+/// manual jumps and earlier passes), not through the queue order.
 #[derive(Debug, Clone)]
 pub struct ShuffleManager {
     /// Whether shuffle is currently enabled
@@ -21,6 +28,12 @@ pub struct ShuffleManager {
 
     /// Total size of the playlist (for regenerating queue)
     playlist_size: usize,
+
+    /// Songs played before the current one, oldest first
+    history: VecDeque<usize>,
+
+    /// Songs stepped back over with `previous_index`, replayed before the queue continues
+    forward: Vec<usize>,
 }
 
 impl ShuffleManager {
@@ -31,6 +44,8 @@ impl ShuffleManager {
             shuffle_queue: Vec::new(),
             queue_position: 0,
             playlist_size: 0,
+            history: VecDeque::new(),
+            forward: Vec::new(),
         }
     }
 
@@ -44,6 +59,7 @@ impl ShuffleManager {
         if !self.enabled {
             self.shuffle_queue.clear();
             self.queue_position = 0;
+            self.clear_history();
         }
     }
 
@@ -70,7 +86,7 @@ impl ShuffleManager {
     /// # Arguments
     /// * `current_index` - The current song
     /// * `loop_playlist` - If true, reshuffles and continues when queue ends.
-    ///                     If false, returns None at end of queue.
+    ///   If false, returns None at end of queue.
     pub fn next_index(&mut self, current_index: Option<usize>, loop_playlist: bool) -> Option<usize> {
         if !self.enabled {
             return current_index.and_then(|idx| {
@@ -80,10 +96,54 @@ impl ShuffleManager {
             });
         }
 
+        if let Some(idx) = self.forward.pop() {
+            self.push_history(current_index);
+            return Some(idx);
+        }
+
         if self.shuffle_queue.is_empty() {
             self.generate_shuffle_queue(current_index);
         }
 
+        let next = self.advance_queue(loop_playlist);
+        if next.is_some() {
+            self.push_history(current_index);
+        }
+        next
+    }
+
+    /// Like `next_index`, but passes over songs `avoid` picks out while the
+    /// queue has others to offer; when every one is avoided the queue plays
+    /// on as usual. Songs stepped back over with "previous" still return.
+    pub fn next_index_avoiding(
+        &mut self,
+        current_index: Option<usize>,
+        loop_playlist: bool,
+        avoid: impl Fn(usize) -> bool,
+    ) -> Option<usize> {
+        if !self.enabled || !self.forward.is_empty() {
+            return self.next_index(current_index, loop_playlist);
+        }
+
+        if self.shuffle_queue.is_empty() {
+            self.generate_shuffle_queue(current_index);
+        }
+
+        let (queue, position) = (self.shuffle_queue.clone(), self.queue_position);
+        for _ in 0..self.playlist_size {
+            let Some(next) = self.advance_queue(loop_playlist) else { break };
+            if !avoid(next) {
+                self.push_history(current_index);
+                return Some(next);
+            }
+        }
+
+        self.shuffle_queue = queue;
+        self.queue_position = position;
+        self.next_index(current_index, loop_playlist)
+    }
+
+    fn advance_queue(&mut self, loop_playlist: bool) -> Option<usize> {
         let next_pos = self.queue_position + 1;
 
         // Check if we reached the end of the shuffled queue
@@ -114,8 +174,29 @@ impl ShuffleManager {
         self.shuffle_queue.get(self.queue_position).copied()
     }
 
+    /// Look at the index `next_index` would return, without advancing.
+    ///
+    /// Returns `None` when the current pass is exhausted: the next pass is
+    /// reshuffled at random, so its first song cannot be known in advance.
+    pub fn peek_next_index(&self, current_index: Option<usize>) -> Option<usize> {
+        if !self.enabled {
+            return current_index
+                .map(|idx| idx + 1)
+                .filter(|&next| next < self.playlist_size);
+        }
+
+        if let Some(&idx) = self.forward.last() {
+            return Some(idx);
+        }
+
+        self.shuffle_queue.get(self.queue_position + 1).copied()
+    }
+
     /// Get the previous index to play
     ///
+    /// With shuffle on this is the song that played before `current_index`,
+    /// even across reshuffles; stepping forward again replays the same songs.
+    ///
     /// # Arguments
     /// * `current_index` - The current song index
     ///
@@ -127,15 +208,36 @@ impl ShuffleManager {
             return current_index.and_then(|idx| if idx > 0 { Some(idx - 1) } else { None });
         }
 
-        if self.queue_position > 0 {
-            self.queue_position -= 1;
-            self.shuffle_queue.get(self.queue_position).copied()
-        } else {
-            // Note: Cannot go back to previous shuffle epoch without a history stack
-            None
+        let previous = self.history.pop_back()?;
+        if let Some(idx) = current_index {
+            self.forward.push(idx);
+        }
+        Some(previous)
+    }
+
+    /// Record a jump away from `current_index` that bypassed the shuffle queue
+    /// (playing a selected or queued song), so "previous" can return to it.
+    pub fn record_jump(&mut self, current_index: Option<usize>) {
+        if self.enabled {
+            self.forward.clear();
+            self.push_history(current_index);
         }
     }
 
+    /// Forget played songs, e.g. when library indices change.
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+        self.forward.clear();
+    }
+
+    fn push_history(&mut self, index: Option<usize>) {
+        let Some(idx) = index else { return };
+        if self.history.len() == MAX_HISTORY {
+            self.history.pop_front();
+        }
+        self.history.push_back(idx);
+    }
+
     /// Generate a new shuffle queue
     ///
     /// Creates a randomized list of indices, optionally ensuring
@@ -162,6 +264,7 @@ impl ShuffleManager {
 
     /// Update playlist size (call when playlist changes)
     pub fn update_playlist_size(&mut self, new_size: usize) {
+        self.clear_history();
         if self.playlist_size != new_size {
             self.playlist_size = new_size;
 
@@ -406,6 +509,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn avoiding_every_song_plays_on_in_queue_order() {
+        let mut m = enabled_manager(4);
+        let first = m.shuffle_queue[0];
+        let next = m.next_index_avoiding(Some(first), false, |_| true);
+        assert_eq!(next, Some(m.shuffle_queue[1]));
+        assert_eq!(m.remaining_in_pass(), 2);
+        assert_eq!(m.previous_index(next), Some(first));
+    }
+
     // ── previous_index — shuffle ENABLED ─────────────────────────────────────
 
     #[test]
@@ -426,6 +539,75 @@ mod tests {
         assert_eq!(m.previous_index(Some(m.shuffle_queue[0])), None);
     }
 
+    #[test]
+    fn prev_shuffle_returns_last_song_of_the_previous_pass() {
+        let mut m = enabled_manager(3);
+        let mut last = m.shuffle_queue[0];
+        for _ in 1..3 {
+            last = m.next_index(Some(last), true).unwrap();
+        }
+        let first_of_new_pass = m.next_index(Some(last), true).unwrap();
+
+        assert_eq!(m.previous_index(Some(first_of_new_pass)), Some(last));
+    }
+
+    #[test]
+    fn next_after_prev_replays_the_songs_stepped_back_over() {
+        let mut m = enabled_manager(5);
+        let first = m.shuffle_queue[0];
+        let second = m.next_index(Some(first), false).unwrap();
+        let third = m.next_index(Some(second), false).unwrap();
+
+        assert_eq!(m.previous_index(Some(third)), Some(second));
+        assert_eq!(m.previous_index(Some(second)), Some(first));
+        assert_eq!(m.peek_next_index(Some(first)), Some(second));
+        assert_eq!(m.next_index(Some(first), false), Some(second));
+        assert_eq!(m.next_index(Some(second), false), Some(third));
+    }
+
+    #[test]
+    fn prev_shuffle_returns_to_song_played_before_a_jump() {
+        let mut m = enabled_manager(5);
+        let first = m.shuffle_queue[0];
+        let jumped_to = (first + 1) % 5;
+
+        m.record_jump(Some(first));
+
+        assert_eq!(m.previous_index(Some(jumped_to)), Some(first));
+    }
+
+    #[test]
+    fn history_is_forgotten_when_the_playlist_changes() {
+        let mut m = enabled_manager(5);
+        let first = m.shuffle_queue[0];
+        let second = m.next_index(Some(first), false).unwrap();
+
+        m.update_playlist_size(5);
+
+        assert_eq!(m.previous_index(Some(second)), None);
+    }
+
+    // ── peek_next_index ───────────────────────────────────────────────────────
+
+    #[test]
+    fn peek_matches_next_without_advancing() {
+        let mut m = enabled_manager(5);
+        let first = m.shuffle_queue[0];
+
+        let peeked = m.peek_next_index(Some(first));
+        assert_eq!(m.queue_position(), 0, "peek must not move the queue");
+        assert_eq!(peeked, m.next_index(Some(first), false));
+    }
+
+    #[test]
+    fn peek_at_end_of_pass_returns_none() {
+        let mut m = enabled_manager(2);
+        let first = m.shuffle_queue[0];
+        let last = m.next_index(Some(first), true).unwrap();
+
+        assert_eq!(m.peek_next_index(Some(last)), None);
+    }
+
     // ── remaining_in_pass ─────────────────────────────────────────────────────
 
     #[test]
@@ -0,0 +1,211 @@
+//! Last.fm's scrobbling API: form posts signed with the account's API secret.
+
+use super::{http_agent, Listen, Service};
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+
+const API_URL: &str = "https://ws.audioscrobbler.com/2.0/";
+
+/// Page where the user grants a request token access to their account.
+const AUTH_URL: &str = "https://www.last.fm/api/auth/";
+
+/// Where users create the API account whose key and secret go in `scrobble.toml`.
+pub const API_ACCOUNT_URL: &str = "https://www.last.fm/api/account/create";
+
+/// Last.fm takes at most this many scrobbles per request.
+const BATCH_MAX: usize = 50;
+
+/// Request parameters; sorted by name, as signing needs them.
+type Params = BTreeMap<String, String>;
+
+/// An authorized Last.fm user, as `auth.getSession` returns it.
+pub struct Session {
+    pub name: String,
+    pub key: String,
+}
+
+/// Signs and sends API calls for one API account.
+pub struct LastFmClient {
+    api_key: String,
+    api_secret: String,
+    agent: ureq::Agent,
+}
+
+impl LastFmClient {
+    pub fn new(api_key: &str, api_secret: &str) -> Self {
+        Self { api_key: api_key.to_string(), api_secret: api_secret.to_string(), agent: http_agent() }
+    }
+
+    /// First step of the handshake: a token for the user to authorize.
+    pub fn request_token(&self) -> Result<String> {
+        let reply = self.call(method("auth.getToken"))?;
+        reply["token"].as_str().map(str::to_string).context("Last.fm sent no token")
+    }
+
+    /// Page on which the user allows `token` to scrobble for them.
+    pub fn auth_url(&self, token: &str) -> String {
+        format!("{}?api_key={}&token={}", AUTH_URL, self.api_key, token)
+    }
+
+    /// Last step of the handshake, once the user authorized `token`.
+    pub fn request_session(&self, token: &str) -> Result<Session> {
+        let mut params = method("auth.getSession");
+        params.insert("token".to_string(), token.to_string());
+        let reply = self.call(params)?;
+
+        let session = &reply["session"];
+        match (session["name"].as_str(), session["key"].as_str()) {
+            (Some(name), Some(key)) => Ok(Session { name: name.to_string(), key: key.to_string() }),
+            _ => anyhow::bail!("Last.fm sent no session"),
+        }
+    }
+
+    fn call(&self, params: Params) -> Result<serde_json::Value> {
+        let params = sign(params, &self.api_key, &self.api_secret);
+        let response = self.agent.post(API_URL).send_form(&params)?;
+        let status = response.status();
+        let body = response.into_body().read_to_string()?;
+        let reply: serde_json::Value = serde_json::from_str(&body)
+            .with_context(|| format!("Last.fm answered {} without JSON", status))?;
+
+        if let Some(code) = reply.get("error") {
+            anyhow::bail!("Last.fm error {}: {}", code, reply["message"].as_str().unwrap_or("no message"));
+        }
+        Ok(reply)
+    }
+}
+
+/// Scrobbles for the user the session key belongs to.
+pub struct LastFm {
+    client: LastFmClient,
+    session_key: String,
+}
+
+impl LastFm {
+    pub fn new(client: LastFmClient, session_key: &str) -> Self {
+        Self { client, session_key: session_key.to_string() }
+    }
+}
+
+impl Service for LastFm {
+    fn name(&self) -> &'static str {
+        "lastfm"
+    }
+
+    fn batch_max(&self) -> usize {
+        BATCH_MAX
+    }
+
+    fn now_playing(&self, listen: &Listen) -> Result<()> {
+        self.client.call(now_playing_params(listen, &self.session_key)).map(drop)
+    }
+
+    fn submit(&self, listens: &[Listen]) -> Result<()> {
+        self.client.call(scrobble_params(listens, &self.session_key)).map(drop)
+    }
+}
+
+fn method(name: &str) -> Params {
+    Params::from([("method".to_string(), name.to_string())])
+}
+
+/// `track.updateNowPlaying` for `listen`.
+fn now_playing_params(listen: &Listen, session_key: &str) -> Params {
+    let mut params = method("track.updateNowPlaying");
+    params.insert("sk".to_string(), session_key.to_string());
+    add_track(&mut params, listen, "");
+    params
+}
+
+/// One `track.scrobble` carrying every listen, numbered `artist[0]`, `artist[1]`, ...
+fn scrobble_params(listens: &[Listen], session_key: &str) -> Params {
+    let mut params = method("track.scrobble");
+    params.insert("sk".to_string(), session_key.to_string());
+    for (i, listen) in listens.iter().enumerate() {
+        let suffix = format!("[{}]", i);
+        add_track(&mut params, listen, &suffix);
+        params.insert(format!("timestamp{}", suffix), listen.started_at.to_string());
+    }
+    params
+}
+
+fn add_track(params: &mut Params, listen: &Listen, suffix: &str) {
+    let mut add = |name: &str, value: String| {
+        params.insert(format!("{}{}", name, suffix), value);
+    };
+    add("artist", listen.artist.clone());
+    add("track", listen.title.clone());
+    if let Some(album) = &listen.album {
+        add("album", album.clone());
+    }
+    if let Some(number) = listen.track_number {
+        add("trackNumber", number.to_string());
+    }
+    if let Some(secs) = listen.duration_secs {
+        add("duration", secs.to_string());
+    }
+}
+
+/// Add the API key and signature, and ask for JSON.
+fn sign(mut params: Params, api_key: &str, api_secret: &str) -> Params {
+    params.insert("api_key".to_string(), api_key.to_string());
+    let signature = api_sig(&params, api_secret);
+    params.insert("api_sig".to_string(), signature);
+    // Not signed, per the API docs.
+    params.insert("format".to_string(), "json".to_string());
+    params
+}
+
+/// MD5 of every name and value in name order, followed by the secret.
+fn api_sig(params: &Params, api_secret: &str) -> String {
+    let mut plain: String = params.iter().map(|(name, value)| format!("{}{}", name, value)).collect();
+    plain.push_str(api_secret);
+    format!("{:x}", md5::compute(plain))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listen(title: &str, started_at: u64) -> Listen {
+        Listen {
+            artist: "Artist".to_string(),
+            title: title.to_string(),
+            album: Some("Album".to_string()),
+            track_number: None,
+            duration_secs: Some(215),
+            started_at,
+        }
+    }
+
+    #[test]
+    fn signature_covers_sorted_params_and_the_secret_but_not_format() {
+        let signed = sign(method("auth.getToken"), "key", "secret");
+
+        // md5("api_keykeymethodauth.getTokensecret")
+        assert_eq!(signed["api_sig"], "b4705499705a550b07ca058a15bde9b0");
+        assert_eq!(signed["format"], "json");
+    }
+
+    #[test]
+    fn scrobbles_are_numbered_per_listen() {
+        let params = scrobble_params(&[listen("One", 100), listen("Two", 400)], "sk");
+
+        assert_eq!(params["method"], "track.scrobble");
+        assert_eq!(params["sk"], "sk");
+        assert_eq!(params["track[0]"], "One");
+        assert_eq!(params["timestamp[1]"], "400");
+        assert_eq!(params["album[1]"], "Album");
+        assert_eq!(params["duration[0]"], "215");
+        assert!(!params.contains_key("trackNumber[0]"), "unknown fields are left out");
+    }
+
+    #[test]
+    fn now_playing_has_no_timestamp() {
+        let params = now_playing_params(&listen("One", 100), "sk");
+
+        assert_eq!(params["method"], "track.updateNowPlaying");
+        assert_eq!(params["track"], "One");
+        assert!(params.keys().all(|name| !name.starts_with("timestamp")));
+    }
+}
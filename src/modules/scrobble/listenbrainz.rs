@@ -0,0 +1,136 @@
+//! ListenBrainz's submission API: JSON posts authorized with the user token.
+
+use super::{http_agent, Listen, Service};
+use crate::utils::APP_NAME;
+use anyhow::Result;
+use serde_json::{json, Map, Value};
+
+const API_URL: &str = "https://api.listenbrainz.org/1/submit-listens";
+
+/// ListenBrainz takes at most this many listens per request.
+const BATCH_MAX: usize = 100;
+
+/// Submits listens for the user the token belongs to.
+pub struct ListenBrainz {
+    token: String,
+    agent: ureq::Agent,
+}
+
+impl ListenBrainz {
+    pub fn new(token: &str) -> Self {
+        Self { token: token.to_string(), agent: http_agent() }
+    }
+
+    fn post(&self, payload: &Value) -> Result<()> {
+        let response = self
+            .agent
+            .post(API_URL)
+            .header("Authorization", format!("Token {}", self.token))
+            .header("Content-Type", "application/json")
+            .send(payload.to_string())?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.into_body().read_to_string().unwrap_or_default();
+            anyhow::bail!("ListenBrainz answered {}: {}", status, body.trim());
+        }
+        Ok(())
+    }
+}
+
+impl Service for ListenBrainz {
+    fn name(&self) -> &'static str {
+        "listenbrainz"
+    }
+
+    fn batch_max(&self) -> usize {
+        BATCH_MAX
+    }
+
+    fn now_playing(&self, listen: &Listen) -> Result<()> {
+        self.post(&payload("playing_now", std::slice::from_ref(listen)))
+    }
+
+    fn submit(&self, listens: &[Listen]) -> Result<()> {
+        let listen_type = if listens.len() == 1 { "single" } else { "import" };
+        self.post(&payload(listen_type, listens))
+    }
+}
+
+/// Request body; `playing_now` listens carry no `listened_at`.
+fn payload(listen_type: &str, listens: &[Listen]) -> Value {
+    let listens: Vec<Value> = listens
+        .iter()
+        .map(|listen| {
+            let mut entry = json!({ "track_metadata": track_metadata(listen) });
+            if listen_type != "playing_now" {
+                entry["listened_at"] = json!(listen.started_at);
+            }
+            entry
+        })
+        .collect();
+
+    json!({ "listen_type": listen_type, "payload": listens })
+}
+
+fn track_metadata(listen: &Listen) -> Value {
+    let mut info = Map::new();
+    info.insert("media_player".to_string(), json!(APP_NAME));
+    info.insert("submission_client".to_string(), json!(APP_NAME));
+    info.insert("submission_client_version".to_string(), json!(env!("CARGO_PKG_VERSION")));
+    if let Some(number) = listen.track_number {
+        info.insert("tracknumber".to_string(), json!(number));
+    }
+    if let Some(secs) = listen.duration_secs {
+        info.insert("duration_ms".to_string(), json!(secs * 1000));
+    }
+
+    let mut metadata = json!({
+        "artist_name": listen.artist,
+        "track_name": listen.title,
+        "additional_info": info,
+    });
+    if let Some(album) = &listen.album {
+        metadata["release_name"] = json!(album);
+    }
+    metadata
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listen(title: &str) -> Listen {
+        Listen {
+            artist: "Artist".to_string(),
+            title: title.to_string(),
+            album: None,
+            track_number: Some(3),
+            duration_secs: Some(200),
+            started_at: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn submitted_listens_carry_their_start_time_and_metadata() {
+        let body = payload("import", &[listen("One"), listen("Two")]);
+
+        assert_eq!(body["listen_type"], "import");
+        assert_eq!(body["payload"].as_array().unwrap().len(), 2);
+        let first = &body["payload"][0];
+        assert_eq!(first["listened_at"], 1_700_000_000);
+        assert_eq!(first["track_metadata"]["track_name"], "One");
+        assert_eq!(first["track_metadata"]["artist_name"], "Artist");
+        assert_eq!(first["track_metadata"]["additional_info"]["tracknumber"], 3);
+        assert_eq!(first["track_metadata"]["additional_info"]["duration_ms"], 200_000);
+        assert!(first["track_metadata"].get("release_name").is_none(), "unknown album is left out");
+    }
+
+    #[test]
+    fn playing_now_has_no_listened_at() {
+        let body = payload("playing_now", &[listen("One")]);
+
+        assert_eq!(body["listen_type"], "playing_now");
+        assert!(body["payload"][0].get("listened_at").is_none());
+    }
+}
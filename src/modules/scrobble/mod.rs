@@ -0,0 +1,338 @@
+//! Scrobbling: reporting what plays to Last.fm and ListenBrainz.
+//!
+//! Credentials live in `<config dir>/hextune/scrobble.toml`, away from the
+//! state files. Requests run on a worker thread so a slow network never stalls
+//! playback, and listens a service did not accept wait in `scrobble_queue.json`
+//! until the next listen or session retries them.
+
+pub mod lastfm;
+pub mod listenbrainz;
+mod queue;
+
+use crate::core::models::Song;
+use crate::core::traits::ScrobbleBackend;
+use crate::utils::APP_NAME;
+use anyhow::{Context, Result};
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use lastfm::{LastFm, LastFmClient};
+use listenbrainz::ListenBrainz;
+use queue::ScrobbleQueue;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// How long one request to a service may take before it counts as failed.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Tracks this short or shorter are never scrobbled, as Last.fm asks.
+const MIN_SCROBBLE_LENGTH: Duration = Duration::from_secs(30);
+
+/// Contents of `scrobble.toml`; a missing section turns that service off.
+#[derive(Debug, Default, Deserialize)]
+pub struct ScrobbleConfig {
+    pub lastfm: Option<LastFmConfig>,
+    pub listenbrainz: Option<ListenBrainzConfig>,
+}
+
+/// `[lastfm]`: the API account, plus the session `scrobble-auth` stores.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LastFmConfig {
+    pub api_key: String,
+    pub api_secret: String,
+    pub session_key: Option<String>,
+}
+
+/// `[listenbrainz]`: the user token from the ListenBrainz settings page.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ListenBrainzConfig {
+    pub token: String,
+}
+
+impl ScrobbleConfig {
+    /// Read `scrobble.toml`. A missing file means scrobbling is off; a malformed
+    /// one is reported on stderr and treated the same.
+    pub fn load(config_dir: &Path) -> Self {
+        let file_path = config_path(config_dir);
+
+        match fs::read_to_string(&file_path) {
+            Ok(toml_str) => toml::from_str(&toml_str).unwrap_or_else(|err| {
+                eprintln!("Warning: Failed to parse '{}': {err}. Scrobbling is off.", file_path.display());
+                Self::default()
+            }),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Self::default(),
+            Err(err) => {
+                eprintln!("Warning: Could not read '{}': {err}", file_path.display());
+                Self::default()
+            }
+        }
+    }
+
+    /// The services with complete credentials.
+    fn services(&self) -> Vec<Box<dyn Service>> {
+        let mut services: Vec<Box<dyn Service>> = Vec::new();
+
+        if let Some(config) = &self.lastfm {
+            match &config.session_key {
+                Some(session_key) => services.push(Box::new(LastFm::new(
+                    LastFmClient::new(&config.api_key, &config.api_secret),
+                    session_key,
+                ))),
+                None => eprintln!("Warning: Last.fm is not linked yet. Run '{} scrobble-auth'.", APP_NAME),
+            }
+        }
+        if let Some(config) = self.listenbrainz.as_ref().filter(|c| !c.token.is_empty()) {
+            services.push(Box::new(ListenBrainz::new(&config.token)));
+        }
+
+        services
+    }
+}
+
+/// `<config dir>/hextune/scrobble.toml`.
+pub fn config_path(config_dir: &Path) -> PathBuf {
+    config_dir.join(APP_NAME).join("scrobble.toml")
+}
+
+fn queue_path(config_dir: &Path) -> PathBuf {
+    config_dir.join(APP_NAME).join("scrobble_queue.json")
+}
+
+/// Store the Last.fm session key in `scrobble.toml`, keeping everything else in it.
+pub fn save_session_key(config_dir: &Path, session_key: &str) -> Result<()> {
+    let file_path = config_path(config_dir);
+    let mut table: toml::Table = match fs::read_to_string(&file_path) {
+        Ok(toml_str) => toml::from_str(&toml_str)
+            .with_context(|| format!("Failed to parse '{}'", file_path.display()))?,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => toml::Table::new(),
+        Err(err) => return Err(err).with_context(|| format!("Could not read '{}'", file_path.display())),
+    };
+
+    let lastfm = table
+        .entry("lastfm")
+        .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+        .as_table_mut()
+        .context("'lastfm' in scrobble.toml must be a table")?;
+    lastfm.insert("session_key".to_string(), toml::Value::String(session_key.to_string()));
+
+    fs::write(&file_path, toml::to_string(&table)?)
+        .with_context(|| format!("Could not write '{}'", file_path.display()))?;
+    // It holds credentials; keep them to the owner.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&file_path, fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(())
+}
+
+/// One listen, in the shape services need and the queue file stores.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Listen {
+    /// The song's first artist, which services match against their catalog.
+    pub artist: String,
+    pub title: String,
+    pub album: Option<String>,
+    pub track_number: Option<u32>,
+    pub duration_secs: Option<u64>,
+    /// When the song started, in seconds since the Unix epoch.
+    pub started_at: u64,
+}
+
+impl Listen {
+    /// `None` for songs without an artist, which services reject.
+    pub fn new(song: &Song, started_at: SystemTime) -> Option<Self> {
+        Some(Self {
+            artist: song.artists.first()?.clone(),
+            title: song.title.clone(),
+            album: song.album.clone(),
+            track_number: song.track_number,
+            duration_secs: song.duration.map(|d| d.as_secs()),
+            started_at: started_at.duration_since(SystemTime::UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+        })
+    }
+}
+
+/// A listening-history service, as the worker talks to it.
+trait Service: Send {
+    /// Key of this service's listens in the queue file.
+    fn name(&self) -> &'static str;
+
+    /// Most listens one `submit` may carry.
+    fn batch_max(&self) -> usize;
+
+    fn now_playing(&self, listen: &Listen) -> Result<()>;
+
+    fn submit(&self, listens: &[Listen]) -> Result<()>;
+}
+
+/// HTTP client shared by the services. Error statuses come back as responses,
+/// so their bodies can explain what went wrong.
+fn http_agent() -> ureq::Agent {
+    ureq::Agent::config_builder()
+        .timeout_global(Some(REQUEST_TIMEOUT))
+        .http_status_as_error(false)
+        .build()
+        .into()
+}
+
+enum Job {
+    NowPlaying(Listen),
+    Scrobble(Listen),
+}
+
+/// Sends listens to every configured service; see the module docs.
+pub struct Scrobbler {
+    jobs: Sender<Job>,
+    /// Path and start time of the song `now_playing` last saw.
+    started: Option<(PathBuf, SystemTime)>,
+}
+
+impl Scrobbler {
+    /// Start scrobbling to the services set up in `scrobble.toml`; `None` when there are none.
+    pub fn start(config_dir: &Path) -> Option<Self> {
+        let services = ScrobbleConfig::load(config_dir).services();
+        if services.is_empty() {
+            return None;
+        }
+
+        let (jobs, rx) = unbounded();
+        let queue_path = queue_path(config_dir);
+        std::thread::spawn(move || run_worker(&services, &queue_path, rx));
+
+        Some(Self { jobs, started: None })
+    }
+}
+
+impl ScrobbleBackend for Scrobbler {
+    fn now_playing(&mut self, song: &Song) {
+        let now = SystemTime::now();
+        self.started = Some((song.path.clone(), now));
+        if let Some(listen) = Listen::new(song, now) {
+            let _ = self.jobs.send(Job::NowPlaying(listen));
+        }
+    }
+
+    fn scrobble(&mut self, song: &Song) {
+        if song.duration.is_none_or(|duration| duration <= MIN_SCROBBLE_LENGTH) {
+            return;
+        }
+        let started_at = self
+            .started
+            .as_ref()
+            .filter(|(path, _)| *path == song.path)
+            .map_or_else(SystemTime::now, |(_, at)| *at);
+        if let Some(listen) = Listen::new(song, started_at) {
+            let _ = self.jobs.send(Job::Scrobble(listen));
+        }
+    }
+}
+
+/// Handle jobs until the `Scrobbler` is dropped, starting with what an earlier session left queued.
+fn run_worker(services: &[Box<dyn Service>], queue_path: &Path, jobs: Receiver<Job>) {
+    let mut queue = ScrobbleQueue::load(queue_path);
+    flush(&mut queue, services, queue_path);
+
+    for job in jobs {
+        match job {
+            Job::NowPlaying(listen) => {
+                for service in services {
+                    if let Err(e) = service.now_playing(&listen) {
+                        log::warn!("{} now playing update failed: {:#}", service.name(), e);
+                    }
+                }
+            }
+            Job::Scrobble(listen) => {
+                for service in services {
+                    queue.push(service.name(), listen.clone());
+                }
+                // Saved before sending, so quitting mid-request loses nothing.
+                save(&queue, queue_path);
+                flush(&mut queue, services, queue_path);
+            }
+        }
+    }
+}
+
+fn flush(queue: &mut ScrobbleQueue, services: &[Box<dyn Service>], queue_path: &Path) {
+    for service in services {
+        if let Err(e) = queue.flush(service.as_ref()) {
+            log::warn!(
+                "{} submission failed; {} listens stay queued: {:#}",
+                service.name(),
+                queue.len(service.name()),
+                e
+            );
+        }
+    }
+    save(queue, queue_path);
+}
+
+fn save(queue: &ScrobbleQueue, queue_path: &Path) {
+    if let Err(e) = queue.save(queue_path) {
+        log::warn!("Could not save the scrobble queue: {:#}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn listens_take_the_first_artist_and_skip_songs_without_one() {
+        let mut song = Song {
+            artists: vec!["Main".to_string(), "Guest".to_string()],
+            duration: Some(Duration::from_secs(200)),
+            ..Song::from_path(Path::new("/m/Track.mp3"))
+        };
+        let at = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        let listen = Listen::new(&song, at).unwrap();
+        assert_eq!((listen.artist.as_str(), listen.started_at), ("Main", 1_700_000_000));
+        assert_eq!(listen.duration_secs, Some(200));
+
+        song.artists.clear();
+        assert_eq!(Listen::new(&song, at), None);
+    }
+
+    #[test]
+    fn short_tracks_are_not_scrobbled() {
+        let (jobs, rx) = unbounded();
+        let mut scrobbler = Scrobbler { jobs, started: None };
+        let song = |secs| Song {
+            artists: vec!["Artist".to_string()],
+            duration: Some(Duration::from_secs(secs)),
+            ..Song::from_path(Path::new("/m/Track.mp3"))
+        };
+
+        scrobbler.scrobble(&song(30));
+        assert!(rx.try_recv().is_err());
+        scrobbler.scrobble(&song(31));
+        assert!(matches!(rx.try_recv(), Ok(Job::Scrobble(_))));
+    }
+
+    #[test]
+    fn config_sections_are_optional() {
+        let config: ScrobbleConfig = toml::from_str("[listenbrainz]\ntoken = \"abc\"\n").unwrap();
+        assert!(config.lastfm.is_none());
+        assert_eq!(config.services().len(), 1);
+
+        let config: ScrobbleConfig = toml::from_str("[lastfm]\napi_key = \"k\"\napi_secret = \"s\"\n").unwrap();
+        assert!(config.services().is_empty(), "Last.fm needs a session key first");
+    }
+
+    #[test]
+    fn session_key_is_added_without_dropping_other_settings() {
+        let dir = std::env::temp_dir().join(format!("hextune_scrobble_cfg_{}", std::process::id()));
+        fs::create_dir_all(dir.join(APP_NAME)).unwrap();
+        fs::write(config_path(&dir), "[lastfm]\napi_key = \"k\"\napi_secret = \"s\"\n\n[listenbrainz]\ntoken = \"t\"\n").unwrap();
+
+        save_session_key(&dir, "session").unwrap();
+        let config = ScrobbleConfig::load(&dir);
+        assert_eq!(config.lastfm.unwrap().session_key.as_deref(), Some("session"));
+        assert_eq!(config.listenbrainz.unwrap().token, "t");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,167 @@
+use super::{Listen, Service};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Listens kept per service at most; past it the oldest are dropped.
+const QUEUE_MAX: usize = 1000;
+
+/// Listens not yet accepted, by service name, so an outage or a quit mid-request loses none.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ScrobbleQueue {
+    pending: BTreeMap<String, Vec<Listen>>,
+}
+
+impl ScrobbleQueue {
+    /// Read the queue file; a missing or unreadable one starts an empty queue.
+    pub fn load(path: &Path) -> Self {
+        let Ok(json) = fs::read_to_string(path) else {
+            return Self::default();
+        };
+        serde_json::from_str(&json).unwrap_or_else(|e| {
+            log::warn!("Ignoring unreadable scrobble queue '{}': {}", path.display(), e);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Could not write '{}'", path.display()))
+    }
+
+    pub fn push(&mut self, service: &str, listen: Listen) {
+        let pending = self.pending.entry(service.to_string()).or_default();
+        pending.push(listen);
+        if pending.len() > QUEUE_MAX {
+            pending.drain(..pending.len() - QUEUE_MAX);
+        }
+    }
+
+    pub fn len(&self, service: &str) -> usize {
+        self.pending.get(service).map_or(0, Vec::len)
+    }
+
+    /// Submit `service`'s listens oldest first, in batches it accepts. Stops at
+    /// the first failure; that batch and everything after it stay queued.
+    pub fn flush(&mut self, service: &dyn Service) -> Result<()> {
+        let Some(pending) = self.pending.get_mut(service.name()) else {
+            return Ok(());
+        };
+
+        while !pending.is_empty() {
+            let batch = pending.len().min(service.batch_max());
+            service.submit(&pending[..batch])?;
+            pending.drain(..batch);
+        }
+        self.pending.remove(service.name());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Accepts `accept` batches, then fails every submission.
+    struct FlakyService {
+        accept: Mutex<usize>,
+        batches: Mutex<Vec<Vec<String>>>,
+    }
+
+    impl FlakyService {
+        fn new(accept: usize) -> Self {
+            Self { accept: Mutex::new(accept), batches: Mutex::new(Vec::new()) }
+        }
+    }
+
+    impl Service for FlakyService {
+        fn name(&self) -> &'static str {
+            "flaky"
+        }
+
+        fn batch_max(&self) -> usize {
+            2
+        }
+
+        fn now_playing(&self, _listen: &Listen) -> Result<()> {
+            Ok(())
+        }
+
+        fn submit(&self, listens: &[Listen]) -> Result<()> {
+            let mut accept = self.accept.lock().unwrap();
+            if *accept == 0 {
+                anyhow::bail!("offline");
+            }
+            *accept -= 1;
+            self.batches.lock().unwrap().push(listens.iter().map(|l| l.title.clone()).collect());
+            Ok(())
+        }
+    }
+
+    fn listen(title: &str) -> Listen {
+        Listen {
+            artist: "Artist".to_string(),
+            title: title.to_string(),
+            album: None,
+            track_number: None,
+            duration_secs: Some(180),
+            started_at: 1_700_000_000,
+        }
+    }
+
+    fn queue_of(titles: &[&str]) -> ScrobbleQueue {
+        let mut queue = ScrobbleQueue::default();
+        for title in titles {
+            queue.push("flaky", listen(title));
+        }
+        queue
+    }
+
+    #[test]
+    fn listens_are_sent_oldest_first_in_batches() {
+        let mut queue = queue_of(&["a", "b", "c"]);
+        let service = FlakyService::new(usize::MAX);
+
+        queue.flush(&service).unwrap();
+        assert_eq!(*service.batches.lock().unwrap(), [vec!["a", "b"], vec!["c"]]);
+        assert_eq!(queue, ScrobbleQueue::default());
+    }
+
+    #[test]
+    fn failed_batches_stay_queued_for_the_next_flush() {
+        let mut queue = queue_of(&["a", "b", "c"]);
+
+        assert!(queue.flush(&FlakyService::new(1)).is_err());
+        assert_eq!(queue.len("flaky"), 1, "the accepted batch is gone");
+
+        queue.push("flaky", listen("d"));
+        let service = FlakyService::new(usize::MAX);
+        queue.flush(&service).unwrap();
+        assert_eq!(*service.batches.lock().unwrap(), [vec!["c", "d"]]);
+    }
+
+    #[test]
+    fn queue_survives_a_restart_and_drops_the_oldest_past_the_limit() {
+        let path = std::env::temp_dir().join(format!("hextune_scrobble_queue_{}.json", std::process::id()));
+        let mut queue = ScrobbleQueue::default();
+        for i in 0..QUEUE_MAX + 2 {
+            queue.push("flaky", listen(&i.to_string()));
+        }
+        queue.save(&path).unwrap();
+
+        let loaded = ScrobbleQueue::load(&path);
+        assert_eq!(loaded, queue);
+        assert_eq!(loaded.len("flaky"), QUEUE_MAX);
+        assert_eq!(loaded.pending["flaky"][0].title, "2");
+
+        let _ = fs::remove_file(&path);
+        assert_eq!(ScrobbleQueue::load(&path), ScrobbleQueue::default());
+    }
+}
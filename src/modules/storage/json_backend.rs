@@ -2,43 +2,43 @@ use crate::core::traits::StorageBackend;
 use crate::application::state::AppState;
 use anyhow::{Context, Result};
 use std::fs;
-use std::path::PathBuf;
-use crate::utils::APP_NAME;
+use std::path::{Path, PathBuf};
+use super::{ConfigFile, LibraryFile, StateFile, StatePaths};
 
+/// Stores the state as JSON: settings in `config.json`, the library in
+/// `library.json` (see `StatePaths`).
 pub struct JsonStorageBackend {
-    file_path: PathBuf,
+    paths: StatePaths,
 }
 
 impl JsonStorageBackend {
     pub fn new() -> Result<Self> {
-        let mut path = dirs::config_dir().context("Could not find config directory")?;
-        path.push(&format!("{}", APP_NAME));
-
-        fs::create_dir_all(&path)?;
+        Ok(Self { paths: StatePaths::in_config_dir("json")? })
+    }
 
-        path.push("db.json");
-        Ok(Self { file_path: path })
+    /// Store the settings in `file_path` and the library beside it, creating
+    /// the directory if needed.
+    pub fn with_path(file_path: PathBuf) -> Result<Self> {
+        Ok(Self { paths: StatePaths::beside(file_path)? })
     }
-    
-    fn backup_path(&self) -> PathBuf {
-        self.file_path.with_extension("json.bak")
+
+    fn backup_path(path: &Path) -> PathBuf {
+        path.with_extension("json.bak")
     }
 
-    fn backup_corrupted_file(&self) -> Result<()> {
-        let backup = self.backup_path();
-        fs::copy(&self.file_path, &backup)
+    fn backup_corrupted_file(path: &Path) -> Result<()> {
+        fs::copy(path, Self::backup_path(path))
             .context("Failed to backup corrupted config file")?;
         Ok(())
     }
-}
 
-impl StorageBackend for JsonStorageBackend {
-    fn load(&self) -> Result<AppState> {
-        if !self.file_path.exists() {
+    /// Read one file as a full state; sections the file does not hold get defaults.
+    fn load_file(path: &Path) -> Result<AppState> {
+        if !path.exists() {
             return Ok(AppState::default());
         }
 
-        let content = fs::read_to_string(&self.file_path)
+        let content = fs::read_to_string(path)
             .context("Failed to read config file")?;
 
         // First attempt: full deserialization
@@ -46,7 +46,7 @@ impl StorageBackend for JsonStorageBackend {
             Ok(state) => Ok(state),
             Err(full_err) => {
                 // Second attempt: partial recovery using serde_json::Value
-                // This preserves any valid fields (like root_path, songs list)
+                // This preserves any valid fields (like root_paths, songs list)
                 // and fills in missing/new fields with defaults.
                 match serde_json::from_str::<serde_json::Value>(&content) {
                     Ok(raw_value) => {
@@ -78,13 +78,13 @@ impl StorageBackend for JsonStorageBackend {
                 }
 
                 // Final fallback: file is unrecoverable —> back it up and start fresh
-                match self.backup_corrupted_file() {
+                match Self::backup_corrupted_file(path) {
                     Ok(_) => {
                         eprintln!(
                             "Warning: Config file was corrupted and could not be recovered. \
                              A backup has been saved to '{}'. \
                              Starting with fresh defaults.",
-                            self.backup_path().display()
+                            Self::backup_path(path).display()
                         );
                     }
                     Err(backup_err) => {
@@ -100,17 +100,49 @@ impl StorageBackend for JsonStorageBackend {
             }
         }
     }
+}
+
+impl StorageBackend for JsonStorageBackend {
+    fn load(&self) -> Result<AppState> {
+        if let Some(legacy) = self.paths.pending_migration() {
+            let mut state = Self::load_file(legacy)?;
+            state.config.migrate_legacy_fields();
+            self.save(&state)?;
+            return Ok(state);
+        }
+
+        let mut state = Self::load_file(&self.paths.library)?;
+        state.config = Self::load_file(&self.paths.config)?.config;
+        state.config.migrate_legacy_fields();
+        state.library.prune_missing_positions();
+        Ok(state)
+    }
 
     fn save(&self, state: &AppState) -> Result<()> {
-        let content = serde_json::to_string_pretty(state)
-            .context("Failed to serialize application state")?;
-        fs::write(&self.file_path, content)
-            .context("Failed to write config file")?;
-        Ok(())
+        self.save_config(state)?;
+        self.save_library(state)
+    }
+
+    fn save_config(&self, state: &AppState) -> Result<()> {
+        let content = serde_json::to_string_pretty(&ConfigFile::of(state))
+            .context("Failed to serialize settings")?;
+        super::write_atomic(&self.paths.config, &content)
+    }
+
+    fn save_library(&self, state: &AppState) -> Result<()> {
+        let content = serde_json::to_string_pretty(&LibraryFile::of(state))
+            .context("Failed to serialize library")?;
+        super::write_atomic(&self.paths.library, &content)
+    }
+
+    fn files(&self) -> Vec<StateFile> {
+        self.paths.files(Self::backup_path)
     }
 }
 
-fn merge_json(base: serde_json::Value, patch: serde_json::Value) -> serde_json::Value {
+/// Overlay `patch` onto `base`, recursing into objects. Used to fill fields a
+/// stored file lacks with defaults; the TOML backend reuses it after converting.
+pub(super) fn merge_json(base: serde_json::Value, patch: serde_json::Value) -> serde_json::Value {
     match (base, patch) {
         (serde_json::Value::Object(mut base_map), serde_json::Value::Object(patch_map)) => {
             for (key, patch_val) in patch_map {
@@ -126,3 +158,171 @@ fn merge_json(base: serde_json::Value, patch: serde_json::Value) -> serde_json::
         (_base, patch) => patch,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::storage::temp_path;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("hextune_json_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn with_path_creates_missing_directories_and_round_trips() {
+        let dir = temp_dir("with_path");
+        let backend = JsonStorageBackend::with_path(dir.join("profiles/work/db.json")).unwrap();
+        let mut state = AppState::default();
+        state.config.root_paths = vec![PathBuf::from("/music/work")];
+
+        backend.save(&state).unwrap();
+
+        assert!(dir.join("profiles/work/db.json").exists());
+        assert_eq!(backend.load().unwrap().config.root_paths, state.config.root_paths);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn interrupted_save_leaves_the_previous_file_intact() {
+        let dir = temp_dir("atomic");
+        let path = dir.join("db.json");
+        let backend = JsonStorageBackend::with_path(path.clone()).unwrap();
+        let mut state = AppState::default();
+        state.config.root_paths = vec![PathBuf::from("/music")];
+        backend.save(&state).unwrap();
+        assert!(!temp_path(&path).exists(), "the temp file is renamed away");
+
+        // A process killed mid-save leaves a truncated temp file behind.
+        let saved = fs::read_to_string(&path).unwrap();
+        fs::write(temp_path(&path), &saved[..saved.len() / 2]).unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), saved);
+        assert_eq!(backend.load().unwrap().config.root_paths, state.config.root_paths);
+        assert!(!path.with_extension("json.bak").exists(), "nothing was treated as corrupt");
+
+        state.config.root_paths = vec![PathBuf::from("/music/next")];
+        backend.save(&state).unwrap();
+        assert_eq!(backend.load().unwrap().config.root_paths, state.config.root_paths);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    fn state_with_songs(titles: &[&str]) -> AppState {
+        let mut state = AppState::default();
+        state.config.volume_percent = 30;
+        state.library.songs = std::sync::Arc::new(
+            titles.iter().map(|t| crate::core::models::Song::from_path(Path::new(&format!("/music/{t}.mp3")))).collect(),
+        );
+        state
+    }
+
+    #[test]
+    fn combined_db_file_is_split_on_first_load() {
+        let dir = temp_dir("migrate");
+        fs::create_dir_all(&dir).unwrap();
+        let state = state_with_songs(&["a", "b"]);
+        fs::write(dir.join("db.json"), serde_json::to_string(&state).unwrap()).unwrap();
+        let backend = JsonStorageBackend { paths: StatePaths::in_dir(dir.clone(), "json").unwrap() };
+
+        let loaded = backend.load().unwrap();
+
+        assert_eq!(loaded.config.volume_percent, 30);
+        assert_eq!(loaded.library.songs.len(), 2);
+        assert!(!fs::read_to_string(dir.join("config.json")).unwrap().contains("songs"));
+        assert!(dir.join("library.json").exists());
+
+        // Later loads read the split files; the old file is no longer consulted.
+        fs::write(dir.join("db.json"), "{}").unwrap();
+        assert_eq!(backend.load().unwrap().library.songs.len(), 2);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn db_file_from_the_first_release_still_loads() {
+        let dir = temp_dir("first_release");
+        fs::create_dir_all(&dir).unwrap();
+        // Combined file, amplitude volume, and songs with only the original fields.
+        fs::write(
+            dir.join("db.json"),
+            r#"{
+                "config": { "root_path": "/music", "volume": 0.5, "shuffle": true, "repeat": "All" },
+                "library": {
+                    "songs": [{
+                        "path": "/music/a.mp3",
+                        "title": "A",
+                        "artists": ["Queen"],
+                        "album": "Jazz",
+                        "track_number": 3,
+                        "duration": { "secs": 180, "nanos": 0 },
+                        "search_key": "a queen jazz"
+                    }],
+                    "active_sort": null
+                },
+                "playback": {}
+            }"#,
+        )
+        .unwrap();
+        let backend = JsonStorageBackend { paths: StatePaths::in_dir(dir.clone(), "json").unwrap() };
+
+        let loaded = backend.load().unwrap();
+
+        assert_eq!(loaded.config.root_paths, [PathBuf::from("/music")]);
+        assert_eq!(loaded.config.volume_percent, crate::utils::amplitude_to_volume(0.5));
+        assert!(loaded.config.shuffle);
+        let song = &loaded.library.songs[0];
+        assert_eq!((song.title.as_str(), song.track_number, song.order), ("A", Some(3), 0));
+        assert_eq!(song.duration, Some(std::time::Duration::from_secs(180)));
+        assert_eq!((song.genre.as_deref(), song.bitrate, song.channels), (None, None, None));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn saving_config_leaves_the_library_file_alone() {
+        let dir = temp_dir("config_only");
+        let backend = JsonStorageBackend { paths: StatePaths::in_dir(dir.clone(), "json").unwrap() };
+        backend.save(&state_with_songs(&["a"])).unwrap();
+        let library = fs::read_to_string(dir.join("library.json")).unwrap();
+
+        let mut state = state_with_songs(&["a", "b", "c"]);
+        state.config.volume_percent = 90;
+        backend.save_config(&state).unwrap();
+
+        assert_eq!(fs::read_to_string(dir.join("library.json")).unwrap(), library);
+        let loaded = backend.load().unwrap();
+        assert_eq!(loaded.config.volume_percent, 90);
+        assert_eq!(loaded.library.songs.len(), 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn every_volume_percent_survives_save_and_load() {
+        let dir = temp_dir("volume_percent");
+        let backend = JsonStorageBackend { paths: StatePaths::in_dir(dir.clone(), "json").unwrap() };
+        let mut state = AppState::default();
+
+        for percent in 0..=crate::utils::VOLUME_MAX {
+            state.config.volume_percent = percent;
+            backend.save_config(&state).unwrap();
+            assert_eq!(backend.load().unwrap().config.volume_percent, percent);
+        }
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn amplitude_volume_from_older_versions_is_converted_once() {
+        let dir = temp_dir("legacy_volume");
+        fs::create_dir_all(&dir).unwrap();
+        let amplitude = crate::utils::volume_percent_to_amplitude(35);
+        fs::write(dir.join("config.json"), format!("{{\"config\": {{\"volume\": {amplitude}}}}}")).unwrap();
+        let backend = JsonStorageBackend { paths: StatePaths::in_dir(dir.clone(), "json").unwrap() };
+
+        let loaded = backend.load().unwrap();
+        assert_eq!(loaded.config.volume_percent, 35);
+
+        backend.save_config(&loaded).unwrap();
+        let saved = fs::read_to_string(dir.join("config.json")).unwrap();
+        assert!(saved.contains("\"volume_percent\": 35") && !saved.contains("\"volume\""));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
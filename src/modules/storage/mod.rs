@@ -1 +1,282 @@
 pub mod json_backend;
+pub mod toml_backend;
+
+use crate::application::state::{AppState, ConfigState, HistoryState, LibraryState, PlaybackState};
+use crate::core::traits::StorageBackend;
+use crate::utils::APP_NAME;
+use anyhow::Result;
+use json_backend::JsonStorageBackend;
+use anyhow::Context;
+use serde::Serialize;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use toml_backend::TomlStorageBackend;
+
+/// Environment variable choosing the state file format when `--config-format` is not given.
+pub const CONFIG_FORMAT_ENV: &str = "HEXTUNE_CONFIG_FORMAT";
+
+/// Environment variable pointing at the state file when `--config` is not given.
+pub const CONFIG_PATH_ENV: &str = "HEXTUNE_CONFIG";
+
+/// On-disk format of the state files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StorageFormat {
+    /// `config.json` and `library.json`, the original format
+    #[default]
+    Json,
+    /// `config.toml` and `library.toml`, easier to edit by hand
+    Toml,
+}
+
+impl StorageFormat {
+    fn parse_env(value: &str) -> Self {
+        match value.to_ascii_lowercase().as_str() {
+            "json" => Self::Json,
+            "toml" => Self::Toml,
+            _ => {
+                eprintln!("Warning: Unknown {}='{}'. Using json.", CONFIG_FORMAT_ENV, value);
+                Self::Json
+            }
+        }
+    }
+}
+
+/// Where and how the state is stored, fixed once per process.
+#[derive(Debug, Clone, PartialEq)]
+struct StorageOptions {
+    format: StorageFormat,
+    /// Settings file to use instead of `<config dir>/hextune/config.<ext>`.
+    path: Option<PathBuf>,
+}
+
+impl StorageOptions {
+    /// Flags win over environment variables. Without either format setting,
+    /// a custom path ending in `.toml` selects TOML.
+    fn resolve(
+        format_flag: Option<StorageFormat>,
+        path_flag: Option<PathBuf>,
+        format_env: Option<String>,
+        path_env: Option<String>,
+    ) -> Self {
+        let path = path_flag.or_else(|| path_env.filter(|p| !p.is_empty()).map(PathBuf::from));
+        let format = format_flag
+            .or_else(|| format_env.as_deref().map(StorageFormat::parse_env))
+            .or_else(|| {
+                path.as_ref()
+                    .filter(|p| p.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("toml")))
+                    .map(|_| StorageFormat::Toml)
+            })
+            .unwrap_or_default();
+
+        Self { format, path }
+    }
+
+    fn from_env(format_flag: Option<StorageFormat>, path_flag: Option<PathBuf>) -> Self {
+        Self::resolve(
+            format_flag,
+            path_flag,
+            std::env::var(CONFIG_FORMAT_ENV).ok(),
+            std::env::var(CONFIG_PATH_ENV).ok(),
+        )
+    }
+}
+
+static OPTIONS: OnceLock<StorageOptions> = OnceLock::new();
+
+/// Record the format and state file picked on the command line; settings left
+/// out fall back to `CONFIG_FORMAT_ENV` and `CONFIG_PATH_ENV`. Only the first
+/// call has an effect.
+pub fn configure(format: Option<StorageFormat>, path: Option<PathBuf>) {
+    OPTIONS.get_or_init(|| StorageOptions::from_env(format, path));
+}
+
+/// Open the storage backend for the configured format and location.
+pub fn open() -> Result<Box<dyn StorageBackend>> {
+    let options = OPTIONS.get_or_init(|| StorageOptions::from_env(None, None));
+
+    Ok(match (options.format, &options.path) {
+        (StorageFormat::Json, None) => Box::new(JsonStorageBackend::new()?),
+        (StorageFormat::Json, Some(path)) => Box::new(JsonStorageBackend::with_path(path.clone())?),
+        (StorageFormat::Toml, None) => Box::new(TomlStorageBackend::new()?),
+        (StorageFormat::Toml, Some(path)) => Box::new(TomlStorageBackend::with_path(path.clone())?),
+    })
+}
+
+/// Where `--verbose` writes its log: `hextune.log` beside the settings file.
+pub fn log_path() -> Result<PathBuf> {
+    let options = OPTIONS.get_or_init(|| StorageOptions::from_env(None, None));
+    let name = format!("{}.log", APP_NAME);
+
+    match &options.path {
+        Some(path) => Ok(path.with_file_name(name)),
+        None => Ok(dirs::config_dir().context("Could not find config directory")?.join(APP_NAME).join(name)),
+    }
+}
+
+/// Where a backend keeps the state. Settings and the library live in separate
+/// files, so changing the volume never rewrites the song list.
+#[derive(Debug, Clone)]
+pub(super) struct StatePaths {
+    /// `ConfigState` only.
+    pub config: PathBuf,
+    /// Songs, play queue and play history.
+    pub library: PathBuf,
+    /// Combined file written by older versions, split on first load.
+    pub legacy: Option<PathBuf>,
+}
+
+impl StatePaths {
+    /// `config.<ext>` and `library.<ext>` in `<config dir>/hextune`.
+    pub fn in_config_dir(ext: &str) -> Result<Self> {
+        let dir = dirs::config_dir().context("Could not find config directory")?;
+        Self::in_dir(dir.join(APP_NAME), ext)
+    }
+
+    /// `config.<ext>` and `library.<ext>` in `dir`, migrating from `db.<ext>`.
+    pub fn in_dir(dir: PathBuf, ext: &str) -> Result<Self> {
+        Self {
+            config: dir.join(format!("config.{ext}")),
+            library: dir.join(format!("library.{ext}")),
+            legacy: Some(dir.join(format!("db.{ext}"))),
+        }
+        .with_dirs()
+    }
+
+    /// Settings in `file` and the library beside it as `<stem>.library.<ext>`.
+    /// A `file` that still holds the whole state is split on first load.
+    pub fn beside(file: PathBuf) -> Result<Self> {
+        let mut library_name = file.file_stem().unwrap_or_default().to_os_string();
+        library_name.push(".library");
+        if let Some(ext) = file.extension() {
+            library_name.push(".");
+            library_name.push(ext);
+        }
+
+        Self {
+            library: file.with_file_name(library_name),
+            legacy: Some(file.clone()),
+            config: file,
+        }
+        .with_dirs()
+    }
+
+    fn with_dirs(self) -> Result<Self> {
+        if let Some(dir) = self.config.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create config directory '{}'", dir.display()))?;
+        }
+        Ok(self)
+    }
+
+    /// The settings and library files, with where `backup` puts a corrupted copy.
+    pub fn files(&self, backup: fn(&Path) -> PathBuf) -> Vec<StateFile> {
+        [("Settings file", &self.config), ("Library file", &self.library)]
+            .into_iter()
+            .map(|(label, path)| StateFile { label, path: path.clone(), backup: backup(path) })
+            .collect()
+    }
+
+    /// The combined file to split, if there is one and no library file yet.
+    pub fn pending_migration(&self) -> Option<&Path> {
+        self.legacy
+            .as_deref()
+            .filter(|legacy| legacy.exists() && !self.library.exists())
+    }
+}
+
+/// A file holding part of the state, as reported by `doctor`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateFile {
+    pub label: &'static str,
+    pub path: PathBuf,
+    /// Where an unreadable copy of `path` is saved before starting fresh.
+    pub backup: PathBuf,
+}
+
+/// Contents of the settings file.
+#[derive(Serialize)]
+pub(super) struct ConfigFile<'a> {
+    config: &'a ConfigState,
+}
+
+/// Contents of the library file.
+#[derive(Serialize)]
+pub(super) struct LibraryFile<'a> {
+    library: &'a LibraryState,
+    playback: &'a PlaybackState,
+    history: &'a HistoryState,
+}
+
+impl<'a> ConfigFile<'a> {
+    pub fn of(state: &'a AppState) -> Self {
+        Self { config: &state.config }
+    }
+}
+
+impl<'a> LibraryFile<'a> {
+    pub fn of(state: &'a AppState) -> Self {
+        Self { library: &state.library, playback: &state.playback, history: &state.history }
+    }
+}
+
+/// Sibling of `path` that `write_atomic` writes to before renaming.
+pub(super) fn temp_path(path: &Path) -> PathBuf {
+    let mut name = std::ffi::OsString::from(".");
+    name.push(path.file_name().unwrap_or_default());
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+/// Replace `path` with `contents` so that a crash leaves either the old or the
+/// new file, never a partial one: write a temp file in the same directory,
+/// flush it to disk, then rename it over the target.
+pub(super) fn write_atomic(path: &Path, contents: &str) -> Result<()> {
+    let tmp = temp_path(path);
+
+    let result = (|| -> Result<()> {
+        let mut file = fs::File::create(&tmp).context("Failed to create temporary config file")?;
+        file.write_all(contents.as_bytes()).context("Failed to write config file")?;
+        file.sync_all().context("Failed to flush config file")?;
+        fs::rename(&tmp, path).context("Failed to replace config file")
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_win_over_environment() {
+        let options = StorageOptions::resolve(
+            Some(StorageFormat::Json),
+            Some(PathBuf::from("/flag/db.toml")),
+            Some("toml".to_owned()),
+            Some("/env/db.json".to_owned()),
+        );
+
+        assert_eq!(options.format, StorageFormat::Json, "an explicit format beats the extension");
+        assert_eq!(options.path, Some(PathBuf::from("/flag/db.toml")));
+    }
+
+    #[test]
+    fn environment_path_is_used_and_toml_extension_picks_the_format() {
+        let options = StorageOptions::resolve(None, None, None, Some("/profiles/work.TOML".to_owned()));
+
+        assert_eq!(options.format, StorageFormat::Toml);
+        assert_eq!(options.path, Some(PathBuf::from("/profiles/work.TOML")));
+    }
+
+    #[test]
+    fn defaults_to_json_in_the_config_dir() {
+        let options = StorageOptions::resolve(None, None, None, Some(String::new()));
+
+        assert_eq!(options, StorageOptions { format: StorageFormat::Json, path: None });
+    }
+}
@@ -0,0 +1,224 @@
+use crate::core::traits::StorageBackend;
+use crate::application::state::AppState;
+use crate::modules::storage::json_backend::merge_json;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use super::{ConfigFile, LibraryFile, StateFile, StatePaths};
+
+/// Stores the state as hand-editable TOML: settings in `config.toml`, the
+/// library in `library.toml` (see `StatePaths`).
+///
+/// Behaves like `JsonStorageBackend`: files from older versions are merged
+/// into the defaults, and an unreadable file is backed up before starting fresh.
+pub struct TomlStorageBackend {
+    paths: StatePaths,
+}
+
+impl TomlStorageBackend {
+    pub fn new() -> Result<Self> {
+        Ok(Self { paths: StatePaths::in_config_dir("toml")? })
+    }
+
+    /// Store the settings in `file_path` and the library beside it, creating
+    /// the directory if needed.
+    pub fn with_path(file_path: PathBuf) -> Result<Self> {
+        Ok(Self { paths: StatePaths::beside(file_path)? })
+    }
+
+    fn backup_path(path: &Path) -> PathBuf {
+        path.with_extension("toml.bak")
+    }
+
+    fn backup_corrupted_file(path: &Path) -> Result<()> {
+        fs::copy(path, Self::backup_path(path))
+            .context("Failed to backup corrupted config file")?;
+        Ok(())
+    }
+
+    /// Parse as a loose TOML document and lay it over the defaults, so fields
+    /// added since the file was written get default values.
+    fn recover(content: &str) -> Option<AppState> {
+        let raw: toml::Value = toml::from_str(content).ok()?;
+        let raw_json = serde_json::to_value(raw).ok()?;
+        let default_json = serde_json::to_value(AppState::default()).ok()?;
+
+        serde_json::from_value(merge_json(default_json, raw_json)).ok()
+    }
+
+    /// Read one file as a full state; sections the file does not hold get defaults.
+    fn load_file(path: &Path) -> Result<AppState> {
+        if !path.exists() {
+            return Ok(AppState::default());
+        }
+
+        let content = fs::read_to_string(path)
+            .context("Failed to read config file")?;
+
+        let full_err = match toml::from_str::<AppState>(&content) {
+            Ok(state) => return Ok(state),
+            Err(err) => err,
+        };
+
+        if let Some(recovered_state) = Self::recover(&content) {
+            eprintln!(
+                "Warning: Config schema has changed ({}). \
+                 Some settings were reset to defaults.",
+                full_err.message()
+            );
+            return Ok(recovered_state);
+        }
+
+        // File is unrecoverable —> back it up and start fresh
+        match Self::backup_corrupted_file(path) {
+            Ok(_) => eprintln!(
+                "Warning: Config file was corrupted and could not be recovered. \
+                 A backup has been saved to '{}'. \
+                 Starting with fresh defaults.",
+                Self::backup_path(path).display()
+            ),
+            Err(backup_err) => eprintln!(
+                "Warning: Config file was corrupted and the backup also failed ({}). \
+                 Starting with fresh defaults.",
+                backup_err
+            ),
+        }
+
+        Ok(AppState::default())
+    }
+}
+
+impl StorageBackend for TomlStorageBackend {
+    fn load(&self) -> Result<AppState> {
+        if let Some(legacy) = self.paths.pending_migration() {
+            let mut state = Self::load_file(legacy)?;
+            state.config.migrate_legacy_fields();
+            self.save(&state)?;
+            return Ok(state);
+        }
+
+        let mut state = Self::load_file(&self.paths.library)?;
+        state.config = Self::load_file(&self.paths.config)?.config;
+        state.config.migrate_legacy_fields();
+        state.library.prune_missing_positions();
+        Ok(state)
+    }
+
+    fn save(&self, state: &AppState) -> Result<()> {
+        self.save_config(state)?;
+        self.save_library(state)
+    }
+
+    fn save_config(&self, state: &AppState) -> Result<()> {
+        let content = toml::to_string_pretty(&ConfigFile::of(state))
+            .context("Failed to serialize settings")?;
+        super::write_atomic(&self.paths.config, &content)
+    }
+
+    fn save_library(&self, state: &AppState) -> Result<()> {
+        let content = toml::to_string_pretty(&LibraryFile::of(state))
+            .context("Failed to serialize library")?;
+        super::write_atomic(&self.paths.library, &content)
+    }
+
+    fn files(&self) -> Vec<StateFile> {
+        self.paths.files(Self::backup_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::models::{PlayRecord, RepeatMode, Song};
+    use crate::modules::library::sorter::SortField;
+    use std::collections::{BTreeMap, VecDeque};
+    use std::sync::Arc;
+    use std::time::{Duration, SystemTime};
+
+    fn temp_backend(name: &str) -> TomlStorageBackend {
+        let dir = std::env::temp_dir().join(format!("hextune_toml_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        TomlStorageBackend::with_path(dir.join("db.toml")).unwrap()
+    }
+
+    fn full_state() -> AppState {
+        let song = Song {
+            path: PathBuf::from("/music/Queen/Bohemian Rhapsody.flac"),
+            title: "Bohemian \"Rhapsody\"".to_owned(),
+            artists: vec!["Queen".to_owned()],
+            album: Some("A Night at the Opera".to_owned()),
+            track_number: Some(11),
+            duration: Some(Duration::from_millis(354_320)),
+            genre: Some("Rock".to_owned()),
+            year: Some(1975),
+            bitrate: Some(1_011),
+            sample_rate: Some(44_100),
+            channels: Some(2),
+            track_gain_db: Some(-7.5),
+            search_key: "bohemian rhapsody queen".to_owned(),
+            scanned_mtime: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+            order: 3,
+            rating: None,
+            favorite: false,
+        };
+        let untagged = Song::from_path(std::path::Path::new("/music/untitled.mp3"));
+
+        let mut state = AppState::default();
+        state.config.root_paths = vec![PathBuf::from("/music")];
+        state.config.volume_percent = 25;
+        state.config.muted_volume = Some(0.5);
+        state.config.shuffle = true;
+        state.config.repeat = RepeatMode::All;
+        state.config.theme = Some("solarized".to_owned());
+        state.config.scan_exclude = vec!["samples/**".to_owned()];
+        state.library.songs = Arc::new(vec![song.clone(), untagged]);
+        state.library.active_sort = Some(SortField::Artist);
+        state.playback.queue = VecDeque::from([1, 0]);
+        state.history.plays = Arc::new(BTreeMap::from([(
+            song.path.clone(),
+            PlayRecord { play_count: 4, last_played: SystemTime::UNIX_EPOCH + Duration::from_secs(42) },
+        )]));
+        state.history.recent = Arc::new(VecDeque::from([(
+            song.path.clone(),
+            SystemTime::UNIX_EPOCH + Duration::from_secs(42),
+        )]));
+        state
+    }
+
+    #[test]
+    fn full_state_round_trips_through_toml() {
+        let backend = temp_backend("round_trip");
+        let state = full_state();
+
+        backend.save(&state).unwrap();
+        let loaded = backend.load().unwrap();
+
+        assert_eq!(serde_json::to_value(&loaded).unwrap(), serde_json::to_value(&state).unwrap());
+        let _ = fs::remove_dir_all(backend.paths.config.parent().unwrap());
+    }
+
+    #[test]
+    fn older_file_keeps_its_fields_and_gets_defaults_for_the_rest() {
+        let backend = temp_backend("recover");
+        // `library.songs` is required, so full deserialization fails.
+        fs::write(&backend.paths.config, "[config]\nroot_path = \"/music\"\n\n[library]\n").unwrap();
+
+        let loaded = backend.load().unwrap();
+
+        assert_eq!(loaded.config.root_paths, [PathBuf::from("/music")]);
+        assert!(!TomlStorageBackend::backup_path(&backend.paths.config).exists());
+        let _ = fs::remove_dir_all(backend.paths.config.parent().unwrap());
+    }
+
+    #[test]
+    fn unparseable_file_is_backed_up() {
+        let backend = temp_backend("corrupt");
+        fs::write(&backend.paths.config, "[config\nthis is not toml").unwrap();
+
+        let loaded = backend.load().unwrap();
+
+        assert!(loaded.config.root_paths.is_empty());
+        assert!(TomlStorageBackend::backup_path(&backend.paths.config).exists());
+        let _ = fs::remove_dir_all(backend.paths.config.parent().unwrap());
+    }
+}
@@ -1,4 +1,5 @@
 pub mod terminal;
 pub mod tui;
 mod key_hints;
-mod progress_formatter;
+mod terminal_guard;
+pub mod progress_formatter;
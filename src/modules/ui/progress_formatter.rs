@@ -1,5 +1,11 @@
+use crate::modules::playback::playback_progress::PlaybackProgress;
+use crate::utils::PULSE_STEP_MS;
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
+/// Shown in place of the total time when a song's length is unknown.
+pub const UNKNOWN_TOTAL: &str = "--:--";
+
 /// Formats a duration as MM:SS or HH:MM:SS
 pub fn format_duration(duration: Duration) -> String {
     let total_secs = duration.as_secs();
@@ -12,4 +18,120 @@ pub fn format_duration(duration: Duration) -> String {
     } else {
         format!("{:02}:{:02}", minutes, seconds)
     }
-}
\ No newline at end of file
+}
+/// Text drawn inside the TUI progress bar.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProgressLabel {
+    /// Just the bar; the times are already shown beside it
+    #[default]
+    None,
+    /// `01:05 / 03:10`
+    Times,
+    /// `34%`
+    Compact,
+    /// `34% (01:05/03:10)`
+    Percentage,
+}
+
+impl ProgressLabel {
+    const ORDER: [Self; 4] = [Self::None, Self::Times, Self::Compact, Self::Percentage];
+
+    /// Cycle to the next style, wrapping around.
+    pub fn cycle(self) -> Self {
+        let i = Self::ORDER.iter().position(|l| *l == self).unwrap_or(0);
+        Self::ORDER[(i + 1) % Self::ORDER.len()]
+    }
+
+    /// Cycle to the previous style, wrapping around.
+    pub fn cycle_back(self) -> Self {
+        let i = Self::ORDER.iter().position(|l| *l == self).unwrap_or(0);
+        Self::ORDER[(i + Self::ORDER.len() - 1) % Self::ORDER.len()]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Times => "times",
+            Self::Compact => "compact",
+            Self::Percentage => "percentage",
+        }
+    }
+
+    /// Label for `progress`; empty for `None` or when the length is unknown.
+    pub fn format(self, progress: &PlaybackProgress) -> String {
+        let Some(total) = progress.total() else {
+            return String::new();
+        };
+        let percent = (progress.ratio() * 100.0).floor() as u32;
+        let elapsed = format_duration(progress.elapsed());
+        let total = format_duration(total);
+
+        match self {
+            Self::None => String::new(),
+            Self::Times => format!("{} / {}", elapsed, total),
+            Self::Compact => format!("{}%", percent),
+            Self::Percentage => format!("{}% ({}/{})", percent, elapsed, total),
+        }
+    }
+}
+
+/// Position of the moving segment drawn instead of a filled bar when the
+/// song's length is unknown: `(start, len)` within a bar `width` cells wide.
+///
+/// The segment bounces between the ends as `elapsed` grows.
+pub fn pulse_segment(width: usize, elapsed: Duration) -> (usize, usize) {
+    let len = (width / 6).max(1).min(width);
+    let travel = width - len;
+    if travel == 0 {
+        return (0, len);
+    }
+
+    let step = (elapsed.as_millis() / PULSE_STEP_MS) as usize % (2 * travel);
+    let start = if step <= travel { step } else { 2 * travel - step };
+    (start, len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at_step(step: u128) -> Duration {
+        Duration::from_millis((step * PULSE_STEP_MS) as u64)
+    }
+
+    #[test]
+    fn pulse_bounces_between_the_ends() {
+        // width 12 → segment of 2 cells with 10 cells of travel
+        assert_eq!(pulse_segment(12, at_step(0)), (0, 2));
+        assert_eq!(pulse_segment(12, at_step(10)), (10, 2));
+        assert_eq!(pulse_segment(12, at_step(13)), (7, 2));
+        assert_eq!(pulse_segment(12, at_step(20)), (0, 2));
+    }
+
+    #[test]
+    fn progress_labels_format_each_style() {
+        let progress = PlaybackProgress::new(Duration::from_secs(34), Duration::from_secs(190)).unwrap();
+
+        assert_eq!(ProgressLabel::None.format(&progress), "");
+        assert_eq!(ProgressLabel::Times.format(&progress), "00:34 / 03:10");
+        assert_eq!(ProgressLabel::Compact.format(&progress), "17%");
+        assert_eq!(ProgressLabel::Percentage.format(&progress), "17% (00:34/03:10)");
+
+        let unknown = PlaybackProgress::unknown_total(Duration::from_secs(34));
+        assert_eq!(ProgressLabel::Percentage.format(&unknown), "", "no label without a length");
+    }
+
+    #[test]
+    fn progress_label_cycles_both_ways() {
+        assert_eq!(ProgressLabel::None.cycle(), ProgressLabel::Times);
+        assert_eq!(ProgressLabel::Percentage.cycle(), ProgressLabel::None);
+        assert_eq!(ProgressLabel::None.cycle_back(), ProgressLabel::Percentage);
+    }
+
+    #[test]
+    fn pulse_fits_tiny_bars() {
+        assert_eq!(pulse_segment(1, at_step(7)), (0, 1));
+        assert_eq!(pulse_segment(0, at_step(7)), (0, 0));
+    }
+}
@@ -1 +1,2 @@
 pub mod renderer;
+mod song_table;
@@ -3,18 +3,21 @@ use crate::core::events::UiEvent;
 use crate::core::models::Song;
 use crate::core::traits::UiRenderer;
 use crate::modules::playback::playback_progress::PlaybackProgress;
-use crate::modules::ui::progress_formatter::format_duration;
-use crate::modules::input::{map_key, InputAction, InputMode, KeyConfig};
+use crate::modules::ui::progress_formatter::{format_duration, pulse_segment, UNKNOWN_TOTAL};
+use crate::modules::input::{drain_pending, map_key, wait_for_input, InputAction, InputMode, KeyConfig};
 use crate::modules::ui::key_hints;
-use crate::utils::PROGRESS_BAR_WIDTH;
+use crate::modules::ui::terminal::song_table::song_table;
+use crate::modules::ui::terminal_guard::TerminalGuard;
+use crate::utils::{PROGRESS_BAR_WIDTH, SEEK_LONG_STEP_SECS, SEEK_STEP_SECS};
 use anyhow::Result;
 use crossterm::cursor::MoveTo;
-use crossterm::{event::{self, Event, KeyCode}, queue, terminal::{self, Clear, ClearType}};
-use std::io::{stdout, Write};
+use crossterm::{event::{Event, KeyCode}, queue, terminal::{self, Clear, ClearType}};
+use std::io::{stdout, IsTerminal, Write};
+use std::ops::Range;
 use std::time::Duration;
 
 pub struct TerminalRenderer {
-    initialized: bool,
+    terminal_guard: Option<TerminalGuard>,
     key_config: KeyConfig,
     key_config_synced: bool,
     shuffle_enabled: bool,
@@ -26,7 +29,7 @@ pub struct TerminalRenderer {
 impl TerminalRenderer {
     pub fn new() -> Self {
         Self {
-            initialized: false,
+            terminal_guard: None,
             key_config: KeyConfig::default(),
             key_config_synced: false,
             shuffle_enabled: false,
@@ -44,17 +47,35 @@ impl TerminalRenderer {
         eprintln!("Error: {}", message);
     }
 
-    pub fn print_song_list(&self, songs: &[Song]) {
-        let total = songs.len();
-        for (index, song) in songs.iter().enumerate() {
-            println!("[{}/{}] {}", index + 1, total, song);
-        }
+    /// Ask a yes/no question on stdin; anything but `y`/`yes`, including end of input, is no.
+    pub fn confirm(&self, question: &str) -> Result<bool> {
+        print!("{} [y/N] ", question);
+        stdout().flush()?;
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
     }
 
-    pub fn print_song_list_refs(&self, songs: &[&Song]) {
-        let total = songs.len();
-        for (index, song) in songs.iter().enumerate() {
-            println!("[{}/{}] {}", index + 1, total, song);
+    /// The library songs in `range`: aligned, colored columns on a terminal;
+    /// with `plain`, or when stdout is piped, one `[i/n] Artist - Title [m:ss]`
+    /// line per song.
+    pub fn print_song_list(&self, songs: &[Song], range: Range<usize>, plain: bool) {
+        if plain || !stdout().is_terminal() {
+            let total = songs.len();
+            for (index, song) in songs.iter().enumerate().take(range.end).skip(range.start) {
+                println!("[{}/{}] {}", index + 1, total, song);
+            }
+            return;
+        }
+
+        // Some pseudo-terminals report zero columns.
+        let width = match terminal::size() {
+            Ok((columns, _)) if columns > 0 => usize::from(columns),
+            _ => 80,
+        };
+        let color = std::env::var_os("NO_COLOR").is_none();
+        for row in song_table(songs, range, width, color) {
+            println!("{}", row);
         }
     }
 
@@ -69,28 +90,51 @@ impl TerminalRenderer {
         }
     }
 
+    pub fn print_song_info(&self, index: usize, song: &Song) {
+        fn or_unknown<T: ToString>(value: Option<T>) -> String {
+            value.map(|v| v.to_string()).unwrap_or_else(|| "—".to_string())
+        }
+
+        println!("─── [{}] {} ───", index, song.title);
+        println!("  Path         {}", song.path.display());
+        println!("  Title        {}", song.title);
+        println!("  Artist       {}", song.format_artists());
+        println!("  Album        {}", or_unknown(song.album.as_deref()));
+        println!("  Track        {}", or_unknown(song.track_number));
+        println!("  Duration     {}", or_unknown(song.duration.map(format_duration)));
+        println!("  Genre        {}", or_unknown(song.genre.as_deref()));
+        println!("  Year         {}", or_unknown(song.year));
+        println!("  Bitrate      {}", or_unknown(song.bitrate.map(|b| format!("{} kbps", b))));
+        println!("  Sample rate  {}", or_unknown(song.sample_rate.map(|r| format!("{} Hz", r))));
+        println!("  Channels     {}", or_unknown(song.channels));
+        println!("  Track gain   {}", or_unknown(song.track_gain_db.map(|g| format!("{:+.2} dB", g))));
+        println!("  Rating       {}", or_unknown(Some(song.rating_marks()).filter(|m| !m.is_empty())));
+    }
+
     fn render_progress_bar(&self, stdout: &mut impl Write) -> Result<()> {
-        // Flatten the nested Options into a single progress object
-        let Some(progress) = self.current_song
-            .as_ref()
-            .and_then(|song| song.duration)
-            .and_then(|total| PlaybackProgress::new(self.current_elapsed, total))
-        else {
+        let Some(song) = &self.current_song else {
             return Ok(());
         };
+        let progress = PlaybackProgress::for_duration(self.current_elapsed, song.duration);
 
-        // Calculate bar segments
-        let filled = ((progress.ratio() * PROGRESS_BAR_WIDTH as f64).round() as usize)
-            .min(PROGRESS_BAR_WIDTH);
-        let empty = PROGRESS_BAR_WIDTH - filled;
+        // Calculate bar segments; songs of unknown length get a moving segment instead
+        let (before, filled) = match progress.total() {
+            Some(_) => (
+                0,
+                ((progress.ratio() * PROGRESS_BAR_WIDTH as f64).round() as usize).min(PROGRESS_BAR_WIDTH),
+            ),
+            None => pulse_segment(PROGRESS_BAR_WIDTH, progress.elapsed()),
+        };
+        let after = PROGRESS_BAR_WIDTH - before - filled;
 
         write!(
             stdout,
-            "  {} [{}{}] {}",
+            "  {} [{}{}{}] {}",
             format_duration(progress.elapsed()),
+            "░".repeat(before),
             "█".repeat(filled),
-            "░".repeat(empty),
-            format_duration(progress.total()),
+            "░".repeat(after),
+            progress.total().map_or_else(|| UNKNOWN_TOTAL.to_string(), format_duration),
         )?;
 
         Ok(())
@@ -100,17 +144,15 @@ impl TerminalRenderer {
 impl UiRenderer for TerminalRenderer {
     fn init(&mut self) -> Result<()> {
         terminal::enable_raw_mode()?;
-        self.initialized = true;
+        self.terminal_guard = Some(TerminalGuard::new(false));
         Ok(())
     }
 
     fn cleanup(&mut self) -> Result<()> {
-        if self.initialized {
-            // execute!(stdout(), LeaveAlternateScreen)?;
-            terminal::disable_raw_mode()?;
-            self.initialized = false;
+        match self.terminal_guard.take() {
+            Some(guard) => guard.release(),
+            None => Ok(()),
         }
-        Ok(())
     }
 
     fn render(&mut self, state: &UiState) -> Result<()> {
@@ -134,6 +176,18 @@ impl UiRenderer for TerminalRenderer {
             InputAction::PreviousTrack,
             &[key_hints::kb(KeyCode::Char('b'))],
         );
+        let back_key = key_hints::pick_binding_with_preference(
+            &self.key_config,
+            InputMode::Normal,
+            InputAction::SeekBackward,
+            &[key_hints::kb(KeyCode::Char(','))],
+        );
+        let forward_key = key_hints::pick_binding_with_preference(
+            &self.key_config,
+            InputMode::Normal,
+            InputAction::SeekForward,
+            &[key_hints::kb(KeyCode::Char('.'))],
+        );
         let shuffle_key = key_hints::pick_binding_with_preference(
             &self.key_config,
             InputMode::Normal,
@@ -194,10 +248,13 @@ impl UiRenderer for TerminalRenderer {
         queue!(stdout, MoveTo(0, 3))?;
         write!(
             stdout,
-            "  [{}: Pause | {}: Next | {}: Prev | {}: Shuffle | {}: Quit]",
+            "  [{}: Pause | {}: Next | {}: Prev | {}/{}: ±{}s | {}: Shuffle | {}: Quit]",
             key_hints::format_binding_opt(pause_key),
             key_hints::format_binding_opt(next_key),
             key_hints::format_binding_opt(prev_key),
+            key_hints::format_binding_opt(back_key),
+            key_hints::format_binding_opt(forward_key),
+            SEEK_STEP_SECS,
             key_hints::format_binding_opt(shuffle_key),
             key_hints::format_binding_opt(quit_key),
         )?;
@@ -214,15 +271,21 @@ impl UiRenderer for TerminalRenderer {
             self.key_config_synced = true;
         }
 
-        if event::poll(Duration::from_millis(0))?
-            && let Event::Key(key) = event::read()?
-                && let Some(action) = map_key(InputMode::Normal, key, config) {
-                    self.apply_action(action, &mut events);
-                }
+        for event in drain_pending()? {
+            if let Event::Key(key) = event
+                && let Some(action) = map_key(InputMode::Normal, key, config)
+            {
+                self.apply_action(action, &mut events);
+            }
+        }
 
         Ok(events)
     }
 
+    fn wait_for_input(&mut self, timeout: Duration) -> Result<bool> {
+        wait_for_input(timeout)
+    }
+
     fn update_state(&mut self, state: &AppState) {
         self.shuffle_enabled = state.config.shuffle;
         self.current_song = state.playback.current_song.clone();
@@ -240,10 +303,45 @@ impl TerminalRenderer {
             InputAction::ToggleShuffle => events.push(UiEvent::ShuffleToggled {
                 shuffle_enabled: self.shuffle_enabled,
             }),
+            InputAction::ToggleMute => events.push(UiEvent::MuteToggled),
             InputAction::Quit => events.push(UiEvent::QuitRequested),
             InputAction::PlaySelected => events.push(UiEvent::PlaySelectedRequested),
             InputAction::Refresh => events.push(UiEvent::RefreshRequested),
+            InputAction::CycleSleepTimer => events.push(UiEvent::SleepTimerCycleRequested),
+            InputAction::SeekForward => events.push(UiEvent::SeekByRequested { delta_secs: SEEK_STEP_SECS }),
+            InputAction::SeekBackward => events.push(UiEvent::SeekByRequested { delta_secs: -SEEK_STEP_SECS }),
+            InputAction::SeekForwardLong => events.push(UiEvent::SeekByRequested { delta_secs: SEEK_LONG_STEP_SECS }),
+            InputAction::SeekBackwardLong => events.push(UiEvent::SeekByRequested { delta_secs: -SEEK_LONG_STEP_SECS }),
+            InputAction::AddBookmark => events.push(UiEvent::BookmarkAddRequested),
+            InputAction::NextBookmark => events.push(UiEvent::NextBookmarkRequested),
+            InputAction::NextAlbum => events.push(UiEvent::AlbumSkipRequested { forward: true }),
+            InputAction::PreviousAlbum => events.push(UiEvent::AlbumSkipRequested { forward: false }),
             _ => {}
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyEvent, KeyModifiers};
+
+    #[test]
+    fn playback_keys_become_session_events() {
+        let renderer = TerminalRenderer::new();
+        let config = KeyConfig::default();
+        let events_for = |code: KeyCode| {
+            let mut events = Vec::new();
+            if let Some(action) = map_key(InputMode::Normal, KeyEvent::new(code, KeyModifiers::NONE), &config) {
+                renderer.apply_action(action, &mut events);
+            }
+            events
+        };
+
+        assert!(matches!(events_for(KeyCode::Char(' '))[..], [UiEvent::TogglePauseRequested]));
+        assert!(matches!(events_for(KeyCode::Char('n'))[..], [UiEvent::NextTrackRequested]));
+        assert!(matches!(events_for(KeyCode::Char('b'))[..], [UiEvent::PreviousTrackRequested]));
+        assert!(matches!(events_for(KeyCode::Char('q'))[..], [UiEvent::QuitRequested]));
+        assert!(events_for(KeyCode::Char('/')).is_empty(), "search has no place in the terminal UI");
+    }
+}
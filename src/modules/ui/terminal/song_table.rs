@@ -0,0 +1,125 @@
+use crate::core::models::Song;
+use crate::utils::{truncate_str, MIN_TRUNCATE_FIELD, MIN_TRUNCATE_TITLE};
+use crossterm::style::Stylize;
+use std::ops::Range;
+
+const GAP: &str = "  ";
+
+/// The library songs in `range` as aligned `index  title  artist  duration`
+/// rows fitting `width` columns, numbered by their library index (as `select`
+/// takes it). Titles and artists that do not fit are cut with `…`, sharing the
+/// room 55/45; `color` styles each column with ANSI codes.
+pub fn song_table(library: &[Song], range: Range<usize>, width: usize, color: bool) -> Vec<String> {
+    let index_width = library.len().saturating_sub(1).to_string().len();
+    let songs = &library[range.clone()];
+    let durations: Vec<String> = songs
+        .iter()
+        .map(|song| if song.duration.is_some() { song.format_duration() } else { String::new() })
+        .collect();
+    let duration_width = durations.iter().map(|d| d.len()).max().unwrap_or(0);
+    let artists: Vec<String> = songs.iter().map(Song::format_artists).collect();
+
+    let fixed = index_width + GAP.len() * 2 + if duration_width > 0 { GAP.len() + duration_width } else { 0 };
+    let space = width.saturating_sub(fixed);
+    let (title_width, artist_width) = split_columns(
+        songs.iter().map(|s| s.title.chars().count()).max().unwrap_or(0),
+        artists.iter().map(|a| a.chars().count()).max().unwrap_or(0),
+        space,
+    );
+
+    songs
+        .iter()
+        .zip(artists.iter().zip(&durations))
+        .zip(range)
+        .map(|((song, (artist, duration)), i)| {
+            let index = format!("{:>width$}", i, width = index_width);
+            let title = format!("{:<width$}", truncate_str(&song.title, title_width), width = title_width);
+            let artist = format!("{:<width$}", truncate_str(artist, artist_width), width = artist_width);
+            let duration = format!("{:>width$}", duration, width = duration_width);
+
+            let row = if color {
+                format!(
+                    "{}{GAP}{}{GAP}{}{GAP}{}",
+                    index.dark_grey(),
+                    title.bold(),
+                    artist.cyan(),
+                    duration.dark_grey()
+                )
+            } else {
+                format!("{index}{GAP}{title}{GAP}{artist}{GAP}{duration}")
+            };
+            row.trim_end().to_string()
+        })
+        .collect()
+}
+
+/// Widths for the title and artist columns: their longest entries when both fit,
+/// otherwise a 55/45 split of `space` with any share one column does not need
+/// going to the other. Never narrower than the truncation minimums.
+fn split_columns(title_max: usize, artist_max: usize, space: usize) -> (usize, usize) {
+    if title_max + artist_max <= space {
+        return (title_max, artist_max);
+    }
+
+    let title_share = space * 55 / 100;
+    let (title, artist) = if title_max <= title_share {
+        (title_max, space - title_max)
+    } else if artist_max <= space - title_share {
+        (space - artist_max, artist_max)
+    } else {
+        (title_share, space - title_share)
+    };
+    (title.max(MIN_TRUNCATE_TITLE), artist.max(MIN_TRUNCATE_FIELD))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use std::time::Duration;
+
+    fn song(title: &str, artist: &str, secs: u64) -> Song {
+        Song {
+            title: title.to_string(),
+            artists: vec![artist.to_string()],
+            duration: Some(Duration::from_secs(secs)),
+            ..Song::from_path(Path::new("/m/x.mp3"))
+        }
+    }
+
+    #[test]
+    fn columns_line_up_and_long_text_is_cut_to_the_width() {
+        let songs: Vec<Song> = (0..10)
+            .map(|i| song(&"Title ".repeat(i + 1), "Queen", 61 * (i as u64 + 1)))
+            .chain([song("Under Pressure", "Queen & David Bowie & Friends & More", 248)])
+            .collect();
+
+        let rows = song_table(&songs, 0..songs.len(), 60, false);
+
+        assert_eq!(rows.len(), 11);
+        assert!(rows.iter().all(|row| row.chars().count() <= 60), "{rows:#?}");
+        assert!(rows[0].starts_with(" 0  Title "));
+        assert!(rows[10].starts_with("10  Under Pressure"));
+        assert!(rows[9].contains('…'), "the longest title is cut");
+        let artist_column = |row: &String| row.find("Queen").map(|at| row[..at].chars().count());
+        assert!(rows.iter().all(|row| artist_column(row) == artist_column(&rows[0])));
+        assert!(rows.iter().all(|row| row.chars().count() == rows[0].chars().count()), "durations are right-aligned");
+    }
+
+    #[test]
+    fn short_columns_keep_their_natural_width() {
+        assert_eq!(split_columns(10, 8, 100), (10, 8));
+        assert_eq!(split_columns(10, 80, 50), (10, 40), "the artist takes what the title leaves");
+        assert_eq!(split_columns(80, 80, 40), (22, 18));
+        assert_eq!(split_columns(80, 80, 0), (MIN_TRUNCATE_TITLE, MIN_TRUNCATE_FIELD));
+    }
+
+    #[test]
+    fn color_wraps_columns_in_ansi_codes() {
+        let plain = song_table(&[song("Intro", "Queen", 60)], 0..1, 80, false);
+        let colored = song_table(&[song("Intro", "Queen", 60)], 0..1, 80, true);
+
+        assert_eq!(plain, ["0  Intro  Queen  1:00"]);
+        assert!(colored[0].contains("\u{1b}[") && colored[0].contains("Intro"));
+    }
+}
@@ -0,0 +1,89 @@
+use anyhow::Result;
+use crossterm::{
+    cursor::Show,
+    event::DisableMouseCapture,
+    execute,
+    terminal::{disable_raw_mode, LeaveAlternateScreen},
+};
+use std::io::stdout;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Once;
+
+const INACTIVE: u8 = 0;
+const RAW_MODE: u8 = 1;
+const FULL_SCREEN: u8 = 2;
+
+/// What the terminal needs undone; shared with the panic hook, which cannot reach the guard.
+static ACTIVE: AtomicU8 = AtomicU8::new(INACTIVE);
+static PANIC_HOOK: Once = Once::new();
+
+/// Held by a renderer while the terminal is in raw mode. Dropping it puts the
+/// terminal back, so an error that skips `cleanup` leaves a usable shell; a
+/// panic hook does the same before the panic message is printed.
+pub struct TerminalGuard(());
+
+impl TerminalGuard {
+    /// Call right after `enable_raw_mode`. With `full_screen`, restoring also
+    /// leaves the alternate screen, stops mouse capture and shows the cursor.
+    pub fn new(full_screen: bool) -> Self {
+        PANIC_HOOK.call_once(|| {
+            let default_hook = std::panic::take_hook();
+            std::panic::set_hook(Box::new(move |info| {
+                let _ = restore();
+                default_hook(info);
+            }));
+        });
+
+        ACTIVE.store(if full_screen { FULL_SCREEN } else { RAW_MODE }, Ordering::SeqCst);
+        Self(())
+    }
+
+    /// Restore the terminal now, reporting what a drop would ignore.
+    pub fn release(self) -> Result<()> {
+        restore()
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = restore();
+    }
+}
+
+/// Undo raw mode (and the full-screen setup); only the first call after a guard is made acts.
+fn restore() -> Result<()> {
+    match ACTIVE.swap(INACTIVE, Ordering::SeqCst) {
+        FULL_SCREEN => {
+            disable_raw_mode()?;
+            execute!(stdout(), DisableMouseCapture, LeaveAlternateScreen, Show)?;
+        }
+        RAW_MODE => disable_raw_mode()?,
+        _ => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_active() -> bool {
+        ACTIVE.load(Ordering::SeqCst) != INACTIVE
+    }
+
+    #[test]
+    fn panic_inside_the_ui_restores_the_terminal() {
+        let result = std::panic::catch_unwind(|| {
+            let _guard = TerminalGuard::new(false);
+            assert!(is_active());
+            panic!("renderer crashed");
+        });
+
+        assert!(result.is_err());
+        assert!(!is_active(), "unwinding dropped the guard");
+
+        let guard = TerminalGuard::new(false);
+        guard.release().unwrap();
+        assert!(!is_active());
+    }
+}
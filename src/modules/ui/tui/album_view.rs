@@ -0,0 +1,124 @@
+use crate::core::models::Song;
+use std::collections::BTreeMap;
+
+pub const UNKNOWN_ALBUM: &str = "Unknown Album";
+
+/// One row of the album-grouped song list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlbumRow {
+    /// Section header; not selectable.
+    Header { album: String, count: usize },
+    /// A song, by its index in the library.
+    Song(usize),
+}
+
+impl AlbumRow {
+    pub fn song_index(&self) -> Option<usize> {
+        match self {
+            Self::Song(idx) => Some(*idx),
+            Self::Header { .. } => None,
+        }
+    }
+}
+
+/// Group `songs` by album for display.
+///
+/// Albums are ordered case-insensitively with "Unknown Album" last; songs within
+/// an album are ordered by track number (untagged last), then scan order.
+/// The library itself is left untouched — rows refer back to it by index.
+pub fn build_album_rows(songs: &[Song]) -> Vec<AlbumRow> {
+    // Key: (no album?, lowercased name) so unknown sorts after every named album.
+    let mut groups: BTreeMap<(bool, String), (String, Vec<usize>)> = BTreeMap::new();
+
+    for (idx, song) in songs.iter().enumerate() {
+        let name = song.album.as_deref().unwrap_or(UNKNOWN_ALBUM);
+        groups
+            .entry((song.album.is_none(), name.to_lowercase()))
+            .or_insert_with(|| (name.to_string(), Vec::new()))
+            .1
+            .push(idx);
+    }
+
+    let mut rows = Vec::with_capacity(songs.len() + groups.len());
+    for (_, (album, mut indices)) in groups {
+        indices.sort_by_key(|&i| (songs[i].track_number.is_none(), songs[i].track_number, songs[i].order));
+
+        rows.push(AlbumRow::Header { album, count: indices.len() });
+        rows.extend(indices.into_iter().map(AlbumRow::Song));
+    }
+
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_song(title: &str, album: Option<&str>, track: Option<u32>, order: usize) -> Song {
+        Song {
+            album: album.map(str::to_owned),
+            track_number: track,
+            order,
+            ..Song::test_fixture(title)
+        }
+    }
+
+    fn header(album: &str, count: usize) -> AlbumRow {
+        AlbumRow::Header { album: album.to_owned(), count }
+    }
+
+    #[test]
+    fn groups_by_album_and_orders_by_track_number() {
+        let songs = vec![
+            make_song("B2", Some("Beta"), Some(2), 0),
+            make_song("A1", Some("Alpha"), Some(1), 1),
+            make_song("B1", Some("Beta"), Some(1), 2),
+        ];
+
+        assert_eq!(
+            build_album_rows(&songs),
+            vec![
+                header("Alpha", 1),
+                AlbumRow::Song(1),
+                header("Beta", 2),
+                AlbumRow::Song(2),
+                AlbumRow::Song(0),
+            ]
+        );
+    }
+
+    #[test]
+    fn songs_without_album_go_under_unknown_album_last() {
+        let songs = vec![
+            make_song("Loose", None, None, 0),
+            make_song("Z", Some("Zeta"), None, 1),
+        ];
+
+        assert_eq!(
+            build_album_rows(&songs),
+            vec![
+                header("Zeta", 1),
+                AlbumRow::Song(1),
+                header(UNKNOWN_ALBUM, 1),
+                AlbumRow::Song(0),
+            ]
+        );
+    }
+
+    #[test]
+    fn untagged_tracks_follow_numbered_ones_in_scan_order() {
+        let songs = vec![
+            make_song("Late", Some("A"), None, 5),
+            make_song("Early", Some("A"), None, 1),
+            make_song("One", Some("A"), Some(1), 9),
+        ];
+
+        let order: Vec<Option<usize>> = build_album_rows(&songs).iter().map(AlbumRow::song_index).collect();
+        assert_eq!(order, [None, Some(2), Some(1), Some(0)]);
+    }
+
+    #[test]
+    fn empty_library_has_no_rows() {
+        assert!(build_album_rows(&[]).is_empty());
+    }
+}
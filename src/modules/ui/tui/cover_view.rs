@@ -0,0 +1,145 @@
+use crate::core::models::Song;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+/// Kitty splits transmitted image data into escape sequences of at most this many bytes.
+const KITTY_CHUNK_LEN: usize = 4096;
+
+/// Inline image protocol used to draw the cover in the now-playing box.
+///
+/// Both protocols hand the encoded image to the terminal. Sixel and half-block
+/// rendering would need the pixels decoded here, so those terminals get no cover.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphicsProtocol {
+    /// Kitty graphics protocol (kitty, Ghostty).
+    Kitty,
+    /// iTerm2 inline images (iTerm2, WezTerm).
+    Iterm2,
+}
+
+impl GraphicsProtocol {
+    /// Detect a supported protocol from the terminal's environment variables.
+    pub fn detect() -> Option<Self> {
+        Self::from_env(
+            std::env::var("TERM").ok().as_deref(),
+            std::env::var("TERM_PROGRAM").ok().as_deref(),
+            std::env::var_os("KITTY_WINDOW_ID").is_some(),
+        )
+    }
+
+    fn from_env(term: Option<&str>, term_program: Option<&str>, kitty_window: bool) -> Option<Self> {
+        if kitty_window
+            || term.is_some_and(|t| t.contains("kitty") || t.contains("ghostty"))
+            || term_program == Some("ghostty")
+        {
+            return Some(Self::Kitty);
+        }
+
+        match term_program {
+            Some("iTerm.app") | Some("WezTerm") => Some(Self::Iterm2),
+            _ => None,
+        }
+    }
+
+    /// Kitty only accepts PNG without client-side decoding; iTerm2 decodes any common format.
+    pub fn supports(&self, mime_type: &str) -> bool {
+        match self {
+            Self::Kitty => mime_type.eq_ignore_ascii_case("image/png"),
+            Self::Iterm2 => mime_type.starts_with("image/"),
+        }
+    }
+
+    /// Escape sequence drawing `cover` scaled into a `cols`×`rows` cell box at the cursor.
+    pub fn draw_sequence(&self, cover: &CoverImage, cols: u16, rows: u16) -> String {
+        match self {
+            Self::Kitty => {
+                let chunks: Vec<&[u8]> = cover.base64.as_bytes().chunks(KITTY_CHUNK_LEN).collect();
+                let mut out = String::with_capacity(cover.base64.len() + chunks.len() * 32);
+
+                for (i, chunk) in chunks.iter().enumerate() {
+                    let more = u8::from(i + 1 < chunks.len());
+                    // q=2 silences replies, which would otherwise arrive as key input.
+                    let control = if i == 0 {
+                        format!("a=T,f=100,q=2,C=1,c={},r={},m={}", cols, rows, more)
+                    } else {
+                        format!("m={}", more)
+                    };
+                    out.push_str("\x1b_G");
+                    out.push_str(&control);
+                    out.push(';');
+                    out.push_str(std::str::from_utf8(chunk).unwrap_or_default());
+                    out.push_str("\x1b\\");
+                }
+                out
+            }
+            Self::Iterm2 => format!(
+                "\x1b]1337;File=inline=1;width={};height={};preserveAspectRatio=1:{}\x07",
+                cols, rows, cover.base64
+            ),
+        }
+    }
+
+    /// Escape sequence removing covers drawn earlier.
+    /// iTerm2 images are simply painted over when the cells are redrawn.
+    pub fn clear_sequence(&self) -> &'static str {
+        match self {
+            Self::Kitty => "\x1b_Ga=d,d=A,q=2\x1b\\",
+            Self::Iterm2 => "",
+        }
+    }
+}
+
+/// A song's cover, encoded once and ready to send to the terminal.
+pub struct CoverImage {
+    base64: String,
+}
+
+impl CoverImage {
+    /// Load `song`'s cover if it has one `protocol` can display.
+    pub fn load(song: &Song, protocol: GraphicsProtocol) -> Option<Self> {
+        let art = song.album_art().filter(|art| protocol.supports(&art.mime_type))?;
+        Some(Self { base64: STANDARD.encode(&art.data) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cover(len: usize) -> CoverImage {
+        CoverImage { base64: "A".repeat(len) }
+    }
+
+    #[test]
+    fn detects_protocol_from_environment() {
+        assert_eq!(GraphicsProtocol::from_env(Some("xterm-kitty"), None, false), Some(GraphicsProtocol::Kitty));
+        assert_eq!(GraphicsProtocol::from_env(Some("xterm-256color"), None, true), Some(GraphicsProtocol::Kitty));
+        assert_eq!(GraphicsProtocol::from_env(None, Some("iTerm.app"), false), Some(GraphicsProtocol::Iterm2));
+        assert_eq!(GraphicsProtocol::from_env(Some("xterm-256color"), Some("vscode"), false), None);
+    }
+
+    #[test]
+    fn kitty_only_takes_png() {
+        assert!(GraphicsProtocol::Kitty.supports("image/png"));
+        assert!(!GraphicsProtocol::Kitty.supports("image/jpeg"));
+        assert!(GraphicsProtocol::Iterm2.supports("image/jpeg"));
+    }
+
+    #[test]
+    fn kitty_payload_is_chunked_with_continuation_flags() {
+        let seq = GraphicsProtocol::Kitty.draw_sequence(&cover(KITTY_CHUNK_LEN * 2 + 10), 12, 6);
+
+        let parts: Vec<&str> = seq.split("\x1b\\").filter(|p| !p.is_empty()).collect();
+        assert_eq!(parts.len(), 3);
+        assert!(parts[0].starts_with("\x1b_Ga=T,f=100,q=2,C=1,c=12,r=6,m=1;"));
+        assert!(parts[1].starts_with("\x1b_Gm=1;"));
+        assert!(parts[2].starts_with("\x1b_Gm=0;"));
+    }
+
+    #[test]
+    fn iterm2_sends_one_sized_sequence() {
+        let seq = GraphicsProtocol::Iterm2.draw_sequence(&cover(8), 10, 5);
+
+        assert_eq!(seq, "\x1b]1337;File=inline=1;width=10;height=5;preserveAspectRatio=1:AAAAAAAA\x07");
+    }
+}
@@ -0,0 +1,183 @@
+use crate::modules::input::{InputAction, InputMode, KeyConfig};
+use crate::modules::ui::key_hints;
+use crate::modules::ui::tui::settings_view::centered_rect;
+use crate::modules::ui::tui::theme::Theme;
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use std::cell::Cell;
+
+/// Width of the key column, so descriptions line up.
+const KEYS_WIDTH: usize = 18;
+
+/// One titled group of bindings in the overlay.
+struct HelpSection {
+    title: &'static str,
+    mode: InputMode,
+    entries: &'static [(InputAction, &'static str)],
+}
+
+const HELP_SECTIONS: &[HelpSection] = &[
+    HelpSection {
+        title: "Playback",
+        mode: InputMode::Normal,
+        entries: &[
+            (InputAction::TogglePause, "Pause / resume"),
+            (InputAction::NextTrack, "Next track"),
+            (InputAction::PreviousTrack, "Previous track"),
+            (InputAction::NextAlbum, "Next album"),
+            (InputAction::PreviousAlbum, "Previous album"),
+            (InputAction::RestartTrack, "Restart current track"),
+            (InputAction::SeekForward, "Forward 10 seconds"),
+            (InputAction::SeekBackward, "Back 10 seconds"),
+            (InputAction::SeekForwardLong, "Forward a minute"),
+            (InputAction::SeekBackwardLong, "Back a minute"),
+            (InputAction::AddBookmark, "Bookmark this position"),
+            (InputAction::NextBookmark, "Jump to the next bookmark"),
+            (InputAction::ToggleShuffle, "Toggle shuffle"),
+            (InputAction::VolumeUp, "Volume up"),
+            (InputAction::VolumeDown, "Volume down"),
+            (InputAction::ToggleMute, "Toggle mute"),
+            (InputAction::LoopSetA, "Set loop start (A)"),
+            (InputAction::LoopSetB, "Set loop end (B)"),
+            (InputAction::LoopClear, "Clear A–B loop"),
+            (InputAction::CycleSleepTimer, "Sleep timer (cycles, then off)"),
+        ],
+    },
+    HelpSection {
+        title: "Library",
+        mode: InputMode::Normal,
+        entries: &[
+            (InputAction::NavigateUp, "Move up"),
+            (InputAction::NavigateDown, "Move down"),
+            (InputAction::PlaySelected, "Play selected"),
+            (InputAction::JumpToPlaying, "Jump to playing song"),
+            (InputAction::ShowRecent, "Recently played"),
+            (InputAction::EnqueueSelected, "Add selected to queue"),
+            (InputAction::RemoveSelected, "Remove from library"),
+            (InputAction::ToggleFavorite, "Mark or unmark favorite"),
+            (InputAction::EnterSearch, "Search"),
+            (InputAction::CycleSort, "Cycle sort order"),
+            (InputAction::ToggleAlbumView, "Group by album"),
+            (InputAction::ToggleVisualizer, "Level meter"),
+            (InputAction::Refresh, "Rescan music folder"),
+        ],
+    },
+    HelpSection {
+        title: "General",
+        mode: InputMode::Normal,
+        entries: &[
+            (InputAction::OpenSettings, "Settings"),
+            (InputAction::ToggleHelp, "This help"),
+            (InputAction::Quit, "Quit"),
+        ],
+    },
+    HelpSection {
+        title: "Search mode",
+        mode: InputMode::Search,
+        entries: &[
+            (InputAction::NavigateUp, "Move up"),
+            (InputAction::NavigateDown, "Move down"),
+            (InputAction::PlaySelected, "Play selected"),
+            (InputAction::SearchEnqueueAll, "Queue all matches"),
+            (InputAction::JumpToPlaying, "Jump to playing song"),
+            (InputAction::TogglePause, "Pause / resume"),
+            (InputAction::SearchClearLine, "Clear query"),
+            (InputAction::SearchHistoryPrev, "Older search"),
+            (InputAction::SearchHistoryNext, "Newer search"),
+            (InputAction::SearchExit, "Exit search"),
+        ],
+    },
+    HelpSection {
+        title: "Settings",
+        mode: InputMode::Settings,
+        entries: &[
+            (InputAction::SettingsNavigateUp, "Previous field"),
+            (InputAction::SettingsNavigateDown, "Next field"),
+            (InputAction::SettingsConfirm, "Edit / confirm"),
+            (InputAction::SettingsLeft, "Decrease / previous"),
+            (InputAction::SettingsRight, "Increase / next"),
+            (InputAction::SettingsClose, "Close"),
+        ],
+    },
+];
+
+/// Draw the key binding overlay. `scroll` is clamped here, where the popup height is known.
+pub fn draw(f: &mut Frame, key_config: &KeyConfig, theme: &Theme, scroll: &Cell<u16>) {
+    let area = centered_rect(60, 80, f.area());
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Block::default()
+            .title(" ? Help ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent)),
+        area,
+    );
+
+    let inner = Rect {
+        x: area.x + 2,
+        y: area.y + 1,
+        width: area.width.saturating_sub(4),
+        height: area.height.saturating_sub(2),
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(inner);
+
+    let lines = help_lines(key_config, theme);
+    let max_scroll = lines.len().saturating_sub(chunks[0].height as usize) as u16;
+    scroll.set(scroll.get().min(max_scroll));
+
+    f.render_widget(Paragraph::new(lines).scroll((scroll.get(), 0)), chunks[0]);
+
+    let footer = if max_scroll > 0 {
+        "↑/↓ PgUp/PgDn: Scroll  •  Any other key: Close"
+    } else {
+        "Any key: Close"
+    };
+    f.render_widget(
+        Paragraph::new(footer)
+            .style(Style::default().fg(theme.faint))
+            .alignment(Alignment::Center),
+        chunks[1],
+    );
+}
+
+fn help_lines(key_config: &KeyConfig, theme: &Theme) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+
+    for section in HELP_SECTIONS {
+        if !lines.is_empty() {
+            lines.push(Line::default());
+        }
+        lines.push(Line::from(Span::styled(
+            section.title,
+            Style::default().fg(theme.header).add_modifier(Modifier::BOLD),
+        )));
+
+        for (action, description) in section.entries {
+            let bindings = key_config.hint_bindings_for_action(section.mode, *action);
+            let keys = if bindings.is_empty() {
+                "—".to_string()
+            } else {
+                key_hints::format_bindings_join(&bindings)
+            };
+
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {:<width$}", keys, width = KEYS_WIDTH),
+                    Style::default().fg(theme.accent),
+                ),
+                Span::styled(*description, Style::default().fg(theme.text)),
+            ]));
+        }
+    }
+
+    lines
+}
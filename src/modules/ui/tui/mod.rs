@@ -1,3 +1,8 @@
+pub mod album_view;
+pub mod cover_view;
+pub mod help_view;
+pub mod recent_view;
 pub mod renderer;
 pub mod settings_state;
 pub mod settings_view;
+pub mod theme;
@@ -0,0 +1,87 @@
+use crate::core::models::Song;
+use crate::modules::library::stats::{format_ago, RecentPlay};
+use crate::modules::ui::tui::settings_view::centered_rect;
+use crate::modules::ui::tui::theme::Theme;
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+use std::time::SystemTime;
+
+/// Width of the "5 min ago" column, so titles line up.
+const AGO_WIDTH: usize = 12;
+
+/// Draw the recently played overlay, newest first, with `selected` highlighted.
+pub fn draw(f: &mut Frame, plays: &[RecentPlay], selected: usize, songs: &[Song], theme: &Theme) {
+    let area = centered_rect(60, 70, f.area());
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Block::default()
+            .title(format!(" Recently played ({}) ", plays.len()))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent)),
+        area,
+    );
+
+    let inner = Rect {
+        x: area.x + 2,
+        y: area.y + 1,
+        width: area.width.saturating_sub(4),
+        height: area.height.saturating_sub(2),
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(inner);
+
+    if plays.is_empty() {
+        f.render_widget(
+            Paragraph::new("No plays recorded yet.").style(Style::default().fg(theme.dim)),
+            chunks[0],
+        );
+    } else {
+        let now = SystemTime::now();
+        let items: Vec<ListItem> = plays.iter().map(|play| recent_item(play, songs, now, theme)).collect();
+        let list = List::new(items).highlight_style(
+            Style::default()
+                .bg(theme.highlight)
+                .add_modifier(Modifier::BOLD),
+        );
+        let mut state = ListState::default().with_selected(Some(selected));
+        f.render_stateful_widget(list, chunks[0], &mut state);
+    }
+
+    f.render_widget(
+        Paragraph::new("↑/↓: Move  •  Enter: Play  •  Any other key: Close")
+            .style(Style::default().fg(theme.faint))
+            .alignment(Alignment::Center),
+        chunks[1],
+    );
+}
+
+/// Songs no longer in the library are listed by path, marked "(missing)".
+fn recent_item(play: &RecentPlay, songs: &[Song], now: SystemTime, theme: &Theme) -> ListItem<'static> {
+    let ago = Span::styled(
+        format!("{:>width$}  ", format_ago(play.played_at, now), width = AGO_WIDTH),
+        Style::default().fg(theme.dim),
+    );
+
+    let line = match play.index.and_then(|i| songs.get(i)) {
+        Some(song) => Line::from(vec![
+            ago,
+            Span::styled(song.title.clone(), Style::default().fg(theme.text)),
+            Span::styled(format!("  {}", song.format_artists()), Style::default().fg(theme.artist)),
+        ]),
+        None => Line::from(vec![
+            ago,
+            Span::styled(play.path.display().to_string(), Style::default().fg(theme.faint)),
+            Span::styled(" (missing)", Style::default().fg(theme.error)),
+        ]),
+    };
+
+    ListItem::new(line)
+}
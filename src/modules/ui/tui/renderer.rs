@@ -1,37 +1,56 @@
-use crate::application::state::UiState;
+use crate::application::state::{HistoryState, SleepTimer, UiState};
 use crate::core::events::UiEvent;
 use crate::core::traits::UiRenderer;
-use crate::modules::input::{map_key, InputAction, InputMode, KeyConfig};
-use crate::modules::ui::progress_formatter::format_duration;
+use crate::modules::input::{
+    coalesce_selection_changes, drain_pending, map_key, wait_for_input, InputAction, InputMode, KeyConfig,
+};
+use crate::modules::ui::progress_formatter::{format_duration, pulse_segment, ProgressLabel, UNKNOWN_TOTAL};
 use crate::modules::ui::key_hints;
+use crate::modules::ui::terminal_guard::TerminalGuard;
 use anyhow::Result;
 use crossterm::{
-    event::{self, Event, KeyCode},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    cursor::MoveTo,
+    event::{
+        EnableMouseCapture, Event, KeyCode, KeyEventKind, MouseButton,
+        MouseEvent, MouseEventKind,
+    },
+    execute, queue,
+    terminal::{enable_raw_mode, EnterAlternateScreen},
 };
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Gauge, List, ListItem, ListState, Paragraph},
-    Frame, Terminal,
+    widgets::{Block, Borders, Clear, Gauge, List, ListItem, ListState, Paragraph, Wrap},
+    Frame, Terminal, TerminalOptions, Viewport,
 };
-use std::cell::RefCell;
-use std::io::{stdout, Stdout};
+use std::cell::{Cell, RefCell};
+use std::io::{stdout, Stdout, Write};
+use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use crate::modules::library::search_engine::SearchEngine;
+use crate::modules::library::stats::{recent_plays, RecentPlay};
 use crate::modules::library::sorter::SortField;
-use crate::modules::playback::playback_progress::PlaybackProgress;
+use crate::modules::playback::level_meter::{bar_fraction, LevelMeter};
+use crate::modules::playback::playback_progress::{ElapsedClock, PlaybackProgress};
+use crate::modules::ui::tui::album_view::{build_album_rows, AlbumRow};
+use crate::modules::ui::tui::cover_view::{CoverImage, GraphicsProtocol};
+use crate::modules::ui::tui::help_view;
+use crate::modules::ui::tui::recent_view;
 use crate::modules::ui::tui::settings_state::SettingsState;
 use crate::modules::ui::tui::settings_view;
+use crate::modules::ui::tui::theme::{available_themes, Theme};
 use crate::utils::{
-    APP_NAME, MIN_TRUNCATE_FIELD, MIN_TRUNCATE_TITLE,
+    VOLUME_BAR_WIDTH, VOLUME_MAX, VOLUME_STEP, APP_NAME, DOUBLE_CLICK_MS, MIN_TRUNCATE_FIELD, MIN_TRUNCATE_TITLE, NOW_PLAYING_ART_HEIGHT, NOW_PLAYING_HEIGHT,
+    VISUALIZER_HEIGHT, MINI_PLAYER_HEIGHT, MARQUEE_MS_PER_CHAR, DEFAULT_FPS, MARQUEE_GAP, RECENT_PLAYS_MAX, TUI_MIN_LIST_HEIGHT, TUI_MIN_WIDTH,
+    SEEK_LONG_STEP_SECS, SEEK_STEP_SECS, truncate_str,
 };
 
 pub struct TuiRenderer {
     terminal: Option<Terminal<CrosstermBackend<Stdout>>>,
+    terminal_guard: Option<TerminalGuard>,
     list_state: RefCell<ListState>,
 
     key_config: KeyConfig,
@@ -39,85 +58,393 @@ pub struct TuiRenderer {
 
     // Display state (synced from AppState)
     songs: Arc<Vec<crate::core::models::Song>>,
+    library_summary: String, // "N songs • total length", rebuilt when `songs` changes
+    history: HistoryState, // play counts; `plays` is an Arc so syncing is O(1)
     current_song: Option<crate::core::models::Song>,
+    current_index: Option<usize>,
     current_elapsed: Duration, // Synced from AppState.playback.current_elapsed
+    elapsed_clock: ElapsedClock, // Advances current_elapsed between syncs for a smooth bar
     is_paused: bool,
     search_active: bool,
     search_query: String,
     search_results: Vec<usize>,
+    // Recent queries (newest first) and the one the search bar shows while stepping through them.
+    search_history: Arc<Vec<String>>,
+    history_cursor: Option<usize>,
+
+    // Type-ahead filter in normal mode; a purely visual narrowing of the list,
+    // so it is matched here instead of going through the library handler.
+    quick_filter_enabled: bool,
+    quick_filter: String,
+    quick_filter_results: Vec<usize>,
+    search_engine: SearchEngine,
+
     shuffle: bool,
+    muted: bool,
+    volume: u8, // percent, set right away on volume keys so the bar never lags
     is_scanning: bool,
     scan_progress: usize,
 
     settings: SettingsState,
 
+    // Mouse: the song list's screen area (filled in while drawing) and the last
+    // left click as (display row, time), for double-click detection.
+    list_area: Cell<Rect>,
+    // The progress bar and the length it spans; `None` while nothing with a known length plays.
+    progress_bar: Cell<Option<(Rect, Duration)>>,
+    last_click: Option<(usize, Instant)>,
+
+    // Key binding overlay; the scroll offset is clamped while drawing.
+    show_help: bool,
+    help_scroll: Cell<u16>,
+
+    // "Quit? y/n" prompt, asked first when `confirm_quit` is set and something would be cut off.
+    confirm_quit: bool,
+    show_quit_confirm: bool,
+
+    // Recently played overlay; the list is taken when it opens.
+    recent: Option<Vec<RecentPlay>>,
+    recent_selected: usize,
+
+    // Colors; re-resolved whenever the configured theme name changes.
+    theme: Theme,
+    theme_name: Option<String>,
+    custom_theme: Option<Theme>,
+
     active_sort: Option<SortField>,
+
+    // Marquee for now-playing text that overflows; the counter only runs while playing.
+    scroll_titles: bool,
+    scroll_frames: u64,
+    frame_ms: u64, // frame length while playing, so the marquee speed ignores `fps`
+
+    // Text drawn over the progress bar.
+    progress_label: ProgressLabel,
+
+    // Album-grouped display; rows are rebuilt whenever the library changes.
+    album_view: bool,
+    album_rows: Vec<AlbumRow>,
+
+    queue_len: usize,
+
+    // Level meter panel; only measured by the backend while shown.
+    level_meter: Option<LevelMeter>,
+    show_visualizer: bool,
+
+    // Counted down in the header while set.
+    sleep_timer: Option<SleepTimer>,
+
+    // A–B loop marks, drawn over the progress bar.
+    loop_a: Option<Duration>,
+    loop_b: Option<Duration>,
+
+    // Bookmarks of the playing track, drawn under the loop marks.
+    bookmarks: Vec<Duration>,
+
+    // Cover art, only when the terminal speaks an inline image protocol.
+    // The cover is decoded once per song; `cover_area` is filled in while drawing
+    // and compared against `cover_placed` so the image is only resent when it moves.
+    graphics: Option<GraphicsProtocol>,
+    cover_source: Option<PathBuf>,
+    cover: Option<CoverImage>,
+    cover_area: Cell<Option<(Rect, Rect)>>,
+    cover_placed: Option<CoverPlacement>,
+
+    // Set by a resize event; the next frame repaints the whole screen.
+    resized: bool,
+
+    // Mini player: a few inline rows under the prompt instead of the full screen.
+    compact: bool,
+}
+
+/// Where a cover image currently sits on screen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CoverPlacement {
+    screen: Rect,
+    area: Rect,
+    source: PathBuf,
 }
 
 impl TuiRenderer {
     pub fn new() -> Self {
         Self {
             terminal: None,
+            terminal_guard: None,
             list_state: RefCell::new(ListState::default()),
             key_config: KeyConfig::default(),
             key_config_synced: false,
             songs: Arc::new(Vec::new()),
+            library_summary: library_summary(&[]),
+            history: HistoryState::default(),
             current_song: None,
+            current_index: None,
             is_paused: false,
             search_active: false,
             search_query: String::new(),
             search_results: Vec::new(),
+            search_history: Arc::new(Vec::new()),
+            history_cursor: None,
+            quick_filter_enabled: false,
+            quick_filter: String::new(),
+            quick_filter_results: Vec::new(),
+            search_engine: SearchEngine::new(),
             shuffle: false,
+            muted: false,
+            volume: VOLUME_MAX,
             is_scanning: false,
             scan_progress: 0,
             current_elapsed: Duration::from_secs(0),
+            elapsed_clock: ElapsedClock::new(Instant::now()),
             settings: SettingsState::default(),
+            list_area: Cell::new(Rect::default()),
+            progress_bar: Cell::new(None),
+            last_click: None,
+            show_help: false,
+            help_scroll: Cell::new(0),
+            confirm_quit: false,
+            show_quit_confirm: false,
+            recent: None,
+            recent_selected: 0,
+            theme: Theme::default(),
+            theme_name: None,
+            custom_theme: None,
             active_sort: None,
+            scroll_titles: true,
+            scroll_frames: 0,
+            frame_ms: 1000 / u64::from(DEFAULT_FPS),
+            progress_label: ProgressLabel::default(),
+            album_view: false,
+            album_rows: Vec::new(),
+            queue_len: 0,
+            level_meter: None,
+            show_visualizer: false,
+            sleep_timer: None,
+            loop_a: None,
+            loop_b: None,
+            bookmarks: Vec::new(),
+            graphics: None,
+            cover_source: None,
+            cover: None,
+            cover_area: Cell::new(None),
+            cover_placed: None,
+            resized: false,
+            compact: false,
         }
     }
 
+    /// Let printable keys in normal mode narrow the list instead of acting as shortcuts.
+    pub fn set_quick_filter(&mut self, enabled: bool) {
+        self.quick_filter_enabled = enabled;
+    }
+
+    /// Draw only the now-playing line and progress bar, inline below the
+    /// prompt, instead of taking over the screen. Only playback keys work.
+    pub fn set_compact(&mut self, compact: bool) {
+        self.compact = compact;
+    }
+
+    /// Levels of the playing audio, drawn by the visualizer panel when toggled on.
+    pub fn set_level_meter(&mut self, meter: LevelMeter) {
+        self.level_meter = Some(meter);
+    }
+
+    /// Make the theme from `theme.toml` available, and the default when none is configured.
+    pub fn set_custom_theme(&mut self, custom: Option<Theme>) {
+        self.settings.set_theme_names(available_themes(custom.is_some()));
+        self.custom_theme = custom;
+        self.theme = Theme::resolve(self.theme_name.as_deref(), self.custom_theme.as_ref());
+    }
+
     pub fn set_songs(&mut self, songs: Arc<Vec<crate::core::models::Song>>) {
+        self.library_summary = library_summary(&songs);
         self.songs = songs;
         if !self.songs.is_empty() && self.list_state.borrow().selected().is_none() {
             self.list_state.borrow_mut().select(Some(0));
         }
     }
 
+    /// Reopen the song list scrolled to `scroll`, as saved by the last session.
+    /// Drawing clamps it to the library and keeps the selection in view.
+    pub fn set_list_scroll(&mut self, scroll: usize) {
+        *self.list_state.borrow_mut().offset_mut() = scroll;
+    }
+
+    /// Whether the cover is drawn this frame; it is hidden under the settings modal.
+    fn shows_cover(&self) -> bool {
+        self.cover.is_some() && !self.settings.is_open() && !self.show_help && self.recent.is_none()
+    }
+
     fn draw_ui(&self, f: &mut Frame) {
-        let base_constraints = if self.search_active {
-            vec![
-                Constraint::Length(3), // Header
-                Constraint::Min(0),    // Main content
-                Constraint::Length(5), // Now playing (with progress bar)
-                Constraint::Length(4), // Search bar (query + help)
-            ]
+        self.cover_area.set(None);
+        self.progress_bar.set(None);
+
+        let header_height = 3;
+        let footer_height = if self.search_active { 4 } else { 3 }; // search bar (query + help) or controls
+        let required = header_height + NOW_PLAYING_HEIGHT + footer_height + TUI_MIN_LIST_HEIGHT;
+        let area = f.area();
+        if area.height < required || area.width < TUI_MIN_WIDTH {
+            self.draw_too_small(f, required);
+            return;
+        }
+
+        // Optional panels give way on short terminals, so the list keeps its rows.
+        let mut spare = area.height - required;
+        let now_playing_height = if self.shows_cover() && spare >= NOW_PLAYING_ART_HEIGHT - NOW_PLAYING_HEIGHT {
+            spare -= NOW_PLAYING_ART_HEIGHT - NOW_PLAYING_HEIGHT;
+            NOW_PLAYING_ART_HEIGHT
         } else {
-            vec![
-                Constraint::Length(3), // Header
-                Constraint::Min(0),    // Main content
-                Constraint::Length(5), // Now playing (with progress bar)
-                Constraint::Length(3), // Controls
-            ]
+            NOW_PLAYING_HEIGHT
         };
+        let visualizer_height = if self.shows_visualizer() && spare >= VISUALIZER_HEIGHT { VISUALIZER_HEIGHT } else { 0 };
 
         let chunks = Layout::default()
             .direction(Direction::Vertical)
-            .constraints(base_constraints)
-            .split(f.area());
+            .constraints([
+                Constraint::Length(header_height),      // Header
+                Constraint::Min(0),                     // Main content
+                Constraint::Length(visualizer_height),  // Level meter (when toggled on)
+                Constraint::Length(now_playing_height), // Now playing (with progress bar)
+                Constraint::Length(footer_height),      // Search bar or controls
+            ])
+            .split(area);
 
         self.draw_header(f, chunks[0]);
         self.draw_song_list(f, chunks[1]);
-        self.draw_now_playing(f, chunks[2]);
+        if visualizer_height > 0 {
+            self.draw_visualizer(f, chunks[2]);
+        }
+        self.draw_now_playing(f, chunks[3]);
 
         if self.search_active {
-            self.draw_search_bar(f, chunks[3]);
+            self.draw_search_bar(f, chunks[4]);
         } else {
-            self.draw_controls(f, chunks[3]);
+            self.draw_controls(f, chunks[4]);
         }
 
         if self.settings.is_open() {
-            settings_view::draw(f, &self.settings, &self.key_config);
+            settings_view::draw(f, &self.settings, &self.key_config, &self.theme);
+        }
+
+        if let Some(plays) = &self.recent {
+            recent_view::draw(f, plays, self.recent_selected, &self.songs, &self.theme);
+        }
+
+        if self.show_help {
+            help_view::draw(f, &self.key_config, &self.theme, &self.help_scroll);
+        }
+
+        if self.show_quit_confirm {
+            self.draw_quit_confirm(f);
+        }
+    }
+
+    fn draw_quit_confirm(&self, f: &mut Frame) {
+        let screen = f.area();
+        let (width, height) = (24.min(screen.width), 3.min(screen.height));
+        let area = Rect {
+            x: screen.x + (screen.width - width) / 2,
+            y: screen.y + (screen.height - height) / 2,
+            width,
+            height,
+        };
+
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::styled("Quit? ", Style::default().fg(self.theme.text).add_modifier(Modifier::BOLD)),
+                Span::styled("y/n", Style::default().fg(self.theme.dim)),
+            ]))
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(self.theme.accent))),
+            area,
+        );
+    }
+
+    /// The mini player: status, song and key hints on one row, the progress bar below.
+    fn draw_mini(&self, f: &mut Frame) {
+        self.progress_bar.set(None);
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Length(1)])
+            .split(f.area());
+
+        let hints = Line::styled(self.mini_hints(), Style::default().fg(self.theme.faint));
+        if self.show_quit_confirm {
+            let prompt = Line::styled("Quit? y/n", Style::default().fg(self.theme.text).add_modifier(Modifier::BOLD));
+            f.render_widget(Paragraph::new(prompt), rows[0]);
+            if let Some(song) = &self.current_song {
+                self.draw_progress(f, song, rows[1]);
+            }
+            return;
+        }
+        let Some(song) = &self.current_song else {
+            f.render_widget(Paragraph::new(Line::styled("No song playing", Style::default().fg(self.theme.dim))), rows[0]);
+            f.render_widget(Paragraph::new(hints), rows[1]);
+            return;
+        };
+
+        let (status, color) = if self.is_paused { ("⏸ ", self.theme.paused) } else { ("▶ ", self.theme.playing) };
+        let mut line = Line::from(Span::styled(status, Style::default().fg(color).add_modifier(Modifier::BOLD)));
+        if self.muted {
+            line.spans.push(Span::styled("🔇 ", Style::default().fg(self.theme.error)));
         }
+
+        // Hints give way to the song on narrow terminals.
+        let width = usize::from(rows[0].width);
+        let hints_width = if width >= line.width() + hints.width() + MIN_TRUNCATE_TITLE { hints.width() + 2 } else { 0 };
+        let text_width = width.saturating_sub(line.width() + hints_width);
+        let offset = if self.scroll_titles { (self.scroll_frames * self.frame_ms / MARQUEE_MS_PER_CHAR) as usize } else { 0 };
+        let text = vec![
+            Span::styled(song.title.clone(), Style::default().fg(self.theme.accent)),
+            Span::raw(" — "),
+            Span::styled(song.format_artists(), Style::default().fg(self.theme.artist)),
+        ];
+        line.spans.extend(marquee(text, text_width, offset));
+
+        f.render_widget(Paragraph::new(line), rows[0]);
+        if hints_width > 0 {
+            f.render_widget(Paragraph::new(hints.right_aligned()), rows[0]);
+        }
+        self.draw_progress(f, song, rows[1]);
+    }
+
+    /// Pause, next, previous and quit keys, e.g. "space pause • n next • b prev • q quit".
+    fn mini_hints(&self) -> String {
+        [
+            (InputAction::TogglePause, KeyCode::Char(' '), "pause"),
+            (InputAction::NextTrack, KeyCode::Char('n'), "next"),
+            (InputAction::PreviousTrack, KeyCode::Char('b'), "prev"),
+            (InputAction::Quit, KeyCode::Char('q'), "quit"),
+        ]
+        .into_iter()
+        .map(|(action, fallback, label)| {
+            let binding = key_hints::pick_binding_with_preference(
+                &self.key_config,
+                InputMode::Normal,
+                action,
+                &[key_hints::kb(fallback)],
+            );
+            format!("{} {}", key_hints::format_binding_opt(binding), label)
+        })
+        .collect::<Vec<_>>()
+        .join(" • ")
+    }
+
+    /// Shown instead of the player when the fixed rows cannot fit; resizing brings it back.
+    fn draw_too_small(&self, f: &mut Frame, min_height: u16) {
+        let area = f.area();
+        let text = vec![
+            Line::styled("Terminal too small", Style::default().fg(self.theme.error).add_modifier(Modifier::BOLD)),
+            Line::styled(
+                format!("{}x{}, need {}x{}", area.width, area.height, TUI_MIN_WIDTH, min_height),
+                Style::default().fg(self.theme.dim),
+            ),
+        ];
+        let top = area.height.saturating_sub(text.len() as u16) / 2;
+        f.render_widget(
+            Paragraph::new(text).alignment(Alignment::Center).wrap(Wrap { trim: true }),
+            Rect { y: area.y + top, height: area.height - top, ..area },
+        );
     }
 
     fn draw_header(&self, f: &mut Frame, area: Rect) {
@@ -127,13 +454,37 @@ impl TuiRenderer {
             format!("♪ {} Player ♪", APP_NAME)
         };
 
-        let title = Paragraph::new(title_text)
-            .style(
-                Style::default()
-                    .fg(if self.search_active { Color::Yellow } else { Color::Cyan })
-                    .add_modifier(Modifier::BOLD),
-            )
-            .block(Block::default().borders(Borders::ALL));
+        let mut spans = vec![Span::styled(
+            title_text,
+            Style::default()
+                .fg(if self.search_active { self.theme.accent } else { self.theme.header })
+                .add_modifier(Modifier::BOLD),
+        )];
+        if !self.search_active && !self.quick_filter.is_empty() {
+            spans.push(Span::styled(
+                format!("  Filter: {}█", self.quick_filter),
+                Style::default().fg(self.theme.accent),
+            ));
+        }
+        if self.queue_len > 0 {
+            spans.push(Span::styled(
+                format!("  ⏭ {} queued", self.queue_len),
+                Style::default().fg(self.theme.album),
+            ));
+        }
+        if let Some(timer) = self.sleep_timer {
+            let left = format_duration(timer.remaining(Instant::now()));
+            let text = if timer.after_track {
+                format!("  ☾ {} (+ track)", left)
+            } else {
+                format!("  ☾ {}", left)
+            };
+            spans.push(Span::styled(text, Style::default().fg(self.theme.accent)));
+        }
+
+        let summary = Line::styled(format!(" {} ", self.library_summary), Style::default().fg(self.theme.dim));
+        let title = Paragraph::new(Line::from(spans))
+            .block(Block::default().borders(Borders::ALL).title_top(summary.right_aligned()));
         f.render_widget(title, area);
     }
 
@@ -142,19 +493,18 @@ impl TuiRenderer {
         // 2 border chars + 2 highlight-symbol chars ("▶ ")
         let content_width = area.width.saturating_sub(4);
 
-        let (items, total_count, match_info): (Vec<ListItem>, usize, String) = if self.search_active {
-            let items: Vec<ListItem> = self
-                .search_results
+        let (items, total_count, match_info): (Vec<ListItem>, usize, String) = if let Some(results) = self.filtered() {
+            let items: Vec<ListItem> = results
                 .iter()
                 .filter_map(|&orig_idx| {
                     self.songs.get(orig_idx).map(|song| {
                         let is_current = current_path.is_some_and(|p| p == &song.path);
-                        song_list_item(None, song, is_current, content_width)
+                        song_list_item(None, song, self.history.play_count(&song.path), is_current, content_width, &self.theme)
                     })
                 })
                 .collect();
 
-            let match_count = self.search_results.len();
+            let match_count = results.len();
             let match_info = if match_count == 0 {
                 " - No matches".to_string()
             } else {
@@ -162,6 +512,20 @@ impl TuiRenderer {
             };
 
             (items, self.songs.len(), match_info)
+        } else if self.album_view {
+            let items: Vec<ListItem> = self
+                .album_rows
+                .iter()
+                .filter_map(|row| match row {
+                    AlbumRow::Header { album, count } => Some(album_header_item(album, *count, &self.theme)),
+                    AlbumRow::Song(i) => self.songs.get(*i).map(|song| {
+                        let is_current = current_path.is_some_and(|p| p == &song.path);
+                        song_list_item(Some(i + 1), song, self.history.play_count(&song.path), is_current, content_width, &self.theme)
+                    }),
+                })
+                .collect();
+
+            (items, self.songs.len(), String::new())
         } else {
             let items: Vec<ListItem> = self
                 .songs
@@ -169,14 +533,20 @@ impl TuiRenderer {
                 .enumerate()
                 .map(|(i, song)| {
                     let is_current = current_path.is_some_and(|p| p == &song.path);
-                    song_list_item(Some(i + 1), song, is_current, content_width)
+                    song_list_item(Some(i + 1), song, self.history.play_count(&song.path), is_current, content_width, &self.theme)
                 })
                 .collect();
 
             (items, self.songs.len(), String::new())
         };
 
-        let sort_label = if self.search_active { "" } else { active_sort_label(self.active_sort) };
+        let sort_label = if self.filtered().is_some() {
+            ""
+        } else if self.album_view {
+            "[albums]"
+        } else {
+            active_sort_label(self.active_sort)
+        };
 
         let list_title = if self.is_scanning {
             if self.scan_progress > 0 {
@@ -194,33 +564,95 @@ impl TuiRenderer {
                     .borders(Borders::ALL)
                     .title(list_title)
                     .title_style(if self.is_scanning {
-                        Style::default().fg(Color::Yellow)
+                        Style::default().fg(self.theme.accent)
                     } else {
                         Style::default()
                     }),
             )
             .highlight_style(
                 Style::default()
-                    .bg(Color::DarkGray)
+                    .bg(self.theme.highlight)
                     .add_modifier(Modifier::BOLD),
             )
             .highlight_symbol("▶ ");
 
+        self.list_area.set(area);
         f.render_stateful_widget(list, area, &mut *self.list_state.borrow_mut());
     }
 
+    fn shows_visualizer(&self) -> bool {
+        self.show_visualizer && self.level_meter.is_some()
+    }
+
+    /// One bar per channel (left and right for stereo). Paused or stopped
+    /// playback draws empty bars rather than the last levels measured.
+    fn draw_visualizer(&self, f: &mut Frame, area: Rect) {
+        let block = Block::default().borders(Borders::ALL).title(" Levels ");
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let playing = self.current_song.is_some() && !self.is_paused;
+        let levels = match &self.level_meter {
+            Some(meter) if playing => meter.levels(),
+            _ => Vec::new(),
+        };
+
+        let labels: &[&str] = if levels.len() == 1 { &["M"] } else { &["L", "R"] };
+        for (row, label) in labels.iter().enumerate().take(inner.height as usize) {
+            let area = Rect { y: inner.y + row as u16, height: 1, ..inner };
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Length(2), Constraint::Min(1)])
+                .split(area);
+
+            f.render_widget(Paragraph::new(*label).style(Style::default().fg(self.theme.dim)), columns[0]);
+            let level = levels.get(row).copied().unwrap_or(0.0);
+            f.render_widget(
+                Gauge::default()
+                    .gauge_style(Style::default().fg(self.theme.playing).bg(self.theme.progress_empty))
+                    .ratio(bar_fraction(level))
+                    .use_unicode(true)
+                    .label(""),
+                columns[1],
+            );
+        }
+    }
+
     fn draw_now_playing(&self, f: &mut Frame, area: Rect) {
         // Create the main block container
-        let block = Block::default()
+        let mut block = Block::default()
             .borders(Borders::ALL)
-            .title(" Now Playing ");
+            .title(" Now Playing ")
+            .title_top(volume_line(self.volume, self.muted, &self.theme).right_aligned());
+
+        // Bitrate and friends sit in the bottom border, clear of the text.
+        if let Some(summary) = self.current_song.as_ref().and_then(|song| song.audio_summary()) {
+            block = block.title_bottom(
+                Line::styled(format!(" {} ", summary), Style::default().fg(self.theme.faint)).right_aligned(),
+            );
+        }
 
         // Calculate the inner area (inside the borders)
-        let inner_area = block.inner(area);
+        let mut inner_area = block.inner(area);
 
         // Render the block borders first
         f.render_widget(block, area);
 
+        // Reserve a roughly square box on the left for the cover; text shrinks to fit.
+        if self.shows_cover() && self.current_song.is_some() && area.height >= NOW_PLAYING_ART_HEIGHT {
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([
+                    Constraint::Length(inner_area.height * 2), // cells are about twice as tall as wide
+                    Constraint::Length(1),
+                    Constraint::Min(1),
+                ])
+                .split(inner_area);
+
+            self.cover_area.set(Some((f.area(), columns[0])));
+            inner_area = columns[2];
+        }
+
         // Split the inner area: Top for Song Info, Bottom for Progress Bar
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -244,78 +676,119 @@ impl TuiRenderer {
                 " ▶️"
             };
 
-            let text_content = vec![
-                Line::from(vec![
-                    Span::styled(
-                        status,
-                        Style::default()
-                            .fg(if self.is_paused { Color::Yellow } else { Color::Green })
-                            .add_modifier(Modifier::BOLD),
-                    ),
-                    Span::styled(
-                        shuffle_indicator,
-                        Style::default().fg(Color::Cyan),
-                    ),
-                    Span::raw("  "),
-                    Span::styled(&song.title, Style::default().fg(Color::Yellow)),
-                ]),
-                Line::from(vec![
-                    Span::raw("  "),
-                    Span::styled(
-                        song.format_artists(),
-                        Style::default().fg(Color::Cyan),
-                    ),
-                    Span::raw(" • "),
-                    Span::styled(
-                        song.album.as_deref().unwrap_or("Unknown Album"),
-                        Style::default().fg(Color::Magenta),
-                    ),
-                ]),
+            let mute_indicator = if self.muted { " 🔇 MUTED" } else { "" };
+
+            let mut title_line = Line::from(vec![
+                Span::styled(
+                    status,
+                    Style::default()
+                        .fg(if self.is_paused { self.theme.paused } else { self.theme.playing })
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    shuffle_indicator,
+                    Style::default().fg(self.theme.header),
+                ),
+                Span::styled(
+                    mute_indicator,
+                    Style::default().fg(self.theme.error).add_modifier(Modifier::BOLD),
+                ),
+                Span::raw("  "),
+            ]);
+            let mut artist_line = Line::from(Span::raw("  "));
+
+            // Only text that overflows the box scrolls; the rest stays put.
+            let width = chunks[0].width as usize;
+            let offset = if self.scroll_titles { (self.scroll_frames * self.frame_ms / MARQUEE_MS_PER_CHAR) as usize } else { 0 };
+            let title = vec![Span::styled(song.title.clone(), Style::default().fg(self.theme.accent))];
+            let details = vec![
+                Span::styled(song.format_artists(), Style::default().fg(self.theme.artist)),
+                Span::raw(" • "),
+                Span::styled(
+                    song.album.clone().unwrap_or_else(|| "Unknown Album".to_string()),
+                    Style::default().fg(self.theme.album),
+                ),
             ];
+            let title_width = width.saturating_sub(title_line.width());
+            let details_width = width.saturating_sub(artist_line.width());
+            title_line.spans.extend(marquee(title, title_width, offset));
+            artist_line.spans.extend(marquee(details, details_width, offset));
+
+            let text_content = vec![title_line, artist_line];
 
             f.render_widget(Paragraph::new(text_content), chunks[0]);
 
-            // Spotify-style Progress Bar (Bottom Chunk): [elapsed] [bar] [total]
-            if let Some(duration) = song.duration
-                && let Some(progress) = PlaybackProgress::new(self.current_elapsed, duration) {
-                    let elapsed_str = format_duration(progress.elapsed());
-                    let total_str = format_duration(progress.total());
-
-                    // Split horizontally: elapsed | padding | bar | padding | total
-                    let progress_chunks = Layout::default()
-                        .direction(Direction::Horizontal)
-                        .constraints([
-                            Constraint::Length(elapsed_str.len() as u16), // Elapsed time
-                            Constraint::Length(1),                         // Left padding
-                            Constraint::Min(1),                            // Bar takes remaining
-                            Constraint::Length(1),                         // Right padding
-                            Constraint::Length(total_str.len() as u16),  // Total time
-                        ])
-                        .split(chunks[1]);
-
-                    // Elapsed time (left)
-                    let elapsed_widget = Paragraph::new(elapsed_str)
-                        .style(Style::default().fg(Color::White));
-                    f.render_widget(elapsed_widget, progress_chunks[0]);
-
-                    // Progress bar (center) - NO LABEL, just the bar
-                    let gauge = Gauge::default()
-                        .gauge_style(Style::default().fg(Color::LightBlue).bg(Color::DarkGray))
-                        .ratio(progress.ratio())
-                        .use_unicode(true)
-                        .label(""); // No percentage
-                    f.render_widget(gauge, progress_chunks[2]);
-
-                    // Total time (right)
-                    let total_widget = Paragraph::new(total_str)
-                        .style(Style::default().fg(Color::Gray));
-                    f.render_widget(total_widget, progress_chunks[4]);
-                }
+            self.draw_progress(f, song, chunks[1]);
         } else {
             f.render_widget(Paragraph::new(vec![Line::from("No song playing")]), chunks[0]);
         }
     }
 
+    /// Spotify-style progress bar for `song`: [elapsed] [bar] [total].
+    fn draw_progress(&self, f: &mut Frame, song: &crate::core::models::Song, area: Rect) {
+        let elapsed = self.elapsed_clock.elapsed(Instant::now(), song.duration);
+        let progress = PlaybackProgress::for_duration(elapsed, song.duration);
+        let elapsed_str = format_duration(progress.elapsed());
+        let total_str = progress.total().map_or_else(|| UNKNOWN_TOTAL.to_string(), format_duration);
+
+        // Split horizontally: elapsed | padding | bar | padding | total
+        let progress_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Length(elapsed_str.len() as u16), // Elapsed time
+                Constraint::Length(1),                         // Left padding
+                Constraint::Min(1),                            // Bar takes remaining
+                Constraint::Length(1),                         // Right padding
+                Constraint::Length(total_str.len() as u16),  // Total time
+            ])
+            .split(area);
+
+        // Elapsed time (left)
+        let elapsed_widget = Paragraph::new(elapsed_str)
+            .style(Style::default().fg(self.theme.text));
+        f.render_widget(elapsed_widget, progress_chunks[0]);
+
+        let bar = progress_chunks[2];
+        if let Some(total) = progress.total() {
+            self.progress_bar.set(Some((bar, total)));
+
+            // Progress bar (center), labelled per the configured style
+            let gauge = Gauge::default()
+                .gauge_style(Style::default().fg(self.theme.progress_filled).bg(self.theme.progress_empty))
+                .ratio(progress.ratio())
+                .use_unicode(true)
+                .label(Span::styled(self.progress_label.format(&progress), Style::default().fg(self.theme.text)));
+            f.render_widget(gauge, bar);
+
+            // Bookmarks, then the A–B loop marks on top of the bar
+            if bar.width > 0 {
+                for &mark in &self.bookmarks {
+                    let offset = (progress.ratio_at(mark) * f64::from(bar.width - 1)).round() as u16;
+                    f.buffer_mut().set_string(bar.x + offset, bar.y, "◆", Style::default().fg(self.theme.dim));
+                }
+            }
+            for (mark, label) in [(self.loop_a, "A"), (self.loop_b, "B")] {
+                if let Some(mark) = mark && bar.width > 0 {
+                    let offset = (progress.ratio_at(mark) * f64::from(bar.width - 1)).round() as u16;
+                    f.buffer_mut().set_string(
+                        bar.x + offset,
+                        bar.y,
+                        label,
+                        Style::default().fg(self.theme.accent).add_modifier(Modifier::BOLD),
+                    );
+                }
+            }
+        } else {
+            // Unknown length: a segment sweeping across an empty bar
+            f.render_widget(pulse_bar(bar.width, progress.elapsed(), &self.theme), bar);
+        }
+
+        // Total time (right)
+        let total_widget = Paragraph::new(total_str)
+            .style(Style::default().fg(self.theme.dim));
+        f.render_widget(total_widget, progress_chunks[4]);
+    }
+
     fn draw_controls(&self, f: &mut Frame, area: Rect) {
         let cfg = &self.key_config;
 
@@ -361,6 +834,12 @@ impl TuiRenderer {
             InputAction::ToggleShuffle,
             &[key_hints::kb(KeyCode::Char('r'))],
         );
+        let mute = key_hints::pick_binding_with_preference(
+            cfg,
+            InputMode::Normal,
+            InputAction::ToggleMute,
+            &[key_hints::kb(KeyCode::Char('m'))],
+        );
         let search = key_hints::pick_binding_with_preference(
             cfg,
             InputMode::Normal,
@@ -385,14 +864,37 @@ impl TuiRenderer {
             InputAction::CycleSort,
             &[key_hints::kb(KeyCode::Char('o'))],
         );
+        let albums = key_hints::pick_binding_with_preference(
+            cfg,
+            InputMode::Normal,
+            InputAction::ToggleAlbumView,
+            &[key_hints::kb(KeyCode::Char('a'))],
+        );
+        let enqueue = key_hints::pick_binding_with_preference(
+            cfg,
+            InputMode::Normal,
+            InputAction::EnqueueSelected,
+            &[key_hints::kb(KeyCode::Char('e'))],
+        );
         let quit = key_hints::pick_binding_with_preference(
             cfg,
             InputMode::Normal,
             InputAction::Quit,
             &[key_hints::kb(KeyCode::Char('q'))],
         );
+        let help = key_hints::pick_binding_with_preference(
+            cfg,
+            InputMode::Normal,
+            InputAction::ToggleHelp,
+            &[key_hints::kb(KeyCode::Char('?'))],
+        );
 
+        // Help comes first so it survives truncation on narrow terminals.
         let controls = Paragraph::new(vec![Line::from(vec![
+            Span::styled(
+                format!("{}: Help • ", key_hints::format_binding_opt(help)),
+                Style::default().fg(self.theme.accent),
+            ),
             Span::raw(format!(
                 "{}/{}: Navigate • ",
                 key_hints::format_binding_opt(nav_up),
@@ -416,15 +918,19 @@ impl TuiRenderer {
             )),
             Span::styled(
                 format!("{}: Shuffle • ", key_hints::format_binding_opt(shuffle)),
-                Style::default().fg(Color::Cyan),
+                Style::default().fg(self.theme.header),
             ),
+            Span::raw(format!(
+                "{}: Mute • ",
+                key_hints::format_binding_opt(mute)
+            )),
             Span::styled(
                 format!("{}: Search • ", key_hints::format_binding_opt(search)),
-                Style::default().fg(Color::Yellow),
+                Style::default().fg(self.theme.accent),
             ),
             Span::styled(
                 format!("{}: Refresh • ", key_hints::format_binding_opt(refresh)),
-                Style::default().fg(Color::Green),
+                Style::default().fg(self.theme.playing),
             ),
             Span::raw(format!(
                 "{}: Settings • ",
@@ -434,9 +940,17 @@ impl TuiRenderer {
                 "{}: Sort • ",
                 key_hints::format_binding_opt(sort)
             )),
+            Span::raw(format!(
+                "{}: Albums • ",
+                key_hints::format_binding_opt(albums)
+            )),
+            Span::raw(format!(
+                "{}: Queue • ",
+                key_hints::format_binding_opt(enqueue)
+            )),
             Span::raw(format!("{}: Quit", key_hints::format_binding_opt(quit))),
         ])])
-            .style(Style::default().fg(Color::Gray))
+            .style(Style::default().fg(self.theme.dim))
             .block(Block::default().borders(Borders::ALL).title(" Controls "));
         f.render_widget(controls, area);
     }
@@ -476,9 +990,9 @@ impl TuiRenderer {
 
         let search_text = vec![
             Line::from(vec![
-                Span::styled("Search: ", Style::default().fg(Color::Yellow)),
-                Span::styled(&self.search_query, Style::default().fg(Color::White)),
-                Span::styled("█", Style::default().fg(Color::Gray)),
+                Span::styled("Search: ", Style::default().fg(self.theme.accent)),
+                Span::styled(&self.search_query, Style::default().fg(self.theme.text)),
+                Span::styled("█", Style::default().fg(self.theme.dim)),
             ]),
             Line::from(vec![
                 Span::raw(format!(
@@ -503,142 +1017,551 @@ impl TuiRenderer {
         ];
 
         let paragraph = Paragraph::new(search_text)
-            .style(Style::default().fg(Color::Gray))
+            .style(Style::default().fg(self.theme.dim))
             .block(Block::default().borders(Borders::ALL).title(" Search Mode "));
         f.render_widget(paragraph, area);
     }
 
     fn navigate_up(&mut self) -> Option<usize> {
-        let max_len = if self.search_active {
-            self.search_results.len()
-        } else {
-            self.songs.len()
-        };
+        let max_len = self.display_len();
 
         if max_len == 0 {
             return None;
         }
 
-        let mut state = self.list_state.borrow_mut();
-        let new_idx = match state.selected() {
-            Some(i) => {
-                if i == 0 {
-                    max_len.saturating_sub(1)
-                } else {
-                    i - 1
+        let new_idx = {
+            let mut state = self.list_state.borrow_mut();
+            let new_idx = match state.selected() {
+                Some(i) => {
+                    if i == 0 {
+                        max_len.saturating_sub(1)
+                    } else {
+                        i - 1
+                    }
                 }
-            }
-            None => 0,
+                None => 0,
+            };
+            let new_idx = self.skip_headers(new_idx, false);
+            state.select(Some(new_idx));
+            new_idx
         };
-        state.select(Some(new_idx));
 
         // Return original index for event
         self.get_original_index(new_idx)
     }
 
     fn navigate_down(&mut self) -> Option<usize> {
-        let max_len = if self.search_active {
-            self.search_results.len()
-        } else {
-            self.songs.len()
-        };
+        let max_len = self.display_len();
 
         if max_len == 0 {
             return None;
         }
 
-        let mut state = self.list_state.borrow_mut();
-        let new_idx = match state.selected() {
-            Some(i) => {
-                if i >= max_len - 1 {
-                    0
-                } else {
-                    i + 1
+        let new_idx = {
+            let mut state = self.list_state.borrow_mut();
+            let new_idx = match state.selected() {
+                Some(i) => {
+                    if i >= max_len - 1 {
+                        0
+                    } else {
+                        i + 1
+                    }
                 }
-            }
-            None => 0,
+                None => 0,
+            };
+            let new_idx = self.skip_headers(new_idx, true);
+            state.select(Some(new_idx));
+            new_idx
         };
-        state.select(Some(new_idx));
 
         // Return original index for event
         self.get_original_index(new_idx)
     }
 
-    fn get_original_index(&self, display_idx: usize) -> Option<usize> {
+    /// Number of rows in the list currently on screen.
+    /// Song indices the list is narrowed to: search results, then the quick filter.
+    fn filtered(&self) -> Option<&[usize]> {
         if self.search_active {
-            self.search_results.get(display_idx).copied()
+            Some(&self.search_results)
+        } else if !self.quick_filter.is_empty() {
+            Some(&self.quick_filter_results)
+        } else {
+            None
+        }
+    }
+
+    /// Re-run the quick filter and select its best match.
+    fn update_quick_filter(&mut self, events: &mut Vec<UiEvent>) {
+        self.quick_filter_results = self
+            .search_engine
+            .search(&self.songs, &self.quick_filter)
+            .into_iter()
+            .map(|r| r.index)
+            .collect();
+
+        if let Some(&index) = self.quick_filter_results.first() {
+            self.list_state.borrow_mut().select(Some(0));
+            events.push(UiEvent::SelectionChanged { index });
+        }
+    }
+
+    fn display_len(&self) -> usize {
+        if let Some(results) = self.filtered() {
+            results.len()
+        } else if self.album_view {
+            self.album_rows.len()
+        } else {
+            self.songs.len()
+        }
+    }
+
+    fn showing_albums(&self) -> bool {
+        self.album_view && self.filtered().is_none()
+    }
+
+    /// Move past album header rows (wrapping) so the selection always lands on a song.
+    fn skip_headers(&self, mut idx: usize, forward: bool) -> usize {
+        if !self.showing_albums() {
+            return idx;
+        }
+
+        let len = self.album_rows.len();
+        for _ in 0..len {
+            if self.album_rows[idx].song_index().is_some() {
+                break;
+            }
+            idx = if forward { (idx + 1) % len } else { (idx + len - 1) % len };
+        }
+        idx
+    }
+
+    fn get_original_index(&self, display_idx: usize) -> Option<usize> {
+        if let Some(results) = self.filtered() {
+            results.get(display_idx).copied()
+        } else if self.album_view {
+            self.album_rows.get(display_idx).and_then(AlbumRow::song_index)
         } else {
             Some(display_idx)
         }
     }
+
+    /// Row on screen that shows the library song at `index`.
+    fn display_index_of(&self, index: usize) -> Option<usize> {
+        if let Some(results) = self.filtered() {
+            results.iter().position(|&orig_idx| orig_idx == index)
+        } else if self.album_view {
+            self.album_rows.iter().position(|row| row.song_index() == Some(index))
+        } else {
+            Some(index)
+        }
+    }
+
+    /// Send the cover image to the terminal when it was drawn somewhere new this frame.
+    ///
+    /// Images live outside ratatui's cell buffer, so a stale one is removed by
+    /// clearing and repainting the screen before the new placement is written.
+    fn place_cover(&mut self, terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> Result<()> {
+        let Some(protocol) = self.graphics else {
+            return Ok(());
+        };
+
+        let wanted = self
+            .cover_area
+            .get()
+            .zip(self.cover_source.clone())
+            .map(|((screen, area), source)| CoverPlacement { screen, area, source });
+        if wanted == self.cover_placed {
+            return Ok(());
+        }
+
+        if self.cover_placed.take().is_some() {
+            write!(terminal.backend_mut(), "{}", protocol.clear_sequence())?;
+            terminal.clear()?;
+            terminal.draw(|f| self.draw_ui(f))?;
+        }
+
+        if let (Some(placement), Some(cover)) = (wanted, &self.cover) {
+            let backend = terminal.backend_mut();
+            queue!(backend, MoveTo(placement.area.x, placement.area.y))?;
+            write!(
+                backend,
+                "{}",
+                protocol.draw_sequence(cover, placement.area.width, placement.area.height)
+            )?;
+            backend.flush()?;
+            self.cover_placed = Some(placement);
+        }
+
+        Ok(())
+    }
+
+    fn apply_help_key(&mut self, code: KeyCode) {
+        const PAGE: u16 = 10;
+        let scroll = self.help_scroll.get();
+
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => self.help_scroll.set(scroll.saturating_sub(1)),
+            KeyCode::Down | KeyCode::Char('j') => self.help_scroll.set(scroll.saturating_add(1)),
+            KeyCode::PageUp => self.help_scroll.set(scroll.saturating_sub(PAGE)),
+            KeyCode::PageDown => self.help_scroll.set(scroll.saturating_add(PAGE)),
+            _ => self.show_help = false,
+        }
+    }
+
+    /// The recently played overlay moves its selection, plays it with Enter, and
+    /// closes on any other key. Songs gone from the library cannot be played.
+    fn apply_recent_key(&mut self, code: KeyCode, events: &mut Vec<UiEvent>) {
+        let Some(plays) = &self.recent else {
+            return;
+        };
+
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => self.recent_selected = self.recent_selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.recent_selected = (self.recent_selected + 1).min(plays.len().saturating_sub(1));
+            }
+            KeyCode::Enter => {
+                if let Some(index) = plays.get(self.recent_selected).and_then(|play| play.index) {
+                    // Back to the full list, so the played song can be selected in it.
+                    self.quick_filter.clear();
+                    events.push(UiEvent::SelectionChanged { index });
+                    events.push(UiEvent::PlaySelectedRequested);
+                    self.recent = None;
+                }
+            }
+            _ => self.recent = None,
+        }
+    }
+
+    /// Turn the terminal events read this tick into UI events, in order, with
+    /// back-to-back selection moves collapsed into the last one.
+    fn handle_input_events(&mut self, pending: Vec<Event>, config: &KeyConfig) -> Vec<UiEvent> {
+        let mut events = Vec::new();
+        for event in pending {
+            self.handle_input_event(event, config, &mut events);
+        }
+        coalesce_selection_changes(events)
+    }
+
+    fn handle_input_event(&mut self, event: Event, config: &KeyConfig, events: &mut Vec<UiEvent>) {
+        let key = match event {
+            Event::Key(key) => key,
+            Event::Mouse(mouse) => return self.apply_mouse(mouse, events),
+            Event::Resize(..) => {
+                self.resized = true;
+                return;
+            }
+            _ => return,
+        };
+
+        // Only y or Enter confirms; any other key keeps the session going.
+        if self.show_quit_confirm {
+            if key.kind != KeyEventKind::Release {
+                self.show_quit_confirm = false;
+                if matches!(key.code, KeyCode::Char('y' | 'Y') | KeyCode::Enter) {
+                    events.push(UiEvent::QuitRequested);
+                }
+            }
+            return;
+        }
+
+        // The help overlay swallows every key: scroll keys move it, anything else closes it.
+        if self.show_help {
+            if key.kind != KeyEventKind::Release {
+                self.apply_help_key(key.code);
+            }
+            return;
+        }
+
+        if self.recent.is_some() {
+            if key.kind != KeyEventKind::Release {
+                self.apply_recent_key(key.code, events);
+            }
+            return;
+        }
+
+        let mode = self.current_mode();
+
+        if let Some(action) = map_key(mode, key, config)
+            && (!self.compact || works_in_mini(action))
+        {
+            self.apply_action(action, events);
+        }
+    }
+
+    /// Clicks select a song (double-click plays it) or seek on the progress bar;
+    /// the wheel moves the selection.
+    fn apply_mouse(&mut self, mouse: MouseEvent, events: &mut Vec<UiEvent>) {
+        if self.show_quit_confirm {
+            return;
+        }
+        if self.show_help {
+            match mouse.kind {
+                MouseEventKind::ScrollUp => self.apply_help_key(KeyCode::Up),
+                MouseEventKind::ScrollDown => self.apply_help_key(KeyCode::Down),
+                _ => {}
+            }
+            return;
+        }
+        if self.recent.is_some() {
+            match mouse.kind {
+                MouseEventKind::ScrollUp => self.apply_recent_key(KeyCode::Up, events),
+                MouseEventKind::ScrollDown => self.apply_recent_key(KeyCode::Down, events),
+                _ => {}
+            }
+            return;
+        }
+        if self.settings.is_open() {
+            return;
+        }
+
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.apply_action(InputAction::NavigateUp, events),
+            MouseEventKind::ScrollDown => self.apply_action(InputAction::NavigateDown, events),
+            MouseEventKind::Down(MouseButton::Left) => {
+                if let Some((bar, total)) = self.progress_bar.get()
+                    && let Some(position) = seek_target(bar, total, mouse.column, mouse.row)
+                {
+                    events.push(UiEvent::SeekRequested { position });
+                    return;
+                }
+
+                // Album headers have no song behind them and are not selectable.
+                let Some((row, index)) = self
+                    .list_row_at(mouse.column, mouse.row)
+                    .and_then(|row| Some((row, self.get_original_index(row)?)))
+                else {
+                    self.last_click = None;
+                    return;
+                };
+
+                self.list_state.borrow_mut().select(Some(row));
+                events.push(UiEvent::SelectionChanged { index });
+
+                let now = Instant::now();
+                let double_click = self.last_click.is_some_and(|(last_row, at)| {
+                    last_row == row && now.duration_since(at) <= Duration::from_millis(DOUBLE_CLICK_MS)
+                });
+                if double_click {
+                    events.push(UiEvent::PlaySelectedRequested);
+                    self.last_click = None;
+                } else {
+                    self.last_click = Some((row, now));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Display row of the song list under the terminal cell (`column`, `row`).
+    fn list_row_at(&self, column: u16, row: u16) -> Option<usize> {
+        let area = self.list_area.get();
+        // Rows inside the border only.
+        let inside = column > area.x
+            && column + 1 < area.x + area.width
+            && row > area.y
+            && row + 1 < area.y + area.height;
+        if !inside {
+            return None;
+        }
+
+        let display_idx = self.list_state.borrow().offset() + usize::from(row - area.y - 1);
+        (display_idx < self.display_len()).then_some(display_idx)
+    }
+
+    /// Put the next older (or newer) remembered query in the search bar; stepping
+    /// newer than the newest clears the bar.
+    fn step_search_history(&mut self, older: bool, events: &mut Vec<UiEvent>) {
+        let cursor = match (self.history_cursor, older) {
+            (None, true) => 0,
+            (Some(i), true) => i + 1,
+            (Some(0), false) => {
+                self.history_cursor = None;
+                events.push(UiEvent::SearchQueryChanged { query: String::new() });
+                return;
+            }
+            (Some(i), false) => i - 1,
+            (None, false) => return,
+        };
+
+        if let Some(query) = self.search_history.get(cursor) {
+            self.history_cursor = Some(cursor);
+            events.push(UiEvent::SearchQueryChanged { query: query.clone() });
+        }
+    }
+
+    /// Show or hide the level meter; the backend only measures while it is shown.
+    fn toggle_visualizer(&mut self) {
+        if let Some(meter) = &self.level_meter {
+            self.show_visualizer = !self.show_visualizer;
+            meter.set_enabled(self.show_visualizer);
+        }
+    }
+
+    /// Switch between the flat list and the album-grouped list, keeping the selected song.
+    fn toggle_album_view(&mut self) {
+        let selected = self
+            .list_state
+            .borrow()
+            .selected()
+            .and_then(|display_idx| self.get_original_index(display_idx));
+
+        self.album_view = !self.album_view;
+        if self.album_view {
+            self.album_rows = build_album_rows(&self.songs);
+        }
+
+        let display_idx = match selected.and_then(|index| self.display_index_of(index)) {
+            Some(idx) => Some(idx),
+            None if self.display_len() > 0 => Some(self.skip_headers(0, true)),
+            None => None,
+        };
+        self.list_state.borrow_mut().select(display_idx);
+    }
 }
 
 impl UiRenderer for TuiRenderer {
     fn init(&mut self) -> Result<()> {
+        if self.compact {
+            enable_raw_mode()?;
+            self.terminal_guard = Some(TerminalGuard::new(false));
+            let options = TerminalOptions { viewport: Viewport::Inline(MINI_PLAYER_HEIGHT) };
+            self.terminal = Some(Terminal::with_options(CrosstermBackend::new(stdout()), options)?);
+            return Ok(());
+        }
+
+        self.graphics = GraphicsProtocol::detect();
         enable_raw_mode()?;
+        self.terminal_guard = Some(TerminalGuard::new(true));
         let mut stdout = stdout();
-        execute!(stdout, EnterAlternateScreen)?;
+        execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
         let backend = CrosstermBackend::new(stdout);
         self.terminal = Some(Terminal::new(backend)?);
         Ok(())
     }
 
     fn cleanup(&mut self) -> Result<()> {
-        disable_raw_mode()?;
         if let Some(mut terminal) = self.terminal.take() {
-            execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-            terminal.show_cursor()?;
+            if self.compact {
+                // Leave the prompt where the player was.
+                terminal.clear()?;
+            } else if let (Some(protocol), Some(_)) = (self.graphics, self.cover_placed.take()) {
+                write!(terminal.backend_mut(), "{}", protocol.clear_sequence())?;
+            }
+        }
+        match self.terminal_guard.take() {
+            Some(guard) => guard.release(),
+            None => Ok(()),
         }
-        Ok(())
     }
 
     fn render(&mut self, _state: &UiState) -> Result<()> {
+        if self.current_song.is_some() && !self.is_paused {
+            self.scroll_frames += 1;
+        }
+
         if let Some(mut terminal) = self.terminal.take() {
-            terminal.draw(|f| self.draw_ui(f))?;
+            if std::mem::take(&mut self.resized) {
+                // Some terminals keep stale cells after a resize; repaint from a blank
+                // screen, and resend the cover since it was cleared with it.
+                if let (Some(protocol), Some(_)) = (self.graphics, self.cover_placed.take()) {
+                    write!(terminal.backend_mut(), "{}", protocol.clear_sequence())?;
+                }
+                terminal.autoresize()?;
+                terminal.clear()?;
+            }
+            if self.compact {
+                terminal.draw(|f| self.draw_mini(f))?;
+            } else {
+                terminal.draw(|f| self.draw_ui(f))?;
+            }
+            let placed = self.place_cover(&mut terminal);
             self.terminal = Some(terminal);
+            placed?;
         }
         Ok(())
     }
 
     fn poll_input(&mut self, config: &KeyConfig) -> Result<Vec<UiEvent>> {
-        let mut events = Vec::new();
-
         if !self.key_config_synced {
             self.key_config = config.clone();
             self.key_config_synced = true;
         }
 
-        if !event::poll(Duration::from_millis(0))? {
-            return Ok(events);
-        }
-
-        let Event::Key(key) = event::read()? else {
-            return Ok(events);
-        };
+        Ok(self.handle_input_events(drain_pending()?, config))
+    }
 
-        let mode = self.current_mode();
+    fn wait_for_input(&mut self, timeout: Duration) -> Result<bool> {
+        wait_for_input(timeout)
+    }
 
-        if let Some(action) = map_key(mode, key, config) {
-            self.apply_action(action, &mut events);
+    fn list_scroll(&self) -> Option<usize> {
+        if self.compact {
+            return None;
         }
-
-        Ok(events)
+        // Searches and album rows are not restored, so their offsets mean nothing next time.
+        let flat = self.filtered().is_none() && !self.showing_albums();
+        Some(if flat { self.list_state.borrow().offset() } else { 0 })
     }
 
     fn update_state(&mut self, app_state: &crate::application::state::AppState) {
         // Sync playback state
+        if self.album_view && !Arc::ptr_eq(&self.songs, &app_state.library.songs) {
+            self.album_rows = build_album_rows(&app_state.library.songs);
+        }
+        let library_changed = !Arc::ptr_eq(&self.songs, &app_state.library.songs);
         self.songs = Arc::clone(&app_state.library.songs);  // Arc::clone so O(1)
+        if library_changed {
+            self.library_summary = library_summary(&self.songs);
+        }
+        if library_changed && !self.quick_filter.is_empty() {
+            // Indices from the old library are stale; the selection comes from AppState below.
+            self.quick_filter_results = self
+                .search_engine
+                .search(&self.songs, &self.quick_filter)
+                .into_iter()
+                .map(|r| r.index)
+                .collect();
+        }
+        self.history = app_state.history.clone();
+        if app_state.config.theme != self.theme_name {
+            self.theme_name = app_state.config.theme.clone();
+            self.theme = Theme::resolve(self.theme_name.as_deref(), self.custom_theme.as_ref());
+        }
+        self.progress_label = app_state.config.progress_label;
+        self.sleep_timer = app_state.playback.sleep_timer;
+        if app_state.playback.current_song.as_ref().map(|s| &s.path) != self.current_song.as_ref().map(|s| &s.path) {
+            // A new song starts scrolling from its beginning.
+            self.scroll_frames = 0;
+        }
         self.current_song = app_state.playback.current_song.clone();
+        self.scroll_titles = app_state.config.scroll_titles;
+        self.confirm_quit = app_state.config.confirm_quit;
+        self.frame_ms = app_state.config.frame_interval().as_millis() as u64;
+        self.current_index = app_state.playback.current_index;
+        if let Some(protocol) = self.graphics {
+            let source = self.current_song.as_ref().map(|song| &song.path);
+            if source != self.cover_source.as_ref() {
+                // Decode only when the song changes, never per frame.
+                self.cover_source = source.cloned();
+                self.cover = self.current_song.as_ref().and_then(|song| CoverImage::load(song, protocol));
+            }
+        }
         self.current_elapsed = app_state.playback.current_elapsed;
         self.is_paused = app_state.playback.is_paused;
+        let running = self.current_song.is_some() && !self.is_paused;
+        self.elapsed_clock.sync(self.current_elapsed, running, Instant::now());
 
         // Sync search state from AppState
         self.search_active = app_state.ui.search_active;
         self.search_query = app_state.ui.search_query.clone();
         self.search_results = app_state.ui.search_results.clone();
+        self.search_history = Arc::clone(&app_state.config.search_history);
 
         // Sync shuffle state
         self.shuffle = app_state.config.shuffle;
+        self.muted = app_state.config.is_muted();
+        self.volume = app_state.config.volume_percent;
         self.settings.sync_from_app_state(app_state);
 
         self.is_scanning   = app_state.library.is_scanning;
@@ -646,10 +1569,9 @@ impl UiRenderer for TuiRenderer {
 
         // Update selected index
         if let Some(index) = app_state.ui.selected_index {
-            // Map to display index (search results or full list)
-            if self.search_active && !self.search_results.is_empty() {
-                // Find position in search results
-                if let Some(pos) = self.search_results.iter().position(|&orig_idx| orig_idx == index) {
+            // Map to display index (search results, album rows or full list)
+            if self.filtered().is_some_and(|results| !results.is_empty()) || self.showing_albums() {
+                if let Some(pos) = self.display_index_of(index) {
                     self.list_state.borrow_mut().select(Some(pos));
                 }
             } else {
@@ -658,6 +1580,11 @@ impl UiRenderer for TuiRenderer {
         }
 
         self.active_sort    = app_state.library.active_sort;
+        self.queue_len      = app_state.playback.queue.len();
+        self.loop_a         = app_state.playback.loop_a;
+        self.loop_b         = app_state.playback.loop_b;
+        self.bookmarks = app_state.playback.current_song.as_ref()
+            .map_or_else(Vec::new, |song| app_state.library.bookmarks_for(&song.path).to_vec());
     }
 }
 
@@ -671,6 +1598,8 @@ impl TuiRenderer {
             }
         } else if self.search_active {
             InputMode::Search
+        } else if self.quick_filter_enabled {
+            InputMode::QuickFilter
         } else {
             InputMode::Normal
         }
@@ -678,16 +1607,52 @@ impl TuiRenderer {
 
     fn apply_action(&mut self, action: InputAction, events: &mut Vec<UiEvent>) {
         match action {
-            InputAction::Quit => events.push(UiEvent::QuitRequested),
+            InputAction::Quit => {
+                if self.confirm_quit && (self.current_song.is_some() || self.queue_len > 0) {
+                    self.show_quit_confirm = true;
+                } else {
+                    events.push(UiEvent::QuitRequested);
+                }
+            }
             InputAction::OpenSettings => self.settings.open(),
+            InputAction::JumpToPlaying => {
+                // Through the search results or album rows; nothing to do if the playing
+                // song is filtered out or nothing is playing.
+                if let Some(index) = self.current_index
+                    && let Some(row) = self.display_index_of(index)
+                {
+                    self.list_state.borrow_mut().select(Some(row));
+                    events.push(UiEvent::SelectionChanged { index });
+                }
+            }
+            InputAction::VolumeUp | InputAction::VolumeDown => {
+                let volume = if action == InputAction::VolumeUp {
+                    self.volume.saturating_add(VOLUME_STEP).min(VOLUME_MAX)
+                } else {
+                    self.volume.saturating_sub(VOLUME_STEP)
+                };
+                self.volume = volume;
+                events.push(UiEvent::VolumeChangeRequested { volume });
+            }
+            InputAction::ToggleHelp => {
+                self.show_help = true;
+                self.help_scroll.set(0);
+            }
+            InputAction::ShowRecent => {
+                self.recent = Some(recent_plays(&self.songs, self.history.recent.iter(), RECENT_PLAYS_MAX));
+                self.recent_selected = 0;
+            }
 
             InputAction::EnterSearch => {
                 if !self.songs.is_empty() {
+                    self.quick_filter.clear();
+                    self.history_cursor = None;
                     events.push(UiEvent::SearchToggled { active: true });
                 }
             }
             InputAction::SearchExit => events.push(UiEvent::SearchToggled { active: false }),
             InputAction::SearchClearLine => {
+                self.history_cursor = None;
                 events.push(UiEvent::SearchQueryChanged {
                     query: String::new(),
                 });
@@ -695,14 +1660,38 @@ impl TuiRenderer {
             InputAction::SearchBackspace => {
                 let mut q = self.search_query.clone();
                 q.pop();
+                self.history_cursor = None;
                 events.push(UiEvent::SearchQueryChanged { query: q });
             }
             InputAction::SearchAppend(c) => {
                 let mut q = self.search_query.clone();
                 q.push(c);
+                self.history_cursor = None;
                 events.push(UiEvent::SearchQueryChanged { query: q });
             }
+            InputAction::SearchHistoryPrev => self.step_search_history(true, events),
+            InputAction::SearchHistoryNext => self.step_search_history(false, events),
+            InputAction::SearchEnqueueAll => events.push(UiEvent::EnqueueAllRequested {
+                indices: self.search_results.clone(),
+            }),
 
+            InputAction::QuickFilterAppend(c) => {
+                self.quick_filter.push(c);
+                self.update_quick_filter(events);
+            }
+            InputAction::QuickFilterBackspace => {
+                if self.quick_filter.pop().is_some() {
+                    self.update_quick_filter(events);
+                }
+            }
+            InputAction::QuickFilterClear => self.quick_filter.clear(),
+
+            // With nothing typed there are no results to move through; recall a query instead.
+            InputAction::NavigateUp
+                if self.search_active && self.search_query.is_empty() && !self.search_history.is_empty() =>
+            {
+                self.step_search_history(true, events)
+            }
             InputAction::NavigateUp => {
                 if let Some(index) = self.navigate_up() {
                     events.push(UiEvent::SelectionChanged { index });
@@ -717,12 +1706,31 @@ impl TuiRenderer {
             InputAction::PlaySelected => events.push(UiEvent::PlaySelectedRequested),
             InputAction::TogglePause => events.push(UiEvent::TogglePauseRequested),
             InputAction::NextTrack => events.push(UiEvent::NextTrackRequested),
+            InputAction::RestartTrack => events.push(UiEvent::RestartTrackRequested),
             InputAction::PreviousTrack => events.push(UiEvent::PreviousTrackRequested),
             InputAction::ToggleShuffle => events.push(UiEvent::ShuffleToggled {
                 shuffle_enabled: self.shuffle,
             }),
+            InputAction::ToggleMute => events.push(UiEvent::MuteToggled),
             InputAction::Refresh => events.push(UiEvent::RefreshRequested),
             InputAction::CycleSort => events.push(UiEvent::SortCycleRequested),
+            InputAction::ToggleAlbumView => self.toggle_album_view(),
+            InputAction::ToggleVisualizer => self.toggle_visualizer(),
+            InputAction::EnqueueSelected => events.push(UiEvent::EnqueueSelectedRequested),
+            InputAction::RemoveSelected => events.push(UiEvent::RemoveSelectedRequested),
+            InputAction::ToggleFavorite => events.push(UiEvent::FavoriteToggleRequested),
+            InputAction::SeekForward => events.push(UiEvent::SeekByRequested { delta_secs: SEEK_STEP_SECS }),
+            InputAction::SeekBackward => events.push(UiEvent::SeekByRequested { delta_secs: -SEEK_STEP_SECS }),
+            InputAction::SeekForwardLong => events.push(UiEvent::SeekByRequested { delta_secs: SEEK_LONG_STEP_SECS }),
+            InputAction::SeekBackwardLong => events.push(UiEvent::SeekByRequested { delta_secs: -SEEK_LONG_STEP_SECS }),
+            InputAction::AddBookmark => events.push(UiEvent::BookmarkAddRequested),
+            InputAction::NextBookmark => events.push(UiEvent::NextBookmarkRequested),
+            InputAction::NextAlbum => events.push(UiEvent::AlbumSkipRequested { forward: true }),
+            InputAction::PreviousAlbum => events.push(UiEvent::AlbumSkipRequested { forward: false }),
+            InputAction::LoopSetA => events.push(UiEvent::SetLoopPointA),
+            InputAction::LoopSetB => events.push(UiEvent::SetLoopPointB),
+            InputAction::LoopClear => events.push(UiEvent::LoopCleared),
+            InputAction::CycleSleepTimer => events.push(UiEvent::SleepTimerCycleRequested),
 
             InputAction::SettingsClose
             | InputAction::SettingsNavigateUp
@@ -743,43 +1751,157 @@ fn active_sort_label(active_sort: Option<SortField>) -> &'static str {
         Some(SortField::Title)    => "[↑ title]",
         Some(SortField::Artist)   => "[↑ artist]",
         Some(SortField::Album)    => "[↑ album]",
+        Some(SortField::Track)    => "[↑ track]",
         Some(SortField::Duration) => "[↑ duration]",
     }
 }
 
-fn truncate_str(s: &str, max_chars: usize) -> String {
-    if max_chars == 0 {
-        return String::new();
+/// "12 songs • 00:48:10 total", noting when some songs' lengths are unknown and left out.
+fn library_summary(songs: &[crate::core::models::Song]) -> String {
+    let total: Duration = songs.iter().filter_map(|song| song.duration).sum();
+    let mut summary = format!(
+        "{} song{} • {} total",
+        songs.len(),
+        if songs.len() == 1 { "" } else { "s" },
+        format_duration(total)
+    );
+    if songs.iter().any(|song| song.duration.is_none()) {
+        summary.push_str(" (some durations unknown)");
+    }
+    summary
+}
+
+/// Keys the mini player acts on; the rest need the song list or an overlay it does not draw.
+fn works_in_mini(action: InputAction) -> bool {
+    matches!(
+        action,
+        InputAction::Quit
+            | InputAction::PlaySelected
+            | InputAction::TogglePause
+            | InputAction::NextTrack
+            | InputAction::PreviousTrack
+            | InputAction::ToggleShuffle
+            | InputAction::ToggleMute
+            | InputAction::VolumeUp
+            | InputAction::VolumeDown
+            | InputAction::CycleSleepTimer
+            | InputAction::RestartTrack
+            | InputAction::ToggleFavorite
+            | InputAction::SeekForward
+            | InputAction::SeekBackward
+            | InputAction::SeekForwardLong
+            | InputAction::SeekBackwardLong
+            | InputAction::AddBookmark
+            | InputAction::NextBookmark
+            | InputAction::NextAlbum
+            | InputAction::PreviousAlbum
+            | InputAction::LoopSetA
+            | InputAction::LoopSetB
+            | InputAction::LoopClear
+    )
+}
+
+/// " 🔊 ▮▮▮▮▮▯▯▯▯▯ 50% " for the now-playing title bar.
+fn volume_line(volume: u8, muted: bool, theme: &Theme) -> Line<'static> {
+    if muted {
+        return Line::from(Span::styled(" 🔇 muted ", Style::default().fg(theme.error)));
     }
-    let mut chars = s.chars();
-    let mut out = String::with_capacity(max_chars);
-    for _ in 0..max_chars {
-        match chars.next() {
-            None => return out,     // fits in full
-            Some(c) => out.push(c),
+
+    let filled = (usize::from(volume) * VOLUME_BAR_WIDTH).div_ceil(usize::from(VOLUME_MAX));
+    Line::from(vec![
+        Span::raw(" 🔊 "),
+        Span::styled("▮".repeat(filled), Style::default().fg(theme.progress_filled)),
+        Span::styled(
+            "▯".repeat(VOLUME_BAR_WIDTH - filled),
+            Style::default().fg(theme.progress_empty),
+        ),
+        Span::styled(format!(" {:>3}% ", volume), Style::default().fg(theme.dim)),
+    ])
+}
+
+/// Indeterminate progress bar for songs of unknown length, `width` cells wide.
+fn pulse_bar(width: u16, elapsed: Duration, theme: &Theme) -> Paragraph<'static> {
+    let width = usize::from(width);
+    let (start, len) = pulse_segment(width, elapsed);
+    let empty = Style::default().fg(theme.progress_empty);
+
+    Paragraph::new(Line::from(vec![
+        Span::styled("─".repeat(start), empty),
+        Span::styled("█".repeat(len), Style::default().fg(theme.progress_filled)),
+        Span::styled("─".repeat(width - start - len), empty),
+    ]))
+}
+
+fn album_header_item(album: &str, count: usize, theme: &Theme) -> ListItem<'static> {
+    ListItem::new(Line::from(vec![
+        Span::styled(
+            format!("── {} ", album),
+            Style::default().fg(theme.album).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!("({} song{})", count, if count == 1 { "" } else { "s" }),
+            Style::default().fg(theme.faint),
+        ),
+    ]))
+}
+
+/// `width` chars of `spans`, starting `offset` chars in and wrapping round after a
+/// `MARQUEE_GAP` of blanks. Each char keeps its span's style. Text that fits is
+/// returned unchanged, whatever the offset.
+fn marquee(spans: Vec<Span<'static>>, width: usize, offset: usize) -> Vec<Span<'static>> {
+    let chars: Vec<(char, Style)> = spans
+        .iter()
+        .flat_map(|span| span.content.chars().map(move |c| (c, span.style)))
+        .chain(std::iter::repeat_n((' ', Style::default()), MARQUEE_GAP))
+        .collect();
+    if chars.len() - MARQUEE_GAP <= width {
+        return spans;
+    }
+
+    let mut out: Vec<Span<'static>> = Vec::new();
+    for &(c, style) in chars.iter().cycle().skip(offset % chars.len()).take(width) {
+        match out.last_mut() {
+            Some(last) if last.style == style => last.content.to_mut().push(c),
+            _ => out.push(Span::styled(c.to_string(), style)),
         }
     }
-    // There are still characters left, we truncated.
-    out.pop();
-    out.push('…');
     out
 }
 
+/// Position in a track of length `total` under the cell (`column`, `row`) of
+/// its progress `bar`, or `None` for a cell outside the bar.
+fn seek_target(bar: Rect, total: Duration, column: u16, row: u16) -> Option<Duration> {
+    let inside = column >= bar.x && column < bar.x + bar.width && row >= bar.y && row < bar.y + bar.height;
+    inside.then(|| total.mul_f64(f64::from(column - bar.x) / f64::from(bar.width)))
+}
 
-fn song_list_item(num: Option<usize>, song: &crate::core::models::Song, is_current: bool, available_width: u16) -> ListItem<'static> {
+fn song_list_item(
+    num: Option<usize>,
+    song: &crate::core::models::Song,
+    plays: u32,
+    is_current: bool,
+    available_width: u16,
+    theme: &Theme,
+) -> ListItem<'static> {
     const SEP: &str = "  ·  ";       // 5 chars
     const INDEX_WIDTH: usize = 6;    // "  1.  "
     const DURATION_WIDTH: usize = 10; // "  [59:59]" worst case
+    const PLAYS_WIDTH: usize = 7;    // "  ▶999" typical
 
     let has_artist = !song.artists.is_empty();
     let has_album  = song.album.is_some();
 
     let sep_count = has_artist as usize + has_album as usize;
     let dur_width = if song.duration.is_some() { DURATION_WIDTH } else { 0 };
+    let plays_width = if plays > 0 { PLAYS_WIDTH } else { 0 };
+    let marks = song.rating_marks();
+    let marks_width = if marks.is_empty() { 0 } else { marks.chars().count() + 2 };
 
     let text_space = (available_width as usize)
         .saturating_sub(INDEX_WIDTH)
         .saturating_sub(dur_width)
+        .saturating_sub(plays_width)
+        .saturating_sub(marks_width)
         .saturating_sub(sep_count * SEP.len());
 
     // Percentage split of the remaining text space
@@ -802,30 +1924,30 @@ fn song_list_item(num: Option<usize>, song: &crate::core::models::Song, is_curre
         .map(|a| truncate_str(a, album_max));
 
     // ── Styles ────────────────────────────────────────────────────────────
-    // When the song is currently playing every colored element turns green
-    // The index and duration are always Gray — structural, not content
+    // When the song is currently playing every colored element takes the playing color
+    // The index and duration are always dim — structural, not content
     let (title_style, artist_style, album_style, sep_style) = if is_current {
         (
-            Style::default().fg(Color::LightGreen).add_modifier(Modifier::BOLD),
-            Style::default().fg(Color::Green),
-            Style::default().fg(Color::Green),
-            Style::default().fg(Color::Green),
+            Style::default().fg(theme.playing_title).add_modifier(Modifier::BOLD),
+            Style::default().fg(theme.playing),
+            Style::default().fg(theme.playing),
+            Style::default().fg(theme.playing),
         )
     } else {
         (
-            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
-            Style::default().fg(Color::Cyan),
-            Style::default().fg(Color::LightBlue),
-            Style::default().fg(Color::Gray),
+            Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+            Style::default().fg(theme.artist),
+            Style::default().fg(theme.album_column),
+            Style::default().fg(theme.dim),
         )
     };
 
-    // Gray for structural/positional text; visible on both black bg and
-    // DarkGray highlight bg without clashing with content colors
-    let structural = Style::default().fg(Color::Gray);
+    // Dim for structural/positional text; visible on both the background and
+    // the highlight bg without clashing with content colors
+    let structural = Style::default().fg(theme.dim);
 
     // ── Assemble ─────────────────────────────────────────────────────────
-    let mut spans: Vec<Span> = Vec::with_capacity(9);
+    let mut spans: Vec<Span> = Vec::with_capacity(10);
 
     match num {
         Some(n) => spans.push(Span::styled(format!("{:3}.  ", n), structural)),
@@ -843,6 +1965,14 @@ fn song_list_item(num: Option<usize>, song: &crate::core::models::Song, is_curre
         spans.push(Span::styled(al, album_style));
     }
 
+    if !marks.is_empty() {
+        spans.push(Span::styled(format!("  {}", marks), Style::default().fg(theme.accent)));
+    }
+
+    if plays > 0 {
+        spans.push(Span::styled(format!("  ▶{}", plays), structural));
+    }
+
     if song.duration.is_some() {
         spans.push(Span::styled(
             format!("  [{}]", song.format_duration()),
@@ -852,3 +1982,240 @@ fn song_list_item(num: Option<usize>, song: &crate::core::models::Song, is_curre
 
     ListItem::new(Line::from(spans))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(spans: &[Span]) -> String {
+        spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn marquee_scrolls_only_overflowing_text_and_wraps_after_a_gap() {
+        let short = marquee(vec![Span::raw("Intro")], 10, 3);
+        assert_eq!(text(&short), "Intro", "text that fits never moves");
+
+        let long = || vec![Span::raw("Long"), Span::styled("Title", Style::default().fg(ratatui::style::Color::Red))];
+        assert_eq!(text(&marquee(long(), 6, 0)), "LongTi");
+        assert_eq!(text(&marquee(long(), 6, 6)), "tle   ");
+        assert_eq!(text(&marquee(long(), 6, 9 + MARQUEE_GAP)), "LongTi", "one full cycle is back at the start");
+
+        let styled = marquee(long(), 6, 2);
+        assert_eq!(styled.len(), 2, "runs of one style stay one span");
+        assert_eq!(styled[1].content, "Titl");
+    }
+
+    #[test]
+    fn seek_target_maps_a_click_across_the_bar_onto_the_track() {
+        let bar = Rect { x: 10, y: 20, width: 50, height: 1 };
+        let total = Duration::from_secs(200);
+
+        assert_eq!(seek_target(bar, total, 10, 20), Some(Duration::ZERO));
+        assert_eq!(seek_target(bar, total, 35, 20), Some(Duration::from_secs(100)));
+        assert_eq!(seek_target(bar, total, 59, 20), Some(Duration::from_secs(196)));
+        assert_eq!(seek_target(bar, total, 9, 20), None, "left of the bar");
+        assert_eq!(seek_target(bar, total, 60, 20), None, "right of the bar");
+        assert_eq!(seek_target(bar, total, 35, 21), None, "below the bar");
+    }
+
+    #[test]
+    fn up_on_an_empty_search_recalls_queries_and_ctrl_n_steps_back() {
+        let mut renderer = TuiRenderer::new();
+        renderer.search_active = true;
+        renderer.search_history = Arc::new(vec!["newest".to_owned(), "older".to_owned()]);
+        let recall = |renderer: &mut TuiRenderer, action| {
+            let mut events = Vec::new();
+            renderer.apply_action(action, &mut events);
+            match events.as_slice() {
+                [UiEvent::SearchQueryChanged { query }] => {
+                    renderer.search_query = query.clone();
+                    Some(query.clone())
+                }
+                _ => None,
+            }
+        };
+
+        assert_eq!(recall(&mut renderer, InputAction::NavigateUp).as_deref(), Some("newest"));
+        assert_eq!(recall(&mut renderer, InputAction::SearchHistoryPrev).as_deref(), Some("older"));
+        assert_eq!(recall(&mut renderer, InputAction::SearchHistoryPrev), None, "no older entry");
+        assert_eq!(recall(&mut renderer, InputAction::SearchHistoryNext).as_deref(), Some("newest"));
+        assert_eq!(recall(&mut renderer, InputAction::SearchHistoryNext).as_deref(), Some(""));
+        assert_eq!(recall(&mut renderer, InputAction::NavigateUp).as_deref(), Some("newest"));
+        assert_eq!(recall(&mut renderer, InputAction::NavigateUp), None, "with a query, Up moves through results");
+    }
+
+    #[test]
+    fn a_burst_of_held_arrow_keys_moves_once_per_tick() {
+        use crossterm::event::{KeyEvent, KeyEventKind, KeyModifiers};
+
+        let mut renderer = TuiRenderer::new();
+        let songs = (0..10).map(|i| crate::core::models::Song::from_path(&PathBuf::from(format!("/m/{i}.mp3"))));
+        renderer.set_songs(Arc::new(songs.collect()));
+        let down = |kind| Event::Key(KeyEvent::new_with_kind(KeyCode::Down, KeyModifiers::NONE, kind));
+        let burst = vec![
+            down(KeyEventKind::Press),
+            down(KeyEventKind::Repeat),
+            down(KeyEventKind::Repeat),
+            down(KeyEventKind::Release),
+        ];
+
+        let events = renderer.handle_input_events(burst, &KeyConfig::default());
+
+        assert!(matches!(events[..], [UiEvent::SelectionChanged { index: 3 }]), "got {:?}", events);
+    }
+
+    #[test]
+    fn enqueue_all_keeps_the_relevance_order() {
+        let mut renderer = TuiRenderer::new();
+        renderer.search_active = true;
+        renderer.search_results = vec![4, 0, 2];
+        let mut events = Vec::new();
+
+        renderer.apply_action(InputAction::SearchEnqueueAll, &mut events);
+
+        assert!(matches!(events.as_slice(), [UiEvent::EnqueueAllRequested { indices }] if indices == &[4, 0, 2]));
+    }
+
+    #[test]
+    fn shrinking_the_terminal_keeps_the_selection_in_view() {
+        use ratatui::backend::TestBackend;
+
+        let mut renderer = TuiRenderer::new();
+        let songs = (0..40).map(|i| crate::core::models::Song::from_path(&PathBuf::from(format!("/m/{i}.mp3"))));
+        renderer.set_songs(Arc::new(songs.collect()));
+        renderer.list_state.borrow_mut().select(Some(30));
+        let mut terminal = Terminal::new(TestBackend::new(80, 60)).unwrap();
+        terminal.draw(|f| renderer.draw_ui(f)).unwrap();
+
+        let mut events = Vec::new();
+        renderer.handle_input_event(Event::Resize(80, 20), &KeyConfig::default(), &mut events);
+        assert!(events.is_empty() && renderer.resized);
+        terminal.backend_mut().resize(80, 20);
+        terminal.draw(|f| renderer.draw_ui(f)).unwrap();
+
+        let rows = usize::from(renderer.list_area.get().height - 2);
+        let offset = renderer.list_state.borrow().offset();
+        assert!((offset..offset + rows).contains(&30), "row 30 hidden at offset {} with {} rows", offset, rows);
+    }
+
+    #[test]
+    fn tiny_terminals_draw_without_panicking() {
+        use ratatui::backend::TestBackend;
+
+        let mut renderer = TuiRenderer::new();
+        let songs: Vec<_> = (0..40).map(|i| crate::core::models::Song::from_path(&PathBuf::from(format!("/m/{i}.mp3")))).collect();
+        renderer.current_song = Some(songs[3].clone());
+        renderer.set_songs(Arc::new(songs));
+        renderer.list_state.borrow_mut().select(Some(30));
+
+        for overlay in 0..5 {
+            renderer.search_active = overlay == 1;
+            renderer.show_help = overlay == 2;
+            renderer.recent = (overlay == 3).then(Vec::new);
+            if overlay == 4 {
+                renderer.settings.open();
+            }
+            for width in [0, 1, 4, 12, 30] {
+                for height in [0, 1, 3, 6, 10, 16] {
+                    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+                    terminal.draw(|f| renderer.draw_ui(f)).unwrap();
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn short_terminals_get_a_size_hint_instead_of_a_squashed_player() {
+        use ratatui::backend::TestBackend;
+
+        let renderer = TuiRenderer::new();
+        let screen = |height| {
+            let mut terminal = Terminal::new(TestBackend::new(40, height)).unwrap();
+            terminal.draw(|f| renderer.draw_ui(f)).unwrap();
+            let buffer = terminal.backend().buffer().clone();
+            buffer.content().iter().map(|cell| cell.symbol()).collect::<String>()
+        };
+
+        assert!(screen(13).contains("Terminal too small"));
+        assert!(screen(13).contains("40x13, need 30x14"));
+        assert!(screen(14).contains("Library (0 songs"), "three fixed panels and one list row fit");
+    }
+
+    #[test]
+    fn the_mini_player_fits_two_rows_and_ignores_list_keys() {
+        use crossterm::event::{KeyEvent, KeyModifiers};
+        use ratatui::backend::TestBackend;
+
+        let mut renderer = TuiRenderer::new();
+        renderer.set_compact(true);
+        renderer.current_song = Some(crate::core::models::Song {
+            title: "Intro".to_owned(),
+            artists: vec!["Queen".to_owned()],
+            duration: Some(Duration::from_secs(200)),
+            ..crate::core::models::Song::from_path(&PathBuf::from("/m/intro.mp3"))
+        });
+        let rows = |width| {
+            let mut terminal = Terminal::new(TestBackend::new(width, MINI_PLAYER_HEIGHT)).unwrap();
+            terminal.draw(|f| renderer.draw_mini(f)).unwrap();
+            let buffer = terminal.backend().buffer().clone();
+            (0..MINI_PLAYER_HEIGHT)
+                .map(|y| (0..width).map(|x| buffer[(x, y)].symbol()).collect::<String>())
+                .collect::<Vec<_>>()
+        };
+
+        let wide = rows(80);
+        assert!(wide[0].starts_with("▶ Intro — Queen"), "{:?}", wide[0]);
+        assert!(wide[0].trim_end().ends_with("q quit"), "{:?}", wide[0]);
+        assert!(wide[1].starts_with("00:00") && wide[1].trim_end().ends_with("03:20"), "{:?}", wide[1]);
+        assert!(!rows(24)[0].contains("quit"), "hints give way on narrow terminals");
+
+        let mut events = Vec::new();
+        for code in [KeyCode::Down, KeyCode::Char('/'), KeyCode::Char('n')] {
+            renderer.handle_input_event(Event::Key(KeyEvent::new(code, KeyModifiers::NONE)), &KeyConfig::default(), &mut events);
+        }
+        assert!(matches!(events[..], [UiEvent::NextTrackRequested]), "got {:?}", events);
+    }
+
+    #[test]
+    fn quitting_mid_song_waits_for_confirmation_when_asked_to() {
+        use crossterm::event::{KeyEvent, KeyModifiers};
+
+        let mut renderer = TuiRenderer::new();
+        let press = |renderer: &mut TuiRenderer, code| {
+            let mut events = Vec::new();
+            renderer.handle_input_event(Event::Key(KeyEvent::new(code, KeyModifiers::NONE)), &KeyConfig::default(), &mut events);
+            events
+        };
+
+        renderer.confirm_quit = true;
+        assert!(matches!(press(&mut renderer, KeyCode::Char('q'))[..], [UiEvent::QuitRequested]), "nothing to lose");
+
+        renderer.current_song = Some(crate::core::models::Song::from_path(&PathBuf::from("/m/a.mp3")));
+        assert!(press(&mut renderer, KeyCode::Char('q')).is_empty());
+        assert!(renderer.show_quit_confirm);
+        assert!(press(&mut renderer, KeyCode::Char('n')).is_empty(), "any other key cancels");
+        assert!(!renderer.show_quit_confirm);
+
+        press(&mut renderer, KeyCode::Char('q'));
+        assert!(matches!(press(&mut renderer, KeyCode::Char('y'))[..], [UiEvent::QuitRequested]));
+
+        renderer.confirm_quit = false;
+        assert!(matches!(press(&mut renderer, KeyCode::Char('q'))[..], [UiEvent::QuitRequested]), "off by default");
+    }
+
+    #[test]
+    fn the_library_summary_adds_up_known_lengths() {
+        let song = |secs: Option<u64>| crate::core::models::Song {
+            duration: secs.map(Duration::from_secs),
+            ..crate::core::models::Song::from_path(&PathBuf::from("/m/a.mp3"))
+        };
+
+        assert_eq!(library_summary(&[]), "0 songs • 00:00 total");
+        assert_eq!(library_summary(&[song(Some(3_000)), song(Some(700))]), "2 songs • 01:01:40 total");
+        assert_eq!(
+            library_summary(&[song(Some(90)), song(None)]),
+            "2 songs • 01:30 total (some durations unknown)"
+        );
+    }
+}
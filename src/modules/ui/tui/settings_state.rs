@@ -2,11 +2,18 @@ use crate::application::state::AppState;
 use crate::core::events::UiEvent;
 use crate::core::models::RepeatMode;
 use crate::modules::input::InputAction;
-use crate::utils::{amplitude_to_volume, VOLUME_MAX, VOLUME_STEP};
+use crate::modules::playback::equalizer::{EqGains, EQ_BANDS};
+use crate::modules::ui::progress_formatter::ProgressLabel;
+use crate::modules::ui::tui::theme::{CUSTOM_THEME, PRESET_NAMES};
+use crate::utils::{CROSSFADE_MAX_SECS, VOLUME_MAX, VOLUME_STEP};
 
 const SETTINGS_FIELDS: &[SettingsField] = &[
     SettingsField::Volume,
     SettingsField::Repeat,
+    SettingsField::Crossfade,
+    SettingsField::Equalizer,
+    SettingsField::Theme,
+    SettingsField::ProgressLabel,
     SettingsField::MusicPath,
 ];
 
@@ -15,6 +22,10 @@ pub enum SettingsField {
     MusicPath,
     Volume,
     Repeat,
+    Crossfade,
+    Equalizer,
+    Theme,
+    ProgressLabel,
 }
 
 /// Inline validation state for the path field.
@@ -37,9 +48,29 @@ pub struct SettingsState {
 
     temp_repeat: RepeatMode,
 
+    temp_crossfade: u8,
+
+    temp_eq: EqGains,
+    /// Band the equalizer row adjusts, an index into `EQ_BANDS`.
+    eq_band: usize,
+
+    /// Themes the user can cycle through; `custom` only when `theme.toml` exists.
+    theme_names: Vec<&'static str>,
+    temp_theme: &'static str,
+
+    temp_progress_label: ProgressLabel,
+
+    /// Configured library roots, as shown in the Music Paths row.
+    roots: Vec<String>,
+    /// Root that Backspace removes, an index into `roots`.
+    root_cursor: usize,
+
+    /// Typing a directory to add to `roots`.
     editing_path: bool,
     temp_path: String,
     path_validation: PathValidation,
+    /// The roots changed but the library has not been rescanned from them yet.
+    needs_refresh: bool,
 }
 
 impl Default for SettingsState {
@@ -50,9 +81,18 @@ impl Default for SettingsState {
             editing_volume: false,
             temp_volume: VOLUME_MAX,
             temp_repeat: RepeatMode::default(),
+            temp_crossfade: 0,
+            temp_eq: EqGains::default(),
+            eq_band: 0,
+            theme_names: PRESET_NAMES.to_vec(),
+            temp_theme: PRESET_NAMES[0],
+            temp_progress_label: ProgressLabel::default(),
+            roots: Vec::new(),
+            root_cursor: 0,
             editing_path: false,
             temp_path: String::new(),
             path_validation: PathValidation::Idle,
+            needs_refresh: false,
         }
     }
 }
@@ -71,6 +111,7 @@ impl SettingsState {
         self.editing_volume = false;
         self.editing_path = false;
         self.path_validation = PathValidation::Idle;
+        self.needs_refresh = false;
     }
 
     pub fn selected(&self) -> SettingsField {
@@ -93,6 +134,38 @@ impl SettingsState {
         self.temp_repeat
     }
 
+    pub fn temp_crossfade(&self) -> u8 {
+        self.temp_crossfade
+    }
+
+    pub fn temp_eq(&self) -> EqGains {
+        self.temp_eq
+    }
+
+    pub fn eq_band(&self) -> usize {
+        self.eq_band
+    }
+
+    pub fn temp_theme(&self) -> &'static str {
+        self.temp_theme
+    }
+
+    pub fn temp_progress_label(&self) -> ProgressLabel {
+        self.temp_progress_label
+    }
+
+    pub fn set_theme_names(&mut self, names: Vec<&'static str>) {
+        self.theme_names = names;
+    }
+
+    pub fn roots(&self) -> &[String] {
+        &self.roots
+    }
+
+    pub fn root_cursor(&self) -> usize {
+        self.root_cursor
+    }
+
     pub fn temp_path(&self) -> &str {
         &self.temp_path
     }
@@ -101,21 +174,75 @@ impl SettingsState {
         &self.path_validation
     }
 
+    pub fn needs_refresh(&self) -> bool {
+        self.needs_refresh
+    }
+
     pub fn sync_from_app_state(&mut self, app_state: &AppState) {
         self.temp_repeat = app_state.config.repeat;
+        self.temp_crossfade = app_state.config.crossfade_secs;
+        self.temp_eq = app_state.config.eq;
+        self.temp_theme = self.configured_theme(app_state.config.theme.as_deref());
+        self.temp_progress_label = app_state.config.progress_label;
+
+        self.roots = app_state
+            .config
+            .root_paths
+            .iter()
+            .map(|p| p.to_string_lossy().into_owned())
+            .collect();
+        self.root_cursor = self.root_cursor.min(self.roots.len().saturating_sub(1));
 
-        if !self.editing_path {
-            self.temp_path = app_state
-                .config
-                .root_path
-                .as_ref()
-                .map(|p| p.to_string_lossy().into_owned())
-                .unwrap_or_default();
+        if !self.editing_volume || self.selected != SettingsField::Volume {
+            self.temp_volume = app_state.config.volume_percent;
         }
+    }
 
-        if !self.editing_volume || self.selected != SettingsField::Volume {
-            self.temp_volume = amplitude_to_volume(app_state.config.volume);
+    /// Name shown for the configured theme, falling back the same way the renderer does.
+    fn configured_theme(&self, configured: Option<&str>) -> &'static str {
+        let fallback = if self.theme_names.contains(&CUSTOM_THEME) {
+            CUSTOM_THEME
+        } else {
+            PRESET_NAMES[0]
+        };
+
+        configured
+            .and_then(|name| self.theme_names.iter().copied().find(|n| *n == name))
+            .unwrap_or(fallback)
+    }
+
+    /// Step to the neighbouring theme (wrapping) and request the switch.
+    fn cycle_theme(&mut self, forward: bool, events: &mut Vec<UiEvent>) {
+        let len = self.theme_names.len();
+        if len == 0 {
+            return;
         }
+
+        let current = self
+            .theme_names
+            .iter()
+            .position(|n| *n == self.temp_theme)
+            .unwrap_or(0);
+        let next = if forward { (current + 1) % len } else { (current + len - 1) % len };
+        self.temp_theme = self.theme_names[next];
+        events.push(UiEvent::ThemeChangeRequested {
+            name: self.temp_theme.to_string(),
+        });
+    }
+
+    /// Move the focused equalizer band by `step` dB, staying within range.
+    fn adjust_eq(&mut self, step: i8, events: &mut Vec<UiEvent>) {
+        let current = self.temp_eq.band(self.eq_band);
+        let gains = self.temp_eq.with_band(self.eq_band, current.saturating_add(step));
+        if gains != self.temp_eq {
+            self.temp_eq = gains;
+            events.push(UiEvent::EqChangeRequested { gains });
+        }
+    }
+
+    fn set_progress_label(&mut self, label: ProgressLabel, events: &mut Vec<UiEvent>) {
+        self.temp_progress_label = label;
+        events.push(UiEvent::ProgressLabelChangeRequested { label });
     }
 
     pub fn apply_action(&mut self, action: InputAction) -> Vec<UiEvent> {
@@ -197,7 +324,8 @@ impl SettingsState {
                 } else {
                     self.editing_path = false;
                     self.path_validation = PathValidation::Idle;
-                    events.push(UiEvent::PathChangeRequested {
+                    self.needs_refresh = true;
+                    events.push(UiEvent::PathAddRequested {
                         path: path.to_path_buf(),
                     });
                 }
@@ -241,11 +369,43 @@ impl SettingsState {
                         mode: self.temp_repeat,
                     });
                 }
+                SettingsField::Crossfade => {
+                    // Enter steps up one second, wrapping back to off past the maximum.
+                    self.temp_crossfade = if self.temp_crossfade >= CROSSFADE_MAX_SECS {
+                        0
+                    } else {
+                        self.temp_crossfade + 1
+                    };
+                    events.push(UiEvent::CrossfadeChangeRequested {
+                        secs: self.temp_crossfade,
+                    });
+                }
+                SettingsField::Equalizer => {
+                    self.eq_band = (self.eq_band + 1) % EQ_BANDS.len();
+                }
+                SettingsField::Theme => self.cycle_theme(true, events),
+                SettingsField::ProgressLabel => self.set_progress_label(self.temp_progress_label.cycle(), events),
                 SettingsField::MusicPath => {
                     self.editing_path = true;
+                    self.temp_path.clear();
                     self.path_validation = PathValidation::Idle;
+                    self.needs_refresh = false;
                 }
             },
+            InputAction::SettingsLeft if self.selected == SettingsField::MusicPath => {
+                self.root_cursor = self.root_cursor.saturating_sub(1);
+            }
+            InputAction::SettingsRight if self.selected == SettingsField::MusicPath => {
+                self.root_cursor = (self.root_cursor + 1).min(self.roots.len().saturating_sub(1));
+            }
+            InputAction::SettingsBackspace
+                if self.selected == SettingsField::MusicPath && self.root_cursor < self.roots.len() =>
+            {
+                let root = self.roots.remove(self.root_cursor);
+                self.root_cursor = self.root_cursor.min(self.roots.len().saturating_sub(1));
+                self.needs_refresh = true;
+                events.push(UiEvent::PathRemoveRequested { path: root.into() });
+            }
             InputAction::SettingsLeft if self.selected == SettingsField::Repeat => {
                 self.temp_repeat = self.temp_repeat.cycle_back();
                 events.push(UiEvent::RepeatChangeRequested {
@@ -258,6 +418,41 @@ impl SettingsState {
                     mode: self.temp_repeat,
                 });
             }
+            InputAction::SettingsLeft
+                if self.selected == SettingsField::Crossfade && self.temp_crossfade > 0 =>
+            {
+                self.temp_crossfade -= 1;
+                events.push(UiEvent::CrossfadeChangeRequested {
+                    secs: self.temp_crossfade,
+                });
+            }
+            InputAction::SettingsRight
+                if self.selected == SettingsField::Crossfade
+                    && self.temp_crossfade < CROSSFADE_MAX_SECS =>
+            {
+                self.temp_crossfade += 1;
+                events.push(UiEvent::CrossfadeChangeRequested {
+                    secs: self.temp_crossfade,
+                });
+            }
+            InputAction::SettingsLeft if self.selected == SettingsField::Equalizer => {
+                self.adjust_eq(-1, events);
+            }
+            InputAction::SettingsRight if self.selected == SettingsField::Equalizer => {
+                self.adjust_eq(1, events);
+            }
+            InputAction::SettingsLeft if self.selected == SettingsField::Theme => {
+                self.cycle_theme(false, events);
+            }
+            InputAction::SettingsRight if self.selected == SettingsField::Theme => {
+                self.cycle_theme(true, events);
+            }
+            InputAction::SettingsLeft if self.selected == SettingsField::ProgressLabel => {
+                self.set_progress_label(self.temp_progress_label.cycle_back(), events);
+            }
+            InputAction::SettingsRight if self.selected == SettingsField::ProgressLabel => {
+                self.set_progress_label(self.temp_progress_label.cycle(), events);
+            }
             _ => {}
         }
     }
@@ -268,6 +463,7 @@ mod tests {
     use super::*;
     use crate::core::events::UiEvent;
     use crate::modules::input::InputAction;
+    use crate::utils::EQ_MAX_DB;
 
     fn open_and_select_repeat(s: &mut SettingsState) {
         s.open();
@@ -275,10 +471,34 @@ mod tests {
         assert_eq!(s.selected(), SettingsField::Repeat);
     }
 
-    fn open_and_select_path(s: &mut SettingsState) {
+    fn open_and_select_crossfade(s: &mut SettingsState) {
         s.open();
         s.apply_action(InputAction::SettingsNavigateDown);
         s.apply_action(InputAction::SettingsNavigateDown);
+        assert_eq!(s.selected(), SettingsField::Crossfade);
+    }
+
+    fn open_and_select_equalizer(s: &mut SettingsState) {
+        open_and_select_crossfade(s);
+        s.apply_action(InputAction::SettingsNavigateDown);
+        assert_eq!(s.selected(), SettingsField::Equalizer);
+    }
+
+    fn open_and_select_theme(s: &mut SettingsState) {
+        open_and_select_equalizer(s);
+        s.apply_action(InputAction::SettingsNavigateDown);
+        assert_eq!(s.selected(), SettingsField::Theme);
+    }
+
+    fn open_and_select_progress_label(s: &mut SettingsState) {
+        open_and_select_theme(s);
+        s.apply_action(InputAction::SettingsNavigateDown);
+        assert_eq!(s.selected(), SettingsField::ProgressLabel);
+    }
+
+    fn open_and_select_path(s: &mut SettingsState) {
+        open_and_select_progress_label(s);
+        s.apply_action(InputAction::SettingsNavigateDown);
         assert_eq!(s.selected(), SettingsField::MusicPath);
     }
 
@@ -371,11 +591,34 @@ mod tests {
         assert!(!s.is_editing_path());
         assert!(matches!(s.path_validation(), PathValidation::Idle));
         assert_eq!(events.len(), 1);
-        assert!(matches!(events[0], UiEvent::PathChangeRequested { .. }));
+        assert!(matches!(events[0], UiEvent::PathAddRequested { .. }));
+        assert!(s.needs_refresh(), "a new path only takes effect after a rescan");
+
+        s.close();
+        assert!(!s.needs_refresh());
 
         let _ = std::fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn backspace_removes_the_picked_root() {
+        let mut s = SettingsState::default();
+        let mut state = AppState::default();
+        state.config.root_paths = vec!["/music".into(), "/podcasts".into()];
+        s.sync_from_app_state(&state);
+        open_and_select_path(&mut s);
+
+        s.apply_action(InputAction::SettingsRight);
+        s.apply_action(InputAction::SettingsRight);
+        assert_eq!(s.root_cursor(), 1, "the cursor stops at the last root");
+
+        let events = s.apply_action(InputAction::SettingsBackspace);
+
+        assert!(matches!(&events[..], [UiEvent::PathRemoveRequested { path }] if path.as_os_str() == "/podcasts"));
+        assert_eq!((s.roots(), s.root_cursor()), (&["/music".to_string()][..], 0));
+        assert!(s.needs_refresh());
+    }
+
     #[test]
     fn ctrl_u_clears_path() {
         let mut s = SettingsState::default();
@@ -442,6 +685,44 @@ mod tests {
         assert_eq!(events.len(), 1);
     }
 
+    #[test]
+    fn crossfade_adjusts_within_bounds_and_emits_each_change() {
+        let mut s = SettingsState::default();
+        open_and_select_crossfade(&mut s);
+
+        let events = s.apply_action(InputAction::SettingsLeft);
+        assert!(events.is_empty(), "already off, nothing to lower");
+
+        let events = s.apply_action(InputAction::SettingsRight);
+        assert_eq!(s.temp_crossfade(), 1);
+        assert!(matches!(events[0], UiEvent::CrossfadeChangeRequested { secs: 1 }));
+
+        for _ in 0..CROSSFADE_MAX_SECS {
+            s.apply_action(InputAction::SettingsRight);
+        }
+        assert_eq!(s.temp_crossfade(), CROSSFADE_MAX_SECS);
+
+        let events = s.apply_action(InputAction::SettingsConfirm);
+        assert_eq!(s.temp_crossfade(), 0, "Enter wraps back to off past the maximum");
+        assert!(matches!(events[0], UiEvent::CrossfadeChangeRequested { secs: 0 }));
+    }
+
+    #[test]
+    fn theme_cycles_through_available_names_and_wraps() {
+        let mut s = SettingsState::default();
+        s.set_theme_names(vec!["dark", "light", CUSTOM_THEME]);
+        open_and_select_theme(&mut s);
+        s.temp_theme = s.configured_theme(None);
+        assert_eq!(s.temp_theme(), CUSTOM_THEME, "theme.toml is the default when present");
+
+        let events = s.apply_action(InputAction::SettingsRight);
+        assert_eq!(s.temp_theme(), "dark");
+        assert!(matches!(&events[0], UiEvent::ThemeChangeRequested { name } if name == "dark"));
+
+        s.apply_action(InputAction::SettingsLeft);
+        assert_eq!(s.temp_theme(), CUSTOM_THEME);
+    }
+
     #[test]
     fn close_in_navigation_closes_modal_but_close_in_edit_exits_edit_only() {
         let mut s = SettingsState::default();
@@ -457,4 +738,38 @@ mod tests {
         s.apply_action(InputAction::SettingsClose);
         assert!(!s.is_open());
     }
+
+    #[test]
+    fn progress_label_cycles_and_requests_the_change() {
+        let mut s = SettingsState::default();
+        open_and_select_progress_label(&mut s);
+
+        let events = s.apply_action(InputAction::SettingsLeft);
+
+        assert_eq!(s.temp_progress_label(), ProgressLabel::Percentage);
+        assert!(matches!(
+            events[..],
+            [UiEvent::ProgressLabelChangeRequested { label: ProgressLabel::Percentage }]
+        ));
+    }
+
+    #[test]
+    fn equalizer_enter_picks_the_band_and_arrows_adjust_it() {
+        let mut s = SettingsState::default();
+        open_and_select_equalizer(&mut s);
+
+        let events = s.apply_action(InputAction::SettingsConfirm);
+        assert!(events.is_empty());
+        assert_eq!(s.eq_band(), 1);
+
+        let events = s.apply_action(InputAction::SettingsRight);
+        assert_eq!(s.temp_eq(), EqGains::new(0, 1, 0));
+        assert!(matches!(events[..], [UiEvent::EqChangeRequested { gains }] if gains.mid == 1));
+
+        for _ in 0..EQ_MAX_DB + 5 {
+            s.apply_action(InputAction::SettingsRight);
+        }
+        assert_eq!(s.temp_eq().mid, EQ_MAX_DB);
+        assert!(s.apply_action(InputAction::SettingsRight).is_empty(), "no event once clamped");
+    }
 }
@@ -1,25 +1,34 @@
 use crate::modules::ui::tui::settings_state::{PathValidation, SettingsField, SettingsState};
+use crate::modules::ui::tui::theme::Theme;
 use crate::modules::input::{InputAction, InputMode, KeyConfig};
 use crate::modules::ui::key_hints;
+use crate::modules::playback::equalizer::EQ_BANDS;
 use crate::utils::repeat_label;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Clear, Paragraph},
     Frame,
 };
 use crossterm::event::KeyCode;
 
-pub fn draw(f: &mut Frame, settings: &SettingsState, key_config: &KeyConfig) {
-    let height_pct = if settings.is_editing_path() { 60 } else { 50 };
-    let area = centered_rect(60, height_pct, f.area());
+pub fn draw(f: &mut Frame, settings: &SettingsState, key_config: &KeyConfig, theme: &Theme) {
+    let path_note_height = match settings.path_validation() {
+        PathValidation::Error(_) => 1,
+        PathValidation::Idle => u16::from(settings.needs_refresh()),
+    };
+
+    // Every field plus the help rows and the padding inside the border.
+    let content_height = 7 * 3 + path_note_height + 2 + 4;
+    let height_pct = if settings.is_editing_path() { 80 } else { 70 };
+    let area = centered_rect_min_height(60, height_pct, content_height, f.area());
     f.render_widget(Clear, area);
     f.render_widget(
         Block::default()
             .title(" ⚙ Settings ")
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Yellow)),
+            .border_style(Style::default().fg(theme.accent)),
         area,
     );
 
@@ -30,31 +39,40 @@ pub fn draw(f: &mut Frame, settings: &SettingsState, key_config: &KeyConfig) {
         height: area.height.saturating_sub(4),
     };
 
-    let path_error_height = match settings.path_validation() {
-        PathValidation::Error(_) => 1,
-        PathValidation::Idle => 0,
-    };
-
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3),                 // Volume
             Constraint::Length(3),                 // Repeat
+            Constraint::Length(3),                 // Crossfade
+            Constraint::Length(3),                 // Equalizer
+            Constraint::Length(3),                 // Theme
+            Constraint::Length(3),                 // Progress label
             Constraint::Length(3),                 // Music Path input
-            Constraint::Length(path_error_height), // Inline error (0 or 1)
+            Constraint::Length(path_note_height),  // Inline error or refresh hint (0 or 1)
             Constraint::Min(0),                    // spacer
             Constraint::Length(2),                 // help
         ])
         .split(inner);
 
-    draw_volume(f, settings, key_config, chunks[0]);
-    draw_repeat(f, settings, key_config, chunks[1]);
-    draw_path(f, settings, key_config, chunks[2]);
-    draw_path_error(f, settings, chunks[3]);
-    draw_help(f, settings, key_config, chunks[5]);
+    draw_volume(f, settings, key_config, theme, chunks[0]);
+    draw_repeat(f, settings, key_config, theme, chunks[1]);
+    draw_crossfade(f, settings, key_config, theme, chunks[2]);
+    draw_equalizer(f, settings, key_config, theme, chunks[3]);
+    draw_theme(f, settings, key_config, theme, chunks[4]);
+    draw_progress_label(f, settings, key_config, theme, chunks[5]);
+    draw_path(f, settings, key_config, theme, chunks[6]);
+    draw_path_note(f, settings, key_config, theme, chunks[7]);
+    draw_help(f, settings, key_config, theme, chunks[9]);
 }
 
-fn draw_volume(f: &mut Frame, settings: &SettingsState, key_config: &KeyConfig, area: Rect) {
+fn draw_volume(
+    f: &mut Frame,
+    settings: &SettingsState,
+    key_config: &KeyConfig,
+    theme: &Theme,
+    area: Rect,
+) {
     let selected = settings.selected() == SettingsField::Volume;
     let editing = selected && settings.is_editing_volume();
 
@@ -96,10 +114,16 @@ fn draw_volume(f: &mut Frame, settings: &SettingsState, key_config: &KeyConfig,
         format!("Volume: {}%", settings.temp_volume())
     };
 
-    f.render_widget(Paragraph::new(label).style(field_style(selected)), area);
+    f.render_widget(Paragraph::new(label).style(field_style(selected, theme)), area);
 }
 
-fn draw_repeat(f: &mut Frame, settings: &SettingsState, key_config: &KeyConfig, area: Rect) {
+fn draw_repeat(
+    f: &mut Frame,
+    settings: &SettingsState,
+    key_config: &KeyConfig,
+    theme: &Theme,
+    area: Rect,
+) {
     let selected = settings.selected() == SettingsField::Repeat;
     let temp_repeat = settings.temp_repeat();
 
@@ -139,10 +163,183 @@ fn draw_repeat(f: &mut Frame, settings: &SettingsState, key_config: &KeyConfig,
         )
     };
 
-    f.render_widget(Paragraph::new(label).style(field_style(selected)), area);
+    f.render_widget(Paragraph::new(label).style(field_style(selected, theme)), area);
+}
+
+fn draw_crossfade(
+    f: &mut Frame,
+    settings: &SettingsState,
+    key_config: &KeyConfig,
+    theme: &Theme,
+    area: Rect,
+) {
+    let selected = settings.selected() == SettingsField::Crossfade;
+    let value = match settings.temp_crossfade() {
+        0 => "Off".to_string(),
+        secs => format!("{}s", secs),
+    };
+
+    let label = if selected {
+        let left = key_hints::pick_binding_with_preference(
+            key_config,
+            InputMode::Settings,
+            InputAction::SettingsLeft,
+            &[key_hints::kb(KeyCode::Left)],
+        );
+        let right = key_hints::pick_binding_with_preference(
+            key_config,
+            InputMode::Settings,
+            InputAction::SettingsRight,
+            &[key_hints::kb(KeyCode::Right)],
+        );
+
+        format!(
+            "Crossfade: {}  [{}/{} adjust • auto-advance only]",
+            value,
+            key_hints::format_binding_opt(left),
+            key_hints::format_binding_opt(right),
+        )
+    } else {
+        format!("Crossfade: {}", value)
+    };
+
+    f.render_widget(Paragraph::new(label).style(field_style(selected, theme)), area);
+}
+
+fn draw_equalizer(
+    f: &mut Frame,
+    settings: &SettingsState,
+    key_config: &KeyConfig,
+    theme: &Theme,
+    area: Rect,
+) {
+    let selected = settings.selected() == SettingsField::Equalizer;
+    let gains = settings.temp_eq();
+
+    let label = if selected {
+        let bands: Vec<String> = EQ_BANDS
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let band = format!("{} {:+}", name, gains.band(i));
+                if i == settings.eq_band() { format!("[{}]", band) } else { band }
+            })
+            .collect();
+        let confirm = key_hints::pick_binding_with_preference(
+            key_config,
+            InputMode::Settings,
+            InputAction::SettingsConfirm,
+            &[key_hints::kb(KeyCode::Enter)],
+        );
+        let left = key_hints::pick_binding_with_preference(
+            key_config,
+            InputMode::Settings,
+            InputAction::SettingsLeft,
+            &[key_hints::kb(KeyCode::Left)],
+        );
+        let right = key_hints::pick_binding_with_preference(
+            key_config,
+            InputMode::Settings,
+            InputAction::SettingsRight,
+            &[key_hints::kb(KeyCode::Right)],
+        );
+
+        format!(
+            "Equalizer: {}  [{} band • {}/{} dB]",
+            bands.join("  "),
+            key_hints::format_binding_opt(confirm),
+            key_hints::format_binding_opt(left),
+            key_hints::format_binding_opt(right),
+        )
+    } else if gains.is_flat() {
+        "Equalizer: Off".to_string()
+    } else {
+        format!("Equalizer: {}", gains)
+    };
+
+    f.render_widget(Paragraph::new(label).style(field_style(selected, theme)), area);
+}
+
+fn draw_theme(
+    f: &mut Frame,
+    settings: &SettingsState,
+    key_config: &KeyConfig,
+    theme: &Theme,
+    area: Rect,
+) {
+    let selected = settings.selected() == SettingsField::Theme;
+
+    let label = if selected {
+        let left = key_hints::pick_binding_with_preference(
+            key_config,
+            InputMode::Settings,
+            InputAction::SettingsLeft,
+            &[key_hints::kb(KeyCode::Left)],
+        );
+        let right = key_hints::pick_binding_with_preference(
+            key_config,
+            InputMode::Settings,
+            InputAction::SettingsRight,
+            &[key_hints::kb(KeyCode::Right)],
+        );
+
+        format!(
+            "Theme: {}  [{}/{} switch]",
+            settings.temp_theme(),
+            key_hints::format_binding_opt(left),
+            key_hints::format_binding_opt(right),
+        )
+    } else {
+        format!("Theme: {}", settings.temp_theme())
+    };
+
+    f.render_widget(Paragraph::new(label).style(field_style(selected, theme)), area);
 }
 
-fn draw_path(f: &mut Frame, settings: &SettingsState, key_config: &KeyConfig, area: Rect) {
+fn draw_progress_label(
+    f: &mut Frame,
+    settings: &SettingsState,
+    key_config: &KeyConfig,
+    theme: &Theme,
+    area: Rect,
+) {
+    let selected = settings.selected() == SettingsField::ProgressLabel;
+    let name = settings.temp_progress_label().name();
+
+    let label = if selected {
+        let left = key_hints::pick_binding_with_preference(
+            key_config,
+            InputMode::Settings,
+            InputAction::SettingsLeft,
+            &[key_hints::kb(KeyCode::Left)],
+        );
+        let right = key_hints::pick_binding_with_preference(
+            key_config,
+            InputMode::Settings,
+            InputAction::SettingsRight,
+            &[key_hints::kb(KeyCode::Right)],
+        );
+
+        format!(
+            "Progress label: {}  [{}/{} switch]",
+            name,
+            key_hints::format_binding_opt(left),
+            key_hints::format_binding_opt(right),
+        )
+    } else {
+        format!("Progress label: {}", name)
+    };
+
+    f.render_widget(Paragraph::new(label).style(field_style(selected, theme)), area);
+}
+
+fn draw_path(
+    f: &mut Frame,
+    settings: &SettingsState,
+    key_config: &KeyConfig,
+    theme: &Theme,
+    area: Rect,
+) {
     let selected = settings.selected() == SettingsField::MusicPath;
     let confirm = key_hints::pick_binding_with_preference(
         key_config,
@@ -151,44 +348,53 @@ fn draw_path(f: &mut Frame, settings: &SettingsState, key_config: &KeyConfig, ar
         &[key_hints::kb(KeyCode::Enter)],
     );
 
-    let label_color = if selected { Color::Yellow } else { Color::White };
-    let hint_color = if selected { Color::Yellow } else { Color::DarkGray };
+    let label_color = if selected { theme.accent } else { theme.text };
+    let hint_color = if selected { theme.accent } else { theme.faint };
 
     let label: Line = if settings.is_editing_path() {
         Line::from(vec![
             Span::styled(
-                "Music Path: ",
+                "Add Music Path: ",
                 Style::default()
                     .fg(label_color)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::styled(settings.temp_path(), Style::default().fg(Color::White)),
-            Span::styled("█", Style::default().fg(Color::DarkGray)),
+            Span::styled(settings.temp_path(), Style::default().fg(theme.text)),
+            Span::styled("█", Style::default().fg(theme.faint)),
             Span::styled(
                 "  [Enter confirm • Esc cancel • Ctrl+U clear]",
-                Style::default().fg(Color::Yellow),
+                Style::default().fg(theme.accent),
             ),
         ])
-    } else if settings.temp_path().is_empty() {
+    } else if settings.roots().is_empty() {
         let mut spans = vec![
-            Span::styled("Music Path: ", Style::default().fg(label_color)),
-            Span::styled("(not set)", Style::default().fg(Color::DarkGray)),
+            Span::styled("Music Paths: ", Style::default().fg(label_color)),
+            Span::styled("(not set)", Style::default().fg(theme.faint)),
         ];
         if selected {
             spans.push(Span::styled(
-                format!("  [{} to set]", key_hints::format_binding_opt(confirm)),
+                format!("  [{} to add]", key_hints::format_binding_opt(confirm)),
                 Style::default().fg(hint_color),
             ));
         }
         Line::from(spans)
     } else {
-        let mut spans = vec![
-            Span::styled("Music Path: ", Style::default().fg(label_color)),
-            Span::styled(settings.temp_path(), Style::default().fg(Color::Cyan)),
-        ];
+        let mut spans = vec![Span::styled("Music Paths: ", Style::default().fg(label_color))];
+        for (i, root) in settings.roots().iter().enumerate() {
+            if i > 0 {
+                spans.push(Span::styled("  ", Style::default()));
+            }
+            // The picked root is the one Backspace removes.
+            let style = if selected && i == settings.root_cursor() {
+                Style::default().fg(theme.header).bg(theme.highlight).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.header)
+            };
+            spans.push(Span::styled(root.as_str(), style));
+        }
         if selected {
             spans.push(Span::styled(
-                format!("  [{} to change]", key_hints::format_binding_opt(confirm)),
+                format!("  [{} to add • Backspace to remove]", key_hints::format_binding_opt(confirm)),
                 Style::default().fg(hint_color),
             ));
         }
@@ -198,22 +404,57 @@ fn draw_path(f: &mut Frame, settings: &SettingsState, key_config: &KeyConfig, ar
     f.render_widget(Paragraph::new(label), area);
 }
 
-fn draw_path_error(f: &mut Frame, settings: &SettingsState, area: Rect) {
+fn draw_path_note(
+    f: &mut Frame,
+    settings: &SettingsState,
+    key_config: &KeyConfig,
+    theme: &Theme,
+    area: Rect,
+) {
     if let PathValidation::Error(msg) = settings.path_validation() {
         f.render_widget(
             Paragraph::new(Line::from(vec![
                 Span::styled(
                     "  ✗ ",
-                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                    Style::default().fg(theme.error).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(msg.as_str(), Style::default().fg(theme.error)),
+            ])),
+            area,
+        );
+    } else if settings.needs_refresh() {
+        let refresh = key_hints::pick_binding_with_preference(
+            key_config,
+            InputMode::Normal,
+            InputAction::Refresh,
+            &[key_hints::kb(KeyCode::F(5))],
+        );
+        f.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::styled(
+                    "  ↻ ",
+                    Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!(
+                        "Paths saved. Close settings and press {} to rescan.",
+                        key_hints::format_binding_opt(refresh)
+                    ),
+                    Style::default().fg(theme.accent),
                 ),
-                Span::styled(msg.as_str(), Style::default().fg(Color::Red)),
             ])),
             area,
         );
     }
 }
 
-fn draw_help(f: &mut Frame, settings: &SettingsState, key_config: &KeyConfig, area: Rect) {
+fn draw_help(
+    f: &mut Frame,
+    settings: &SettingsState,
+    key_config: &KeyConfig,
+    theme: &Theme,
+    area: Rect,
+) {
     let nav_up = key_hints::pick_binding_with_preference(
         key_config,
         InputMode::Settings,
@@ -291,11 +532,48 @@ fn draw_help(f: &mut Frame, settings: &SettingsState, key_config: &KeyConfig, ar
                     close_keys
                 )
             }
+            SettingsField::Crossfade => format!(
+                "{}/{}: Navigate  •  {}/{}: Adjust seconds  •  {}: Close",
+                key_hints::format_binding_opt(nav_up),
+                key_hints::format_binding_opt(nav_down),
+                key_hints::format_binding_opt(left),
+                key_hints::format_binding_opt(right),
+                close_keys
+            ),
+            SettingsField::Equalizer => format!(
+                "{}/{}: Navigate  •  {}: Next band  •  {}/{}: Adjust dB  •  {}: Close",
+                key_hints::format_binding_opt(nav_up),
+                key_hints::format_binding_opt(nav_down),
+                key_hints::format_binding_opt(confirm),
+                key_hints::format_binding_opt(left),
+                key_hints::format_binding_opt(right),
+                close_keys
+            ),
+            SettingsField::Theme => format!(
+                "{}/{}: Navigate  •  {}/{} or {}: Switch theme  •  {}: Close",
+                key_hints::format_binding_opt(nav_up),
+                key_hints::format_binding_opt(nav_down),
+                key_hints::format_binding_opt(left),
+                key_hints::format_binding_opt(right),
+                key_hints::format_binding_opt(confirm),
+                close_keys
+            ),
+            SettingsField::ProgressLabel => format!(
+                "{}/{}: Navigate  •  {}/{} or {}: Switch label  •  {}: Close",
+                key_hints::format_binding_opt(nav_up),
+                key_hints::format_binding_opt(nav_down),
+                key_hints::format_binding_opt(left),
+                key_hints::format_binding_opt(right),
+                key_hints::format_binding_opt(confirm),
+                close_keys
+            ),
             SettingsField::MusicPath => format!(
-                "{}/{}: Navigate  •  {}: Edit path  •  {}: Close",
+                "{}/{}: Navigate  •  {}: Add path  •  {}/{}: Pick  •  Backspace: Remove  •  {}: Close",
                 key_hints::format_binding_opt(nav_up),
                 key_hints::format_binding_opt(nav_down),
                 key_hints::format_binding_opt(confirm),
+                key_hints::format_binding_opt(left),
+                key_hints::format_binding_opt(right),
                 close_keys
             ),
         }
@@ -303,23 +581,24 @@ fn draw_help(f: &mut Frame, settings: &SettingsState, key_config: &KeyConfig, ar
 
     f.render_widget(
         Paragraph::new(text)
-            .style(Style::default().fg(Color::DarkGray))
+            .style(Style::default().fg(theme.faint))
             .alignment(Alignment::Center),
         area,
     );
 }
 
-fn field_style(selected: bool) -> Style {
+fn field_style(selected: bool, theme: &Theme) -> Style {
     if selected {
         Style::default()
-            .fg(Color::Yellow)
+            .fg(theme.accent)
             .add_modifier(Modifier::BOLD)
     } else {
-        Style::default().fg(Color::White)
+        Style::default().fg(theme.text)
     }
 }
 
-fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+    let (percent_x, percent_y) = (percent_x.min(100), percent_y.min(100));
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -338,3 +617,38 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         ])
         .split(popup_layout[1])[1]
 }
+
+/// `centered_rect`, grown to `min_height` rows when the percentage leaves too few,
+/// but never taller than `r`.
+pub fn centered_rect_min_height(percent_x: u16, percent_y: u16, min_height: u16, r: Rect) -> Rect {
+    let area = centered_rect(percent_x, percent_y, r);
+    if area.height >= min_height {
+        return area;
+    }
+    let height = min_height.min(r.height);
+    Rect { y: r.y + (r.height - height) / 2, height, ..area }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn popups_fit_inside_tiny_screens() {
+        for (width, height) in [(0, 0), (1, 1), (3, 2), (80, 5)] {
+            let screen = Rect { x: 2, y: 1, width, height };
+            for area in [
+                centered_rect(60, 70, screen),
+                centered_rect(150, 120, screen),
+                centered_rect_min_height(60, 70, 28, screen),
+            ] {
+                assert!(screen.contains(area.as_position()) || area.is_empty(), "{:?} outside {:?}", area, screen);
+                assert!(area.right() <= screen.right() && area.bottom() <= screen.bottom());
+            }
+        }
+
+        let tall = Rect { x: 0, y: 0, width: 100, height: 30 };
+        assert_eq!(centered_rect_min_height(60, 70, 28, tall).height, 28, "grows to fit its content");
+        assert_eq!(centered_rect_min_height(60, 70, 10, tall), centered_rect(60, 70, tall));
+    }
+}
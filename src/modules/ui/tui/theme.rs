@@ -0,0 +1,278 @@
+use ratatui::style::Color;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use crate::utils::APP_NAME;
+
+/// Built-in theme names, in the order the settings modal cycles through them.
+pub const PRESET_NAMES: &[&str] = &["dark", "light", "solarized"];
+
+/// Name of the theme defined by `theme.toml`, when that file exists.
+pub const CUSTOM_THEME: &str = "custom";
+
+/// Named colors used throughout the TUI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// App title in the header.
+    pub header: Color,
+    /// Search mode, focused settings rows, the now-playing title.
+    pub accent: Color,
+    /// Background of the selected list row.
+    pub highlight: Color,
+    /// Playing status and the playing song's row.
+    pub playing: Color,
+    /// Title of the playing song's row.
+    pub playing_title: Color,
+    pub paused: Color,
+    pub progress_filled: Color,
+    pub progress_empty: Color,
+    /// Primary text such as song titles.
+    pub text: Color,
+    /// Structural text: indices, durations, help lines.
+    pub dim: Color,
+    /// Least important hints and counts.
+    pub faint: Color,
+    pub artist: Color,
+    /// Album in the now-playing box and album headers.
+    pub album: Color,
+    /// Album column of the song list.
+    pub album_column: Color,
+    pub error: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    /// The original look of the TUI.
+    pub fn dark() -> Self {
+        Self {
+            header: Color::Cyan,
+            accent: Color::Yellow,
+            highlight: Color::DarkGray,
+            playing: Color::Green,
+            playing_title: Color::LightGreen,
+            paused: Color::Yellow,
+            progress_filled: Color::LightBlue,
+            progress_empty: Color::DarkGray,
+            text: Color::White,
+            dim: Color::Gray,
+            faint: Color::DarkGray,
+            artist: Color::Cyan,
+            album: Color::Magenta,
+            album_column: Color::LightBlue,
+            error: Color::Red,
+        }
+    }
+
+    /// For terminals with a light background.
+    pub fn light() -> Self {
+        Self {
+            header: Color::Blue,
+            accent: Color::Magenta,
+            highlight: Color::Gray,
+            playing: Color::Green,
+            playing_title: Color::Green,
+            paused: Color::Magenta,
+            progress_filled: Color::Blue,
+            progress_empty: Color::Gray,
+            text: Color::Black,
+            dim: Color::DarkGray,
+            faint: Color::DarkGray,
+            artist: Color::Blue,
+            album: Color::Magenta,
+            album_column: Color::Cyan,
+            error: Color::Red,
+        }
+    }
+
+    /// Solarized dark palette.
+    pub fn solarized() -> Self {
+        const BASE02: Color = Color::Rgb(0x07, 0x36, 0x42);
+        const BASE01: Color = Color::Rgb(0x58, 0x6e, 0x75);
+        const BASE0: Color = Color::Rgb(0x83, 0x94, 0x96);
+        const BASE1: Color = Color::Rgb(0x93, 0xa1, 0xa1);
+        const YELLOW: Color = Color::Rgb(0xb5, 0x89, 0x00);
+        const ORANGE: Color = Color::Rgb(0xcb, 0x4b, 0x16);
+        const RED: Color = Color::Rgb(0xdc, 0x32, 0x2f);
+        const MAGENTA: Color = Color::Rgb(0xd3, 0x36, 0x82);
+        const VIOLET: Color = Color::Rgb(0x6c, 0x71, 0xc4);
+        const BLUE: Color = Color::Rgb(0x26, 0x8b, 0xd2);
+        const CYAN: Color = Color::Rgb(0x2a, 0xa1, 0x98);
+        const GREEN: Color = Color::Rgb(0x85, 0x99, 0x00);
+
+        Self {
+            header: CYAN,
+            accent: YELLOW,
+            highlight: BASE02,
+            playing: GREEN,
+            playing_title: GREEN,
+            paused: ORANGE,
+            progress_filled: BLUE,
+            progress_empty: BASE02,
+            text: BASE1,
+            dim: BASE0,
+            faint: BASE01,
+            artist: CYAN,
+            album: MAGENTA,
+            album_column: VIOLET,
+            error: RED,
+        }
+    }
+
+    /// Look up a built-in theme by name.
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "solarized" => Some(Self::solarized()),
+            _ => None,
+        }
+    }
+
+    /// Theme to use for a configured `name`: a preset, the custom theme, or
+    /// (when unset or unknown) the custom theme if there is one, else `dark`.
+    pub fn resolve(name: Option<&str>, custom: Option<&Theme>) -> Self {
+        name.and_then(Self::preset)
+            .or_else(|| custom.copied())
+            .unwrap_or_default()
+    }
+
+    /// Load the custom theme from `<config dir>/hextune/theme.toml`.
+    ///
+    /// Returns `None` when the file does not exist. Unknown color names and
+    /// malformed values are reported on stderr and skipped.
+    pub fn load_custom(config_dir: &Path) -> Option<Self> {
+        let file_path = theme_path(config_dir);
+
+        match fs::read_to_string(&file_path) {
+            Ok(toml_str) => match Self::parse(&toml_str) {
+                Ok(theme) => Some(theme),
+                Err(err) => {
+                    eprintln!(
+                        "Warning: Failed to parse theme at '{}': {err}. Using defaults.",
+                        file_path.display()
+                    );
+                    None
+                }
+            },
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => {
+                eprintln!("Warning: Could not read theme at '{}': {err}", file_path.display());
+                None
+            }
+        }
+    }
+
+    fn parse(toml_str: &str) -> Result<Self, String> {
+        let value: toml::Value = toml::from_str(toml_str).map_err(|e| format!("TOML error: {e}"))?;
+
+        let mut theme = match value.get("base").and_then(|v| v.as_str()) {
+            Some(base) => Self::preset(base).ok_or_else(|| {
+                format!("unknown base theme '{base}' (expected one of: {})", PRESET_NAMES.join(", "))
+            })?,
+            None => Self::dark(),
+        };
+
+        let Some(colors) = value.get("colors") else {
+            return Ok(theme);
+        };
+        let Some(colors) = colors.as_table() else {
+            return Err("'[colors]' must be a table".to_string());
+        };
+
+        for (name, value) in colors {
+            let Some(slot) = theme.slot_mut(name) else {
+                eprintln!("Warning: Unknown theme color '{name}' ignored.");
+                continue;
+            };
+
+            match value.as_str().map(Color::from_str) {
+                Some(Ok(color)) => *slot = color,
+                _ => eprintln!("Warning: Invalid color for '{name}': {value}. Ignoring."),
+            }
+        }
+
+        Ok(theme)
+    }
+
+    fn slot_mut(&mut self, name: &str) -> Option<&mut Color> {
+        Some(match name {
+            "header" => &mut self.header,
+            "accent" => &mut self.accent,
+            "highlight" => &mut self.highlight,
+            "playing" => &mut self.playing,
+            "playing_title" => &mut self.playing_title,
+            "paused" => &mut self.paused,
+            "progress_filled" => &mut self.progress_filled,
+            "progress_empty" => &mut self.progress_empty,
+            "text" => &mut self.text,
+            "dim" => &mut self.dim,
+            "faint" => &mut self.faint,
+            "artist" => &mut self.artist,
+            "album" => &mut self.album,
+            "album_column" => &mut self.album_column,
+            "error" => &mut self.error,
+            _ => return None,
+        })
+    }
+}
+
+/// Theme names the user can pick from, in cycling order.
+pub fn available_themes(has_custom: bool) -> Vec<&'static str> {
+    let mut names = PRESET_NAMES.to_vec();
+    if has_custom {
+        names.push(CUSTOM_THEME);
+    }
+    names
+}
+
+fn theme_path(config_dir: &Path) -> PathBuf {
+    config_dir.join(APP_NAME).join("theme.toml")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_theme_overrides_colors_on_top_of_base() {
+        let toml_str = r##"
+base = "light"
+
+[colors]
+accent = "#ff8800"
+playing = "lightgreen"
+"##;
+
+        let theme = Theme::parse(toml_str).unwrap();
+
+        assert_eq!(theme.accent, Color::Rgb(0xff, 0x88, 0x00));
+        assert_eq!(theme.playing, Color::LightGreen);
+        assert_eq!(theme.text, Theme::light().text, "unset colors come from the base");
+    }
+
+    #[test]
+    fn bad_colors_are_skipped_and_bad_base_is_an_error() {
+        let theme = Theme::parse("[colors]\nheader = \"not-a-color\"\nbogus = \"red\"\n").unwrap();
+        assert_eq!(theme, Theme::dark());
+
+        assert!(Theme::parse("base = \"neon\"\n").is_err());
+    }
+
+    #[test]
+    fn resolve_prefers_named_preset_then_custom_then_dark() {
+        let mut custom = Theme::dark();
+        custom.accent = Color::Red;
+
+        assert_eq!(Theme::resolve(Some("solarized"), Some(&custom)), Theme::solarized());
+        assert_eq!(Theme::resolve(Some(CUSTOM_THEME), Some(&custom)), custom);
+        assert_eq!(Theme::resolve(None, Some(&custom)), custom);
+        assert_eq!(Theme::resolve(None, None), Theme::dark());
+        assert_eq!(Theme::resolve(Some(CUSTOM_THEME), None), Theme::dark());
+    }
+}
@@ -1,18 +1,55 @@
 use crate::core::models::RepeatMode;
+use std::path::PathBuf;
+use std::time::Duration;
 
 pub const APP_NAME: &str = "hextune";
 
-pub const SUPPORTED_EXTENSIONS: &[&str] = &["mp3", "flac", "wav", "ogg"];
+pub const SUPPORTED_EXTENSIONS: &[&str] = &["mp3", "flac", "wav", "ogg", "m4a", "aac", "opus"];
+/// Scanned (lofty reads their tags) but not decodable by the playback backend.
+pub const UNPLAYABLE_EXTENSIONS: &[&str] = &["opus"];
 
-pub const TICK_RATE_MS: u64 = 16; // ~60 FPS event loop
+pub const DEFAULT_FPS: u16 = 60; // event loop rate while playing or scanning; `fps` in the config
+pub const FPS_MIN: u16 = 5;
+pub const FPS_MAX: u16 = 120;
+pub const IDLE_TICK_RATE_MS: u64 = 250; // event loop wait otherwise; keys and app events still wake it
 pub const PROGRESS_BAR_WIDTH: usize = 40; // terminal progress bar chars
 pub const EVENT_CHANNEL_CAPACITY: usize = 100;
 pub const MIN_TRUNCATE_TITLE: usize = 8; // TUI list item min title width
 pub const MIN_TRUNCATE_FIELD: usize = 4; // TUI list item min field width
+pub const LIST_PAGE_SIZE: usize = 50; // `list --page` songs per page unless --per-page says otherwise
 pub const VOLUME_MAX: u8 = 100;
 pub const VOLUME_STEP: u8 = 5;
+pub const RATING_MAX: u8 = 5; // song ratings are 1 to this many stars
 pub const VOLUME_CURVE_EXPONENT: i32 = 4;
 pub const CLI_PLAYBACK_POLL_MS: u64 = 100;
+pub const MAX_CONSECUTIVE_PLAY_FAILURES: u32 = 5; // stop auto-skipping broken files after this many in a row
+pub const SEEK_STEP_SECS: i64 = 10; // `.`/`,` jump this far in the playing track...
+pub const SEEK_LONG_STEP_SECS: i64 = 60; // ...and `>`/`<` this far
+pub const GAPLESS_PRELOAD_SECS: u64 = 5; // queue the next track this close to the end
+pub const RESUME_MIN_TRACK_SECS: u64 = 20 * 60; // tracks this long reopen where they were left
+pub const RESUME_MARGIN_SECS: u64 = 30; // positions this close to either end are not kept
+pub const CROSSFADE_MAX_SECS: u8 = 12;
+pub const EQ_MAX_DB: i8 = 12; // equalizer bands boost or cut at most this much
+pub const PLAY_THRESHOLD_PCT: u8 = 50; // by default a play counts after this much of the track...
+pub const PLAY_THRESHOLD_SECS: u32 = 240; // ...or this many seconds, whichever comes first
+pub const NOW_PLAYING_HEIGHT: u16 = 5; // TUI now-playing box, text only
+pub const TUI_MIN_WIDTH: u16 = 30; // below this (or the fixed rows plus a list row) the TUI shows a size hint instead
+pub const TUI_MIN_LIST_HEIGHT: u16 = 3; // song list borders plus one row
+pub const NOW_PLAYING_ART_HEIGHT: u16 = 8; // TUI now-playing box with cover art
+pub const MARQUEE_MS_PER_CHAR: u64 = 320; // TUI: overflowing now-playing text scrolls one char this often
+pub const MARQUEE_GAP: usize = 5; // blank chars between the end of scrolling text and its start
+pub const VISUALIZER_HEIGHT: u16 = 4; // TUI level meter panel: one row per stereo channel plus borders
+pub const MINI_PLAYER_HEIGHT: u16 = 2; // `--mini`: now playing and hints, then the progress bar
+pub const VOLUME_BAR_WIDTH: usize = 10; // TUI volume bar cells, one per 10%
+pub const DOUBLE_CLICK_MS: u64 = 400; // TUI: two clicks on a row this close together play it
+pub const RECENT_PLAYS_MAX: usize = 200; // play history keeps this many recent plays, newest replacing oldest
+pub const SEARCH_HISTORY_MAX: usize = 50; // distinct search queries remembered, newest first
+pub const SAVE_DEBOUNCE_SECS: u64 = 3; // changed state is written at most this often while running
+pub const SLEEP_TIMER_PRESETS_MINS: &[u32] = &[15, 30, 45, 60, 90]; // TUI sleep key steps through these, then off
+pub const PULSE_STEP_MS: u128 = 150; // progress bar pulse moves one cell this often when the length is unknown
+pub const SEARCH_TITLE_WEIGHT: i64 = 100; // fuzzy search: percent of a field's match score that counts...
+pub const SEARCH_ARTIST_WEIGHT: i64 = 85; // ...so a title match outranks an equally good artist match...
+pub const SEARCH_ALBUM_WEIGHT: i64 = 70; // ...which outranks an album match
 
 pub const SCAN_PROGRESS_INTERVAL: usize = 25;
 
@@ -33,7 +70,7 @@ pub fn volume_percent_to_amplitude(percent: u8) -> f32 {
 }
 
 /// Convert amplitude multiplier (0.0-1.0) back to user volume percentage (0-100)
-/// This is the inverse of volume_percent_to_amplitude().
+/// This is the inverse of volume_percent_to_amplitude(), exact for every whole percent.
 /// It converts the stored logarithmic amplitude back to linear percentage for display.
 /// # Arguments
 /// *
@@ -93,10 +130,82 @@ pub fn format_artists(artists: &[String]) -> String {
     }
 }
 
+/// Library roots for messages: the path itself when there is one, else a count.
+pub fn format_roots(roots: &[PathBuf]) -> String {
+    match roots {
+        [root] => format!("{:?}", root),
+        roots => format!("{} folders", roots.len()),
+    }
+}
+
 pub fn repeat_label(mode: RepeatMode) -> &'static str {
     match mode {
         RepeatMode::Off => "Off",
         RepeatMode::All => "All",
         RepeatMode::One => "One",
     }
-}
\ No newline at end of file
+}
+
+/// Cut `s` to at most `max_chars` characters, ending in `…` when anything was dropped.
+pub fn truncate_str(s: &str, max_chars: usize) -> String {
+    if max_chars == 0 {
+        return String::new();
+    }
+    let mut chars = s.chars();
+    let mut out = String::with_capacity(max_chars);
+    for _ in 0..max_chars {
+        match chars.next() {
+            None => return out,     // fits in full
+            Some(c) => out.push(c),
+        }
+    }
+    if chars.next().is_none() {
+        return out; // exactly max_chars long
+    }
+    // There are still characters left, we truncated.
+    out.pop();
+    out.push('…');
+    out
+}
+
+/// Parse a track position given as seconds (`90`), `m:ss` (`1:30`) or `h:mm:ss` (`1:02:03`).
+pub fn parse_position(raw: &str) -> Result<Duration, String> {
+    let invalid = || format!("invalid position '{}'; use seconds, m:ss or h:mm:ss", raw);
+    let parts: Vec<&str> = raw.trim().split(':').collect();
+    if parts.len() > 3 {
+        return Err(invalid());
+    }
+
+    let mut secs = 0u64;
+    for (i, part) in parts.iter().enumerate() {
+        let value: u64 = part.parse().map_err(|_| invalid())?;
+        // Only the leading field may exceed a clock's 59.
+        if i > 0 && (value > 59 || part.len() != 2) {
+            return Err(invalid());
+        }
+        secs = secs * 60 + value;
+    }
+    Ok(Duration::from_secs(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_volume_percent_survives_the_amplitude_round_trip() {
+        for percent in 0..=VOLUME_MAX {
+            assert_eq!(amplitude_to_volume(volume_percent_to_amplitude(percent)), percent);
+        }
+    }
+
+    #[test]
+    fn positions_parse_as_seconds_or_clock_time() {
+        assert_eq!(parse_position("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_position("1:30"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_position("1:02:03"), Ok(Duration::from_secs(3723)));
+        for bad in ["", "1:5", "1:75", "-3", "1:02:03:04", "abc"] {
+            assert!(parse_position(bad).is_err(), "{:?} should be rejected", bad);
+        }
+    }
+}